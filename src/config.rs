@@ -84,6 +84,12 @@ pub struct Config {
     #[serde(default)]
     pub account: String,
 
+    /// Additional accounts (E.164) to run alongside `account`, each with its
+    /// own signal-cli backend and database. Switch between them with the
+    /// `switch_account` key.
+    #[serde(default)]
+    pub accounts: Vec<String>,
+
     /// Path to signal-cli binary
     #[serde(default = "default_signal_cli_path")]
     pub signal_cli_path: String,
@@ -178,6 +184,10 @@ pub struct Config {
     #[serde(default)]
     pub sidebar_on_right: bool,
 
+    /// Show contact and group avatars next to sidebar entries
+    #[serde(default)]
+    pub sidebar_avatars: bool,
+
     /// Sidebar width in columns (14-40, default 22)
     #[serde(default = "default_sidebar_width")]
     pub sidebar_width: u16,
@@ -197,6 +207,16 @@ pub struct Config {
     /// Signal TLS proxy URL passed through to signal-cli (e.g., `<https://signal-proxy.example.com>`)
     #[serde(default)]
     pub proxy: String,
+
+    /// Path of a `signal-cli daemon --socket` to attach to instead of
+    /// spawning signal-cli. Takes precedence over `signal_cli_tcp`.
+    #[serde(default)]
+    pub signal_cli_socket: String,
+
+    /// `host:port` of a `signal-cli daemon --tcp` to attach to instead of
+    /// spawning signal-cli.
+    #[serde(default)]
+    pub signal_cli_tcp: String,
 }
 
 fn default_true() -> bool {
@@ -237,6 +257,7 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             account: String::new(),
+            accounts: Vec::new(),
             signal_cli_path: default_signal_cli_path(),
             download_dir: default_download_dir(),
             notify_direct: true,
@@ -260,11 +281,14 @@ impl Default for Config {
             send_read_receipts: true,
             mouse_enabled: true,
             sidebar_on_right: false,
+            sidebar_avatars: false,
             sidebar_width: default_sidebar_width(),
             theme: default_theme(),
             keybinding_profile: default_keybinding_profile(),
             settings_profile: default_settings_profile(),
             proxy: String::new(),
+            signal_cli_socket: String::new(),
+            signal_cli_tcp: String::new(),
         }
     }
 }
@@ -352,6 +376,29 @@ impl Config {
         self.account.is_empty()
     }
 
+    /// Every configured account in switcher order: `account` first, then
+    /// `accounts` with blanks and duplicates dropped.
+    pub fn all_accounts(&self) -> Vec<String> {
+        let mut out: Vec<String> = Vec::new();
+        for acct in std::iter::once(&self.account).chain(&self.accounts) {
+            let acct = acct.trim();
+            if !acct.is_empty() && !out.iter().any(|a| a == acct) {
+                out.push(acct.to_string());
+            }
+        }
+        out
+    }
+
+    /// A copy of this config targeting a different account. Everything else
+    /// (signal-cli path, transport, download dir, UI prefs) is shared.
+    pub fn for_account(&self, account: &str) -> Self {
+        Self {
+            account: account.to_string(),
+            accounts: Vec::new(),
+            ..self.clone()
+        }
+    }
+
     pub fn default_config_path() -> PathBuf {
         dirs::config_dir()
             .unwrap_or_else(|| PathBuf::from(".config"))
@@ -381,6 +428,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn all_accounts_puts_primary_first_and_dedups() {
+        let c = Config {
+            account: "+15550000001".to_string(),
+            accounts: vec![
+                "+15550000002".to_string(),
+                " ".to_string(),
+                "+15550000001".to_string(),
+                "+15550000002".to_string(),
+            ],
+            ..Config::default()
+        };
+        assert_eq!(c.all_accounts(), vec!["+15550000001", "+15550000002"]);
+        assert!(Config::default().all_accounts().is_empty());
+    }
+
+    #[test]
+    fn for_account_swaps_only_the_account() {
+        let c = Config {
+            account: "+15550000001".to_string(),
+            accounts: vec!["+15550000002".to_string()],
+            proxy: "https://proxy.example".to_string(),
+            ..Config::default()
+        };
+        let other = c.for_account("+15550000002");
+        assert_eq!(other.account, "+15550000002");
+        assert!(other.accounts.is_empty());
+        assert_eq!(other.proxy, c.proxy);
+    }
+
     #[test]
     fn migrate_legacy_image_mode_native_wins() {
         let mut c = legacy_config(true, true);
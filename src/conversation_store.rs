@@ -9,8 +9,11 @@ use ratatui::text::Line;
 use std::collections::{HashMap, HashSet};
 
 use crate::db::Database;
+use crate::domain::{LocalContact, MessageAttachment, ViewOnce, mask_spoilers};
+use crate::image_render::{self, ImageTile};
 use crate::signal::types::{
-    Group, LinkPreview, Mention, MessageStatus, PollData, PollVote, Reaction, StyleType, TextStyle,
+    Group, LinkPreview, Mention, MessageStatus, PollData, PollVote, Reaction, SharedContact,
+    StyleType, TextStyle,
 };
 
 /// Log a database error via debug_log (no-op when --debug is off).
@@ -132,6 +135,20 @@ pub struct Quote {
     pub timestamp_ms: i64,
     /// Original phone number / account ID for wire protocol (not resolved to display name)
     pub author_id: String,
+    /// Text style ranges as byte offsets into `body`
+    pub style_ranges: Vec<(usize, usize, StyleType)>,
+}
+
+impl Quote {
+    /// The quoted text, with spoilers blacked out unless the quoted
+    /// message's spoilers were revealed.
+    pub fn shown_body(&self, revealed: bool) -> String {
+        if revealed {
+            self.body.clone()
+        } else {
+            mask_spoilers(&self.body, &self.style_ranges)
+        }
+    }
 }
 
 /// A single displayed message in a conversation
@@ -140,11 +157,17 @@ pub struct DisplayMessage {
     pub sender: String,
     pub timestamp: DateTime<Utc>,
     pub body: String,
+    /// Attachments, drawn as labels ahead of the body
+    pub attachments: Vec<MessageAttachment>,
     pub is_system: bool,
+    /// A system line warning about something, such as a changed safety
+    /// number, drawn to stand out
+    pub is_warning: bool,
     /// Pre-rendered halfblock image lines (for image attachments)
     pub image_lines: Option<Vec<Line<'static>>>,
-    /// Local filesystem path for native protocol rendering (Kitty/iTerm2)
-    pub image_path: Option<String>,
+    /// Where each image sits within `image_lines`, for native protocol
+    /// rendering (Kitty/iTerm2/Sixel). One tile per image in an album.
+    pub image_tiles: Vec<ImageTile>,
     /// Delivery/read status (Some for outgoing, None for incoming)
     pub status: Option<MessageStatus>,
     /// Millisecond epoch timestamp for receipt matching
@@ -186,6 +209,10 @@ pub struct DisplayMessage {
     pub preview_image_lines: Option<Vec<Line<'static>>>,
     /// Local filesystem path for native protocol link preview thumbnail
     pub preview_image_path: Option<String>,
+    /// View-once state (`None` for ordinary messages)
+    pub view_once: Option<ViewOnce>,
+    /// Contact cards shared in this message
+    pub shared_contacts: Vec<SharedContact>,
 }
 
 impl DisplayMessage {
@@ -203,6 +230,76 @@ impl DisplayMessage {
         self.status.is_some() || self.sender == OUTGOING_SENDER || self.sender_id == OUTGOING_SENDER
     }
 
+    /// Local paths of the image attachments that are on disk, in order, for
+    /// inline rendering.
+    pub fn image_attachment_paths(&self) -> Vec<&str> {
+        self.attachments
+            .iter()
+            .filter(|a| a.is_image())
+            .filter_map(MessageAttachment::downloaded_path)
+            .collect()
+    }
+
+    /// Draw the image attachments that are on disk into `image_lines` and
+    /// `image_tiles`: one full-size image, or a thumbnail grid for an album.
+    pub fn render_images(&mut self) {
+        let (lines, tiles) =
+            image_render::render_attachment_images(&self.image_attachment_paths()).unzip();
+        self.image_lines = lines;
+        self.image_tiles = tiles.unwrap_or_default();
+    }
+
+    /// Whether any part of the body is marked as a spoiler.
+    pub fn has_spoiler(&self) -> bool {
+        self.style_ranges
+            .iter()
+            .any(|&(_, _, style)| style == StyleType::Spoiler)
+    }
+
+    /// The body with each attachment's label in front, for plain-text uses
+    /// (copy, export, reply snippets) where the chat pane's links don't
+    /// carry over.
+    pub fn text_with_attachments(&self) -> String {
+        self.join_attachment_labels(self.body.clone())
+    }
+
+    /// Like [`Self::text_with_attachments`], with spoiler text blacked out
+    /// for places that show a message outside the chat pane.
+    pub fn masked_text_with_attachments(&self) -> String {
+        self.join_attachment_labels(mask_spoilers(&self.body, &self.style_ranges))
+    }
+
+    /// This message's style ranges moved onto `snippet`, a reply snippet
+    /// of [`Self::text_with_attachments`] that may have been cut short.
+    pub fn snippet_styles(&self, snippet: &str) -> Vec<(usize, usize, StyleType)> {
+        let text = self.text_with_attachments();
+        let offset = text.len() - self.body.len();
+        // Bytes the snippet shares with the full text (before any "…")
+        let kept = snippet
+            .char_indices()
+            .zip(text.chars())
+            .take_while(|((_, a), b)| a == b)
+            .last()
+            .map_or(0, |((i, c), _)| i + c.len_utf8());
+        self.style_ranges
+            .iter()
+            .filter(|&&(start, _, _)| start + offset < kept)
+            .map(|&(start, end, style)| (start + offset, (end + offset).min(kept), style))
+            .collect()
+    }
+
+    fn join_attachment_labels(&self, body: String) -> String {
+        let mut parts: Vec<String> = self
+            .attachments
+            .iter()
+            .map(MessageAttachment::label)
+            .collect();
+        if !body.is_empty() {
+            parts.push(body);
+        }
+        parts.join(" ")
+    }
+
     /// Wire identifier suitable for routing replies / reactions / pin queries
     /// back to the original author. Resolves to `my_account` for outgoing
     /// messages or legacy rows with no `sender_id`; otherwise returns the
@@ -269,6 +366,16 @@ impl Conversation {
             None
         }
     }
+
+    /// `ts`, or the first millisecond after it that no message here has,
+    /// for a local line that lookups by timestamp mustn't confuse with a
+    /// message.
+    pub fn free_timestamp(&self, mut ts: i64) -> i64 {
+        while self.find_msg_idx(ts).is_some() {
+            ts += 1;
+        }
+        ts
+    }
 }
 
 /// Owns all conversation data: conversations, ordering, contact names, groups, and read markers.
@@ -289,6 +396,10 @@ pub struct ConversationStore {
     pub groups: HashMap<String, Group>,
     /// Conversations that have more messages in the database to load.
     pub has_more_messages: HashSet<String>,
+    /// Username each 1:1 conversation was opened by (conversation id → username).
+    pub usernames: HashMap<String, String>,
+    /// Local nicknames and notes (phone number → details).
+    pub local_contacts: HashMap<String, LocalContact>,
 }
 
 impl ConversationStore {
@@ -302,9 +413,26 @@ impl ConversationStore {
             last_read_index: HashMap::new(),
             groups: HashMap::new(),
             has_more_messages: HashSet::new(),
+            usernames: HashMap::new(),
+            local_contacts: HashMap::new(),
         }
     }
 
+    /// Our local nickname for a contact, which wins over signal-cli's name.
+    pub fn nickname(&self, id: &str) -> Option<&str> {
+        self.local_contacts
+            .get(id)
+            .and_then(|c| c.nickname.as_deref())
+    }
+
+    /// The conversation previously opened by `username`, if any.
+    pub fn conversation_for_username(&self, username: &str) -> Option<&str> {
+        self.usernames
+            .iter()
+            .find(|(_, u)| u.as_str() == username)
+            .map(|(id, _)| id.as_str())
+    }
+
     /// Ensure a conversation exists; create it if not. Returns a mutable ref.
     pub fn get_or_create_conversation(
         &mut self,
@@ -357,6 +485,23 @@ impl ConversationStore {
         }
     }
 
+    /// The phone number behind `id` when it is an ACI we know the number
+    /// for, otherwise `id` as it is.
+    pub fn number_for<'a>(&'a self, id: &'a str) -> &'a str {
+        self.number_to_uuid
+            .iter()
+            .find(|(_, uuid)| *uuid == id)
+            .map_or(id, |(number, _)| number)
+    }
+
+    /// Whether two ids name the same person: signal-cli identifies a sender
+    /// by phone number when it knows one and by ACI otherwise.
+    pub fn is_same_person(&self, a: &str, b: &str) -> bool {
+        a == b
+            || self.number_to_uuid.get(a).is_some_and(|uuid| uuid == b)
+            || self.number_to_uuid.get(b).is_some_and(|uuid| uuid == a)
+    }
+
     /// Move a conversation to the top of the sidebar order.
     /// Returns `true` if the conversation was actually reordered.
     pub fn move_conversation_to_top(&mut self, id: &str) -> bool {
@@ -13,13 +13,91 @@ use std::path::Path;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use rusqlite::{Connection, params};
-
-use crate::app::{Conversation, DisplayMessage};
+use serde::{Deserialize, Serialize};
+
+use crate::app::{Conversation, DisplayMessage, SendRequest};
+use crate::domain::{
+    CallDirection, CallOutcome, CallRecord, DownloadState, LocalContact, MessageAttachment,
+    MessageRevision, OutboxEntry, ReactionDetail, RecipientReceipt, Story, ViewOnce,
+    parse_legacy_body,
+};
 use crate::mute::MuteState;
-use crate::signal::types::{LinkPreview, Mention, MessageStatus, PollData, PollVote, Reaction};
+use crate::signal::types::{
+    LinkPreview, Mention, MessageStatus, PollData, PollVote, Reaction, SharedContact, StyleType,
+    TextStyle,
+};
+
+/// (sender, body, timestamp_ms, conversation_id, conversation_name, style_ranges,
+/// from_revision). When `from_revision` is set, only an earlier version of the
+/// message matched and `body` / `style_ranges` are that version's.
+pub type SearchRow = (
+    String,
+    String,
+    i64,
+    String,
+    String,
+    Vec<(usize, usize, StyleType)>,
+    bool,
+);
+
+/// Columns and joins shared by the message searches. `?1` is the LIKE
+/// pattern and `?2` says whether earlier revisions may match; the newest
+/// matching revision is joined only when the current body doesn't match.
+/// A message with no text shows the attachment filename that matched.
+const SEARCH_SELECT: &str = "
+    SELECT m.sender,
+           CASE WHEN r.body IS NULL AND m.body = '' THEN COALESCE((
+               SELECT a.filename FROM attachments a
+               WHERE a.message_rowid = m.rowid
+                 AND a.filename LIKE ?1 ESCAPE '\\' COLLATE NOCASE
+               ORDER BY a.position LIMIT 1
+           ), '') ELSE COALESCE(r.body, m.body) END,
+           m.timestamp_ms, c.id, c.name,
+           CASE WHEN r.body IS NULL THEN m.style_ranges ELSE r.style_ranges END,
+           r.body IS NOT NULL
+    FROM messages m
+    JOIN conversations c ON c.id = m.conversation_id
+    LEFT JOIN message_revisions r ON r.rowid = (
+        SELECT r2.rowid FROM message_revisions r2
+        WHERE ?2 AND r2.message_rowid = m.rowid
+          AND m.body NOT LIKE ?1 ESCAPE '\\' COLLATE NOCASE
+          AND r2.body LIKE ?1 ESCAPE '\\' COLLATE NOCASE
+        ORDER BY r2.revision_ms DESC LIMIT 1
+    )";
+
+/// Search condition on top of [`SEARCH_SELECT`]: the body, an earlier
+/// revision (when asked for), or an attachment's filename matches.
+const SEARCH_MATCH: &str = "
+    (m.body LIKE ?1 ESCAPE '\\' COLLATE NOCASE
+     OR r.body IS NOT NULL
+     OR EXISTS (
+         SELECT 1 FROM attachments a
+         WHERE a.message_rowid = m.rowid
+           AND a.filename LIKE ?1 ESCAPE '\\' COLLATE NOCASE
+     ))";
+
+/// `%query%` with LIKE wildcards in `query` escaped.
+fn like_pattern(query: &str) -> String {
+    let escaped = query
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{escaped}%")
+}
 
-/// (sender, body, timestamp_ms, conversation_id, conversation_name)
-pub type SearchRow = (String, String, i64, String, String);
+fn search_row(row: &rusqlite::Row) -> rusqlite::Result<SearchRow> {
+    let body = row.get::<_, String>(1)?;
+    let styles = parse_style_ranges(row.get::<_, Option<String>>(5)?.as_deref(), &body);
+    Ok((
+        row.get(0)?,
+        body,
+        row.get(2)?,
+        row.get(3)?,
+        row.get(4)?,
+        styles,
+        row.get(6)?,
+    ))
+}
 
 /// A schema migration: the target version it brings the database up to, and
 /// the SQL batch that performs the change. Each batch is responsible for its
@@ -212,8 +290,345 @@ const MIGRATIONS: &[Migration] = &[
             COMMIT;
         ",
     },
+    Migration {
+        version: 15,
+        sql: "
+            BEGIN;
+            CREATE TABLE IF NOT EXISTS stories (
+                author          TEXT NOT NULL,
+                timestamp_ms    INTEGER NOT NULL,
+                group_id        TEXT,
+                body            TEXT,
+                content_type    TEXT,
+                attachment_path TEXT,
+                allows_replies  INTEGER NOT NULL DEFAULT 1,
+                viewed          INTEGER NOT NULL DEFAULT 0,
+                expires_at_ms   INTEGER NOT NULL,
+                UNIQUE(author, timestamp_ms)
+            );
+            CREATE INDEX IF NOT EXISTS idx_stories_expiry ON stories(expires_at_ms);
+            UPDATE schema_version SET version = 15;
+            COMMIT;
+        ",
+    },
+    Migration {
+        version: 16,
+        sql: "
+            BEGIN;
+            ALTER TABLE messages ADD COLUMN view_once TEXT;
+            UPDATE schema_version SET version = 16;
+            COMMIT;
+        ",
+    },
+    Migration {
+        version: 17,
+        sql: "
+            BEGIN;
+            CREATE TABLE IF NOT EXISTS calls (
+                peer        TEXT NOT NULL,
+                call_id     INTEGER NOT NULL,
+                direction   TEXT NOT NULL,
+                is_video    INTEGER NOT NULL DEFAULT 0,
+                started_ms  INTEGER NOT NULL,
+                ended_ms    INTEGER,
+                outcome     TEXT NOT NULL,
+                UNIQUE(peer, call_id)
+            );
+            CREATE INDEX IF NOT EXISTS idx_calls_started ON calls(started_ms);
+            UPDATE schema_version SET version = 17;
+            COMMIT;
+        ",
+    },
+    Migration {
+        version: 18,
+        sql: "
+            BEGIN;
+            ALTER TABLE conversations ADD COLUMN username TEXT;
+            CREATE TABLE IF NOT EXISTS account_settings (
+                key   TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );
+            UPDATE schema_version SET version = 18;
+            COMMIT;
+        ",
+    },
+    Migration {
+        version: 19,
+        sql: "
+            BEGIN;
+            CREATE TABLE IF NOT EXISTS contact_details (
+                number   TEXT PRIMARY KEY,
+                nickname TEXT,
+                note     TEXT
+            );
+            UPDATE schema_version SET version = 19;
+            COMMIT;
+        ",
+    },
+    Migration {
+        version: 20,
+        sql: "
+            BEGIN;
+            CREATE TABLE IF NOT EXISTS attachments (
+                message_rowid  INTEGER NOT NULL REFERENCES messages(rowid) ON DELETE CASCADE,
+                position       INTEGER NOT NULL DEFAULT 0,
+                content_type   TEXT NOT NULL,
+                filename       TEXT,
+                size           INTEGER,
+                local_path     TEXT,
+                width          INTEGER,
+                height         INTEGER,
+                download_state TEXT NOT NULL DEFAULT 'pending',
+                UNIQUE(message_rowid, position)
+            );
+            UPDATE schema_version SET version = 20;
+            COMMIT;
+        ",
+    },
+    Migration {
+        version: 21,
+        sql: "
+            BEGIN;
+            ALTER TABLE messages ADD COLUMN style_ranges TEXT;
+            UPDATE schema_version SET version = 21;
+            COMMIT;
+        ",
+    },
+    Migration {
+        version: 22,
+        sql: "
+            BEGIN;
+            CREATE TABLE IF NOT EXISTS message_revisions (
+                message_rowid INTEGER NOT NULL REFERENCES messages(rowid) ON DELETE CASCADE,
+                revision_ms   INTEGER NOT NULL,
+                body          TEXT NOT NULL,
+                style_ranges  TEXT,
+                is_outgoing   INTEGER NOT NULL DEFAULT 0,
+                UNIQUE(message_rowid, revision_ms)
+            );
+            UPDATE schema_version SET version = 22;
+            COMMIT;
+        ",
+    },
+    Migration {
+        version: 23,
+        sql: "
+            BEGIN;
+            CREATE TABLE IF NOT EXISTS receipts (
+                message_rowid INTEGER NOT NULL REFERENCES messages(rowid) ON DELETE CASCADE,
+                recipient     TEXT NOT NULL,
+                delivered_ms  INTEGER,
+                read_ms       INTEGER,
+                viewed_ms     INTEGER,
+                UNIQUE(message_rowid, recipient)
+            );
+            ALTER TABLE reactions ADD COLUMN reacted_at_ms INTEGER;
+            UPDATE schema_version SET version = 23;
+            COMMIT;
+        ",
+    },
+    Migration {
+        version: 24,
+        sql: "
+            BEGIN;
+            CREATE TABLE IF NOT EXISTS identity_keys (
+                number        TEXT PRIMARY KEY,
+                fingerprint   TEXT NOT NULL,
+                acknowledged  INTEGER NOT NULL DEFAULT 1
+            );
+            UPDATE schema_version SET version = 24;
+            COMMIT;
+        ",
+    },
+    Migration {
+        version: 25,
+        sql: "
+            BEGIN;
+            ALTER TABLE messages ADD COLUMN shared_contacts TEXT;
+            UPDATE schema_version SET version = 25;
+            COMMIT;
+        ",
+    },
+    Migration {
+        version: 26,
+        sql: "
+            BEGIN;
+            CREATE TABLE IF NOT EXISTS outbox (
+                conversation_id  TEXT NOT NULL,
+                local_ts_ms      INTEGER NOT NULL,
+                payload          TEXT NOT NULL,
+                attempts         INTEGER NOT NULL DEFAULT 0,
+                next_attempt_ms  INTEGER,
+                PRIMARY KEY (conversation_id, local_ts_ms)
+            );
+            UPDATE schema_version SET version = 26;
+            COMMIT;
+        ",
+    },
+    Migration {
+        version: 27,
+        sql: "
+            BEGIN;
+            ALTER TABLE calls ADD COLUMN video_known INTEGER NOT NULL DEFAULT 1;
+            UPDATE calls SET video_known = 0 WHERE direction = 'outgoing';
+            UPDATE schema_version SET version = 27;
+            COMMIT;
+        ",
+    },
+    Migration {
+        version: 28,
+        sql: "
+            BEGIN;
+            ALTER TABLE messages ADD COLUMN quote_style_ranges TEXT;
+            UPDATE schema_version SET version = 28;
+            COMMIT;
+        ",
+    },
+    Migration {
+        version: 29,
+        sql: "
+            BEGIN;
+            ALTER TABLE messages ADD COLUMN is_warning INTEGER NOT NULL DEFAULT 0;
+            UPDATE messages SET is_warning = 1
+             WHERE is_system = 1 AND body LIKE '\u{26A0}%';
+            UPDATE schema_version SET version = 29;
+            COMMIT;
+        ",
+    },
 ];
 
+/// The `outbox.payload` column: a `SendRequest::Message` as JSON.
+#[derive(Serialize, Deserialize)]
+struct OutboxPayload {
+    recipient: String,
+    body: String,
+    is_group: bool,
+    mentions: Vec<(usize, String)>,
+    /// `(start, length, style)` in UTF-16 units, as sent
+    text_styles: Vec<(usize, usize, StyleType)>,
+    attachments: Vec<std::path::PathBuf>,
+    quote_timestamp: Option<i64>,
+    quote_author: Option<String>,
+    quote_body: Option<String>,
+}
+
+fn outbox_payload_json(req: &SendRequest) -> Result<String> {
+    let SendRequest::Message {
+        recipient,
+        body,
+        is_group,
+        mentions,
+        text_styles,
+        attachments,
+        quote_timestamp,
+        quote_author,
+        quote_body,
+        ..
+    } = req
+    else {
+        anyhow::bail!("only messages go in the outbox");
+    };
+    Ok(serde_json::to_string(&OutboxPayload {
+        recipient: recipient.clone(),
+        body: body.clone(),
+        is_group: *is_group,
+        mentions: mentions.clone(),
+        text_styles: text_styles
+            .iter()
+            .map(|s| (s.start, s.length, s.style))
+            .collect(),
+        attachments: attachments.clone(),
+        quote_timestamp: *quote_timestamp,
+        quote_author: quote_author.clone(),
+        quote_body: quote_body.clone(),
+    })?)
+}
+
+fn outbox_request(json: &str, local_ts_ms: i64) -> Option<SendRequest> {
+    let p: OutboxPayload = serde_json::from_str(json).ok()?;
+    Some(SendRequest::Message {
+        recipient: p.recipient,
+        body: p.body,
+        is_group: p.is_group,
+        local_ts_ms,
+        mentions: p.mentions,
+        text_styles: p
+            .text_styles
+            .into_iter()
+            .map(|(start, length, style)| TextStyle {
+                start,
+                length,
+                style,
+            })
+            .collect(),
+        attachments: p.attachments,
+        quote_timestamp: p.quote_timestamp,
+        quote_author: p.quote_author,
+        quote_body: p.quote_body,
+    })
+}
+
+/// Encode style ranges for a `style_ranges` column; an empty list is NULL.
+fn style_ranges_json(styles: &[(usize, usize, StyleType)]) -> Result<Option<String>> {
+    if styles.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(serde_json::to_string(styles)?))
+    }
+}
+
+/// Decode a stored `style_ranges` column, dropping any range that no longer
+/// lands on character boundaries of `body` (e.g. after a later body rewrite).
+fn parse_style_ranges(json: Option<&str>, body: &str) -> Vec<(usize, usize, StyleType)> {
+    json.and_then(|j| serde_json::from_str::<Vec<(usize, usize, StyleType)>>(j).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|&(start, end, _)| {
+            start < end && body.is_char_boundary(start) && body.is_char_boundary(end)
+        })
+        .collect()
+}
+
+/// Media path from a stored `view_once` column, while it is still unopened.
+fn unopened_media(json: &str) -> Option<String> {
+    serde_json::from_str::<ViewOnce>(json)
+        .ok()?
+        .unopened_path()
+        .map(str::to_string)
+}
+
+/// Schema version that moved attachments out of message bodies. Upgrading
+/// past it runs [`Database::backfill_attachments`].
+const ATTACHMENTS_VERSION: i32 = 20;
+
+/// Insert `attachments` for the message at `message_rowid`, numbering them
+/// from zero. Shared by the live insert path and the v20 backfill, which
+/// runs inside a transaction.
+fn insert_attachment_rows(
+    conn: &Connection,
+    message_rowid: i64,
+    attachments: &[MessageAttachment],
+) -> Result<()> {
+    for (position, att) in attachments.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO attachments (message_rowid, position, content_type, filename, size, local_path, width, height, download_state)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                message_rowid,
+                position as i64,
+                att.content_type,
+                att.filename,
+                att.size.map(|s| s as i64),
+                att.local_path,
+                att.width,
+                att.height,
+                att.state.as_str(),
+            ],
+        )?;
+    }
+    Ok(())
+}
+
 pub struct Database {
     conn: Connection,
 }
@@ -253,6 +668,37 @@ impl Database {
                 self.conn.execute_batch(migration.sql)?;
             }
         }
+        if current < ATTACHMENTS_VERSION {
+            self.backfill_attachments()?;
+        }
+        Ok(())
+    }
+
+    /// Move attachments encoded in pre-v20 bodies (`[image: label](file:///...)`
+    /// and friends) into the `attachments` table, leaving only the typed text
+    /// in `body`. Parsing needs Rust, so this runs after the v20 batch rather
+    /// than inside it.
+    fn backfill_attachments(&self) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        let legacy: Vec<(i64, String)> = tx
+            .prepare(
+                "SELECT rowid, body FROM messages
+                 WHERE is_system = 0
+                   AND (body LIKE '[image: %' OR body LIKE '[attachment: %')",
+            )?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<std::result::Result<_, _>>()?;
+        for (rowid, body) in legacy {
+            let Some((attachment, text)) = parse_legacy_body(&body) else {
+                continue;
+            };
+            insert_attachment_rows(&tx, rowid, std::slice::from_ref(&attachment))?;
+            tx.execute(
+                "UPDATE messages SET body = ?2 WHERE rowid = ?1",
+                params![rowid, text],
+            )?;
+        }
+        tx.commit()?;
         Ok(())
     }
 
@@ -289,6 +735,8 @@ impl Database {
             "DELETE FROM read_markers WHERE conversation_id = ?1",
             params![id],
         )?;
+        self.conn
+            .execute("DELETE FROM outbox WHERE conversation_id = ?1", params![id])?;
         self.conn
             .execute("DELETE FROM conversations WHERE id = ?1", params![id])?;
         Ok(())
@@ -303,12 +751,12 @@ impl Database {
         offset: usize,
     ) -> Result<Vec<DisplayMessage>> {
         let mut msg_stmt = self.conn.prepare(
-            "SELECT sender, timestamp, body, is_system, status, timestamp_ms, is_edited, is_deleted, quote_author, quote_body, quote_ts_ms, sender_id, expires_in_seconds, expiration_start_ms, pinned, poll_data, link_preview, body_raw, mentions_json FROM messages
+            "SELECT sender, timestamp, body, is_system, status, timestamp_ms, is_edited, is_deleted, quote_author, quote_body, quote_ts_ms, sender_id, expires_in_seconds, expiration_start_ms, pinned, poll_data, link_preview, body_raw, mentions_json, view_once, rowid, style_ranges, shared_contacts, quote_style_ranges, is_warning FROM messages
              WHERE conversation_id = ?1
              ORDER BY timestamp_ms DESC, rowid DESC LIMIT ?2 OFFSET ?3",
         )?;
 
-        let mut messages: Vec<DisplayMessage> = msg_stmt
+        let mut rows: Vec<(i64, DisplayMessage)> = msg_stmt
             .query_map(params![conv_id, limit as i64, offset as i64], |row| {
                 let sender: String = row.get(0)?;
                 let ts_str: String = row.get(1)?;
@@ -329,6 +777,12 @@ impl Database {
                 let link_preview_json: Option<String> = row.get(16)?;
                 let body_raw: Option<String> = row.get(17)?;
                 let mentions_json: Option<String> = row.get(18)?;
+                let view_once_json: Option<String> = row.get(19)?;
+                let rowid: i64 = row.get(20)?;
+                let style_ranges_json: Option<String> = row.get(21)?;
+                let shared_contacts_json: Option<String> = row.get(22)?;
+                let quote_styles_json: Option<String> = row.get(23)?;
+                let is_warning: bool = row.get::<_, i32>(24)? != 0;
                 Ok((
                     sender,
                     ts_str,
@@ -349,6 +803,12 @@ impl Database {
                     link_preview_json,
                     body_raw,
                     mentions_json,
+                    view_once_json,
+                    rowid,
+                    style_ranges_json,
+                    shared_contacts_json,
+                    quote_styles_json,
+                    is_warning,
                 ))
             })?
             .filter_map(|r| r.ok())
@@ -373,17 +833,29 @@ impl Database {
                     link_preview_json,
                     body_raw,
                     mentions_json,
+                    view_once_json,
+                    rowid,
+                    style_ranges_json,
+                    shared_contacts_json,
+                    quote_styles_json,
+                    is_warning,
                 )| {
                     let timestamp = chrono::DateTime::parse_from_rfc3339(&ts_str)
                         .ok()?
                         .with_timezone(&chrono::Utc);
                     let quote = match (quote_author, quote_body, quote_ts_ms) {
-                        (Some(author), Some(body), Some(ts)) => Some(crate::app::Quote {
-                            author_id: author.clone(),
-                            author,
-                            body: body.replace('\u{FFFC}', ""),
-                            timestamp_ms: ts,
-                        }),
+                        (Some(author), Some(body), Some(ts)) => {
+                            let body = body.replace('\u{FFFC}', "");
+                            let style_ranges =
+                                parse_style_ranges(quote_styles_json.as_deref(), &body);
+                            Some(crate::app::Quote {
+                                author_id: author.clone(),
+                                author,
+                                body,
+                                timestamp_ms: ts,
+                                style_ranges,
+                            })
+                        }
                         _ => None,
                     };
                     let poll_data =
@@ -394,39 +866,65 @@ impl Database {
                         .as_deref()
                         .and_then(|j| serde_json::from_str(j).ok())
                         .unwrap_or_default();
-                    Some(DisplayMessage {
-                        sender,
-                        timestamp,
-                        body,
-                        is_system,
-                        image_lines: None,
-                        image_path: None,
-                        status: MessageStatus::from_i32(status_i32),
-                        timestamp_ms,
-                        reactions: Vec::new(),
-                        mention_ranges: Vec::new(),
-                        style_ranges: Vec::new(),
-                        body_raw,
-                        mentions,
-                        quote,
-                        is_edited,
-                        is_deleted,
-                        is_pinned,
-                        sender_id,
-                        expires_in_seconds,
-                        expiration_start_ms,
-                        poll_data,
-                        poll_votes: Vec::new(),
-                        preview,
-                        preview_image_lines: None,
-                        preview_image_path: None,
-                    })
+                    let view_once =
+                        view_once_json.and_then(|j| serde_json::from_str::<ViewOnce>(&j).ok());
+                    let style_ranges = parse_style_ranges(style_ranges_json.as_deref(), &body);
+                    let shared_contacts: Vec<SharedContact> = shared_contacts_json
+                        .as_deref()
+                        .and_then(|j| serde_json::from_str(j).ok())
+                        .unwrap_or_default();
+                    Some((
+                        rowid,
+                        DisplayMessage {
+                            sender,
+                            timestamp,
+                            body,
+                            is_system,
+                            is_warning,
+                            attachments: Vec::new(),
+                            image_lines: None,
+                            image_tiles: Vec::new(),
+                            status: MessageStatus::from_i32(status_i32),
+                            timestamp_ms,
+                            reactions: Vec::new(),
+                            mention_ranges: Vec::new(),
+                            style_ranges,
+                            body_raw,
+                            mentions,
+                            quote,
+                            is_edited,
+                            is_deleted,
+                            is_pinned,
+                            sender_id,
+                            expires_in_seconds,
+                            expiration_start_ms,
+                            poll_data,
+                            poll_votes: Vec::new(),
+                            preview,
+                            preview_image_lines: None,
+                            preview_image_path: None,
+                            view_once,
+                            shared_contacts,
+                        },
+                    ))
                 },
             )
             .collect();
 
         // Reverse so oldest first
-        messages.reverse();
+        rows.reverse();
+
+        // Attach attachments
+        let rowids = rows.iter().map(|(rowid, _)| *rowid);
+        if let (Some(first), Some(last)) = (rowids.clone().min(), rowids.max()) {
+            let mut by_rowid = self.load_attachments(conv_id, first, last)?;
+            for (rowid, msg) in &mut rows {
+                if let Some(attachments) = by_rowid.remove(rowid) {
+                    msg.attachments = attachments;
+                }
+            }
+        }
+        let mut messages: Vec<DisplayMessage> = rows.into_iter().map(|(_, msg)| msg).collect();
 
         // Attach reactions
         let mut ts_to_idx: HashMap<i64, Vec<usize>> = HashMap::new();
@@ -648,6 +1146,78 @@ impl Database {
         Ok(count as usize)
     }
 
+    // --- Attachments ---
+
+    /// Store the attachments of a freshly inserted message, in order.
+    pub fn insert_attachments(
+        &self,
+        message_rowid: i64,
+        attachments: &[MessageAttachment],
+    ) -> Result<()> {
+        insert_attachment_rows(&self.conn, message_rowid, attachments)
+    }
+
+    /// Replace the attachments of the message at `timestamp_ms` (for
+    /// placeholders filled in later, like a fetched sticker image).
+    pub fn set_message_attachments(
+        &self,
+        conv_id: &str,
+        timestamp_ms: i64,
+        attachments: &[MessageAttachment],
+    ) -> Result<()> {
+        let rowid: Option<i64> = self.conn.query_row(
+            "SELECT MIN(rowid) FROM messages WHERE conversation_id = ?1 AND timestamp_ms = ?2",
+            params![conv_id, timestamp_ms],
+            |row| row.get(0),
+        )?;
+        let Some(rowid) = rowid else {
+            return Ok(());
+        };
+        self.conn.execute(
+            "DELETE FROM attachments WHERE message_rowid = ?1",
+            params![rowid],
+        )?;
+        insert_attachment_rows(&self.conn, rowid, attachments)
+    }
+
+    /// Attachments of the messages in `conv_id` with rowids in
+    /// `first..=last`, grouped by message rowid.
+    fn load_attachments(
+        &self,
+        conv_id: &str,
+        first: i64,
+        last: i64,
+    ) -> Result<HashMap<i64, Vec<MessageAttachment>>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT a.message_rowid, a.content_type, a.filename, a.size, a.local_path,
+                    a.width, a.height, a.download_state
+             FROM attachments a JOIN messages m ON m.rowid = a.message_rowid
+             WHERE m.conversation_id = ?1 AND a.message_rowid BETWEEN ?2 AND ?3
+             ORDER BY a.message_rowid, a.position",
+        )?;
+        let mut by_rowid: HashMap<i64, Vec<MessageAttachment>> = HashMap::new();
+        let rows = stmt.query_map(params![conv_id, first, last], |row| {
+            let state: String = row.get(7)?;
+            Ok((
+                row.get::<_, i64>(0)?,
+                MessageAttachment {
+                    content_type: row.get(1)?,
+                    filename: row.get(2)?,
+                    size: row.get::<_, Option<i64>>(3)?.map(|s| s as u64),
+                    local_path: row.get(4)?,
+                    width: row.get(5)?,
+                    height: row.get(6)?,
+                    state: DownloadState::parse(&state),
+                },
+            ))
+        })?;
+        for row in rows {
+            let (rowid, attachment) = row?;
+            by_rowid.entry(rowid).or_default().push(attachment);
+        }
+        Ok(by_rowid)
+    }
+
     // --- Reactions ---
 
     pub fn upsert_reaction(
@@ -657,13 +1227,22 @@ impl Database {
         target_author: &str,
         sender: &str,
         emoji: &str,
+        reacted_at_ms: i64,
     ) -> Result<()> {
         self.conn.execute(
-            "INSERT INTO reactions (conversation_id, target_ts_ms, target_author, sender, emoji)
-             VALUES (?1, ?2, ?3, ?4, ?5)
+            "INSERT INTO reactions
+                 (conversation_id, target_ts_ms, target_author, sender, emoji, reacted_at_ms)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
              ON CONFLICT(conversation_id, target_ts_ms, target_author, sender)
-             DO UPDATE SET emoji = excluded.emoji",
-            params![conv_id, target_ts_ms, target_author, sender, emoji],
+             DO UPDATE SET emoji = excluded.emoji, reacted_at_ms = excluded.reacted_at_ms",
+            params![
+                conv_id,
+                target_ts_ms,
+                target_author,
+                sender,
+                emoji,
+                reacted_at_ms
+            ],
         )?;
         Ok(())
     }
@@ -699,6 +1278,91 @@ impl Database {
         Ok(rows)
     }
 
+    /// Reactions on one message with their senders and times, oldest first.
+    pub fn load_message_reactions(
+        &self,
+        conv_id: &str,
+        target_ts_ms: i64,
+    ) -> Result<Vec<ReactionDetail>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT sender, emoji, reacted_at_ms FROM reactions
+             WHERE conversation_id = ?1 AND target_ts_ms = ?2
+             ORDER BY reacted_at_ms IS NULL, reacted_at_ms, rowid",
+        )?;
+        let rows = stmt
+            .query_map(params![conv_id, target_ts_ms], |row| {
+                Ok(ReactionDetail {
+                    sender: row.get(0)?,
+                    emoji: row.get(1)?,
+                    reacted_at_ms: row.get(2)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    // --- Receipts ---
+
+    /// Record a delivery, read or viewed receipt from one recipient of a
+    /// message we sent. Each stage keeps the first time it was reported, and
+    /// a later stage fills in the earlier ones it implies (a read receipt
+    /// means the message was delivered, even if that receipt never came).
+    pub fn record_receipt(
+        &self,
+        conv_id: &str,
+        timestamp_ms: i64,
+        recipient: &str,
+        status: MessageStatus,
+        when_ms: i64,
+    ) -> Result<()> {
+        let at = |stage: MessageStatus| (status >= stage).then_some(when_ms);
+        self.conn.execute(
+            "INSERT INTO receipts (message_rowid, recipient, delivered_ms, read_ms, viewed_ms)
+             SELECT rowid, ?3, ?4, ?5, ?6 FROM messages
+             WHERE conversation_id = ?1 AND timestamp_ms = ?2
+             ORDER BY rowid LIMIT 1
+             ON CONFLICT(message_rowid, recipient) DO UPDATE SET
+                 delivered_ms = COALESCE(delivered_ms, excluded.delivered_ms),
+                 read_ms = COALESCE(read_ms, excluded.read_ms),
+                 viewed_ms = COALESCE(viewed_ms, excluded.viewed_ms)",
+            params![
+                conv_id,
+                timestamp_ms,
+                recipient,
+                at(MessageStatus::Delivered),
+                at(MessageStatus::Read),
+                at(MessageStatus::Viewed)
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Receipts for one message, one entry per recipient that sent any.
+    pub fn load_message_receipts(
+        &self,
+        conv_id: &str,
+        timestamp_ms: i64,
+    ) -> Result<Vec<RecipientReceipt>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT recipient, delivered_ms, read_ms, viewed_ms FROM receipts
+             WHERE message_rowid = (
+                 SELECT MIN(rowid) FROM messages WHERE conversation_id = ?1 AND timestamp_ms = ?2
+             )
+             ORDER BY rowid",
+        )?;
+        let rows = stmt
+            .query_map(params![conv_id, timestamp_ms], |row| {
+                Ok(RecipientReceipt {
+                    recipient: row.get(0)?,
+                    delivered_ms: row.get(1)?,
+                    read_ms: row.get(2)?,
+                    viewed_ms: row.get(3)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
     /// Update the body and mark a message as edited.
     pub fn update_message_body(&self, conv_id: &str, timestamp_ms: i64, body: &str) -> Result<()> {
         self.conn.execute(
@@ -709,101 +1373,262 @@ impl Database {
         Ok(())
     }
 
-    /// Mark a message as locally deleted.
-    pub fn mark_message_deleted(&self, conv_id: &str, timestamp_ms: i64) -> Result<()> {
-        self.conn.execute(
-            "UPDATE messages SET is_deleted = 1, body = '[deleted]'
-             WHERE conversation_id = ?1 AND timestamp_ms = ?2",
+    /// Apply an edit: replace the body and style ranges, mark the message
+    /// edited and add the new text to `message_revisions`. The first edit
+    /// also stores the text it replaces, so the history starts with the
+    /// original. An edit that changes neither text nor styles is ignored.
+    #[allow(clippy::too_many_arguments)]
+    pub fn record_edit(
+        &self,
+        conv_id: &str,
+        timestamp_ms: i64,
+        revision_ms: i64,
+        body: &str,
+        styles: &[(usize, usize, StyleType)],
+        is_outgoing: bool,
+    ) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        let rowid: Option<i64> = tx.query_row(
+            "SELECT MIN(rowid) FROM messages WHERE conversation_id = ?1 AND timestamp_ms = ?2",
             params![conv_id, timestamp_ms],
+            |row| row.get(0),
         )?;
-        Ok(())
-    }
-
-    /// Set the pinned state of a message.
-    pub fn set_message_pinned(&self, conv_id: &str, timestamp_ms: i64, pinned: bool) -> Result<()> {
-        self.conn.execute(
-            "UPDATE messages SET pinned = ?3
+        let Some(rowid) = rowid else {
+            return Ok(());
+        };
+        let json = style_ranges_json(styles)?;
+        let current: (String, Option<String>) = tx.query_row(
+            "SELECT body, style_ranges FROM messages WHERE rowid = ?1",
+            params![rowid],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        if current.0 == body && current.1 == json {
+            return Ok(());
+        }
+        tx.execute(
+            "INSERT OR IGNORE INTO message_revisions
+                 (message_rowid, revision_ms, body, style_ranges, is_outgoing)
+             SELECT rowid, timestamp_ms, body, style_ranges, ?2 FROM messages
+             WHERE rowid = ?1
+               AND NOT EXISTS (SELECT 1 FROM message_revisions WHERE message_rowid = ?1)",
+            params![rowid, is_outgoing as i32],
+        )?;
+        // Keep revisions in order even if the clocks disagree (or the edit
+        // lands in the same millisecond as the message)
+        let latest: i64 = tx.query_row(
+            "SELECT MAX(revision_ms) FROM message_revisions WHERE message_rowid = ?1",
+            params![rowid],
+            |row| row.get(0),
+        )?;
+        let revision_ms = revision_ms.max(latest + 1);
+        tx.execute(
+            "INSERT INTO message_revisions
+                 (message_rowid, revision_ms, body, style_ranges, is_outgoing)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![rowid, revision_ms, body, json, is_outgoing as i32],
+        )?;
+        tx.execute(
+            "UPDATE messages SET body = ?3, style_ranges = ?4, is_edited = 1
              WHERE conversation_id = ?1 AND timestamp_ms = ?2",
-            params![conv_id, timestamp_ms, pinned as i32],
+            params![conv_id, timestamp_ms, body, json],
         )?;
+        tx.commit()?;
         Ok(())
     }
 
-    // --- Search ---
-
-    /// Search messages in a specific conversation using case-insensitive LIKE.
-    /// Returns (sender, body, timestamp_ms, conversation_id, conversation_name) tuples,
-    /// most recent first, limited to `limit` results.
-    pub fn search_messages(
+    /// Stored revisions of a message, oldest first. Empty for messages
+    /// never edited, or edited before revisions were kept.
+    pub fn load_message_revisions(
         &self,
         conv_id: &str,
-        query: &str,
-        limit: usize,
-    ) -> Result<Vec<SearchRow>> {
-        let escaped = query
-            .replace('\\', "\\\\")
-            .replace('%', "\\%")
-            .replace('_', "\\_");
-        let pattern = format!("%{escaped}%");
+        timestamp_ms: i64,
+    ) -> Result<Vec<MessageRevision>> {
         let mut stmt = self.conn.prepare(
-            "SELECT m.sender, m.body, m.timestamp_ms, c.id, c.name
-             FROM messages m
-             JOIN conversations c ON c.id = m.conversation_id
-             WHERE m.conversation_id = ?1
-               AND m.body LIKE ?2 ESCAPE '\\' COLLATE NOCASE
-               AND m.is_system = 0
-               AND m.is_deleted = 0
-             ORDER BY m.timestamp_ms DESC
-             LIMIT ?3",
+            "SELECT r.revision_ms, r.body, r.style_ranges, r.is_outgoing
+             FROM message_revisions r
+             WHERE r.message_rowid = (
+                 SELECT MIN(rowid) FROM messages WHERE conversation_id = ?1 AND timestamp_ms = ?2
+             )
+             ORDER BY r.revision_ms",
         )?;
         let rows = stmt
-            .query_map(params![conv_id, pattern, limit as i64], |row| {
-                Ok((
-                    row.get::<_, String>(0)?,
-                    row.get::<_, String>(1)?,
-                    row.get::<_, i64>(2)?,
-                    row.get::<_, String>(3)?,
-                    row.get::<_, String>(4)?,
-                ))
+            .query_map(params![conv_id, timestamp_ms], |row| {
+                let body: String = row.get(1)?;
+                let style_ranges =
+                    parse_style_ranges(row.get::<_, Option<String>>(2)?.as_deref(), &body);
+                Ok(MessageRevision {
+                    revision_ms: row.get(0)?,
+                    body,
+                    style_ranges,
+                    is_outgoing: row.get::<_, i32>(3)? != 0,
+                })
             })?
             .collect::<std::result::Result<Vec<_>, _>>()?;
         Ok(rows)
     }
 
-    /// Search messages across all conversations using case-insensitive LIKE.
-    /// Returns (sender, body, timestamp_ms, conversation_id, conversation_name) tuples,
-    /// most recent first, limited to `limit` results.
-    pub fn search_all_messages(&self, query: &str, limit: usize) -> Result<Vec<SearchRow>> {
-        let escaped = query
-            .replace('\\', "\\\\")
-            .replace('%', "\\%")
-            .replace('_', "\\_");
-        let pattern = format!("%{escaped}%");
-        let mut stmt = self.conn.prepare(
-            "SELECT m.sender, m.body, m.timestamp_ms, c.id, c.name
-             FROM messages m
-             JOIN conversations c ON c.id = m.conversation_id
-             WHERE m.body LIKE ?1 ESCAPE '\\' COLLATE NOCASE
-               AND m.is_system = 0
-               AND m.is_deleted = 0
-             ORDER BY m.timestamp_ms DESC
-             LIMIT ?2",
+    /// Replace the stored body without marking the message as edited (for
+    /// placeholders that are filled in later, like a fetched sticker image).
+    pub fn replace_message_body(&self, conv_id: &str, timestamp_ms: i64, body: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE messages SET body = ?3
+             WHERE conversation_id = ?1 AND timestamp_ms = ?2",
+            params![conv_id, timestamp_ms, body],
         )?;
-        let rows = stmt
-            .query_map(params![pattern, limit as i64], |row| {
-                Ok((
-                    row.get::<_, String>(0)?,
-                    row.get::<_, String>(1)?,
-                    row.get::<_, i64>(2)?,
-                    row.get::<_, String>(3)?,
-                    row.get::<_, String>(4)?,
-                ))
-            })?
-            .collect::<std::result::Result<Vec<_>, _>>()?;
-        Ok(rows)
+        Ok(())
     }
 
-    /// Find the max rowid for messages up to (and including) a given timestamp.
+    /// Store the view-once state of a message.
+    pub fn set_view_once(&self, conv_id: &str, timestamp_ms: i64, state: &ViewOnce) -> Result<()> {
+        let json = serde_json::to_string(state)?;
+        self.conn.execute(
+            "UPDATE messages SET view_once = ?3
+             WHERE conversation_id = ?1 AND timestamp_ms = ?2",
+            params![conv_id, timestamp_ms, json],
+        )?;
+        Ok(())
+    }
+
+    /// Media path of a view-once message that is still unopened.
+    pub fn unopened_view_once_media(
+        &self,
+        conv_id: &str,
+        timestamp_ms: i64,
+    ) -> Result<Option<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT view_once FROM messages
+             WHERE conversation_id = ?1 AND timestamp_ms = ?2 AND view_once IS NOT NULL",
+        )?;
+        let mut rows = stmt.query_map(params![conv_id, timestamp_ms], |row| {
+            row.get::<_, String>(0)
+        })?;
+        let json = rows.next().transpose()?;
+        Ok(json.as_deref().and_then(unopened_media))
+    }
+
+    /// Store the contact cards shared in a message.
+    pub fn set_shared_contacts(
+        &self,
+        conv_id: &str,
+        timestamp_ms: i64,
+        contacts: &[SharedContact],
+    ) -> Result<()> {
+        let json = serde_json::to_string(contacts)?;
+        self.conn.execute(
+            "UPDATE messages SET shared_contacts = ?3
+             WHERE conversation_id = ?1 AND timestamp_ms = ?2",
+            params![conv_id, timestamp_ms, json],
+        )?;
+        Ok(())
+    }
+
+    /// Flag the system line stored at `rowid` as a warning.
+    pub fn mark_warning(&self, rowid: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE messages SET is_warning = 1 WHERE rowid = ?1",
+            params![rowid],
+        )?;
+        Ok(())
+    }
+
+    /// Remove a message outright, as when an unsent one is discarded.
+    pub fn delete_message(&self, conv_id: &str, timestamp_ms: i64) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM messages WHERE conversation_id = ?1 AND timestamp_ms = ?2",
+            params![conv_id, timestamp_ms],
+        )?;
+        Ok(())
+    }
+
+    /// Mark a message as locally deleted. Its edit history goes with it.
+    pub fn mark_message_deleted(&self, conv_id: &str, timestamp_ms: i64) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM message_revisions WHERE message_rowid IN (
+                 SELECT rowid FROM messages WHERE conversation_id = ?1 AND timestamp_ms = ?2
+             )",
+            params![conv_id, timestamp_ms],
+        )?;
+        self.conn.execute(
+            "UPDATE messages SET is_deleted = 1, body = '[deleted]'
+             WHERE conversation_id = ?1 AND timestamp_ms = ?2",
+            params![conv_id, timestamp_ms],
+        )?;
+        Ok(())
+    }
+
+    /// Set the pinned state of a message.
+    pub fn set_message_pinned(&self, conv_id: &str, timestamp_ms: i64, pinned: bool) -> Result<()> {
+        self.conn.execute(
+            "UPDATE messages SET pinned = ?3
+             WHERE conversation_id = ?1 AND timestamp_ms = ?2",
+            params![conv_id, timestamp_ms, pinned as i32],
+        )?;
+        Ok(())
+    }
+
+    // --- Search ---
+
+    /// Search messages in a specific conversation using case-insensitive LIKE,
+    /// most recent first, limited to `limit` results. A message matches on its
+    /// body or an attachment's filename; with `include_revisions`, also when
+    /// only an earlier version of it does.
+    pub fn search_messages(
+        &self,
+        conv_id: &str,
+        query: &str,
+        limit: usize,
+        include_revisions: bool,
+    ) -> Result<Vec<SearchRow>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "{SEARCH_SELECT}
+             WHERE m.conversation_id = ?3
+               AND {SEARCH_MATCH}
+               AND m.is_system = 0
+               AND m.is_deleted = 0
+             ORDER BY m.timestamp_ms DESC
+             LIMIT ?4"
+        ))?;
+        let rows = stmt
+            .query_map(
+                params![
+                    like_pattern(query),
+                    include_revisions,
+                    conv_id,
+                    limit as i64
+                ],
+                search_row,
+            )?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Search messages across all conversations using case-insensitive LIKE,
+    /// most recent first, limited to `limit` results. Matches the same way as
+    /// [`Database::search_messages`].
+    pub fn search_all_messages(
+        &self,
+        query: &str,
+        limit: usize,
+        include_revisions: bool,
+    ) -> Result<Vec<SearchRow>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "{SEARCH_SELECT}
+             WHERE {SEARCH_MATCH}
+               AND m.is_system = 0
+               AND m.is_deleted = 0
+             ORDER BY m.timestamp_ms DESC
+             LIMIT ?3"
+        ))?;
+        let rows = stmt
+            .query_map(
+                params![like_pattern(query), include_revisions, limit as i64],
+                search_row,
+            )?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Find the max rowid for messages up to (and including) a given timestamp.
     /// Uses the idx_messages_conv_ts_ms index for efficient lookup.
     pub fn max_rowid_up_to_timestamp(
         &self,
@@ -890,6 +1715,66 @@ impl Database {
         Ok(ids.into_iter().collect())
     }
 
+    // --- Identity keys ---
+
+    /// Remember `number`'s identity key fingerprint. Returns true when a
+    /// different fingerprint was stored before, i.e. their safety number
+    /// changed; the change stays unacknowledged until
+    /// [`Database::acknowledge_identity`]. A key seen for the first time is
+    /// taken as is.
+    pub fn record_identity_key(&self, number: &str, fingerprint: &str) -> Result<bool> {
+        let previous: Option<String> = {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT fingerprint FROM identity_keys WHERE number = ?1")?;
+            let mut rows = stmt.query_map(params![number], |row| row.get(0))?;
+            rows.next().transpose()?
+        };
+        // An empty fingerprint was stored by mark_identity_changed before
+        // the new key was known
+        let changed = previous
+            .as_deref()
+            .is_some_and(|p| !p.is_empty() && p != fingerprint);
+        self.conn.execute(
+            "INSERT INTO identity_keys (number, fingerprint) VALUES (?1, ?2)
+             ON CONFLICT(number) DO UPDATE SET
+                 fingerprint = excluded.fingerprint,
+                 acknowledged = CASE WHEN ?3 THEN 0 ELSE acknowledged END",
+            params![number, fingerprint, changed],
+        )?;
+        Ok(changed)
+    }
+
+    /// Flag `number`'s safety number as changed without knowing the new key,
+    /// as when a send to them is refused.
+    pub fn mark_identity_changed(&self, number: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO identity_keys (number, fingerprint, acknowledged) VALUES (?1, '', 0)
+             ON CONFLICT(number) DO UPDATE SET acknowledged = 0",
+            params![number],
+        )?;
+        Ok(())
+    }
+
+    pub fn acknowledge_identity(&self, number: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE identity_keys SET acknowledged = 1 WHERE number = ?1",
+            params![number],
+        )?;
+        Ok(())
+    }
+
+    /// Numbers whose safety number changed and hasn't been acknowledged.
+    pub fn load_unacknowledged_identities(&self) -> Result<std::collections::HashSet<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT number FROM identity_keys WHERE acknowledged = 0")?;
+        let numbers: Vec<String> = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(numbers.into_iter().collect())
+    }
+
     // --- Disappearing messages ---
 
     pub fn update_expiration_timer(&self, conv_id: &str, seconds: i64) -> Result<()> {
@@ -900,6 +1785,21 @@ impl Database {
         Ok(())
     }
 
+    /// Media paths of unopened view-once messages whose disappearing timer
+    /// has run out, read before [`Self::delete_expired_messages`] drops them.
+    pub fn expired_view_once_media(&self, now_ms: i64) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT view_once FROM messages WHERE view_once IS NOT NULL
+             AND expires_in_seconds > 0
+             AND expiration_start_ms > 0
+             AND (expiration_start_ms + expires_in_seconds * 1000) < ?1",
+        )?;
+        let json = stmt
+            .query_map(params![now_ms], |row| row.get::<_, String>(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(json.iter().filter_map(|j| unopened_media(j)).collect())
+    }
+
     pub fn delete_expired_messages(&self, now_ms: i64) -> Result<usize> {
         let deleted = self.conn.execute(
             "DELETE FROM messages WHERE expires_in_seconds > 0
@@ -942,6 +1842,40 @@ impl Database {
         Ok(())
     }
 
+    /// Store the text style ranges (byte offsets into the stored body) of a
+    /// message; an empty list clears them.
+    pub fn set_message_styles(
+        &self,
+        conv_id: &str,
+        timestamp_ms: i64,
+        styles: &[(usize, usize, StyleType)],
+    ) -> Result<()> {
+        let json = style_ranges_json(styles)?;
+        self.conn.execute(
+            "UPDATE messages SET style_ranges = ?3
+             WHERE conversation_id = ?1 AND timestamp_ms = ?2",
+            params![conv_id, timestamp_ms, json],
+        )?;
+        Ok(())
+    }
+
+    /// Store the style ranges (byte offsets into the stored quote body) of
+    /// the text a message quotes.
+    pub fn set_quote_styles(
+        &self,
+        conv_id: &str,
+        timestamp_ms: i64,
+        styles: &[(usize, usize, StyleType)],
+    ) -> Result<()> {
+        let json = style_ranges_json(styles)?;
+        self.conn.execute(
+            "UPDATE messages SET quote_style_ranges = ?3
+             WHERE conversation_id = ?1 AND timestamp_ms = ?2",
+            params![conv_id, timestamp_ms, json],
+        )?;
+        Ok(())
+    }
+
     /// Store the raw body (with U+FFFC placeholders) and raw mentions for a message,
     /// so later contact list updates can re-resolve the display body.
     pub fn upsert_message_mentions(
@@ -1027,6 +1961,301 @@ impl Database {
         }
         Ok(())
     }
+
+    // --- Stories ---
+
+    pub fn insert_story(&self, story: &Story) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO stories
+                (author, timestamp_ms, group_id, body, content_type, attachment_path,
+                 allows_replies, viewed, expires_at_ms)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                story.author,
+                story.timestamp_ms,
+                story.group_id,
+                story.text,
+                story.content_type,
+                story.attachment_path,
+                story.allows_replies as i32,
+                story.viewed as i32,
+                story.expires_at_ms(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Stories that have not expired as of `now_ms`, oldest first.
+    pub fn load_stories(&self, now_ms: i64) -> Result<Vec<Story>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT author, timestamp_ms, group_id, body, content_type, attachment_path,
+                    allows_replies, viewed
+             FROM stories WHERE expires_at_ms > ?1
+             ORDER BY timestamp_ms ASC",
+        )?;
+        let rows = stmt
+            .query_map(params![now_ms], |row| {
+                Ok(Story {
+                    author: row.get(0)?,
+                    timestamp_ms: row.get(1)?,
+                    group_id: row.get(2)?,
+                    text: row.get(3)?,
+                    content_type: row.get(4)?,
+                    attachment_path: row.get(5)?,
+                    allows_replies: row.get::<_, i32>(6)? != 0,
+                    viewed: row.get::<_, i32>(7)? != 0,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    pub fn mark_story_viewed(&self, author: &str, timestamp_ms: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE stories SET viewed = 1 WHERE author = ?1 AND timestamp_ms = ?2",
+            params![author, timestamp_ms],
+        )?;
+        Ok(())
+    }
+
+    /// Delete expired stories. Returns the local paths of their downloaded
+    /// attachments so the files can be removed too.
+    pub fn delete_expired_stories(&self, now_ms: i64) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "DELETE FROM stories WHERE expires_at_ms <= ?1
+             RETURNING attachment_path",
+        )?;
+        let paths = stmt
+            .query_map(params![now_ms], |row| row.get::<_, Option<String>>(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(paths.into_iter().flatten().collect())
+    }
+
+    // --- Calls ---
+
+    pub fn upsert_call(&self, call: &CallRecord) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO calls
+                (peer, call_id, direction, is_video, video_known, started_ms, ended_ms, outcome)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT(peer, call_id) DO UPDATE SET
+                ended_ms = excluded.ended_ms,
+                outcome = excluded.outcome",
+            params![
+                call.peer,
+                call.call_id,
+                call.direction.as_str(),
+                call.is_video == Some(true),
+                call.is_video.is_some(),
+                call.started_ms,
+                call.ended_ms,
+                call.outcome.as_str(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_call(&self, peer: &str, call_id: i64) -> Result<Option<CallRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT peer, call_id, direction, is_video, video_known, started_ms, ended_ms, outcome
+             FROM calls WHERE peer = ?1 AND call_id = ?2",
+        )?;
+        let mut rows = stmt.query_map(params![peer, call_id], call_from_row)?;
+        Ok(rows.next().transpose()?)
+    }
+
+    /// Most recent calls across all conversations, newest first.
+    pub fn load_recent_calls(&self, limit: usize) -> Result<Vec<CallRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT peer, call_id, direction, is_video, video_known, started_ms, ended_ms, outcome
+             FROM calls ORDER BY started_ms DESC LIMIT ?1",
+        )?;
+        let rows = stmt
+            .query_map(params![limit as i64], call_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Calls not ended yet: still ringing, or answered and awaiting a hangup.
+    pub fn load_open_calls(&self) -> Result<Vec<CallRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT peer, call_id, direction, is_video, video_known, started_ms, ended_ms, outcome
+             FROM calls WHERE ended_ms IS NULL AND outcome IN ('ringing', 'answered')",
+        )?;
+        let rows = stmt
+            .query_map([], call_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    // --- Outbox ---
+
+    /// Add a message to the outbox, or update its retry schedule if it's
+    /// already there. The payload is written once and never changes.
+    pub fn save_outbox_entry(&self, entry: &OutboxEntry) -> Result<()> {
+        let payload = outbox_payload_json(&entry.request)?;
+        self.conn.execute(
+            "INSERT INTO outbox
+                (conversation_id, local_ts_ms, payload, attempts, next_attempt_ms)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(conversation_id, local_ts_ms) DO UPDATE SET
+                attempts = excluded.attempts,
+                next_attempt_ms = excluded.next_attempt_ms",
+            params![
+                entry.conv_id,
+                entry.local_ts_ms,
+                payload,
+                entry.attempts,
+                entry.next_attempt_ms,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_outbox_entry(&self, conv_id: &str, local_ts_ms: i64) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM outbox WHERE conversation_id = ?1 AND local_ts_ms = ?2",
+            params![conv_id, local_ts_ms],
+        )?;
+        Ok(())
+    }
+
+    /// Every unconfirmed message, oldest first. Rows whose payload no longer
+    /// parses are skipped.
+    pub fn load_outbox(&self) -> Result<Vec<OutboxEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT conversation_id, local_ts_ms, payload, attempts, next_attempt_ms
+             FROM outbox ORDER BY local_ts_ms",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, u32>(3)?,
+                    row.get::<_, Option<i64>>(4)?,
+                ))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(rows
+            .into_iter()
+            .filter_map(
+                |(conv_id, local_ts_ms, payload, attempts, next_attempt_ms)| {
+                    Some(OutboxEntry {
+                        request: outbox_request(&payload, local_ts_ms)?,
+                        conv_id,
+                        local_ts_ms,
+                        attempts,
+                        next_attempt_ms,
+                    })
+                },
+            )
+            .collect())
+    }
+
+    // --- Usernames ---
+
+    /// Remember the username a 1:1 conversation was opened by. Conversations
+    /// started from a username are keyed by ACI, and this is their only
+    /// human-readable name until the contact's profile name arrives.
+    pub fn set_conversation_username(&self, conv_id: &str, username: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE conversations SET username = ?2 WHERE id = ?1",
+            params![conv_id, username],
+        )?;
+        Ok(())
+    }
+
+    /// All (conversation id, username) pairs.
+    pub fn load_conversation_usernames(&self) -> Result<Vec<(String, String)>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, username FROM conversations WHERE username IS NOT NULL")?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    // --- Account settings ---
+
+    pub fn get_account_setting(&self, key: &str) -> Result<Option<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT value FROM account_settings WHERE key = ?1")?;
+        let mut rows = stmt.query_map(params![key], |row| row.get(0))?;
+        Ok(rows.next().transpose()?)
+    }
+
+    /// Store a per-account setting; `None` removes it.
+    pub fn set_account_setting(&self, key: &str, value: Option<&str>) -> Result<()> {
+        match value {
+            Some(value) => self.conn.execute(
+                "INSERT INTO account_settings (key, value) VALUES (?1, ?2)
+                 ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                params![key, value],
+            )?,
+            None => self
+                .conn
+                .execute("DELETE FROM account_settings WHERE key = ?1", params![key])?,
+        };
+        Ok(())
+    }
+
+    // --- Contact details ---
+
+    /// Every contact with a local nickname or note.
+    pub fn load_contact_details(&self) -> Result<Vec<(String, LocalContact)>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT number, nickname, note FROM contact_details")?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get(0)?,
+                    LocalContact {
+                        nickname: row.get(1)?,
+                        note: row.get(2)?,
+                    },
+                ))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Store a contact's nickname and note; a contact with neither is removed.
+    pub fn save_contact_details(&self, number: &str, details: &LocalContact) -> Result<()> {
+        if details.is_empty() {
+            self.conn.execute(
+                "DELETE FROM contact_details WHERE number = ?1",
+                params![number],
+            )?;
+        } else {
+            self.conn.execute(
+                "INSERT INTO contact_details (number, nickname, note) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(number) DO UPDATE
+                 SET nickname = excluded.nickname, note = excluded.note",
+                params![number, details.nickname, details.note],
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn call_from_row(row: &rusqlite::Row) -> rusqlite::Result<CallRecord> {
+    Ok(CallRecord {
+        peer: row.get(0)?,
+        call_id: row.get(1)?,
+        direction: CallDirection::parse(&row.get::<_, String>(2)?),
+        is_video: row
+            .get::<_, bool>(4)?
+            .then(|| row.get::<_, bool>(3))
+            .transpose()?,
+        started_ms: row.get(5)?,
+        ended_ms: row.get(6)?,
+        outcome: CallOutcome::parse(&row.get::<_, String>(7)?),
+    })
 }
 
 #[cfg(test)]
@@ -1430,7 +2659,7 @@ mod tests {
         .unwrap();
 
         // Insert a reaction
-        db.upsert_reaction("+1", 1000, "Alice", "Bob", "👍")
+        db.upsert_reaction("+1", 1000, "Alice", "Bob", "👍", 5000)
             .unwrap();
         let reactions = db.load_reactions("+1").unwrap();
         assert_eq!(reactions.len(), 1);
@@ -1445,7 +2674,7 @@ mod tests {
         );
 
         // Replace: same sender reacts with different emoji
-        db.upsert_reaction("+1", 1000, "Alice", "Bob", "❤️")
+        db.upsert_reaction("+1", 1000, "Alice", "Bob", "❤️", 5000)
             .unwrap();
         let reactions = db.load_reactions("+1").unwrap();
         assert_eq!(reactions.len(), 1);
@@ -1456,7 +2685,7 @@ mod tests {
     fn remove_reaction(db: Database) {
         db.upsert_conversation("+1", "Alice", false).unwrap();
 
-        db.upsert_reaction("+1", 1000, "Alice", "Bob", "👍")
+        db.upsert_reaction("+1", 1000, "Alice", "Bob", "👍", 5000)
             .unwrap();
         assert_eq!(db.load_reactions("+1").unwrap().len(), 1);
 
@@ -1480,9 +2709,9 @@ mod tests {
         db.insert_message("+1", "you", "2025-01-01T00:01:00Z", "hi", false, None, 2000)
             .unwrap();
 
-        db.upsert_reaction("+1", 1000, "Alice", "Bob", "👍")
+        db.upsert_reaction("+1", 1000, "Alice", "Bob", "👍", 5000)
             .unwrap();
-        db.upsert_reaction("+1", 2000, "you", "Alice", "❤️")
+        db.upsert_reaction("+1", 2000, "you", "Alice", "❤️", 5000)
             .unwrap();
 
         let convs = db.load_conversations(100).unwrap();
@@ -1527,7 +2756,7 @@ mod tests {
         .unwrap();
 
         // Case-insensitive search for "hello"
-        let results = db.search_messages("+1", "hello", 50).unwrap();
+        let results = db.search_messages("+1", "hello", 50, false).unwrap();
         assert_eq!(results.len(), 2);
         // Most recent first
         assert_eq!(results[0].1, "Hello again");
@@ -1558,7 +2787,7 @@ mod tests {
         )
         .unwrap();
 
-        let results = db.search_messages("+1", "hello", 50).unwrap();
+        let results = db.search_messages("+1", "hello", 50, false).unwrap();
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].1, "real hello");
     }
@@ -1595,7 +2824,7 @@ mod tests {
             1000,
         )
         .unwrap();
-        db.upsert_reaction("+1", 1000, "Alice", "Bob", "👍")
+        db.upsert_reaction("+1", 1000, "Alice", "Bob", "👍", 5000)
             .unwrap();
         db.save_read_marker("+1", 1).unwrap();
 
@@ -1638,10 +2867,753 @@ mod tests {
         )
         .unwrap();
 
-        let results = db.search_all_messages("hello", 50).unwrap();
+        let results = db.search_all_messages("hello", 50, false).unwrap();
         assert_eq!(results.len(), 2);
         // Most recent first
         assert_eq!(results[0].3, "+2"); // Bob's conversation
         assert_eq!(results[1].3, "+1"); // Alice's conversation
     }
+
+    fn story(author: &str, timestamp_ms: i64) -> Story {
+        Story {
+            author: author.to_string(),
+            timestamp_ms,
+            group_id: None,
+            text: Some("hello".to_string()),
+            content_type: None,
+            attachment_path: None,
+            allows_replies: true,
+            viewed: false,
+        }
+    }
+
+    #[rstest]
+    fn story_round_trip_and_viewed(db: Database) {
+        db.insert_story(&story("+1", 1000)).unwrap();
+        // Re-delivery of the same story is ignored
+        db.insert_story(&story("+1", 1000)).unwrap();
+        db.mark_story_viewed("+1", 1000).unwrap();
+        let stories = db.load_stories(2000).unwrap();
+        assert_eq!(stories.len(), 1);
+        assert_eq!(stories[0].text.as_deref(), Some("hello"));
+        assert!(stories[0].viewed);
+    }
+
+    #[rstest]
+    fn expired_stories_are_hidden_and_deleted(db: Database) {
+        let old = story("+1", 1000);
+        let fresh = story("+2", 5000);
+        let old = Story {
+            attachment_path: Some("/tmp/story.jpg".to_string()),
+            ..old
+        };
+        db.insert_story(&old).unwrap();
+        db.insert_story(&fresh).unwrap();
+        let now = old.expires_at_ms();
+        let live = db.load_stories(now).unwrap();
+        assert_eq!(live.len(), 1);
+        assert_eq!(live[0].author, "+2");
+        assert_eq!(db.delete_expired_stories(now).unwrap(), ["/tmp/story.jpg"]);
+        assert!(db.delete_expired_stories(now).unwrap().is_empty());
+    }
+
+    fn call(call_id: i64, started_ms: i64) -> CallRecord {
+        CallRecord {
+            peer: "+1".to_string(),
+            call_id,
+            direction: CallDirection::Incoming,
+            is_video: Some(true),
+            started_ms,
+            ended_ms: None,
+            outcome: CallOutcome::Ringing,
+        }
+    }
+
+    #[rstest]
+    fn call_upsert_updates_outcome(db: Database) {
+        let mut c = call(7, 1000);
+        db.upsert_call(&c).unwrap();
+        assert_eq!(db.load_open_calls().unwrap(), vec![c.clone()]);
+        c.outcome = CallOutcome::Answered;
+        db.upsert_call(&c).unwrap();
+        assert_eq!(db.load_open_calls().unwrap(), vec![c.clone()]);
+        c.ended_ms = Some(61_000);
+        db.upsert_call(&c).unwrap();
+        assert_eq!(db.get_call("+1", 7).unwrap(), Some(c));
+        assert!(db.get_call("+1", 8).unwrap().is_none());
+        assert!(db.load_open_calls().unwrap().is_empty());
+
+        // A call we placed elsewhere keeps its type unknown
+        let mut placed = call(9, 2000);
+        placed.direction = CallDirection::Outgoing;
+        placed.is_video = None;
+        db.upsert_call(&placed).unwrap();
+        assert_eq!(db.get_call("+1", 9).unwrap(), Some(placed));
+    }
+
+    #[rstest]
+    fn recent_calls_newest_first(db: Database) {
+        for (id, ts) in [(1, 1000), (2, 3000), (3, 2000)] {
+            db.upsert_call(&call(id, ts)).unwrap();
+        }
+        let ids: Vec<i64> = db
+            .load_recent_calls(2)
+            .unwrap()
+            .iter()
+            .map(|c| c.call_id)
+            .collect();
+        assert_eq!(ids, vec![2, 3]);
+    }
+
+    #[rstest]
+    fn outbox_round_trips_full_message(db: Database) {
+        let mut entry = OutboxEntry {
+            conv_id: "g1".to_string(),
+            local_ts_ms: 1000,
+            request: SendRequest::Message {
+                recipient: "g1".to_string(),
+                body: "hi @".to_string(),
+                is_group: true,
+                local_ts_ms: 1000,
+                mentions: vec![(3, "+2".to_string())],
+                text_styles: vec![TextStyle {
+                    start: 0,
+                    length: 2,
+                    style: StyleType::Bold,
+                }],
+                attachments: vec![std::path::PathBuf::from("/tmp/a.png")],
+                quote_timestamp: Some(500),
+                quote_author: Some("+2".to_string()),
+                quote_body: Some("earlier".to_string()),
+            },
+            attempts: 0,
+            next_attempt_ms: None,
+        };
+        db.save_outbox_entry(&entry).unwrap();
+        entry.attempts = 2;
+        entry.next_attempt_ms = Some(9000);
+        db.save_outbox_entry(&entry).unwrap();
+
+        let loaded = db.load_outbox().unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].attempts, 2);
+        assert_eq!(loaded[0].next_attempt_ms, Some(9000));
+        let SendRequest::Message {
+            body,
+            is_group,
+            local_ts_ms,
+            mentions,
+            text_styles,
+            attachments,
+            quote_timestamp,
+            quote_body,
+            ..
+        } = &loaded[0].request
+        else {
+            panic!("expected a message");
+        };
+        assert_eq!(body, "hi @");
+        assert!(*is_group);
+        assert_eq!(*local_ts_ms, 1000);
+        assert_eq!(mentions, &vec![(3, "+2".to_string())]);
+        assert_eq!(text_styles[0].style, StyleType::Bold);
+        assert_eq!(attachments, &vec![std::path::PathBuf::from("/tmp/a.png")]);
+        assert_eq!(*quote_timestamp, Some(500));
+        assert_eq!(quote_body.as_deref(), Some("earlier"));
+
+        db.delete_outbox_entry("g1", 1000).unwrap();
+        assert!(db.load_outbox().unwrap().is_empty());
+    }
+
+    #[rstest]
+    fn view_once_state_round_trip(db: Database) {
+        db.upsert_conversation("+1", "Alice", false).unwrap();
+        db.insert_message(
+            "+1",
+            "Alice",
+            "2025-01-01T00:00:00Z",
+            "[View-once message]",
+            false,
+            None,
+            1000,
+        )
+        .unwrap();
+        db.insert_message(
+            "+1",
+            "Alice",
+            "2025-01-01T00:00:01Z",
+            "plain",
+            false,
+            None,
+            2000,
+        )
+        .unwrap();
+        let unopened = ViewOnce::Unopened {
+            path: Some("/tmp/a.jpg".to_string()),
+            content_type: Some("image/jpeg".to_string()),
+        };
+        db.set_view_once("+1", 1000, &unopened).unwrap();
+        let msgs = db.load_messages_page("+1", 10, 0).unwrap();
+        assert_eq!(msgs[0].view_once, Some(unopened));
+        assert_eq!(msgs[1].view_once, None);
+
+        assert_eq!(
+            db.unopened_view_once_media("+1", 1000).unwrap().as_deref(),
+            Some("/tmp/a.jpg")
+        );
+        assert_eq!(db.unopened_view_once_media("+1", 2000).unwrap(), None);
+
+        db.set_view_once("+1", 1000, &ViewOnce::Viewed).unwrap();
+        let msgs = db.load_messages_page("+1", 10, 0).unwrap();
+        assert_eq!(msgs[0].view_once, Some(ViewOnce::Viewed));
+        assert_eq!(db.unopened_view_once_media("+1", 1000).unwrap(), None);
+    }
+
+    #[rstest]
+    fn expired_view_once_media_lists_unopened_files(db: Database) {
+        db.upsert_conversation("+1", "Alice", false).unwrap();
+        for (ts, expires_in) in [(1000, 10), (2000, 10), (3000, 0)] {
+            db.insert_message_full(
+                "+1",
+                "Alice",
+                "2025-01-01T00:00:00Z",
+                "[View-once message]",
+                false,
+                None,
+                ts,
+                "+1",
+                None,
+                None,
+                None,
+                expires_in,
+                ts,
+            )
+            .unwrap();
+            let unopened = ViewOnce::Unopened {
+                path: Some(format!("/tmp/{ts}.jpg")),
+                content_type: None,
+            };
+            db.set_view_once("+1", ts, &unopened).unwrap();
+        }
+        db.set_view_once("+1", 2000, &ViewOnce::Viewed).unwrap();
+
+        // Only the expired, still unopened message's media is listed
+        assert_eq!(
+            db.expired_view_once_media(100_000).unwrap(),
+            ["/tmp/1000.jpg"]
+        );
+        assert!(db.expired_view_once_media(5_000).unwrap().is_empty());
+    }
+
+    #[rstest]
+    fn shared_contacts_round_trip(db: Database) {
+        db.upsert_conversation("+1", "Alice", false).unwrap();
+        db.insert_message(
+            "+1",
+            "Alice",
+            "2025-01-01T00:00:00Z",
+            "[Contact: Bob]",
+            false,
+            None,
+            1000,
+        )
+        .unwrap();
+        let card = SharedContact {
+            name: "Bob".to_string(),
+            organization: Some("Acme".to_string()),
+            phones: vec![crate::signal::types::ContactPoint {
+                value: "+15550001111".to_string(),
+                label: Some("Mobile".to_string()),
+            }],
+            emails: Vec::new(),
+        };
+        db.set_shared_contacts("+1", 1000, std::slice::from_ref(&card))
+            .unwrap();
+        let msgs = db.load_messages_page("+1", 10, 0).unwrap();
+        assert_eq!(msgs[0].shared_contacts, vec![card]);
+    }
+
+    #[rstest]
+    fn style_ranges_round_trip_and_reach_search(db: Database) {
+        db.upsert_conversation("+1", "Alice", false).unwrap();
+        db.insert_message(
+            "+1",
+            "Alice",
+            "2025-01-01T00:00:00Z",
+            "the butler did it",
+            false,
+            None,
+            1000,
+        )
+        .unwrap();
+        let styles = vec![(0, 3, StyleType::Bold), (4, 10, StyleType::Spoiler)];
+        db.set_message_styles("+1", 1000, &styles).unwrap();
+
+        let msgs = db.load_messages_page("+1", 10, 0).unwrap();
+        assert_eq!(msgs[0].style_ranges, styles);
+        assert_eq!(
+            db.search_messages("+1", "butler", 50, false).unwrap()[0].5,
+            styles
+        );
+        assert_eq!(
+            db.search_all_messages("butler", 50, false).unwrap()[0].5,
+            styles
+        );
+
+        db.set_message_styles("+1", 1000, &[]).unwrap();
+        let msgs = db.load_messages_page("+1", 10, 0).unwrap();
+        assert!(msgs[0].style_ranges.is_empty());
+    }
+
+    #[rstest]
+    fn style_ranges_off_char_boundaries_are_dropped(db: Database) {
+        db.upsert_conversation("+1", "Alice", false).unwrap();
+        db.insert_message(
+            "+1",
+            "Alice",
+            "2025-01-01T00:00:00Z",
+            "über",
+            false,
+            None,
+            1000,
+        )
+        .unwrap();
+        db.set_message_styles(
+            "+1",
+            1000,
+            &[(1, 3, StyleType::Bold), (2, 5, StyleType::Italic)],
+        )
+        .unwrap();
+        let msgs = db.load_messages_page("+1", 10, 0).unwrap();
+        assert_eq!(msgs[0].style_ranges, vec![(2, 5, StyleType::Italic)]);
+    }
+
+    fn sent_message(db: &Database) {
+        db.upsert_conversation("g1", "Family", true).unwrap();
+        db.insert_message(
+            "g1",
+            "you",
+            "2025-01-01T00:00:00Z",
+            "dinner at 7",
+            false,
+            Some(MessageStatus::Sent),
+            1000,
+        )
+        .unwrap();
+    }
+
+    #[rstest]
+    fn record_receipt_keeps_first_time_per_stage(db: Database) {
+        sent_message(&db);
+        db.record_receipt("g1", 1000, "+2", MessageStatus::Delivered, 2000)
+            .unwrap();
+        db.record_receipt("g1", 1000, "+2", MessageStatus::Read, 3000)
+            .unwrap();
+        db.record_receipt("g1", 1000, "+2", MessageStatus::Read, 9000)
+            .unwrap();
+        // A read receipt with no delivery receipt before it implies delivery
+        db.record_receipt("g1", 1000, "+3", MessageStatus::Read, 4000)
+            .unwrap();
+        // Receipts for unknown messages are dropped
+        db.record_receipt("g1", 5555, "+2", MessageStatus::Read, 4000)
+            .unwrap();
+
+        let receipt = |recipient: &str, delivered_ms, read_ms| RecipientReceipt {
+            recipient: recipient.to_string(),
+            delivered_ms: Some(delivered_ms),
+            read_ms: Some(read_ms),
+            viewed_ms: None,
+        };
+        assert_eq!(
+            db.load_message_receipts("g1", 1000).unwrap(),
+            vec![receipt("+2", 2000, 3000), receipt("+3", 4000, 4000)]
+        );
+        assert!(db.load_message_receipts("g1", 5555).unwrap().is_empty());
+    }
+
+    #[rstest]
+    fn receipts_go_away_with_their_conversation(db: Database) {
+        sent_message(&db);
+        db.record_receipt("g1", 1000, "+2", MessageStatus::Delivered, 2000)
+            .unwrap();
+        db.delete_conversation("g1").unwrap();
+        let count: i64 = db
+            .conn
+            .query_row("SELECT COUNT(*) FROM receipts", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[rstest]
+    fn message_reactions_come_with_their_time(db: Database) {
+        sent_message(&db);
+        db.upsert_reaction("g1", 1000, "you", "+3", "🎉", 3000)
+            .unwrap();
+        db.upsert_reaction("g1", 1000, "you", "+2", "👍", 2000)
+            .unwrap();
+        db.upsert_reaction("g1", 1000, "you", "+4", "❤️", 2500)
+            .unwrap();
+        // Rows from before reaction times were kept sort last
+        db.conn
+            .execute(
+                "UPDATE reactions SET reacted_at_ms = NULL WHERE sender = '+4'",
+                [],
+            )
+            .unwrap();
+
+        let reactions = db.load_message_reactions("g1", 1000).unwrap();
+        let rows: Vec<(&str, &str, Option<i64>)> = reactions
+            .iter()
+            .map(|r| (r.sender.as_str(), r.emoji.as_str(), r.reacted_at_ms))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("+2", "👍", Some(2000)),
+                ("+3", "🎉", Some(3000)),
+                ("+4", "❤️", None)
+            ]
+        );
+    }
+
+    #[rstest]
+    fn identity_key_change_stays_flagged_until_acknowledged(db: Database) {
+        // First sighting is taken as is
+        assert!(!db.record_identity_key("+1", "aa").unwrap());
+        assert!(!db.record_identity_key("+1", "aa").unwrap());
+        assert!(db.load_unacknowledged_identities().unwrap().is_empty());
+
+        assert!(db.record_identity_key("+1", "bb").unwrap());
+        assert!(db.load_unacknowledged_identities().unwrap().contains("+1"));
+        // Seeing the new key again doesn't clear the flag
+        assert!(!db.record_identity_key("+1", "bb").unwrap());
+        assert!(db.load_unacknowledged_identities().unwrap().contains("+1"));
+
+        db.acknowledge_identity("+1").unwrap();
+        assert!(db.load_unacknowledged_identities().unwrap().is_empty());
+    }
+
+    #[rstest]
+    fn refused_send_flags_identity_before_its_key_is_known(db: Database) {
+        db.mark_identity_changed("+2").unwrap();
+        assert!(db.load_unacknowledged_identities().unwrap().contains("+2"));
+        // The key that turns up next is the new one, not another change
+        assert!(!db.record_identity_key("+2", "cc").unwrap());
+        assert!(db.load_unacknowledged_identities().unwrap().contains("+2"));
+    }
+
+    fn edited_message(db: &Database) {
+        db.upsert_conversation("+1", "Alice", false).unwrap();
+        db.insert_message(
+            "+1",
+            "Alice",
+            "2025-01-01T00:00:00Z",
+            "see you at 8",
+            false,
+            None,
+            1000,
+        )
+        .unwrap();
+        db.record_edit("+1", 1000, 2000, "see you at 9", &[], false)
+            .unwrap();
+        db.record_edit("+1", 1000, 3000, "see you at 10", &[], false)
+            .unwrap();
+    }
+
+    #[rstest]
+    fn record_edit_keeps_every_revision(db: Database) {
+        edited_message(&db);
+        // A repeated edit adds nothing
+        db.record_edit("+1", 1000, 4000, "see you at 10", &[], false)
+            .unwrap();
+
+        let revisions = db.load_message_revisions("+1", 1000).unwrap();
+        let summary: Vec<(i64, &str)> = revisions
+            .iter()
+            .map(|r| (r.revision_ms, r.body.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (1000, "see you at 8"),
+                (2000, "see you at 9"),
+                (3000, "see you at 10")
+            ]
+        );
+        assert!(revisions.iter().all(|r| !r.is_outgoing));
+        let msgs = db.load_messages_page("+1", 10, 0).unwrap();
+        assert_eq!(msgs[0].body, "see you at 10");
+        assert!(msgs[0].is_edited);
+    }
+
+    #[rstest]
+    fn record_edit_keeps_order_when_timestamps_collide(db: Database) {
+        db.upsert_conversation("+1", "Alice", false).unwrap();
+        db.insert_message(
+            "+1",
+            "you",
+            "2025-01-01T00:00:00Z",
+            "one",
+            false,
+            None,
+            1000,
+        )
+        .unwrap();
+        // Edit stamped in the same millisecond, then one from a clock behind
+        db.record_edit("+1", 1000, 1000, "two", &[], true).unwrap();
+        db.record_edit("+1", 1000, 900, "three", &[], true).unwrap();
+
+        let bodies: Vec<String> = db
+            .load_message_revisions("+1", 1000)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.body)
+            .collect();
+        assert_eq!(bodies, vec!["one", "two", "three"]);
+    }
+
+    #[rstest]
+    fn record_edit_stores_styles_per_revision(db: Database) {
+        db.upsert_conversation("+1", "Alice", false).unwrap();
+        db.insert_message("+1", "you", "2025-01-01T00:00:00Z", "hi", false, None, 1000)
+            .unwrap();
+        db.set_message_styles("+1", 1000, &[(0, 2, StyleType::Bold)])
+            .unwrap();
+        db.record_edit(
+            "+1",
+            1000,
+            2000,
+            "hi all",
+            &[(3, 6, StyleType::Spoiler)],
+            true,
+        )
+        .unwrap();
+
+        let revisions = db.load_message_revisions("+1", 1000).unwrap();
+        assert_eq!(revisions[0].style_ranges, vec![(0, 2, StyleType::Bold)]);
+        assert_eq!(revisions[1].style_ranges, vec![(3, 6, StyleType::Spoiler)]);
+        assert!(revisions.iter().all(|r| r.is_outgoing));
+        let msgs = db.load_messages_page("+1", 10, 0).unwrap();
+        assert_eq!(msgs[0].style_ranges, vec![(3, 6, StyleType::Spoiler)]);
+    }
+
+    #[rstest]
+    fn search_can_match_earlier_revisions(db: Database) {
+        edited_message(&db);
+        assert!(
+            db.search_messages("+1", "at 8", 50, false)
+                .unwrap()
+                .is_empty()
+        );
+
+        let rows = db.search_messages("+1", "at 8", 50, true).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].1, "see you at 8");
+        assert!(rows[0].6);
+        let rows = db.search_all_messages("at 9", 50, true).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].1, "see you at 9");
+
+        // A match in the current text shows the current text
+        let rows = db.search_messages("+1", "see you", 50, true).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].1, "see you at 10");
+        assert!(!rows[0].6);
+    }
+
+    #[rstest]
+    fn deleting_a_message_drops_its_history(db: Database) {
+        edited_message(&db);
+        db.mark_message_deleted("+1", 1000).unwrap();
+        assert!(db.load_message_revisions("+1", 1000).unwrap().is_empty());
+        assert!(
+            db.search_messages("+1", "at 8", 50, true)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    fn pdf(name: &str) -> MessageAttachment {
+        MessageAttachment {
+            content_type: "application/pdf".to_string(),
+            filename: Some(name.to_string()),
+            size: Some(2048),
+            local_path: Some(format!("/tmp/{name}")),
+            width: None,
+            height: None,
+            state: DownloadState::Downloaded,
+        }
+    }
+
+    #[rstest]
+    fn attachments_round_trip_in_order(db: Database) {
+        db.upsert_conversation("+1", "Alice", false).unwrap();
+        let rowid = db
+            .insert_message("+1", "Alice", "2025-01-01T00:00:00Z", "", false, None, 1000)
+            .unwrap();
+        let photo = MessageAttachment {
+            content_type: "image/png".to_string(),
+            filename: Some("shot.png".to_string()),
+            size: None,
+            local_path: None,
+            width: Some(640),
+            height: Some(480),
+            state: DownloadState::Pending,
+        };
+        db.insert_attachments(rowid, &[pdf("a.pdf"), photo.clone()])
+            .unwrap();
+        db.insert_message(
+            "+1",
+            "Alice",
+            "2025-01-01T00:00:01Z",
+            "hi",
+            false,
+            None,
+            2000,
+        )
+        .unwrap();
+
+        let msgs = db.load_messages_page("+1", 10, 0).unwrap();
+        assert_eq!(msgs[0].attachments, vec![pdf("a.pdf"), photo]);
+        assert!(msgs[1].attachments.is_empty());
+
+        db.set_message_attachments("+1", 1000, &[pdf("b.pdf")])
+            .unwrap();
+        let msgs = db.load_messages_page("+1", 10, 0).unwrap();
+        assert_eq!(msgs[0].attachments, vec![pdf("b.pdf")]);
+
+        db.delete_conversation("+1").unwrap();
+        let count: i64 = db
+            .conn
+            .query_row("SELECT COUNT(*) FROM attachments", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[rstest]
+    fn search_matches_attachment_filenames(db: Database) {
+        db.upsert_conversation("+1", "Alice", false).unwrap();
+        let photo = db
+            .insert_message("+1", "Alice", "2025-01-01T00:00:00Z", "", false, None, 1000)
+            .unwrap();
+        db.insert_attachments(photo, &[pdf("notes.pdf"), pdf("Holiday.jpg")])
+            .unwrap();
+        let captioned = db
+            .insert_message(
+                "+1",
+                "Alice",
+                "2025-01-01T00:00:01Z",
+                "from the trip",
+                false,
+                None,
+                2000,
+            )
+            .unwrap();
+        db.insert_attachments(captioned, &[pdf("holiday.jpg")])
+            .unwrap();
+
+        let results = db.search_messages("+1", "holiday.jpg", 50, false).unwrap();
+        assert_eq!(results.len(), 2);
+        // A caption shows as is; a bare attachment shows the matching name
+        assert_eq!(results[0].1, "from the trip");
+        assert_eq!(results[1].1, "Holiday.jpg");
+        assert_eq!(
+            db.search_all_messages("holiday", 50, false).unwrap().len(),
+            2
+        );
+        // LIKE wildcards in the query stay literal
+        assert!(
+            db.search_all_messages("holiday_jpg", 50, false)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[rstest]
+    fn legacy_attachment_bodies_are_backfilled(db: Database) {
+        db.upsert_conversation("+1", "Alice", false).unwrap();
+        for (i, body) in [
+            "[attachment: a.pdf](file:///tmp/a.pdf)",
+            "[image: holiday.jpg] look at this",
+            "[image] is not an attachment",
+        ]
+        .iter()
+        .enumerate()
+        {
+            db.insert_message(
+                "+1",
+                "Alice",
+                &format!("2025-01-01T00:00:0{i}Z"),
+                body,
+                false,
+                None,
+                i as i64 * 1000,
+            )
+            .unwrap();
+        }
+
+        db.backfill_attachments().unwrap();
+        let msgs = db.load_messages_page("+1", 10, 0).unwrap();
+        assert_eq!(msgs[0].body, "");
+        assert_eq!(msgs[0].attachments[0].filename.as_deref(), Some("a.pdf"));
+        assert_eq!(msgs[0].attachments[0].state, DownloadState::Downloaded);
+        assert_eq!(msgs[1].body, "look at this");
+        assert_eq!(msgs[1].attachments[0].content_type, "image/jpeg");
+        assert_eq!(msgs[1].attachments[0].state, DownloadState::Pending);
+        assert_eq!(msgs[2].body, "[image] is not an attachment");
+        assert!(msgs[2].attachments.is_empty());
+
+        // Converted bodies no longer match, so a second pass is a no-op
+        db.backfill_attachments().unwrap();
+        let count: i64 = db
+            .conn
+            .query_row("SELECT COUNT(*) FROM attachments", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 2);
+    }
+
+    #[rstest]
+    fn conversation_username_round_trip(db: Database) {
+        let aci = "a1b2c3d4-0000-4000-8000-000000000001";
+        db.upsert_conversation(aci, "@alice.42", false).unwrap();
+        db.upsert_conversation("+1", "Bob", false).unwrap();
+        db.set_conversation_username(aci, "alice.42").unwrap();
+        assert_eq!(
+            db.load_conversation_usernames().unwrap(),
+            vec![(aci.to_string(), "alice.42".to_string())]
+        );
+        let convs = db.load_conversations(10).unwrap();
+        let conv = convs.iter().find(|c| c.id == aci).unwrap();
+        assert_eq!(conv.name, "@alice.42");
+        assert!(!conv.is_group);
+    }
+
+    #[rstest]
+    fn account_setting_set_and_clear(db: Database) {
+        assert_eq!(db.get_account_setting("username").unwrap(), None);
+        db.set_account_setting("username", Some("me.01")).unwrap();
+        db.set_account_setting("username", Some("me.02")).unwrap();
+        assert_eq!(
+            db.get_account_setting("username").unwrap().as_deref(),
+            Some("me.02")
+        );
+        db.set_account_setting("username", None).unwrap();
+        assert_eq!(db.get_account_setting("username").unwrap(), None);
+    }
+
+    #[rstest]
+    fn contact_details_round_trip(db: Database) {
+        let details = LocalContact {
+            nickname: Some("Ali".to_string()),
+            note: Some("Met at RustConf".to_string()),
+        };
+        db.save_contact_details("+1", &details).unwrap();
+        assert_eq!(
+            db.load_contact_details().unwrap(),
+            vec![("+1".to_string(), details)]
+        );
+        db.save_contact_details("+1", &LocalContact::default())
+            .unwrap();
+        assert!(db.load_contact_details().unwrap().is_empty());
+    }
 }
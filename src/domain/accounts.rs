@@ -0,0 +1,90 @@
+//! Multi-account switcher state.
+//!
+//! Each configured account runs as its own `App` with its own database
+//! and signal-cli backend; the main loop owns the set and swaps which one
+//! is drawn. This struct is the slice of that picture an individual `App`
+//! sees: its own short `label`, the per-account unread `badges` refreshed
+//! by the main loop each tick, the `pending_switch` request raised by the
+//! switch key, and the conversation `parked` while the account is in the
+//! background. Everything stays empty with a single account.
+
+/// Unread badge for one configured account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountBadge {
+    pub account: String,
+    pub unread: usize,
+    /// True for the account whose `App` holds this state.
+    pub is_self: bool,
+}
+
+/// State for the multi-account switcher.
+#[derive(Default)]
+pub struct AccountsState {
+    /// Short label for this account (e.g. `…4567`). `None` with a single account.
+    pub label: Option<String>,
+    /// Every configured account in switcher order. Refreshed by the main loop.
+    pub badges: Vec<AccountBadge>,
+    /// Set by the switch-account key; drained by the main loop.
+    pub pending_switch: bool,
+    /// Conversation that was open when this account was sent to the background.
+    pub parked: Option<String>,
+}
+
+impl AccountsState {
+    /// Whether more than one account is configured.
+    pub fn is_multi(&self) -> bool {
+        self.label.is_some()
+    }
+
+    /// Total unread across the accounts other than this one.
+    pub fn other_unread(&self) -> usize {
+        self.badges
+            .iter()
+            .filter(|b| !b.is_self)
+            .map(|b| b.unread)
+            .sum()
+    }
+}
+
+/// Compact display form of an account number: the last four characters
+/// behind an ellipsis, or the whole thing if it is that short already.
+pub fn account_label(account: &str) -> String {
+    let chars: Vec<char> = account.chars().collect();
+    if chars.len() <= 4 {
+        return account.to_string();
+    }
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("\u{2026}{tail}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn account_label_keeps_last_four() {
+        assert_eq!(account_label("+15551234567"), "\u{2026}4567");
+        assert_eq!(account_label("+123"), "+123");
+    }
+
+    #[test]
+    fn other_unread_skips_self() {
+        let state = AccountsState {
+            label: Some(account_label("+15550000001")),
+            badges: vec![
+                AccountBadge {
+                    account: "+15550000001".to_string(),
+                    unread: 5,
+                    is_self: true,
+                },
+                AccountBadge {
+                    account: "+15550000002".to_string(),
+                    unread: 2,
+                    is_self: false,
+                },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(state.other_unread(), 2);
+    }
+}
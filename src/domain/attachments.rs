@@ -0,0 +1,405 @@
+//! Message attachments.
+//!
+//! Attachments are stored as rows of the `attachments` table, not spelled
+//! into the message body, so search and export only ever see what was
+//! typed. The chat pane draws each one as an `[image: ...]` or
+//! `[attachment: ...]` label, linked to the file once it is on disk.
+//! Bodies written before the table existed are converted on upgrade with
+//! [`parse_legacy_body`].
+
+use std::path::{Path, PathBuf};
+
+use crate::signal::types::Attachment;
+
+/// Image types rendered inline; anything else is listed as a file.
+const INLINE_IMAGE_TYPES: &[&str] = &["image/jpeg", "image/png", "image/gif", "image/webp"];
+
+/// Whether the file behind an attachment is available locally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadState {
+    /// signal-cli has not delivered the file (or we never had it)
+    Pending,
+    /// `local_path` points at the file
+    Downloaded,
+    /// Downloaded once, but the file has since been moved or deleted
+    Missing,
+}
+
+impl DownloadState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Downloaded => "downloaded",
+            Self::Missing => "missing",
+        }
+    }
+
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "downloaded" => Self::Downloaded,
+            "missing" => Self::Missing,
+            _ => Self::Pending,
+        }
+    }
+}
+
+/// An attachment on a displayed message, persisted in the `attachments` table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageAttachment {
+    pub content_type: String,
+    pub filename: Option<String>,
+    /// Size in bytes, when known
+    pub size: Option<u64>,
+    pub local_path: Option<String>,
+    /// Pixel dimensions, for images and videos
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub state: DownloadState,
+}
+
+impl MessageAttachment {
+    /// Attachment received from signal-cli.
+    pub fn from_wire(att: &Attachment) -> Self {
+        Self {
+            content_type: att.content_type.clone(),
+            filename: att.filename.clone(),
+            size: att.size,
+            local_path: att.local_path.clone(),
+            width: att.width,
+            height: att.height,
+            state: if att.local_path.is_some() {
+                DownloadState::Downloaded
+            } else {
+                DownloadState::Pending
+            },
+        }
+    }
+
+    /// Attachment for a local file we are sending (or a cached sticker).
+    /// The content type comes from the extension; size and dimensions are
+    /// read from the file when it exists.
+    pub fn from_path(path: &Path, filename: Option<String>) -> Self {
+        let content_type = content_type_for(&path.to_string_lossy())
+            .unwrap_or("application/octet-stream")
+            .to_string();
+        let dimensions = if content_type.starts_with("image/") {
+            image::image_dimensions(path).ok()
+        } else {
+            None
+        };
+        Self {
+            filename: filename.or_else(|| {
+                path.file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+            }),
+            size: std::fs::metadata(path).ok().map(|m| m.len()),
+            local_path: Some(path.to_string_lossy().into_owned()),
+            width: dimensions.map(|(w, _)| w),
+            height: dimensions.map(|(_, h)| h),
+            state: DownloadState::Downloaded,
+            content_type,
+        }
+    }
+
+    /// Whether the chat pane renders this attachment inline.
+    pub fn is_image(&self) -> bool {
+        INLINE_IMAGE_TYPES.contains(&self.content_type.as_str())
+    }
+
+    /// `[image: photo.jpg]`, `[attachment: audio/ogg]`, ...
+    pub fn label(&self) -> String {
+        let kind = if self.is_image() {
+            "image"
+        } else {
+            "attachment"
+        };
+        let name = self.filename.as_deref().unwrap_or(&self.content_type);
+        format!("[{kind}: {name}]")
+    }
+
+    /// Path of the file, if it is on disk.
+    pub fn downloaded_path(&self) -> Option<&str> {
+        match self.state {
+            DownloadState::Downloaded => self.local_path.as_deref(),
+            DownloadState::Pending | DownloadState::Missing => None,
+        }
+    }
+
+    /// `file:///` URI of the file, if it is on disk.
+    pub fn file_uri(&self) -> Option<String> {
+        self.downloaded_path().map(path_to_file_uri)
+    }
+
+    /// Copy the file into `dir` under its original name, adding ` (1)`,
+    /// ` (2)`, ... before the extension rather than overwrite anything.
+    /// Only the last component of the sender-supplied name is used, so it
+    /// cannot point outside `dir`. Returns the path written.
+    pub fn save_copy(&self, dir: &Path) -> anyhow::Result<PathBuf> {
+        let source = self
+            .downloaded_path()
+            .ok_or_else(|| anyhow::anyhow!("not downloaded"))?;
+        let name = self
+            .filename
+            .as_deref()
+            .and_then(|name| Path::new(name).file_name())
+            .or_else(|| Path::new(source).file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "attachment".to_string());
+
+        let stem = Path::new(&name)
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| name.clone());
+        let ext = Path::new(&name)
+            .extension()
+            .map(|e| format!(".{}", e.to_string_lossy()))
+            .unwrap_or_default();
+        let mut dest = dir.join(&name);
+        let mut n = 1;
+        while dest.exists() {
+            dest = dir.join(format!("{stem} ({n}){ext}"));
+            n += 1;
+        }
+        std::fs::create_dir_all(dir)?;
+        std::fs::copy(source, &dest)?;
+        Ok(dest)
+    }
+
+    /// Downgrade to [`DownloadState::Missing`] if the file is gone.
+    pub fn check_on_disk(&mut self) {
+        if let Some(path) = self.downloaded_path()
+            && !Path::new(path).exists()
+        {
+            self.state = DownloadState::Missing;
+        }
+    }
+}
+
+/// Content type implied by a file name's extension.
+fn content_type_for(name: &str) -> Option<&'static str> {
+    let ext = Path::new(name).extension()?.to_str()?.to_ascii_lowercase();
+    Some(match ext.as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "mp4" => "video/mp4",
+        "mov" => "video/quicktime",
+        "mp3" => "audio/mpeg",
+        "ogg" => "audio/ogg",
+        "aac" => "audio/aac",
+        "pdf" => "application/pdf",
+        "txt" => "text/plain",
+        _ => return None,
+    })
+}
+
+/// Convert a local file path to a file:/// URI (forward slashes, for terminal Ctrl+Click).
+fn path_to_file_uri(path: &str) -> String {
+    let normalized = path.replace('\\', "/");
+    if normalized.starts_with('/') {
+        format!("file://{normalized}")
+    } else {
+        format!("file:///{normalized}")
+    }
+}
+
+/// Extract a local file path from a file:/// URI. On Unix the third slash is the
+/// root path separator, so it must be preserved; on Windows it's just the scheme.
+pub fn file_uri_to_path(uri: &str) -> String {
+    let uri = uri.trim();
+    if let Some(rest) = uri.strip_prefix("file:///") {
+        #[cfg(windows)]
+        {
+            rest.to_string()
+        }
+        #[cfg(not(windows))]
+        {
+            format!("/{rest}")
+        }
+    } else if let Some(rest) = uri.strip_prefix("file://") {
+        rest.to_string()
+    } else {
+        uri.to_string()
+    }
+}
+
+/// Split a body from before the `attachments` table into the attachment it
+/// described and the text typed alongside it. Handles every form siggy has
+/// written: `[image: label](file:///path)`, `[image: label] file:///path`,
+/// `[image: label -> /path]`, and the path-less `[image: label] caption`
+/// used for sent files. Returns `None` for ordinary bodies.
+pub fn parse_legacy_body(body: &str) -> Option<(MessageAttachment, String)> {
+    let (is_image, rest) = match body.strip_prefix("[image: ") {
+        Some(rest) => (true, rest),
+        None => (false, body.strip_prefix("[attachment: ")?),
+    };
+    let close = rest.find(']')?;
+    let mut label = &rest[..close];
+    let mut tail = &rest[close + 1..];
+
+    let mut path = None;
+    if let Some((name, arrow_path)) = label.split_once(" -> ") {
+        label = name;
+        path = Some(arrow_path.to_string());
+    } else if let Some(inner) = tail.strip_prefix("(file://")
+        && let Some(end) = inner.rfind(')')
+    {
+        path = Some(file_uri_to_path(&format!("file://{}", &inner[..end])));
+        tail = &inner[end + 1..];
+    } else if let Some(uri) = tail.strip_prefix(" file://") {
+        let end = uri.find(char::is_whitespace).unwrap_or(uri.len());
+        path = Some(file_uri_to_path(&format!("file://{}", &uri[..end])));
+        tail = &uri[end..];
+    }
+
+    // Attachments without a filename were labelled with their content type
+    let label_is_type = label.contains('/') && !label.contains('.');
+    let content_type = if label_is_type {
+        label.to_string()
+    } else {
+        path.as_deref()
+            .and_then(content_type_for)
+            .or_else(|| content_type_for(label))
+            // Only the four inline types were ever labelled `[image: ...]`
+            .or(is_image.then_some("image/jpeg"))
+            .unwrap_or("application/octet-stream")
+            .to_string()
+    };
+    let size = path
+        .as_deref()
+        .and_then(|p| std::fs::metadata(p).ok())
+        .map(|m| m.len());
+    let attachment = MessageAttachment {
+        content_type,
+        filename: (!label_is_type).then(|| label.to_string()),
+        size,
+        state: if path.is_some() {
+            DownloadState::Downloaded
+        } else {
+            DownloadState::Pending
+        },
+        local_path: path,
+        width: None,
+        height: None,
+    };
+    Some((attachment, tail.trim().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(
+        "[image: photo.jpg](file:///home/user/photo.jpg)",
+        "image/jpeg",
+        Some("photo.jpg"),
+        Some("/home/user/photo.jpg"),
+        ""
+    )]
+    #[case(
+        "[attachment: doc.pdf] file:///tmp/doc.pdf",
+        "application/pdf",
+        Some("doc.pdf"),
+        Some("/tmp/doc.pdf"),
+        ""
+    )]
+    #[case(
+        "[image: cat.png -> /tmp/cat.png]",
+        "image/png",
+        Some("cat.png"),
+        Some("/tmp/cat.png"),
+        ""
+    )]
+    #[case(
+        "[image: sticker \u{1F602}](file:///tmp/stickers/abc/5.webp)",
+        "image/webp",
+        Some("sticker \u{1F602}"),
+        Some("/tmp/stickers/abc/5.webp"),
+        ""
+    )]
+    #[case("[attachment: audio/ogg]", "audio/ogg", None, None, "")]
+    #[case(
+        "[image: holiday.jpg] look at this",
+        "image/jpeg",
+        Some("holiday.jpg"),
+        None,
+        "look at this"
+    )]
+    #[cfg(not(windows))]
+    fn legacy_bodies_are_parsed(
+        #[case] body: &str,
+        #[case] content_type: &str,
+        #[case] filename: Option<&str>,
+        #[case] path: Option<&str>,
+        #[case] caption: &str,
+    ) {
+        let (att, rest) = parse_legacy_body(body).expect("legacy body");
+        assert_eq!(att.content_type, content_type);
+        assert_eq!(att.filename.as_deref(), filename);
+        assert_eq!(att.local_path.as_deref(), path);
+        assert_eq!(att.state == DownloadState::Downloaded, path.is_some());
+        assert_eq!(rest, caption);
+    }
+
+    #[rstest]
+    #[case("hello world")]
+    #[case("[Sticker: \u{1F602}]")]
+    #[case("[image without a colon]")]
+    fn ordinary_bodies_are_not_attachments(#[case] body: &str) {
+        assert!(parse_legacy_body(body).is_none());
+    }
+
+    #[test]
+    fn save_copy_keeps_name_and_never_overwrites() {
+        let src_dir = tempfile::tempdir().unwrap();
+        let dest_dir = tempfile::tempdir().unwrap();
+        let source = src_dir.path().join("a1b2c3");
+        std::fs::write(&source, b"jpeg bytes").unwrap();
+        let att = MessageAttachment {
+            content_type: "image/jpeg".to_string(),
+            filename: Some("../../beach.jpg".to_string()),
+            size: None,
+            local_path: Some(source.to_string_lossy().into_owned()),
+            width: None,
+            height: None,
+            state: DownloadState::Downloaded,
+        };
+
+        let first = att.save_copy(dest_dir.path()).unwrap();
+        let second = att.save_copy(dest_dir.path()).unwrap();
+        assert_eq!(first, dest_dir.path().join("beach.jpg"));
+        assert_eq!(second, dest_dir.path().join("beach (1).jpg"));
+        assert_eq!(std::fs::read(&second).unwrap(), b"jpeg bytes");
+
+        let pending = MessageAttachment {
+            state: DownloadState::Pending,
+            ..att
+        };
+        assert!(pending.save_copy(dest_dir.path()).is_err());
+    }
+
+    #[test]
+    fn label_and_uri_follow_state() {
+        let mut att = MessageAttachment {
+            content_type: "application/pdf".to_string(),
+            filename: Some("doc.pdf".to_string()),
+            size: None,
+            local_path: Some("/nonexistent/doc.pdf".to_string()),
+            width: None,
+            height: None,
+            state: DownloadState::Downloaded,
+        };
+        assert_eq!(att.label(), "[attachment: doc.pdf]");
+        assert_eq!(
+            att.file_uri().as_deref(),
+            Some("file:///nonexistent/doc.pdf")
+        );
+
+        att.check_on_disk();
+        assert_eq!(att.state, DownloadState::Missing);
+        assert_eq!(att.file_uri(), None);
+    }
+}
@@ -0,0 +1,238 @@
+//! Contact and group avatars.
+//!
+//! Avatars are fetched from signal-cli with `getAvatar` the first time a
+//! detail view (contact menu, group menu, `/profile`) needs one, or for
+//! every conversation when sidebar avatars are on. Each fetched image is
+//! decoded off the main loop by `render_avatar` at two sizes: a square for the detail views and a two-cell glyph for
+//! the sidebar. With a native image protocol the detail square is also
+//! encoded for it, so the detail views can show the real image. A
+//! conversation is only asked for once per session, whether or not it
+//! turned out to have an avatar; a failed fetch is retried after
+//! `AVATAR_RETRY_MS`.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::mpsc;
+
+use ratatui::text::Line;
+
+use crate::image_render;
+use crate::signal::types::AvatarRef;
+
+/// Width in cells of the avatar in detail views (half as many rows).
+pub const AVATAR_DETAIL_WIDTH: u32 = 12;
+
+/// Width in cells of the sidebar glyph: one row of halfblocks.
+pub const AVATAR_GLYPH_WIDTH: u32 = 2;
+
+/// How long after a failed fetch an avatar may be asked for again.
+const AVATAR_RETRY_MS: i64 = 60_000;
+
+/// An avatar decoded by [`render_avatar`], waiting for the main loop.
+pub struct RenderedAvatar {
+    pub id: String,
+    /// Cache file the avatar was read from, which keys the native caches
+    pub path: String,
+    /// Detail rows and sidebar glyph; `None` if the image didn't decode
+    pub halfblock: Option<(Vec<Line<'static>>, Line<'static>)>,
+    /// Detail-size PNG for the native protocols: (base64, pixel_w, pixel_h)
+    pub native_png: Option<(String, u32, u32)>,
+    /// Detail-size Sixel, when that is the protocol in use
+    pub sixel: Option<String>,
+}
+
+/// Decode the avatar at `path` at both sizes, plus the native encodings
+/// when `native` is set (and the Sixel one when `sixel_cell_px` is).
+/// Slow on big images, so callers run it with `spawn_blocking`.
+pub fn render_avatar(
+    id: String,
+    path: &Path,
+    native: bool,
+    sixel_cell_px: Option<(u16, u16)>,
+) -> RenderedAvatar {
+    let square = image_render::decode_avatar(path);
+    let halfblock = square.as_ref().and_then(|img| {
+        let detail = image_render::avatar_lines(img, AVATAR_DETAIL_WIDTH);
+        let glyph = image_render::avatar_lines(img, AVATAR_GLYPH_WIDTH)
+            .into_iter()
+            .next()?;
+        Some((detail, glyph))
+    });
+    let native_png = square
+        .as_ref()
+        .filter(|_| native)
+        .and_then(|img| image_render::encode_avatar_png(img, AVATAR_DETAIL_WIDTH));
+    let sixel = native_png
+        .as_ref()
+        .zip(sixel_cell_px)
+        .and_then(|(png, px)| {
+            let width = AVATAR_DETAIL_WIDTH as u16;
+            image_render::encode_sixel(&png.0, width, width / 2, px)
+        });
+    RenderedAvatar {
+        id,
+        path: path.to_string_lossy().into_owned(),
+        halfblock,
+        native_png,
+        sixel,
+    }
+}
+
+/// Rendered avatars, keyed by conversation id.
+pub struct AvatarState {
+    /// Detail-view avatars
+    pub detail: HashMap<String, Vec<Line<'static>>>,
+    /// Sidebar glyphs
+    pub glyphs: HashMap<String, Line<'static>>,
+    /// Cache file of each avatar with a native rendering, for placing it
+    /// over the halfblock one in the detail views
+    pub native: HashMap<String, String>,
+    /// Ids already fetched (or being fetched) this session
+    requested: HashSet<String>,
+    /// Ids whose fetch failed, with when they may be asked for again
+    retry_at: HashMap<String, i64>,
+    /// Show avatar glyphs next to sidebar entries
+    pub sidebar: bool,
+    /// Background render channel (sender)
+    pub render_tx: mpsc::Sender<RenderedAvatar>,
+    /// Background render channel (receiver)
+    pub render_rx: mpsc::Receiver<RenderedAvatar>,
+}
+
+impl Default for AvatarState {
+    fn default() -> Self {
+        let (render_tx, render_rx) = mpsc::channel();
+        Self {
+            detail: HashMap::new(),
+            glyphs: HashMap::new(),
+            native: HashMap::new(),
+            requested: HashSet::new(),
+            retry_at: HashMap::new(),
+            sidebar: false,
+            render_tx,
+            render_rx,
+        }
+    }
+}
+
+impl AvatarState {
+    /// Mark `avatar` as requested. True the first time, or once a failed
+    /// fetch may be retried, when the caller should fetch it.
+    pub fn request(&mut self, avatar: &AvatarRef, now_ms: i64) -> bool {
+        let id = avatar.id();
+        if self.retry_at.get(id).is_some_and(|&at| now_ms >= at) {
+            self.retry_at.remove(id);
+            self.requested.remove(id);
+        }
+        self.requested.insert(id.to_string())
+    }
+
+    /// The fetch for `id` failed: allow another try after a while.
+    pub fn fetch_failed(&mut self, id: &str, now_ms: i64) {
+        self.retry_at
+            .insert(id.to_string(), now_ms + AVATAR_RETRY_MS);
+    }
+
+    /// Store the rendered avatar for `id`, with the cache file of its
+    /// native rendering if it has one, or forget the old avatar when the
+    /// image didn't decode.
+    pub fn apply(
+        &mut self,
+        id: &str,
+        halfblock: Option<(Vec<Line<'static>>, Line<'static>)>,
+        native_path: Option<String>,
+    ) {
+        let Some((detail, glyph)) = halfblock else {
+            self.forget(id);
+            return;
+        };
+        self.detail.insert(id.to_string(), detail);
+        self.glyphs.insert(id.to_string(), glyph);
+        match native_path {
+            Some(path) => self.native.insert(id.to_string(), path),
+            None => self.native.remove(id),
+        };
+    }
+
+    /// Drop the avatar for `id`, which has none any more.
+    pub fn forget(&mut self, id: &str) {
+        self.detail.remove(id);
+        self.glyphs.remove(id);
+        self.native.remove(id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn avatars_are_requested_once() {
+        let mut state = AvatarState::default();
+        let alice = AvatarRef::Contact("+15550001111".to_string());
+        assert!(state.request(&alice, 0));
+        assert!(!state.request(&alice, 0));
+        assert!(state.request(&AvatarRef::Group("abc=".to_string()), 0));
+    }
+
+    #[test]
+    fn failed_fetch_is_retried_after_backoff() {
+        let mut state = AvatarState::default();
+        let alice = AvatarRef::Contact("+15550001111".to_string());
+        assert!(state.request(&alice, 0));
+        state.fetch_failed(alice.id(), 1_000);
+        assert!(!state.request(&alice, 1_000 + AVATAR_RETRY_MS - 1));
+        assert!(state.request(&alice, 1_000 + AVATAR_RETRY_MS));
+        assert!(!state.request(&alice, 1_000 + 2 * AVATAR_RETRY_MS));
+    }
+
+    #[test]
+    fn render_produces_both_sizes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("avatar.png");
+        image::RgbaImage::from_pixel(8, 8, image::Rgba([10, 20, 30, 255]))
+            .save(&path)
+            .unwrap();
+
+        let mut state = AvatarState::default();
+        let rendered = render_avatar("+15550001111".to_string(), &path, true, None);
+        let (w, h) = rendered.native_png.as_ref().map(|p| (p.1, p.2)).unwrap();
+        assert_eq!((w, h), (AVATAR_DETAIL_WIDTH * 8, AVATAR_DETAIL_WIDTH * 8));
+        assert!(rendered.sixel.is_none());
+        state.apply(&rendered.id, rendered.halfblock, Some(rendered.path));
+        assert_eq!(
+            state.detail["+15550001111"].len(),
+            AVATAR_DETAIL_WIDTH as usize / 2
+        );
+        assert_eq!(
+            state.glyphs["+15550001111"].width(),
+            AVATAR_GLYPH_WIDTH as usize
+        );
+        assert_eq!(state.native["+15550001111"], path.to_string_lossy());
+
+        state.forget("+15550001111");
+        assert!(state.detail.is_empty());
+        assert!(state.glyphs.is_empty());
+        assert!(state.native.is_empty());
+    }
+
+    #[test]
+    fn undecodable_avatar_is_forgotten() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("avatar.png");
+        image::RgbaImage::from_pixel(8, 8, image::Rgba([10, 20, 30, 255]))
+            .save(&path)
+            .unwrap();
+        let mut state = AvatarState::default();
+        let rendered = render_avatar("abc=".to_string(), &path, false, None);
+        assert!(rendered.native_png.is_none());
+        state.apply("abc=", rendered.halfblock, None);
+        assert!(state.detail.contains_key("abc="));
+
+        std::fs::write(&path, b"not an image").unwrap();
+        let rendered = render_avatar("abc=".to_string(), &path, false, None);
+        state.apply("abc=", rendered.halfblock, None);
+        assert!(state.detail.is_empty());
+        assert!(state.glyphs.is_empty());
+    }
+}
@@ -0,0 +1,305 @@
+//! Call history: one record per 1:1 call and the `/calls` overlay cursor.
+//!
+//! signal-cli delivers each call as a series of `callMessage` steps sharing
+//! a call id. [`CallRecord::apply`] folds one step into the record stored in
+//! the `calls` table. An incoming offer that is never answered or hung up
+//! is closed as missed by `App::sweep_stale_calls` after
+//! [`RING_TIMEOUT_MS`]; an answered call whose hangup never arrives is
+//! closed as unfinished after [`CALL_TIMEOUT_MS`].
+
+use crate::signal::types::CallSignalKind;
+
+/// How long an incoming call may ring before it counts as missed.
+pub const RING_TIMEOUT_MS: i64 = 90 * 1000;
+
+/// How long an answered call may go without a hangup before we stop
+/// waiting for one.
+pub const CALL_TIMEOUT_MS: i64 = 12 * 60 * 60 * 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallDirection {
+    Incoming,
+    Outgoing,
+}
+
+impl CallDirection {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Incoming => "incoming",
+            Self::Outgoing => "outgoing",
+        }
+    }
+
+    pub fn parse(s: &str) -> Self {
+        if s == "outgoing" {
+            Self::Outgoing
+        } else {
+            Self::Incoming
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallOutcome {
+    /// Offered, not answered or ended yet
+    Ringing,
+    Answered,
+    Missed,
+    Declined,
+    Busy,
+    /// Answered, but the hangup never arrived, so its length is unknown
+    Unfinished,
+}
+
+impl CallOutcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Ringing => "ringing",
+            Self::Answered => "answered",
+            Self::Missed => "missed",
+            Self::Declined => "declined",
+            Self::Busy => "busy",
+            Self::Unfinished => "unfinished",
+        }
+    }
+
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "answered" => Self::Answered,
+            "missed" => Self::Missed,
+            "declined" => Self::Declined,
+            "busy" => Self::Busy,
+            "unfinished" => Self::Unfinished,
+            _ => Self::Ringing,
+        }
+    }
+}
+
+/// A call as stored in the `calls` table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallRecord {
+    pub peer: String,
+    pub call_id: i64,
+    pub direction: CallDirection,
+    /// `None` when the call was first seen from an answer or busy reply,
+    /// which don't say whether it was video
+    pub is_video: Option<bool>,
+    /// Timestamp of the offer (or the first step we saw)
+    pub started_ms: i64,
+    /// Timestamp of the hangup, once the call has ended
+    pub ended_ms: Option<i64>,
+    pub outcome: CallOutcome,
+}
+
+impl CallRecord {
+    /// Start a record from the first step seen for a call. An offer means
+    /// the peer is calling us; an answer or busy reply means we placed the
+    /// call from another device, and leaves the call type unknown. A hangup
+    /// alone tells us nothing.
+    pub fn from_signal(peer: &str, call_id: i64, kind: &CallSignalKind, ts: i64) -> Option<Self> {
+        let (direction, is_video) = match kind {
+            CallSignalKind::Offer { video } => (CallDirection::Incoming, Some(*video)),
+            CallSignalKind::Answer | CallSignalKind::Busy => (CallDirection::Outgoing, None),
+            CallSignalKind::Hangup { .. } => return None,
+        };
+        let mut record = Self {
+            peer: peer.to_string(),
+            call_id,
+            direction,
+            is_video,
+            started_ms: ts,
+            ended_ms: None,
+            outcome: CallOutcome::Ringing,
+        };
+        record.apply(kind, ts);
+        Some(record)
+    }
+
+    /// Fold one signalling step into the record. Steps arriving after the
+    /// call has ended are ignored. An `ACCEPTED` hangup means another of our
+    /// devices answered, so the call stays open until the final hangup. A
+    /// hangup that turns up after the call was given up on still ends it.
+    pub fn apply(&mut self, kind: &CallSignalKind, ts: i64) {
+        if self.ended_ms.is_some() {
+            return;
+        }
+        match kind {
+            CallSignalKind::Offer { .. } => {}
+            CallSignalKind::Answer => self.outcome = CallOutcome::Answered,
+            CallSignalKind::Busy => {
+                self.outcome = CallOutcome::Busy;
+                self.ended_ms = Some(ts);
+            }
+            CallSignalKind::Hangup { hangup_type } if hangup_type == "ACCEPTED" => {
+                self.outcome = CallOutcome::Answered;
+            }
+            CallSignalKind::Hangup { hangup_type } => {
+                self.outcome = match (hangup_type.as_str(), self.outcome) {
+                    ("DECLINED", _) => CallOutcome::Declined,
+                    ("BUSY", _) => CallOutcome::Busy,
+                    (_, CallOutcome::Ringing) => CallOutcome::Missed,
+                    (_, CallOutcome::Unfinished) => CallOutcome::Answered,
+                    (_, outcome) => outcome,
+                };
+                self.ended_ms = Some(ts);
+            }
+        }
+    }
+
+    /// Time from the offer to the hangup, for answered calls.
+    pub fn duration_ms(&self) -> Option<i64> {
+        if self.outcome != CallOutcome::Answered {
+            return None;
+        }
+        self.ended_ms.map(|end| (end - self.started_ms).max(0))
+    }
+
+    /// A call still ringing at `now_ms` after the ring timeout, or still
+    /// answered without a hangup after the call timeout.
+    pub fn is_stale(&self, now_ms: i64) -> bool {
+        let timeout = match self.outcome {
+            CallOutcome::Ringing => RING_TIMEOUT_MS,
+            CallOutcome::Answered => CALL_TIMEOUT_MS,
+            _ => return false,
+        };
+        self.ended_ms.is_none() && now_ms - self.started_ms >= timeout
+    }
+
+    /// "voice call", "video call", or just "call" when we can't tell.
+    pub fn kind_label(&self) -> &'static str {
+        match self.is_video {
+            Some(true) => "video call",
+            Some(false) => "voice call",
+            None => "call",
+        }
+    }
+
+    /// Chat pane notice for a missed call.
+    pub fn missed_body(&self) -> String {
+        format!("Missed {}", self.kind_label())
+    }
+}
+
+/// Format a call duration as `m:ss`, or `h:mm:ss` past an hour.
+pub fn format_call_duration(ms: i64) -> String {
+    let secs = ms / 1000;
+    let (h, m, s) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    if h > 0 {
+        format!("{h}:{m:02}:{s:02}")
+    } else {
+        format!("{m}:{s:02}")
+    }
+}
+
+/// State for the `/calls` overlay.
+#[derive(Default)]
+pub struct CallsState {
+    /// Recent calls, newest first
+    pub records: Vec<CallRecord>,
+    pub index: usize,
+}
+
+impl CallsState {
+    pub fn selected(&self) -> Option<&CallRecord> {
+        self.records.get(self.index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn hangup(t: &str) -> CallSignalKind {
+        CallSignalKind::Hangup {
+            hangup_type: t.to_string(),
+        }
+    }
+
+    fn offer() -> CallRecord {
+        CallRecord::from_signal("+1", 7, &CallSignalKind::Offer { video: true }, 1_000).unwrap()
+    }
+
+    #[test]
+    fn answered_call_records_duration() {
+        let mut call = offer();
+        assert_eq!(call.direction, CallDirection::Incoming);
+        assert_eq!(call.outcome, CallOutcome::Ringing);
+        call.apply(&CallSignalKind::Answer, 3_000);
+        call.apply(&hangup("NORMAL"), 63_000);
+        assert_eq!(call.outcome, CallOutcome::Answered);
+        assert_eq!(call.duration_ms(), Some(62_000));
+    }
+
+    #[rstest]
+    #[case("NORMAL", CallOutcome::Missed)]
+    #[case("DECLINED", CallOutcome::Declined)]
+    #[case("BUSY", CallOutcome::Busy)]
+    fn hangup_while_ringing(#[case] hangup_type: &str, #[case] expected: CallOutcome) {
+        let mut call = offer();
+        call.apply(&hangup(hangup_type), 5_000);
+        assert_eq!(call.outcome, expected);
+        assert_eq!(call.ended_ms, Some(5_000));
+    }
+
+    #[test]
+    fn answered_on_another_device_stays_open() {
+        let mut call = offer();
+        call.apply(&hangup("ACCEPTED"), 5_000);
+        assert_eq!(call.outcome, CallOutcome::Answered);
+        assert_eq!(call.ended_ms, None);
+        assert!(!call.is_stale(1_000 + RING_TIMEOUT_MS));
+        call.apply(&hangup("NORMAL"), 65_000);
+        assert_eq!(call.outcome, CallOutcome::Answered);
+        assert_eq!(call.duration_ms(), Some(64_000));
+    }
+
+    #[test]
+    fn answer_without_offer_is_outgoing() {
+        let call = CallRecord::from_signal("+1", 7, &CallSignalKind::Answer, 1_000).unwrap();
+        assert_eq!(call.direction, CallDirection::Outgoing);
+        assert_eq!(call.outcome, CallOutcome::Answered);
+        assert_eq!(call.is_video, None);
+        assert_eq!(call.kind_label(), "call");
+        assert!(CallRecord::from_signal("+1", 7, &hangup("NORMAL"), 1_000).is_none());
+    }
+
+    #[test]
+    fn steps_after_end_are_ignored() {
+        let mut call = offer();
+        call.apply(&hangup("NORMAL"), 2_000);
+        call.apply(&CallSignalKind::Answer, 3_000);
+        assert_eq!(call.outcome, CallOutcome::Missed);
+        assert_eq!(call.ended_ms, Some(2_000));
+        assert_eq!(call.duration_ms(), None);
+    }
+
+    #[test]
+    fn stale_after_ring_timeout() {
+        let call = offer();
+        assert!(!call.is_stale(1_000 + RING_TIMEOUT_MS - 1));
+        assert!(call.is_stale(1_000 + RING_TIMEOUT_MS));
+    }
+
+    #[test]
+    fn answered_call_without_hangup_goes_stale() {
+        let mut call = offer();
+        call.apply(&CallSignalKind::Answer, 3_000);
+        assert!(!call.is_stale(1_000 + CALL_TIMEOUT_MS - 1));
+        assert!(call.is_stale(1_000 + CALL_TIMEOUT_MS));
+
+        // A hangup after it was given up on still ends it
+        call.outcome = CallOutcome::Unfinished;
+        assert!(!call.is_stale(1_000 + CALL_TIMEOUT_MS));
+        call.apply(&hangup("NORMAL"), 61_000);
+        assert_eq!(call.outcome, CallOutcome::Answered);
+        assert_eq!(call.duration_ms(), Some(60_000));
+    }
+
+    #[test]
+    fn duration_formatting() {
+        assert_eq!(format_call_duration(62_000), "1:02");
+        assert_eq!(format_call_duration(3_723_000), "1:02:03");
+        assert_eq!(format_call_duration(0), "0:00");
+    }
+}
@@ -0,0 +1,305 @@
+//! Local contact details and vCard import.
+//!
+//! Nicknames and notes are ours alone: they live in the `contact_details`
+//! table, never leave this device, and a nickname wins over whatever name
+//! signal-cli reports. vCard files are parsed here into (number, name)
+//! pairs for `updateContact`; entries without an international number are
+//! skipped because Signal can't guess the country code. `/share-contact`
+//! goes the other way and writes a contact out as a vCard.
+
+use crate::signal::types::SharedContact;
+
+/// Nickname and note kept locally for one contact.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LocalContact {
+    pub nickname: Option<String>,
+    pub note: Option<String>,
+}
+
+impl LocalContact {
+    pub fn is_empty(&self) -> bool {
+        self.nickname.is_none() && self.note.is_none()
+    }
+}
+
+/// Reduce a phone number to E.164: `+1 (555) 123-4567` -> `+15551234567`.
+/// A `00` international prefix counts as `+`; numbers without either are
+/// rejected.
+pub fn normalize_phone(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    let raw = raw.strip_prefix("tel:").unwrap_or(raw);
+    let digits: String = raw
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '.' | '(' | ')' | '/'))
+        .collect();
+    let national = digits
+        .strip_prefix('+')
+        .or_else(|| digits.strip_prefix("00"))?;
+    (national.len() >= 7 && national.len() <= 15 && national.chars().all(|c| c.is_ascii_digit()))
+        .then(|| format!("+{national}"))
+}
+
+/// Parse the add-contact input, `<number> <name>`. The number may contain
+/// the same separators [`normalize_phone`] accepts; the name starts at the
+/// first character that can't be part of it.
+pub fn parse_new_contact(input: &str) -> Option<(String, String)> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '+' | ' ' | '-' | '.' | '(' | ')')))
+        .unwrap_or(input.len());
+    let (number, name) = input.split_at(split);
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    Some((normalize_phone(number)?, name.to_string()))
+}
+
+/// Contacts found in a vCard file.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct VcardImport {
+    /// (E.164 number, display name), in file order
+    pub contacts: Vec<(String, String)>,
+    /// Cards skipped for lacking a name or an international number
+    pub skipped: usize,
+}
+
+/// Undo vCard text escaping (`\,` `\;` `\n` `\\`).
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n' | 'N') => out.push(' '),
+                Some(other) => out.push(other),
+                None => {}
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Escape a vCard text value, the reverse of [`unescape`].
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' | ',' | ';' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// A vCard 3.0 for one contact, the file `/share-contact` sends.
+pub fn contact_vcard(number: &str, name: &str) -> String {
+    let name = escape(name);
+    format!(
+        "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:{name}\r\nN:;{name};;;\r\nTEL;TYPE=CELL:{number}\r\nEND:VCARD\r\n"
+    )
+}
+
+/// The number to message or save for a shared contact: the first mobile
+/// number, or the first number at all, that is in international form.
+pub fn shared_contact_number(contact: &SharedContact) -> Option<String> {
+    let mobile = contact
+        .phones
+        .iter()
+        .filter(|p| p.label.as_deref() == Some("Mobile"))
+        .find_map(|p| normalize_phone(&p.value));
+    mobile.or_else(|| {
+        contact
+            .phones
+            .iter()
+            .find_map(|p| normalize_phone(&p.value))
+    })
+}
+
+/// Fields collected from the vCard being parsed.
+#[derive(Default)]
+struct Card {
+    /// `FN`
+    full_name: Option<String>,
+    /// `N`, as "Given Family"
+    structured_name: Option<String>,
+    /// (is mobile, E.164 number) for each usable `TEL`
+    tels: Vec<(bool, String)>,
+}
+
+/// Parse every `BEGIN:VCARD` ... `END:VCARD` block in `text`. The name comes
+/// from `FN`, falling back to `N`; the number is the first mobile `TEL`, or
+/// the first `TEL` if none is marked mobile.
+pub fn parse_vcards(text: &str) -> VcardImport {
+    // Unfold continuation lines (RFC 6350 3.2)
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = line.trim_end_matches('\r');
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(prev)) => prev.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut import = VcardImport::default();
+    let mut card: Option<Card> = None;
+    for line in &lines {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let mut params = key.split(';');
+        let property = params.next().unwrap_or_default().to_ascii_uppercase();
+        // Grouped properties look like `item1.TEL`
+        let property = property.rsplit('.').next().unwrap_or_default();
+        match (property, card.as_mut()) {
+            ("BEGIN", _) if value.eq_ignore_ascii_case("VCARD") => {
+                card = Some(Card::default());
+            }
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VCARD") => {
+                let Card {
+                    full_name,
+                    structured_name,
+                    tels,
+                } = card.take().unwrap_or_default();
+                let name = full_name.or(structured_name).filter(|n| !n.is_empty());
+                let number = tels
+                    .iter()
+                    .find(|(mobile, _)| *mobile)
+                    .or(tels.first())
+                    .map(|(_, number)| number.clone());
+                match (name, number) {
+                    (Some(name), Some(number)) => import.contacts.push((number, name)),
+                    _ => import.skipped += 1,
+                }
+            }
+            ("FN", Some(card)) => card.full_name = Some(unescape(value).trim().to_string()),
+            ("N", Some(card)) => {
+                // Family;Given;Additional;Prefix;Suffix
+                let parts: Vec<String> = value.split(';').map(unescape).collect();
+                let name = [parts.get(1), parts.first()]
+                    .into_iter()
+                    .flatten()
+                    .map(|s| s.trim())
+                    .filter(|s| !s.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                card.structured_name = Some(name);
+            }
+            ("TEL", Some(card)) => {
+                if let Some(number) = normalize_phone(value) {
+                    let mobile = params.any(|p| {
+                        let p = p.to_ascii_uppercase();
+                        p.contains("CELL") || p.contains("MOBILE")
+                    });
+                    card.tels.push((mobile, number));
+                }
+            }
+            _ => {}
+        }
+    }
+    import
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal::types::ContactPoint;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("+1 (555) 123-4567", Some("+15551234567"))]
+    #[case("0044 20 7946 0958", Some("+442079460958"))]
+    #[case("tel:+49-30-1234567", Some("+49301234567"))]
+    #[case("555-123-4567", None)]
+    #[case("+12", None)]
+    #[case("+1555abc4567", None)]
+    fn phone_normalization(#[case] input: &str, #[case] expected: Option<&str>) {
+        assert_eq!(normalize_phone(input).as_deref(), expected);
+    }
+
+    #[rstest]
+    #[case("+15551234567 Alice Smith", Some(("+15551234567", "Alice Smith")))]
+    #[case("+1 (555) 123-4567 Bob", Some(("+15551234567", "Bob")))]
+    #[case("+15551234567", None)]
+    #[case("Alice +15551234567", None)]
+    fn new_contact_input(#[case] input: &str, #[case] expected: Option<(&str, &str)>) {
+        let parsed = parse_new_contact(input);
+        assert_eq!(
+            parsed.as_ref().map(|(n, m)| (n.as_str(), m.as_str())),
+            expected
+        );
+    }
+
+    #[test]
+    fn vcards_pick_name_and_mobile_number() {
+        let text = "BEGIN:VCARD\r\n\
+            VERSION:3.0\r\n\
+            FN:Alice Smith\r\n\
+            TEL;TYPE=WORK:+1 555 000 1111\r\n\
+            TEL;TYPE=CELL:+1 555 000 2222\r\n\
+            END:VCARD\r\n\
+            BEGIN:VCARD\r\n\
+            VERSION:4.0\r\n\
+            N:Jones;Bob;;;\r\n\
+            item1.TEL;VALUE=uri:tel:+44-20-7946-\r\n 0958\r\n\
+            END:VCARD\r\n\
+            BEGIN:VCARD\r\n\
+            FN:Carol\\, the intern\r\n\
+            TEL:555-0100\r\n\
+            END:VCARD\r\n";
+        let import = parse_vcards(text);
+        assert_eq!(
+            import.contacts,
+            vec![
+                ("+15550002222".to_string(), "Alice Smith".to_string()),
+                ("+442079460958".to_string(), "Bob Jones".to_string()),
+            ]
+        );
+        assert_eq!(import.skipped, 1);
+    }
+
+    #[test]
+    fn shared_vcard_parses_back() {
+        let text = contact_vcard("+15551234567", "Smith, Alice");
+        let import = parse_vcards(&text);
+        assert_eq!(
+            import.contacts,
+            vec![("+15551234567".to_string(), "Smith, Alice".to_string())]
+        );
+    }
+
+    #[test]
+    fn shared_contact_prefers_mobile_number() {
+        let point = |value: &str, label: &str| ContactPoint {
+            value: value.to_string(),
+            label: Some(label.to_string()),
+        };
+        let mut contact = SharedContact {
+            name: "Dana".to_string(),
+            phones: vec![
+                point("555-0100", "Home"),
+                point("+1 555 000 1111", "Work"),
+                point("+1 555 000 2222", "Mobile"),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            shared_contact_number(&contact).as_deref(),
+            Some("+15550002222")
+        );
+        contact.phones.pop();
+        assert_eq!(
+            shared_contact_number(&contact).as_deref(),
+            Some("+15550001111")
+        );
+        contact.phones.pop();
+        assert_eq!(shared_contact_number(&contact), None);
+    }
+}
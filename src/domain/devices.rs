@@ -0,0 +1,111 @@
+//! Linked devices: the `/devices` overlay state and link URI validation.
+//!
+//! The list is fetched from signal-cli each time the overlay opens and
+//! again after every change, so it always shows what the server has. The
+//! primary device (id 1) can unlink other devices and link new ones from
+//! the `sgnl://linkdevice` URI shown on the new device's QR code; any
+//! device can rename itself.
+
+use crate::signal::types::Device;
+
+/// What the `/devices` overlay is showing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DevicesMode {
+    #[default]
+    List,
+    /// Waiting for y/n before unlinking the selected device
+    ConfirmRemove,
+    /// Editing this device's name
+    Rename,
+    /// Pasting a `sgnl://linkdevice` URI
+    Link,
+}
+
+/// State for the `/devices` overlay.
+#[derive(Default)]
+pub struct DevicesState {
+    /// Devices on the account, in id order
+    pub devices: Vec<Device>,
+    pub index: usize,
+    pub mode: DevicesMode,
+    /// Text buffer for the rename and link inputs
+    pub input: String,
+    /// True until the first `listDevices` answer arrives
+    pub loading: bool,
+}
+
+impl DevicesState {
+    pub fn selected(&self) -> Option<&Device> {
+        self.devices.get(self.index)
+    }
+
+    /// Replace the list, keeping the cursor in range.
+    pub fn set_devices(&mut self, devices: Vec<Device>) {
+        self.devices = devices;
+        self.index = self.index.min(self.devices.len().saturating_sub(1));
+        self.loading = false;
+    }
+
+    /// The device siggy runs as, when signal-cli tells us.
+    pub fn this_device(&self) -> Option<&Device> {
+        self.devices.iter().find(|d| d.is_this_device)
+    }
+}
+
+/// Validate a device link URI pasted from the new device's QR code. Both
+/// the current `sgnl://linkdevice` and the legacy `tsdevice:/` scheme carry
+/// the `uuid` and `pub_key` parameters signal-cli needs.
+pub fn device_link_uri(text: &str) -> Option<String> {
+    let uri = text.trim();
+    let query = uri
+        .strip_prefix("sgnl://linkdevice?")
+        .or_else(|| uri.strip_prefix("tsdevice:/?"))?;
+    let has = |key: &str| {
+        query.split('&').any(|pair| {
+            pair.split_once('=')
+                .is_some_and(|(k, v)| k == key && !v.is_empty())
+        })
+    };
+    (has("uuid") && has("pub_key")).then(|| uri.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn device(id: i64) -> Device {
+        Device {
+            id,
+            name: None,
+            created_ms: 0,
+            last_seen_ms: 0,
+            is_this_device: false,
+        }
+    }
+
+    #[rstest]
+    #[case("sgnl://linkdevice?uuid=abc&pub_key=BQ%2Bx", true)]
+    #[case("  tsdevice:/?uuid=abc&pub_key=BQx  ", true)]
+    #[case("sgnl://linkdevice?uuid=abc", false)]
+    #[case("sgnl://linkdevice?uuid=&pub_key=BQx", false)]
+    #[case("https://signal.group/#abc", false)]
+    fn link_uri(#[case] input: &str, #[case] valid: bool) {
+        assert_eq!(device_link_uri(input).is_some(), valid);
+    }
+
+    #[test]
+    fn set_devices_clamps_cursor() {
+        let mut state = DevicesState {
+            index: 2,
+            loading: true,
+            ..Default::default()
+        };
+        state.set_devices(vec![device(1), device(2)]);
+        assert_eq!(state.index, 1);
+        assert!(!state.loading);
+        state.set_devices(Vec::new());
+        assert_eq!(state.index, 0);
+        assert!(state.selected().is_none());
+    }
+}
@@ -1,10 +1,11 @@
-//! File browser overlay used to pick attachments.
+//! File browser overlay used to pick attachments and our profile avatar.
 //!
 //! Opens at the user's home directory and walks the filesystem with a
 //! sorted directory-first listing. Type-to-filter narrows the visible
 //! entries; Enter descends into a directory or selects a file (returned
-//! as `FilePickerOutcome::Selected`). Backspace pops the filter or
-//! navigates up when the filter is empty.
+//! as `FilePickerOutcome::Selected`). When picking attachments, Tab marks
+//! files across directories so an album goes out as one message.
+//! Backspace pops the filter or navigates up when the filter is empty.
 
 use std::path::PathBuf;
 
@@ -14,14 +15,27 @@ use crossterm::event::KeyCode;
 pub enum FilePickerOutcome {
     /// Picker should stay open for further navigation.
     Continue,
-    /// User selected a file; caller should consume the path and close the overlay.
-    Selected(PathBuf),
+    /// User selected files (marked ones, else the highlighted one); caller
+    /// should consume the paths and close the overlay.
+    Selected(Vec<PathBuf>),
     /// User cancelled (Esc); caller should close the overlay.
     Cancelled,
 }
 
+/// What the picked file will be used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FilePickerPurpose {
+    /// Attach to the next message
+    #[default]
+    Attachment,
+    /// Upload as our profile avatar
+    ProfileAvatar,
+}
+
 /// State for the file browser overlay used to select attachments.
 pub struct FilePickerState {
+    /// What the selection is for
+    pub purpose: FilePickerPurpose,
     /// Current directory in file browser
     pub dir: PathBuf,
     /// Directory entries: (name, is_dir, size_bytes)
@@ -34,29 +48,35 @@ pub struct FilePickerState {
     pub filtered: Vec<usize>,
     /// Error message from directory read
     pub error: Option<String>,
+    /// Files marked with Tab, in the order they were marked
+    pub marked: Vec<PathBuf>,
 }
 
 impl Default for FilePickerState {
     fn default() -> Self {
         Self {
+            purpose: FilePickerPurpose::Attachment,
             dir: dirs::home_dir().unwrap_or_else(|| PathBuf::from(".")),
             entries: Vec::new(),
             index: 0,
             filter: String::new(),
             filtered: Vec::new(),
             error: None,
+            marked: Vec::new(),
         }
     }
 }
 
 impl FilePickerState {
-    /// Reset state for a fresh browse. Caller must also call
+    /// Reset state for a fresh browse for `purpose`. Caller must also call
     /// `App::open_overlay` to make the picker visible.
-    pub fn open(&mut self) {
+    pub fn open(&mut self, purpose: FilePickerPurpose) {
+        self.purpose = purpose;
         self.dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
         self.index = 0;
         self.filter.clear();
         self.error = None;
+        self.marked.clear();
         self.refresh_entries();
     }
 
@@ -129,11 +149,16 @@ impl FilePickerState {
                         self.index = 0;
                         self.filter.clear();
                         self.refresh_entries();
+                    } else if self.marked.is_empty() {
+                        return FilePickerOutcome::Selected(vec![self.dir.join(&name)]);
                     } else {
-                        return FilePickerOutcome::Selected(self.dir.join(&name));
+                        return FilePickerOutcome::Selected(std::mem::take(&mut self.marked));
                     }
                 }
             }
+            KeyCode::Tab if self.purpose == FilePickerPurpose::Attachment => {
+                self.toggle_mark();
+            }
             KeyCode::Backspace => {
                 if !self.filter.is_empty() {
                     self.filter.pop();
@@ -155,6 +180,34 @@ impl FilePickerState {
         FilePickerOutcome::Continue
     }
 
+    /// Mark or unmark the highlighted file and move to the next entry.
+    /// Directories cannot be marked.
+    fn toggle_mark(&mut self) {
+        let Some(&entry_idx) = self.filtered.get(self.index) else {
+            return;
+        };
+        let (ref name, is_dir, _) = self.entries[entry_idx];
+        if is_dir {
+            return;
+        }
+        let path = self.dir.join(name);
+        if let Some(pos) = self.marked.iter().position(|p| *p == path) {
+            self.marked.remove(pos);
+        } else {
+            self.marked.push(path);
+        }
+        if self.index + 1 < self.filtered.len() {
+            self.index += 1;
+        }
+    }
+
+    /// Whether the entry at `entry_idx` in the current directory is marked.
+    pub fn is_marked(&self, entry_idx: usize) -> bool {
+        self.entries
+            .get(entry_idx)
+            .is_some_and(|(name, _, _)| self.marked.contains(&self.dir.join(name)))
+    }
+
     /// Navigate to the parent directory in the file browser.
     fn navigate_up(&mut self) {
         if let Some(parent) = self.dir.parent() {
@@ -0,0 +1,231 @@
+//! Group change descriptions.
+//!
+//! signal-cli reports a groupV2 update as a bare `UPDATE` with the editor's
+//! number, so the details are recovered by diffing the cached [`Group`]
+//! against the next `listGroups` result. [`diff_groups`] lists what changed;
+//! [`GroupChange::describe`] turns each change into a system message.
+//! [`group_invite_url`] validates the links used to join a group.
+
+use crate::signal::parse::format_timer_duration;
+use crate::signal::types::Group;
+
+/// One difference between two snapshots of the same group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupChange {
+    MembersAdded(Vec<String>),
+    MembersRemoved(Vec<String>),
+    Renamed(String),
+    /// New description, `None` when it was cleared
+    DescriptionChanged(Option<String>),
+    /// New disappearing message timer in seconds (0 = off)
+    TimerChanged(i64),
+    AdminsAdded(Vec<String>),
+    AdminsRemoved(Vec<String>),
+}
+
+/// Everything that differs between `old` and `new`, in a stable order:
+/// membership, then details, then admin roles.
+pub fn diff_groups(old: &Group, new: &Group) -> Vec<GroupChange> {
+    let added = |from: &[String], to: &[String]| -> Vec<String> {
+        to.iter().filter(|m| !from.contains(m)).cloned().collect()
+    };
+    let mut changes = Vec::new();
+    let joined = added(&old.members, &new.members);
+    if !joined.is_empty() {
+        changes.push(GroupChange::MembersAdded(joined));
+    }
+    let left = added(&new.members, &old.members);
+    if !left.is_empty() {
+        changes.push(GroupChange::MembersRemoved(left));
+    }
+    if !new.name.is_empty() && new.name != old.name {
+        changes.push(GroupChange::Renamed(new.name.clone()));
+    }
+    if new.description != old.description {
+        changes.push(GroupChange::DescriptionChanged(new.description.clone()));
+    }
+    if new.expiration_seconds != old.expiration_seconds {
+        changes.push(GroupChange::TimerChanged(new.expiration_seconds));
+    }
+    // Admins who left the group lose the role implicitly; only report
+    // role changes for people who are still members.
+    let promoted = added(&old.admins, &new.admins);
+    if !promoted.is_empty() {
+        changes.push(GroupChange::AdminsAdded(promoted));
+    }
+    let demoted: Vec<String> = added(&new.admins, &old.admins)
+        .into_iter()
+        .filter(|m| new.members.contains(m))
+        .collect();
+    if !demoted.is_empty() {
+        changes.push(GroupChange::AdminsRemoved(demoted));
+    }
+    changes
+}
+
+/// A group `UPDATE` waiting for the `listGroups` refresh that reveals what
+/// it changed.
+#[derive(Debug, Clone)]
+pub struct PendingGroupUpdate {
+    pub group_id: String,
+    pub editor: String,
+    pub timestamp_ms: i64,
+    /// Already described from the update itself (an inline rename), so
+    /// there is no need to fall back to a generic message
+    pub described: bool,
+}
+
+/// "Alice", "Alice and Bob", "Alice, Bob and Carol".
+fn join_names(names: &[String]) -> String {
+    match names {
+        [] => String::new(),
+        [one] => one.clone(),
+        [init @ .., last] => format!("{} and {last}", init.join(", ")),
+    }
+}
+
+impl GroupChange {
+    /// System message for this change. `editor` is the number of whoever
+    /// made it; `name_of` resolves a number to a display name.
+    pub fn describe(&self, editor: &str, name_of: &dyn Fn(&str) -> String) -> String {
+        let by = name_of(editor);
+        let names =
+            |ids: &[String]| join_names(&ids.iter().map(|id| name_of(id)).collect::<Vec<_>>());
+        match self {
+            Self::MembersAdded(ids) if ids.len() == 1 && ids[0] == editor => {
+                format!("{by} joined the group")
+            }
+            Self::MembersAdded(ids) => format!("{by} added {}", names(ids)),
+            Self::MembersRemoved(ids) if ids.len() == 1 && ids[0] == editor => {
+                format!("{by} left the group")
+            }
+            Self::MembersRemoved(ids) => format!("{by} removed {}", names(ids)),
+            Self::Renamed(name) => format!("{by} renamed the group to \"{name}\""),
+            Self::DescriptionChanged(Some(_)) => format!("{by} changed the group description"),
+            Self::DescriptionChanged(None) => format!("{by} removed the group description"),
+            Self::TimerChanged(0) => format!("{by} turned off disappearing messages"),
+            Self::TimerChanged(secs) => format!(
+                "{by} set disappearing messages to {}",
+                format_timer_duration(*secs)
+            ),
+            Self::AdminsAdded(ids) => format!("{by} made {} an admin", names(ids)),
+            Self::AdminsRemoved(ids) => format!("{by} removed {} as admin", names(ids)),
+        }
+    }
+}
+
+const INVITE_URL_PREFIX: &str = "https://signal.group/#";
+
+/// Find a `signal.group` invite link in `text`. The fragment carries the
+/// base64url-encoded group secrets and must not be empty.
+pub fn group_invite_url(text: &str) -> Option<&str> {
+    text.split_whitespace()
+        .map(|word| word.trim_end_matches(['.', ',', ')', '>', ']']))
+        .find(|word| {
+            word.strip_prefix(INVITE_URL_PREFIX)
+                .is_some_and(|fragment| {
+                    !fragment.is_empty()
+                        && fragment
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+                })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(members: &[&str], admins: &[&str]) -> Group {
+        Group {
+            id: "g1".to_string(),
+            name: "Family".to_string(),
+            members: members.iter().map(|s| s.to_string()).collect(),
+            admins: admins.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn name_of(id: &str) -> String {
+        match id {
+            "+1" => "Alice".to_string(),
+            "+2" => "Bob".to_string(),
+            "+3" => "Carol".to_string(),
+            "+me" => "you".to_string(),
+            other => other.to_string(),
+        }
+    }
+
+    fn describe_all(old: &Group, new: &Group, editor: &str) -> Vec<String> {
+        diff_groups(old, new)
+            .iter()
+            .map(|c| c.describe(editor, &name_of))
+            .collect()
+    }
+
+    #[test]
+    fn unchanged_group_has_no_changes() {
+        let g = group(&["+1", "+2"], &["+1"]);
+        assert!(diff_groups(&g, &g.clone()).is_empty());
+    }
+
+    #[test]
+    fn membership_changes() {
+        let old = group(&["+1", "+2"], &["+1"]);
+        let new = group(&["+1", "+3", "+me"], &["+1"]);
+        assert_eq!(
+            describe_all(&old, &new, "+1"),
+            vec!["Alice added Carol and you", "Alice removed Bob"]
+        );
+    }
+
+    #[test]
+    fn join_and_leave_by_the_editor() {
+        let old = group(&["+1"], &[]);
+        let joined = group(&["+1", "+2"], &[]);
+        assert_eq!(
+            describe_all(&old, &joined, "+2"),
+            vec!["Bob joined the group"]
+        );
+        assert_eq!(
+            describe_all(&joined, &old, "+2"),
+            vec!["Bob left the group"]
+        );
+    }
+
+    #[test]
+    fn detail_and_admin_changes() {
+        let old = group(&["+1", "+2", "+3"], &["+1", "+3"]);
+        let mut new = group(&["+1", "+2", "+3"], &["+1", "+2"]);
+        new.name = "Weekend".to_string();
+        new.description = Some("Plans".to_string());
+        new.expiration_seconds = 604800;
+        assert_eq!(
+            describe_all(&old, &new, "+me"),
+            vec![
+                "you renamed the group to \"Weekend\"",
+                "you changed the group description",
+                "you set disappearing messages to 1 week",
+                "you made Bob an admin",
+                "you removed Carol as admin",
+            ]
+        );
+    }
+
+    #[test]
+    fn departed_admin_is_not_reported_as_demoted() {
+        let old = group(&["+1", "+2"], &["+1", "+2"]);
+        let new = group(&["+1"], &["+1"]);
+        assert_eq!(describe_all(&old, &new, "+1"), vec!["Alice removed Bob"]);
+    }
+
+    #[test]
+    fn invite_url_validation() {
+        let link = "https://signal.group/#CjQKIAbc-_12";
+        assert_eq!(group_invite_url(link), Some(link));
+        assert_eq!(group_invite_url(&format!("join us: {link}.")), Some(link));
+        assert_eq!(group_invite_url("https://signal.group/#"), None);
+        assert_eq!(group_invite_url("https://signal.group/#a/b"), None);
+        assert_eq!(group_invite_url("https://example.com/#abc"), None);
+    }
+}
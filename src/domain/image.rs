@@ -35,6 +35,9 @@ pub struct ImageState {
     pub cell_px: (u16, u16),
     /// Images visible on screen for native protocol overlay (cleared each frame)
     pub visible_images: Vec<VisibleImage>,
+    /// Images placed by the open overlay, such as a detail avatar; drawn
+    /// instead of the chat's while there are any (cleared each frame)
+    pub overlay_images: Vec<VisibleImage>,
     /// Previous scroll offset for Sixel stale pixel detection
     pub sixel_prev_scroll: usize,
     /// Previous frame's visible images, for skipping redundant image redraws
@@ -76,6 +79,7 @@ impl ImageState {
             image_protocol: image_render::detect_protocol(),
             cell_px: image_render::detect_cell_pixel_size(),
             visible_images: Vec::new(),
+            overlay_images: Vec::new(),
             sixel_prev_scroll: 0,
             prev_visible_images: Vec::new(),
             native_image_cache: HashMap::new(),
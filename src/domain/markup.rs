@@ -0,0 +1,420 @@
+//! Composer text formatting.
+//!
+//! Messages typed with `*bold*`, `_italic_`, `~strike~`, `` `mono` `` or
+//! `||spoiler||` are sent as plain text plus Signal `textStyle` ranges.
+//! [`parse_markup`] scans the raw composer buffer once and keeps the
+//! positions of both the markers and the styled runs, so the same result
+//! drives the composer's live preview ([`Markup::preview_ranges`],
+//! [`Markup::marker_ranges`]) and the outgoing message ([`Markup::to_plain`]).
+//! [`wire_text_styles`] then converts byte ranges on the plain text into the
+//! UTF-16 ranges signal-cli expects, accounting for mentions that collapse to
+//! a single placeholder on the wire. [`mask_spoilers`] blanks out spoiler text
+//! wherever a message is shown outside the chat pane.
+//!
+//! A marker only opens at the start of a word and only closes at the end of
+//! one, so `snake_case` and `2*3*4` stay literal. A backslash before a marker
+//! character (or another backslash) sends that character as-is: `\*not bold\*`.
+//! Markers can nest, except inside `` `mono` `` where text is taken literally.
+
+use crate::signal::types::{StyleType, TextStyle};
+
+/// Markers in the order they are tried at each position.
+const MARKERS: &[(&str, StyleType)] = &[
+    ("||", StyleType::Spoiler),
+    ("*", StyleType::Bold),
+    ("_", StyleType::Italic),
+    ("~", StyleType::Strikethrough),
+    ("`", StyleType::Monospace),
+];
+
+/// Characters a backslash escapes.
+const ESCAPABLE: &[char] = &['*', '_', '~', '`', '|', '\\'];
+
+/// Formatting found in a raw composer buffer. All offsets are byte offsets
+/// into the text that was parsed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Markup {
+    /// Styled runs, excluding their markers
+    styles: Vec<(usize, usize, StyleType)>,
+    /// Marker characters and escaping backslashes, which are not sent
+    hidden: Vec<(usize, usize)>,
+}
+
+impl Markup {
+    /// Styled runs on the raw text, for the composer preview.
+    pub fn preview_ranges(&self) -> &[(usize, usize, StyleType)] {
+        &self.styles
+    }
+
+    /// Markers and escape backslashes on the raw text, which the composer
+    /// dims because they will not be sent.
+    pub fn marker_ranges(&self) -> &[(usize, usize)] {
+        &self.hidden
+    }
+
+    /// Strip the markers from `raw` (the text this markup was parsed from).
+    /// Returns the text to send and its style ranges as byte offsets into it.
+    pub fn to_plain(&self, raw: &str) -> (String, Vec<(usize, usize, StyleType)>) {
+        let mut hidden = self.hidden.clone();
+        hidden.sort_by_key(|&(start, _)| start);
+
+        let mut plain = String::with_capacity(raw.len());
+        // raw byte offset -> plain byte offset, for every raw boundary
+        let mut map = Vec::with_capacity(raw.len() + 1);
+        let mut pos = 0;
+        for &(start, end) in &hidden {
+            map.extend((pos..start).map(|i| plain.len() + i - pos));
+            plain.push_str(&raw[pos..start]);
+            map.extend((start..end).map(|_| plain.len()));
+            pos = end;
+        }
+        map.extend((pos..=raw.len()).map(|i| plain.len() + i - pos));
+        plain.push_str(&raw[pos..]);
+
+        let mut styles: Vec<(usize, usize, StyleType)> = self
+            .styles
+            .iter()
+            .map(|&(start, end, style)| (map[start], map[end], style))
+            .filter(|(start, end, _)| start < end)
+            .collect();
+        styles.sort_by_key(|&(start, _, _)| start);
+        (plain, styles)
+    }
+}
+
+/// Find the formatting markers in `text`.
+pub fn parse_markup(text: &str) -> Markup {
+    let mut markup = Markup::default();
+    scan(text, 0, text.len(), false, &mut markup);
+    markup
+}
+
+/// Parse `text` and strip its markers in one go. Returns the text to send and
+/// its style ranges as byte offsets into that text.
+pub fn strip_markup(text: &str) -> (String, Vec<(usize, usize, StyleType)>) {
+    parse_markup(text).to_plain(text)
+}
+
+/// Write `text` and its style ranges back as composer markup, for editing a
+/// sent message. Marker characters in the text are escaped only when they
+/// would otherwise be read as markup. Ranges that cross each other cannot be
+/// written; if the styles cannot be expressed at all, the plain text is
+/// returned with its marker characters escaped.
+pub fn to_markup(text: &str, styles: &[(usize, usize, StyleType)]) -> String {
+    let mut sorted = styles.to_vec();
+    sorted.sort_by_key(|&(start, end, _)| (start, std::cmp::Reverse(end)));
+    let mut nested: Vec<(usize, usize, StyleType)> = Vec::new();
+    for range in sorted {
+        let fits = nested.iter().all(|&(start, end, _)| {
+            range.1 <= start || range.0 >= end || (range.0 >= start && range.1 <= end)
+        });
+        if fits && range.0 < range.1 && range.1 <= text.len() {
+            nested.push(range);
+        }
+    }
+
+    let mut expected = nested.clone();
+    expected.sort_by_key(|&(start, _, _)| start);
+    for escape_all in [false, true] {
+        let candidate = write_markup(text, &nested, escape_all);
+        if strip_markup(&candidate) == (text.to_string(), expected.clone()) {
+            return candidate;
+        }
+    }
+    write_markup(text, &[], true)
+}
+
+/// Interleave markers for properly nested `ranges` (sorted outermost first)
+/// with `text`, escaping marker characters if `escape_all`.
+fn write_markup(text: &str, ranges: &[(usize, usize, StyleType)], escape_all: bool) -> String {
+    let marker = |style: StyleType| {
+        MARKERS
+            .iter()
+            .find(|(_, s)| *s == style)
+            .map_or("", |(m, _)| *m)
+    };
+    let mut out = String::with_capacity(text.len() + ranges.len() * 4);
+    let mut open: Vec<(usize, StyleType)> = Vec::new();
+    let mut next = 0;
+    let boundaries = text
+        .char_indices()
+        .map(|(i, c)| (i, Some(c)))
+        .chain(std::iter::once((text.len(), None)));
+    for (i, ch) in boundaries {
+        while let Some(&(end, style)) = open.last()
+            && end <= i
+        {
+            out.push_str(marker(style));
+            open.pop();
+        }
+        while let Some(&(start, end, style)) = ranges.get(next)
+            && start <= i
+        {
+            out.push_str(marker(style));
+            open.push((end, style));
+            next += 1;
+        }
+        if let Some(ch) = ch {
+            if escape_all && ESCAPABLE.contains(&ch) {
+                out.push('\\');
+            }
+            out.push(ch);
+        }
+    }
+    out
+}
+
+/// Replace the text of each spoiler range with block characters, for
+/// places that show message text outside the chat pane (notifications,
+/// search results, exports). Line breaks are kept.
+pub fn mask_spoilers(text: &str, styles: &[(usize, usize, StyleType)]) -> String {
+    let spoilers: Vec<(usize, usize)> = styles
+        .iter()
+        .filter(|(_, _, style)| *style == StyleType::Spoiler)
+        .map(|&(start, end, _)| (start, end))
+        .collect();
+    if spoilers.is_empty() {
+        return text.to_string();
+    }
+    text.char_indices()
+        .map(|(i, c)| {
+            let hidden = spoilers.iter().any(|&(start, end)| start <= i && i < end);
+            if hidden && c != '\n' { '\u{2588}' } else { c }
+        })
+        .collect()
+}
+
+/// Convert byte style ranges on `text` into the UTF-16 ranges Signal uses.
+/// `collapsed` lists byte ranges (mentions) that are replaced by a single
+/// U+FFFC on the wire; a style boundary inside one snaps to its edge.
+pub fn wire_text_styles(
+    text: &str,
+    styles: &[(usize, usize, StyleType)],
+    collapsed: &[(usize, usize)],
+) -> Vec<TextStyle> {
+    let wire_offset = |byte: usize, is_end: bool| -> usize {
+        // A boundary inside a mention covers the whole mention
+        let byte = collapsed
+            .iter()
+            .find(|&&(start, end)| start < byte && byte < end)
+            .map_or(byte, |&(start, end)| if is_end { end } else { start });
+        let saved: usize = collapsed
+            .iter()
+            .filter(|&&(_, end)| end <= byte)
+            .map(|&(start, end)| text[start..end].encode_utf16().count() - 1)
+            .sum();
+        text[..byte].encode_utf16().count() - saved
+    };
+
+    styles
+        .iter()
+        .filter_map(|&(start, end, style)| {
+            let start = wire_offset(start, false);
+            let end = wire_offset(end, true);
+            (end > start).then(|| TextStyle {
+                start,
+                length: end - start,
+                style,
+            })
+        })
+        .collect()
+}
+
+/// Scan `text[from..to]` for escapes and (unless `literal`) markers.
+fn scan(text: &str, from: usize, to: usize, literal: bool, markup: &mut Markup) {
+    let mut i = from;
+    while i < to {
+        let rest = &text[i..to];
+        if let Some(len) = escape_len(rest) {
+            markup.hidden.push((i, i + 1));
+            i += len;
+            continue;
+        }
+        if !literal
+            && let Some((marker, style)) = opening_marker(text, from, i, to)
+            && let Some(close) = find_close(text, i + marker.len(), to, marker)
+        {
+            let inner_start = i + marker.len();
+            markup.hidden.push((i, inner_start));
+            markup.hidden.push((close, close + marker.len()));
+            markup.styles.push((inner_start, close, style));
+            scan(
+                text,
+                inner_start,
+                close,
+                style == StyleType::Monospace,
+                markup,
+            );
+            i = close + marker.len();
+            continue;
+        }
+        i += rest.chars().next().map_or(1, char::len_utf8);
+    }
+}
+
+/// Byte length of an escape sequence at the start of `rest`, if any.
+fn escape_len(rest: &str) -> Option<usize> {
+    let mut chars = rest.chars();
+    if chars.next() != Some('\\') {
+        return None;
+    }
+    chars
+        .next()
+        .filter(|c| ESCAPABLE.contains(c))
+        .map(|c| 1 + c.len_utf8())
+}
+
+/// The marker starting at `i`, if one can open there: it must begin a word
+/// and be followed by something other than whitespace.
+fn opening_marker(
+    text: &str,
+    from: usize,
+    i: usize,
+    to: usize,
+) -> Option<(&'static str, StyleType)> {
+    let &(marker, style) = MARKERS.iter().find(|(m, _)| text[i..to].starts_with(m))?;
+    let before_ok = i == from
+        || text[..i]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !c.is_alphanumeric());
+    let after_ok = text[i + marker.len()..to]
+        .chars()
+        .next()
+        .is_some_and(|c| !c.is_whitespace());
+    (before_ok && after_ok).then_some((marker, style))
+}
+
+/// Position of the marker closing a run that starts at `start`: it must end a
+/// word, and the run must not be empty. Escaped characters are skipped.
+fn find_close(text: &str, start: usize, to: usize, marker: &str) -> Option<usize> {
+    let mut j = start;
+    while j < to {
+        let rest = &text[j..to];
+        if let Some(len) = escape_len(rest) {
+            j += len;
+            continue;
+        }
+        if j > start && rest.starts_with(marker) {
+            let before_ok = text[..j]
+                .chars()
+                .next_back()
+                .is_some_and(|c| !c.is_whitespace());
+            let after_ok = text[j + marker.len()..to]
+                .chars()
+                .next()
+                .is_none_or(|c| !c.is_alphanumeric());
+            if before_ok && after_ok {
+                return Some(j);
+            }
+        }
+        j += rest.chars().next().map_or(1, char::len_utf8);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("*bold*", "bold", &[(0, 4, StyleType::Bold)])]
+    #[case("an _italic_ word", "an italic word", &[(3, 9, StyleType::Italic)])]
+    #[case("~gone~ `x = 1`", "gone x = 1", &[(0, 4, StyleType::Strikethrough), (5, 10, StyleType::Monospace)])]
+    #[case("the end is ||near||", "the end is near", &[(11, 15, StyleType::Spoiler)])]
+    #[case("*bold _both_*", "bold both", &[(0, 9, StyleType::Bold), (5, 9, StyleType::Italic)])]
+    #[case("`*not bold*`", "*not bold*", &[(0, 10, StyleType::Monospace)])]
+    fn markers_become_style_ranges(
+        #[case] raw: &str,
+        #[case] plain: &str,
+        #[case] styles: &[(usize, usize, StyleType)],
+    ) {
+        assert_eq!(strip_markup(raw), (plain.to_string(), styles.to_vec()));
+    }
+
+    #[rstest]
+    #[case("snake_case_name")]
+    #[case("2*3*4")]
+    #[case("* bullet *")]
+    #[case("a lone * star")]
+    #[case("**")]
+    #[case("a || b")]
+    #[case("C:\\path\\file")]
+    fn text_without_markup_is_unchanged(#[case] raw: &str) {
+        assert_eq!(strip_markup(raw), (raw.to_string(), Vec::new()));
+    }
+
+    #[test]
+    fn backslash_escapes_markers() {
+        assert_eq!(
+            strip_markup("\\*not bold\\* and \\\\"),
+            ("*not bold* and \\".to_string(), Vec::new())
+        );
+        // An escaped marker inside a run does not close it
+        assert_eq!(
+            strip_markup("*a \\* b*"),
+            ("a * b".to_string(), vec![(0, 5, StyleType::Bold)])
+        );
+    }
+
+    #[test]
+    fn preview_keeps_raw_offsets() {
+        let markup = parse_markup("hi *there* \\_");
+        assert_eq!(markup.preview_ranges(), &[(4, 9, StyleType::Bold)]);
+        let mut markers = markup.marker_ranges().to_vec();
+        markers.sort();
+        assert_eq!(markers, vec![(3, 4), (9, 10), (11, 12)]);
+    }
+
+    #[rstest]
+    #[case("plain", &[])]
+    #[case("bold and italic", &[(0, 4, StyleType::Bold), (9, 15, StyleType::Italic)])]
+    #[case("outer inner", &[(0, 11, StyleType::Bold), (6, 11, StyleType::Spoiler)])]
+    #[case("2*3*4 is *not* bold", &[(15, 19, StyleType::Bold)])]
+    #[case("snake_case", &[(0, 10, StyleType::Monospace)])]
+    fn to_markup_round_trips(#[case] text: &str, #[case] styles: &[(usize, usize, StyleType)]) {
+        let markup = to_markup(text, styles);
+        assert_eq!(strip_markup(&markup), (text.to_string(), styles.to_vec()));
+    }
+
+    #[test]
+    fn to_markup_escapes_only_when_needed() {
+        assert_eq!(to_markup("snake_case", &[]), "snake_case");
+        assert_eq!(
+            to_markup("*hi* there", &[(5, 10, StyleType::Bold)]),
+            "\\*hi\\* *there*"
+        );
+        // Mid-word styles cannot be written back; the text survives
+        assert_eq!(to_markup("abc", &[(1, 2, StyleType::Bold)]), "abc");
+    }
+
+    #[test]
+    fn mask_spoilers_hides_only_spoiler_text() {
+        let styles = [(0, 4, StyleType::Bold), (9, 16, StyleType::Spoiler)];
+        assert_eq!(
+            mask_spoilers("bold and über\nx end", &styles),
+            "bold and ████\n█ end"
+        );
+        assert_eq!(mask_spoilers("plain", &styles[..1]), "plain");
+    }
+
+    #[test]
+    fn wire_ranges_count_utf16_units() {
+        // "é" is one UTF-16 unit, the emoji is two
+        let (plain, styles) = strip_markup("é 😀 *bold*");
+        let wire = wire_text_styles(&plain, &styles, &[]);
+        assert_eq!(wire.len(), 1);
+        assert_eq!((wire[0].start, wire[0].length), (5, 4));
+    }
+
+    #[test]
+    fn wire_ranges_shift_past_collapsed_mentions() {
+        // "@Alice" goes out as a single U+FFFC
+        let (plain, styles) = strip_markup("@Alice said *hi* _@Alice_");
+        assert_eq!(plain, "@Alice said hi @Alice");
+        let wire = wire_text_styles(&plain, &styles, &[(0, 6), (15, 21)]);
+        let ranges: Vec<(usize, usize)> = wire.iter().map(|s| (s.start, s.length)).collect();
+        assert_eq!(ranges, vec![(7, 2), (10, 1)]);
+    }
+}
@@ -0,0 +1,100 @@
+//! Message info overlay state.
+//!
+//! Signal reports delivery, read and viewed receipts per recipient, but a
+//! message only carries the furthest status any of them reached. The
+//! `receipts` table keeps each recipient's times; this module holds what
+//! the "Message info" overlay shows for one message: a row per recipient
+//! (every group member, whether or not they have sent a receipt yet) and
+//! the reactions on it.
+
+/// Receipt times reported by one recipient of a message we sent.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecipientReceipt {
+    /// Phone number (or ACI when the number is hidden)
+    pub recipient: String,
+    pub delivered_ms: Option<i64>,
+    pub read_ms: Option<i64>,
+    pub viewed_ms: Option<i64>,
+}
+
+/// One reaction on a message, with who sent it and when.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReactionDetail {
+    pub sender: String,
+    pub emoji: String,
+    /// `None` for reactions stored before their time was recorded
+    pub reacted_at_ms: Option<i64>,
+}
+
+/// State for the message info overlay.
+#[derive(Default)]
+pub struct MessageInfoState {
+    pub conv_id: String,
+    pub timestamp_ms: i64,
+    pub is_outgoing: bool,
+    /// One row per recipient, empty for incoming messages
+    pub receipts: Vec<RecipientReceipt>,
+    pub reactions: Vec<ReactionDetail>,
+    /// First line shown when the content is taller than the popup
+    pub scroll: usize,
+}
+
+impl MessageInfoState {
+    /// Number of recipient and reaction rows, the most the overlay can scroll.
+    pub fn row_count(&self) -> usize {
+        self.receipts.len() + self.reactions.len()
+    }
+}
+
+/// Pair up the recipients of a message with the receipts they sent.
+/// Recipients come first, in the order given, with empty times when nothing
+/// has arrived from them; receipts from anyone else (a member who has since
+/// left, say) follow.
+pub fn merge_recipients(
+    recipients: &[String],
+    mut receipts: Vec<RecipientReceipt>,
+) -> Vec<RecipientReceipt> {
+    let mut rows: Vec<RecipientReceipt> = recipients
+        .iter()
+        .map(|r| match receipts.iter().position(|x| &x.recipient == r) {
+            Some(i) => receipts.remove(i),
+            None => RecipientReceipt {
+                recipient: r.clone(),
+                ..Default::default()
+            },
+        })
+        .collect();
+    rows.append(&mut receipts);
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn receipt(recipient: &str, read_ms: Option<i64>) -> RecipientReceipt {
+        RecipientReceipt {
+            recipient: recipient.to_string(),
+            delivered_ms: Some(1),
+            read_ms,
+            viewed_ms: None,
+        }
+    }
+
+    #[test]
+    fn merge_keeps_member_order_and_fills_gaps() {
+        let members = vec!["+1".to_string(), "+2".to_string(), "+3".to_string()];
+        let rows = merge_recipients(
+            &members,
+            vec![
+                receipt("+3", Some(5)),
+                receipt("+9", None),
+                receipt("+1", None),
+            ],
+        );
+        let names: Vec<&str> = rows.iter().map(|r| r.recipient.as_str()).collect();
+        assert_eq!(names, vec!["+1", "+2", "+3", "+9"]);
+        assert_eq!(rows[1].delivered_ms, None);
+        assert_eq!(rows[2].read_ms, Some(5));
+    }
+}
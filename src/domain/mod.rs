@@ -6,35 +6,74 @@
 //! since each was ~10-30 lines of pure-data struct and the per-file
 //! split added navigation cost without payoff.
 
+mod accounts;
+mod attachments;
+mod avatars;
+mod calls;
+mod contacts;
+mod devices;
 mod emoji_picker;
 mod file_picker;
+mod group_changes;
 mod image;
 mod input;
 mod lock;
+mod markup;
+mod message_info;
 mod mouse;
 mod notification;
+mod outbox;
 mod overlays;
 mod pending;
+mod rate_limit;
 mod reaction;
+mod revisions;
 mod scroll;
 mod search;
+mod stickers;
+mod stories;
 mod typing;
+mod usernames;
+mod view_once;
 
+pub use accounts::{AccountBadge, AccountsState, account_label};
+pub use attachments::{DownloadState, MessageAttachment, file_uri_to_path, parse_legacy_body};
+pub use avatars::{
+    AVATAR_DETAIL_WIDTH, AVATAR_GLYPH_WIDTH, AvatarState, RenderedAvatar, render_avatar,
+};
+pub use calls::{
+    CallDirection, CallOutcome, CallRecord, CallsState, RING_TIMEOUT_MS, format_call_duration,
+};
+pub use contacts::{
+    LocalContact, contact_vcard, parse_new_contact, parse_vcards, shared_contact_number,
+};
+pub use devices::{DevicesMode, DevicesState, device_link_uri};
 pub use emoji_picker::{CATEGORIES, EmojiPickerAction, EmojiPickerSource, EmojiPickerState};
-pub use file_picker::{FilePickerOutcome, FilePickerState};
+pub use file_picker::{FilePickerOutcome, FilePickerPurpose, FilePickerState};
+pub use group_changes::{GroupChange, PendingGroupUpdate, diff_groups, group_invite_url};
 pub use image::{ImageMode, ImageState};
 pub use input::InputState;
 pub use lock::{LockPhase, LockState};
 pub use lock::{hash_passphrase, load_hash, lock_hash_path, save_hash, verify_passphrase};
+pub use markup::{Markup, mask_spoilers, parse_markup, strip_markup, to_markup, wire_text_styles};
+pub use message_info::{MessageInfoState, ReactionDetail, RecipientReceipt, merge_recipients};
 pub use mouse::MouseState;
 pub use notification::{NotificationPreview, NotificationState};
+pub use outbox::{MAX_SEND_ATTEMPTS, OutboxEntry, retry_delay_ms};
 pub use overlays::{
-    ActionMenuState, ContactsOverlayState, ForwardOverlayState, GroupMenuOverlayState,
-    KeybindingsOverlayState, PinDurationOverlayState, PollVoteOverlayState, ProfileOverlayState,
-    SettingsOverlayState, SettingsProfileOverlayState, ThemePickerState, VerifyOverlayState,
+    ActionMenuState, AttachmentListState, ContactsMode, ContactsOverlayState, ForwardOverlayState,
+    GroupMenuOverlayState, KeybindingsOverlayState, PinDurationOverlayState, PollVoteOverlayState,
+    ProfileOverlayState, SettingsOverlayState, SettingsProfileOverlayState, ThemePickerState,
+    VerifyOverlayState,
 };
 pub use pending::PendingState;
+pub use rate_limit::{CAPTCHA_URL, DEFAULT_RETRY_AFTER_SECS, RateLimitState, captcha_token};
 pub use reaction::ReactionState;
+pub use revisions::{DiffChunk, EditHistoryState, MessageRevision};
 pub use scroll::ScrollState;
 pub use search::{SearchAction, SearchState};
+pub use stickers::{StickerPickerAction, StickerPickerState, sticker_pack_url};
+pub use stories::{StoriesState, Story};
 pub use typing::TypingState;
+pub use usernames::{parse_own_username, parse_username};
+pub use view_once::{VIEW_ONCE_VIEWED_BODY, ViewOnce, ViewOnceState, purge_media};
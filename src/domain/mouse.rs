@@ -13,6 +13,9 @@ pub struct MouseState {
     pub sidebar_inner: Option<Rect>,
     /// Inner area of the messages block.
     pub messages_area: Rect,
+    /// Message index drawn on each row of `messages_area`, top to bottom
+    /// (`None` for separators and blank rows).
+    pub message_rows: Vec<Option<usize>>,
     /// Outer area of the composer input box (includes borders).
     pub input_area: Rect,
     /// Badge + "> " length in the composer input box.
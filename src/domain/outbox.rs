@@ -0,0 +1,64 @@
+//! Outbox: outgoing messages Signal hasn't confirmed yet.
+//!
+//! Every `SendRequest::Message` is written to the `outbox` table when it is
+//! dispatched and removed once signal-cli reports its server timestamp, so
+//! unconfirmed messages survive a restart. A message that couldn't go out
+//! (signal-cli down, or a `SendFailed`) shows as queued and is retried with
+//! exponential backoff while connected. After [`MAX_SEND_ATTEMPTS`] failures
+//! it is marked failed and waits for the user to resend or discard it. A
+//! send that timed out may still have gone out, so it is marked failed at
+//! once rather than retried.
+
+use crate::app::SendRequest;
+
+/// Failed attempts before a message stops being retried automatically.
+pub const MAX_SEND_ATTEMPTS: u32 = 5;
+
+/// Delay before the first retry; doubles with each failed attempt.
+const BASE_RETRY_DELAY_MS: i64 = 5_000;
+
+/// Longest wait between retries.
+const MAX_RETRY_DELAY_MS: i64 = 5 * 60 * 1000;
+
+/// One unconfirmed message.
+#[derive(Clone)]
+pub struct OutboxEntry {
+    pub conv_id: String,
+    pub local_ts_ms: i64,
+    /// The full `SendRequest::Message`, replayed as-is on retry
+    pub request: SendRequest,
+    /// Failed attempts so far
+    pub attempts: u32,
+    /// When to try again (epoch ms). `None` while in flight, or once
+    /// `attempts` reaches [`MAX_SEND_ATTEMPTS`].
+    pub next_attempt_ms: Option<i64>,
+}
+
+impl OutboxEntry {
+    /// Automatic retries have given up on this message.
+    pub fn exhausted(&self) -> bool {
+        self.attempts >= MAX_SEND_ATTEMPTS
+    }
+}
+
+/// Wait before retrying a message that has failed `attempts` times.
+pub fn retry_delay_ms(attempts: u32) -> i64 {
+    let doublings = attempts.saturating_sub(1).min(16);
+    (BASE_RETRY_DELAY_MS << doublings).min(MAX_RETRY_DELAY_MS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(1, 5_000)]
+    #[case(2, 10_000)]
+    #[case(4, 40_000)]
+    #[case(7, 300_000)]
+    #[case(40, 300_000)]
+    fn retry_delay_doubles_up_to_cap(#[case] attempts: u32, #[case] expected: i64) {
+        assert_eq!(retry_delay_ms(attempts), expected);
+    }
+}
@@ -25,6 +25,14 @@ pub struct ActionMenuState {
     pub index: usize,
 }
 
+/// State for the attachment list overlay (one entry per file on the
+/// focused message).
+#[derive(Default)]
+pub struct AttachmentListState {
+    /// Cursor position in the attachment list
+    pub index: usize,
+}
+
 /// State for the settings overlay and its Customize sub-overlay.
 #[derive(Default)]
 pub struct SettingsOverlayState {
@@ -39,6 +47,27 @@ pub struct SettingsOverlayState {
     pub mouse_snapshot: bool,
 }
 
+/// Which screen of the contacts overlay is showing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContactsMode {
+    #[default]
+    List,
+    /// Details and actions for the selected contact (Tab)
+    Menu,
+    /// Text input: local nickname (pre-filled)
+    Nickname,
+    /// Text input: local note (pre-filled)
+    Note,
+    /// Text input: name in signal-cli's contact list (pre-filled)
+    Rename,
+    /// Text input: `<number> <name>`
+    Add,
+    /// Text input: path to a `.vcf` file
+    Import,
+    /// y/n confirmation before removing the selected contact
+    ConfirmRemove,
+}
+
 /// State for the contacts list overlay.
 #[derive(Default)]
 pub struct ContactsOverlayState {
@@ -48,6 +77,13 @@ pub struct ContactsOverlayState {
     pub filter: String,
     /// Filtered list of (phone_number, display_name)
     pub filtered: Vec<(String, String)>,
+    /// Current screen (list, contact menu, or one of its inputs)
+    pub mode: ContactsMode,
+    /// Text buffer for the nickname, note, rename, add and import inputs
+    pub input: String,
+    /// Opened by `/share-contact`: Enter sends the selected contact as a
+    /// card to the active conversation instead of opening theirs
+    pub sharing: bool,
 }
 
 /// State for the forward message picker overlay.
@@ -103,6 +139,10 @@ pub struct ProfileOverlayState {
     pub fields: [String; 4],
     /// Temp buffer while editing a profile field
     pub edit_buffer: String,
+    /// Our Signal username (`nickname.discriminator`), if we have one
+    pub username: Option<String>,
+    /// Shareable `signal.me` link for `username`
+    pub username_link: Option<String>,
 }
 
 /// State for the group management menu overlay.
@@ -3,12 +3,23 @@
 //! Tracks send RPCs awaiting a `SendTimestamp` response (`sends`),
 //! receipts that arrived before their matching send (`receipts`), the
 //! queued typing-stop request from conversation switches (`typing_stop`),
-//! and queued outgoing read receipts (`read_receipts`). All four are
-//! drained by the main event loop.
+//! queued outgoing read receipts (`read_receipts`), stickers whose
+//! image still has to be fetched (`sticker_fetches`, `outgoing_stickers`),
+//! avatars to fetch
+//! (`avatar_fetches`), group updates waiting on a `listGroups`
+//! refresh (`group_updates`, `refresh_groups`), sends held until a
+//! changed safety number is acknowledged (`held_sends`, `released_sends`),
+//! sends refused by a rate limit (`rate_limited_sends`), and the
+//! in-memory copy of the `outbox` table (`outbox`). All but the outbox are
+//! drained by the main event loop. `payloads` keeps the original request
+//! for each entry in `sends` so it can be replayed if signal-cli restarts
+//! before confirming it.
 
 use std::collections::HashMap;
 
 use crate::app::SendRequest;
+use crate::domain::{OutboxEntry, PendingGroupUpdate};
+use crate::signal::types::{AvatarRef, StickerRef};
 
 /// State for in-flight signal-cli work awaiting confirmation or dispatch.
 #[derive(Default)]
@@ -19,15 +30,102 @@ pub struct PendingState {
     /// `SendTimestamp` (success) or `SendFailed` (error). Used to correlate
     /// signal-cli responses with local messages.
     pub sends: HashMap<String, (String, i64)>,
+    /// Original request for each entry in `sends`, keyed by the same rpc id.
+    ///
+    /// Removed alongside `sends`. Drained by `take_replayable()` after a
+    /// signal-cli respawn so unconfirmed messages are dispatched again.
+    pub payloads: HashMap<String, SendRequest>,
     /// Receipts that arrived before their matching `SendTimestamp`.
     ///
     /// Populated by `handle_receipt()` when no matching pending send exists
     /// yet. Drained immediately after each `SendTimestamp` confirms a send.
-    pub receipts: Vec<(String, String, Vec<i64>)>,
+    /// `(sender, receipt_type, timestamps, when_ms)`.
+    pub receipts: Vec<(String, String, Vec<i64>, i64)>,
     /// Queued typing-stop request from conversation switches.
     ///
     /// Drained by the main loop.
     pub typing_stop: Option<SendRequest>,
     /// Queued read receipts to dispatch: `(recipient_phone, timestamps)`.
     pub read_receipts: Vec<(String, Vec<i64>)>,
+    /// Sticker images to fetch from signal-cli: `(conv_id, timestamp_ms, sticker)`.
+    ///
+    /// Populated for sticker messages that arrive without a local image.
+    pub sticker_fetches: Vec<(String, i64, StickerRef)>,
+    /// Stickers we sent whose image is still being fetched:
+    /// `(conv_id, local_timestamp_ms) -> current timestamp_ms`.
+    ///
+    /// Populated by `dispatch_send()` and kept up to date by `SendTimestamp`,
+    /// so the image finds its message after it is re-keyed to the server
+    /// timestamp. Drained when the `StickerFetched` event arrives.
+    pub outgoing_stickers: HashMap<(String, i64), i64>,
+    /// Avatars to fetch from signal-cli. Populated by `App::queue_avatar`.
+    pub avatar_fetches: Vec<AvatarRef>,
+    /// Group updates to describe once the next `listGroups` result arrives.
+    ///
+    /// Populated by `GroupUpdated` events; drained by `handle_group_list()`.
+    pub group_updates: Vec<PendingGroupUpdate>,
+    /// Set when the group list should be re-fetched. Drained by the main loop.
+    pub refresh_groups: bool,
+    /// Sends waiting on a recipient whose safety number changed.
+    ///
+    /// Populated by `App::hold_for_identity_change()`, by sends signal-cli
+    /// refused over an untrusted identity, and on load for outbox messages
+    /// still waiting. Held messages keep their outbox entry, unscheduled.
+    /// Moved to `released_sends` by `App::acknowledge_identity()` once
+    /// nobody they go to is still flagged.
+    pub held_sends: Vec<SendRequest>,
+    /// Held sends cleared to go, oldest first. Drained by the main loop.
+    pub released_sends: Vec<SendRequest>,
+    /// Sends signal-cli refused because Signal rate-limited us, and those
+    /// made while the limit lasts, oldest first. Held messages keep their
+    /// outbox entry, unscheduled.
+    ///
+    /// Moved to `released_sends` by `App::retry_rate_limited()` once the
+    /// challenge is answered, when the user retries a plain rate limit, or
+    /// by `App::release_rate_limited()` once Signal's wait is over.
+    pub rate_limited_sends: Vec<SendRequest>,
+    /// Warnings about recipients a send went out without: `rpc_id -> lines`.
+    ///
+    /// Populated when signal-cli reports a send that reached only some of a
+    /// group. Drained by the matching `SendTimestamp`, which adds the lines
+    /// under the sent message.
+    pub undelivered: HashMap<String, Vec<String>>,
+    /// Messages not yet confirmed by Signal, oldest first. Mirrors the
+    /// `outbox` table: loaded by `App::load_from_db()`, entries added by
+    /// `App::admit_send()` and removed on `SendTimestamp` or discard.
+    pub outbox: Vec<OutboxEntry>,
+}
+
+impl PendingState {
+    /// Forget an in-flight send once signal-cli has answered it.
+    /// Returns the `(conv_id, local_timestamp_ms)` pair if it was tracked.
+    pub fn resolve_send(&mut self, rpc_id: &str) -> Option<(String, i64)> {
+        self.payloads.remove(rpc_id);
+        self.sends.remove(rpc_id)
+    }
+
+    /// The outbox entry for a message, if it hasn't been confirmed yet.
+    pub fn outbox_entry(&mut self, conv_id: &str, local_ts_ms: i64) -> Option<&mut OutboxEntry> {
+        self.outbox
+            .iter_mut()
+            .find(|e| e.conv_id == conv_id && e.local_ts_ms == local_ts_ms)
+    }
+
+    /// Drain every unconfirmed send, oldest first, for re-dispatch after a
+    /// respawn. The old rpc ids are dead with the previous process, so every
+    /// `sends` entry is dropped too, including those with no payload to
+    /// replay (edits); re-dispatch registers fresh ones.
+    pub fn take_replayable(&mut self) -> Vec<(String, SendRequest)> {
+        let mut out: Vec<(i64, String, SendRequest)> = self
+            .payloads
+            .drain()
+            .map(|(rpc_id, req)| {
+                let local_ts = self.sends.get(&rpc_id).map_or(0, |(_, ts)| *ts);
+                (local_ts, rpc_id, req)
+            })
+            .collect();
+        self.sends.clear();
+        out.sort_by_key(|(ts, _, _)| *ts);
+        out.into_iter().map(|(_, id, req)| (id, req)).collect()
+    }
 }
@@ -0,0 +1,78 @@
+//! Rate-limit challenge: the overlay state and captcha token validation.
+//!
+//! When Signal rate-limits our sends it either asks us to wait or, for a
+//! proof-required failure, hands out a challenge token that is answered
+//! with a captcha solved at signalcaptchas.org. Refused sends, and any
+//! sent while the limit lasts, are held in
+//! [`super::PendingState::rate_limited_sends`]. They go out again once the
+//! captcha is accepted or, without a challenge, once the wait is over or
+//! the user retries.
+
+/// Where the captcha for a rate-limit challenge is solved.
+pub const CAPTCHA_URL: &str = "https://signalcaptchas.org/challenge/generate.html";
+
+/// How long to wait when Signal doesn't say.
+pub const DEFAULT_RETRY_AFTER_SECS: i64 = 60;
+
+/// State for the rate-limit challenge overlay.
+#[derive(Default)]
+pub struct RateLimitState {
+    /// Token of the latest proof-required challenge, if Signal sent one
+    pub challenge: Option<String>,
+    /// When Signal said we may send again (epoch ms)
+    pub retry_at_ms: Option<i64>,
+    /// Captcha token being pasted
+    pub input: String,
+}
+
+impl RateLimitState {
+    /// Whether sends should be held: a challenge is unanswered, or Signal's
+    /// wait isn't over yet.
+    pub fn blocks_sends(&self, now_ms: i64) -> bool {
+        self.challenge.is_some() || self.retry_at_ms.is_some_and(|at| at > now_ms)
+    }
+}
+
+/// Validate a captcha token pasted from the browser once the captcha is
+/// solved: the `signalcaptcha://` link the page tries to open.
+pub fn captcha_token(text: &str) -> Option<String> {
+    let token = text.trim();
+    let rest = token.strip_prefix("signalcaptcha://")?;
+    (!rest.is_empty() && !rest.contains(char::is_whitespace)).then(|| token.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(
+        "  signalcaptcha://signal-hcaptcha.5fad.challenge.P1_eyJ0 \n",
+        Some("signalcaptcha://signal-hcaptcha.5fad.challenge.P1_eyJ0")
+    )]
+    #[case("signalcaptcha://", None)]
+    #[case("signal-hcaptcha.5fad.challenge.P1_eyJ0", None)]
+    #[case("signalcaptcha://two words", None)]
+    fn captcha_token_validation(#[case] input: &str, #[case] expected: Option<&str>) {
+        assert_eq!(captcha_token(input).as_deref(), expected);
+    }
+
+    #[rstest]
+    #[case(None, None, false)]
+    #[case(None, Some(2_000), true)]
+    #[case(None, Some(1_000), false)]
+    #[case(Some("chal"), Some(1_000), true)]
+    fn sends_are_blocked_until_cleared(
+        #[case] challenge: Option<&str>,
+        #[case] retry_at_ms: Option<i64>,
+        #[case] blocked: bool,
+    ) {
+        let state = RateLimitState {
+            challenge: challenge.map(str::to_string),
+            retry_at_ms,
+            ..Default::default()
+        };
+        assert_eq!(state.blocks_sends(1_000), blocked);
+    }
+}
@@ -0,0 +1,259 @@
+//! Edit history: stored revisions of a message and the word diff between
+//! two of them.
+//!
+//! Every edit, ours or the sender's, adds a row to the `message_revisions`
+//! table; the first edit also stores the original text, so the history
+//! always starts with what was first sent. The edit history overlay lists
+//! the revisions oldest first and shows what each one changed, working the
+//! diff out once per selection rather than on every frame.
+
+use super::mask_spoilers;
+use crate::signal::types::StyleType;
+
+/// Largest diff table (words before times words after) worth building;
+/// past this the diff is shown as the whole old text, then the new.
+const DIFF_MAX_CELLS: usize = 250_000;
+
+/// One stored version of a message body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageRevision {
+    /// When this version was sent (the message timestamp for the original)
+    pub revision_ms: i64,
+    pub body: String,
+    /// Text style ranges on `body`
+    pub style_ranges: Vec<(usize, usize, StyleType)>,
+    /// Whether we wrote this version (from this or a linked device)
+    pub is_outgoing: bool,
+}
+
+/// A run of text in a diff between two revisions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffChunk {
+    Same(String),
+    Added(String),
+    Removed(String),
+}
+
+/// Split text into alternating runs of whitespace and non-whitespace, so a
+/// diff works on whole words and keeps the original spacing.
+fn tokens(text: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    let mut prev_space: Option<bool> = None;
+    for (i, c) in text.char_indices() {
+        let space = c.is_whitespace();
+        if prev_space.is_some_and(|p| p != space) {
+            out.push(&text[start..i]);
+            start = i;
+        }
+        prev_space = Some(space);
+    }
+    if start < text.len() {
+        out.push(&text[start..]);
+    }
+    out
+}
+
+/// Word-level diff from `old` to `new`, via the longest common
+/// subsequence of their words. Adjacent runs of the same kind are merged,
+/// and removals come before additions where both replace the same span.
+/// Texts too long to compare word by word come back as a plain removal of
+/// `old` and addition of `new`.
+pub fn diff_words(old: &str, new: &str) -> Vec<DiffChunk> {
+    let a = tokens(old);
+    let b = tokens(new);
+    if (a.len() + 1).saturating_mul(b.len() + 1) > DIFF_MAX_CELLS {
+        let mut chunks = Vec::new();
+        if !old.is_empty() {
+            chunks.push(DiffChunk::Removed(old.to_string()));
+        }
+        if !new.is_empty() {
+            chunks.push(DiffChunk::Added(new.to_string()));
+        }
+        return chunks;
+    }
+    // lcs[i][j] = length of the LCS of a[i..] and b[j..]
+    let mut lcs = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut chunks: Vec<DiffChunk> = Vec::new();
+    let mut push = |chunk: DiffChunk| match (chunks.last_mut(), chunk) {
+        (Some(DiffChunk::Same(s)), DiffChunk::Same(t))
+        | (Some(DiffChunk::Added(s)), DiffChunk::Added(t))
+        | (Some(DiffChunk::Removed(s)), DiffChunk::Removed(t)) => s.push_str(&t),
+        (_, chunk) => chunks.push(chunk),
+    };
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            push(DiffChunk::Same(a[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            push(DiffChunk::Removed(a[i].to_string()));
+            i += 1;
+        } else {
+            push(DiffChunk::Added(b[j].to_string()));
+            j += 1;
+        }
+    }
+    chunks
+}
+
+/// State for the edit history overlay.
+#[derive(Default)]
+pub struct EditHistoryState {
+    /// Conversation and timestamp of the message being shown
+    pub conv_id: String,
+    pub timestamp_ms: i64,
+    /// Stored revisions, oldest first
+    pub revisions: Vec<MessageRevision>,
+    /// Cursor over `revisions`
+    pub index: usize,
+    /// Show spoilers unmasked (the message was revealed)
+    pub revealed: bool,
+    /// What the selected revision changed from the one before it
+    pub diff: Vec<DiffChunk>,
+}
+
+impl EditHistoryState {
+    /// Select revision `index` and work out its diff.
+    pub fn select(&mut self, index: usize) {
+        self.index = index;
+        let text_of = |rev: &MessageRevision| {
+            if self.revealed {
+                rev.body.clone()
+            } else {
+                mask_spoilers(&rev.body, &rev.style_ranges)
+            }
+        };
+        self.diff = match self.selected_pair() {
+            Some((Some(previous), current)) => diff_words(&text_of(previous), &text_of(current)),
+            Some((None, original)) => vec![DiffChunk::Same(text_of(original))],
+            None => Vec::new(),
+        };
+    }
+
+    /// The selected revision and the one before it (`None` for the
+    /// original), for drawing the diff.
+    pub fn selected_pair(&self) -> Option<(Option<&MessageRevision>, &MessageRevision)> {
+        let current = self.revisions.get(self.index)?;
+        let previous = self
+            .index
+            .checked_sub(1)
+            .and_then(|i| self.revisions.get(i));
+        Some((previous, current))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn same(s: &str) -> DiffChunk {
+        DiffChunk::Same(s.to_string())
+    }
+    fn added(s: &str) -> DiffChunk {
+        DiffChunk::Added(s.to_string())
+    }
+    fn removed(s: &str) -> DiffChunk {
+        DiffChunk::Removed(s.to_string())
+    }
+
+    #[rstest]
+    #[case("see you at 8", "see you at 9", vec![same("see you at "), removed("8"), added("9")])]
+    #[case("hello", "hello there", vec![same("hello"), added(" there")])]
+    #[case("hello there", "hello", vec![same("hello"), removed(" there")])]
+    #[case("same", "same", vec![same("same")])]
+    #[case("", "new", vec![added("new")])]
+    #[case(
+        "the quick fox",
+        "a quick brown fox",
+        vec![removed("the"), added("a"), same(" quick "), added("brown "), same("fox")]
+    )]
+    fn diff_words_cases(#[case] old: &str, #[case] new: &str, #[case] expected: Vec<DiffChunk>) {
+        assert_eq!(diff_words(old, new), expected);
+    }
+
+    #[test]
+    fn diff_reassembles_both_sides() {
+        let (old, new) = ("line one\nline  two", "line 1\nline  two!");
+        let chunks = diff_words(old, new);
+        let side = |keep_added: bool| -> String {
+            chunks
+                .iter()
+                .filter_map(|c| match c {
+                    DiffChunk::Same(s) => Some(s.as_str()),
+                    DiffChunk::Added(s) if keep_added => Some(s.as_str()),
+                    DiffChunk::Removed(s) if !keep_added => Some(s.as_str()),
+                    _ => None,
+                })
+                .collect()
+        };
+        assert_eq!(side(false), old);
+        assert_eq!(side(true), new);
+    }
+
+    #[test]
+    fn selected_pair_starts_with_the_original() {
+        let rev = |ms: i64, body: &str| MessageRevision {
+            revision_ms: ms,
+            body: body.to_string(),
+            style_ranges: Vec::new(),
+            is_outgoing: false,
+        };
+        let mut state = EditHistoryState {
+            revisions: vec![rev(1, "a"), rev(2, "b")],
+            ..Default::default()
+        };
+        let (prev, cur) = state.selected_pair().unwrap();
+        assert!(prev.is_none());
+        assert_eq!(cur.body, "a");
+        state.index = 1;
+        let (prev, cur) = state.selected_pair().unwrap();
+        assert_eq!(prev.unwrap().body, "a");
+        assert_eq!(cur.body, "b");
+    }
+
+    #[test]
+    fn select_diffs_against_the_previous_revision() {
+        let rev = |ms: i64, body: &str| MessageRevision {
+            revision_ms: ms,
+            body: body.to_string(),
+            style_ranges: vec![(0, 3, StyleType::Spoiler)],
+            is_outgoing: false,
+        };
+        let mut state = EditHistoryState {
+            revisions: vec![rev(1, "abc at 8"), rev(2, "abc at 9")],
+            ..Default::default()
+        };
+        state.select(1);
+        assert_eq!(
+            state.diff,
+            vec![
+                same("\u{2588}\u{2588}\u{2588} at "),
+                removed("8"),
+                added("9")
+            ]
+        );
+        state.revealed = true;
+        state.select(0);
+        assert_eq!(state.diff, vec![same("abc at 8")]);
+    }
+
+    #[test]
+    fn oversized_diff_falls_back_to_before_and_after() {
+        let old = "word ".repeat(600);
+        let new = "other ".repeat(600);
+        assert_eq!(diff_words(&old, &new), vec![removed(&old), added(&new)]);
+    }
+}
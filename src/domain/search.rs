@@ -6,11 +6,13 @@
 //! `index` is the cursor over results. `handle_key` returns a
 //! `SearchAction` for the App to dispatch (jump, status, cancel).
 //! `jump_to_result` powers `n`/`N` traversal within the active
-//! conversation with wrap-around.
+//! conversation with wrap-around. Tab toggles `include_revisions`, which
+//! also matches text that was later edited away.
 
 use crossterm::event::KeyCode;
 
 use crate::db::Database;
+use crate::signal::types::StyleType;
 
 /// A search result entry.
 #[derive(Debug, Clone)]
@@ -20,6 +22,10 @@ pub struct SearchResult {
     pub timestamp_ms: i64,
     pub conv_id: String,
     pub conv_name: String,
+    /// Text style ranges on `body`; spoilers stay masked in the result list
+    pub style_ranges: Vec<(usize, usize, StyleType)>,
+    /// Only an earlier version matched; `body` is that version's text
+    pub from_revision: bool,
 }
 
 /// Action returned by `SearchState::handle_key` / `jump_to_result` for App to dispatch.
@@ -44,6 +50,8 @@ pub struct SearchState {
     pub query: String,
     pub results: Vec<SearchResult>,
     pub index: usize,
+    /// Also match earlier versions of edited messages
+    pub include_revisions: bool,
 }
 
 impl SearchState {
@@ -88,6 +96,10 @@ impl SearchState {
                 self.query.clear();
                 return SearchAction::Cancel;
             }
+            KeyCode::Tab => {
+                self.include_revisions = !self.include_revisions;
+                self.run(active_conversation, db);
+            }
             KeyCode::Backspace if !self.query.is_empty() => {
                 self.query.pop();
                 self.run(active_conversation, db);
@@ -109,21 +121,31 @@ impl SearchState {
             return;
         }
         let results = if let Some(conv_id) = active_conversation {
-            db.search_messages(conv_id, &self.query, 50)
+            db.search_messages(conv_id, &self.query, 50, self.include_revisions)
         } else {
-            db.search_all_messages(&self.query, 50)
+            db.search_all_messages(&self.query, 50, self.include_revisions)
         };
         match results {
             Ok(rows) => {
                 self.results = rows
                     .into_iter()
                     .map(
-                        |(sender, body, timestamp_ms, conv_id, conv_name)| SearchResult {
+                        |(
+                            sender,
+                            body,
+                            timestamp_ms,
+                            conv_id,
+                            conv_name,
+                            style_ranges,
+                            from_revision,
+                        )| SearchResult {
                             sender,
                             body,
                             timestamp_ms,
                             conv_id,
                             conv_name,
+                            style_ranges,
+                            from_revision,
                         },
                     )
                     .collect();
@@ -0,0 +1,285 @@
+//! Sticker picker overlay: state, key handling, and pack links.
+//!
+//! The picker lists the packs installed in signal-cli (fetched with
+//! `listStickerPacks` each time it opens) as a tab row, with the selected
+//! pack's stickers shown as a grid of their emoji. Navigation mirrors the
+//! emoji picker: h/j/k/l move in the grid, Tab cycles packs. Packs are
+//! installed from `signal.art` add-stickers links, found in message text by
+//! [`sticker_pack_url`].
+
+use crossterm::event::KeyCode;
+
+use crate::signal::types::{StickerInfo, StickerPack, StickerRef};
+
+const PACK_URL_PREFIX: &str = "https://signal.art/addstickers/#";
+
+/// Action returned by the sticker picker key handler.
+pub enum StickerPickerAction {
+    /// User chose a sticker to send.
+    Select(StickerRef),
+    /// User closed the picker without selecting.
+    Close,
+    /// No action (internal navigation).
+    None,
+}
+
+/// State for the sticker picker overlay.
+pub struct StickerPickerState {
+    /// Installed packs, ordered by title
+    pub packs: Vec<StickerPack>,
+    pub pack_index: usize,
+    pub selected_index: usize,
+    /// Grid columns for keyboard navigation (derived from STICKER_POPUP_WIDTH).
+    pub cols: usize,
+    /// True while the pack list is being fetched.
+    pub loading: bool,
+    /// Why the last fetch failed, shown in place of the grid.
+    pub error: Option<String>,
+}
+
+impl Default for StickerPickerState {
+    fn default() -> Self {
+        Self {
+            packs: Vec::new(),
+            pack_index: 0,
+            selected_index: 0,
+            cols: 16,
+            loading: false,
+            error: None,
+        }
+    }
+}
+
+impl StickerPickerState {
+    /// Reset the cursor and mark the pack list as loading. Caller must also
+    /// queue `SendRequest::ListStickerPacks` and call `App::open_overlay`.
+    pub fn open(&mut self) {
+        self.pack_index = 0;
+        self.selected_index = 0;
+        self.loading = true;
+        self.error = None;
+    }
+
+    /// Replace the pack list with the installed packs from a fresh fetch.
+    pub fn set_packs(&mut self, packs: Vec<StickerPack>) {
+        let mut packs: Vec<StickerPack> = packs
+            .into_iter()
+            .filter(|p| p.installed && !p.stickers.is_empty())
+            .collect();
+        packs.sort_by_key(|p| p.title.to_lowercase());
+        self.packs = packs;
+        self.loading = false;
+        self.error = None;
+        if self.pack_index >= self.packs.len() {
+            self.pack_index = 0;
+        }
+        self.clamp_selection();
+    }
+
+    /// Record a failed fetch.
+    pub fn set_error(&mut self, error: String) {
+        self.loading = false;
+        self.error = Some(error);
+    }
+
+    pub fn current_pack(&self) -> Option<&StickerPack> {
+        self.packs.get(self.pack_index)
+    }
+
+    pub fn selected_sticker(&self) -> Option<&StickerInfo> {
+        self.current_pack()?.stickers.get(self.selected_index)
+    }
+
+    fn sticker_count(&self) -> usize {
+        self.current_pack().map_or(0, |p| p.stickers.len())
+    }
+
+    fn clamp_selection(&mut self) {
+        let count = self.sticker_count();
+        if count == 0 {
+            self.selected_index = 0;
+        } else if self.selected_index >= count {
+            self.selected_index = count - 1;
+        }
+    }
+
+    /// Handle a key press. Returns an action for the caller to dispatch.
+    pub fn handle_key(&mut self, code: KeyCode) -> StickerPickerAction {
+        let count = self.sticker_count();
+        match code {
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                match (self.current_pack(), self.selected_sticker()) {
+                    (Some(pack), Some(sticker)) => StickerPickerAction::Select(StickerRef {
+                        pack_id: pack.pack_id.clone(),
+                        sticker_id: sticker.id,
+                        emoji: sticker.emoji.clone(),
+                        local_path: None,
+                    }),
+                    _ => StickerPickerAction::None,
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => StickerPickerAction::Close,
+
+            KeyCode::Char('h') | KeyCode::Left => {
+                self.selected_index = self.selected_index.saturating_sub(1);
+                StickerPickerAction::None
+            }
+            KeyCode::Char('l') | KeyCode::Right => {
+                if self.selected_index + 1 < count {
+                    self.selected_index += 1;
+                }
+                StickerPickerAction::None
+            }
+            KeyCode::Char('j') | KeyCode::Down => {
+                if self.selected_index + self.cols < count {
+                    self.selected_index += self.cols;
+                }
+                StickerPickerAction::None
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.selected_index = self.selected_index.saturating_sub(self.cols);
+                StickerPickerAction::None
+            }
+
+            KeyCode::Tab if !self.packs.is_empty() => {
+                self.pack_index = (self.pack_index + 1) % self.packs.len();
+                self.selected_index = 0;
+                StickerPickerAction::None
+            }
+            KeyCode::BackTab if !self.packs.is_empty() => {
+                self.pack_index = self
+                    .pack_index
+                    .checked_sub(1)
+                    .unwrap_or(self.packs.len() - 1);
+                self.selected_index = 0;
+                StickerPickerAction::None
+            }
+
+            _ => StickerPickerAction::None,
+        }
+    }
+}
+
+/// Find a `signal.art` add-stickers link in `text`. The link must carry
+/// both a hex `pack_id` and a hex `pack_key` to be installable.
+pub fn sticker_pack_url(text: &str) -> Option<&str> {
+    text.split_whitespace()
+        .map(|word| word.trim_end_matches(['.', ',', ')', '>', ']']))
+        .find(|word| {
+            let Some(fragment) = word.strip_prefix(PACK_URL_PREFIX) else {
+                return false;
+            };
+            let param = |name: &str| {
+                fragment.split('&').find_map(|kv| {
+                    kv.strip_prefix(name)
+                        .and_then(|rest| rest.strip_prefix('='))
+                        .filter(|v| !v.is_empty() && v.chars().all(|c| c.is_ascii_hexdigit()))
+                })
+            };
+            param("pack_id").is_some() && param("pack_key").is_some()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pack(title: &str, installed: bool, n: u32) -> StickerPack {
+        StickerPack {
+            pack_id: format!("{:032x}", title.len()),
+            title: title.to_string(),
+            author: String::new(),
+            installed,
+            stickers: (0..n)
+                .map(|id| StickerInfo {
+                    id,
+                    emoji: Some("\u{1F600}".to_string()),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn set_packs_keeps_installed_sorted() {
+        let mut state = StickerPickerState::default();
+        state.open();
+        state.set_packs(vec![
+            pack("Zebra", true, 3),
+            pack("Remote", false, 3),
+            pack("apple", true, 2),
+            pack("Empty", true, 0),
+        ]);
+        let titles: Vec<&str> = state.packs.iter().map(|p| p.title.as_str()).collect();
+        assert_eq!(titles, vec!["apple", "Zebra"]);
+        assert!(!state.loading);
+    }
+
+    #[test]
+    fn grid_navigation_and_pack_cycling() {
+        let mut state = StickerPickerState {
+            cols: 4,
+            ..Default::default()
+        };
+        state.set_packs(vec![pack("a", true, 6), pack("b", true, 2)]);
+        state.handle_key(KeyCode::Down);
+        assert_eq!(state.selected_index, 4);
+        // No full row below: stay put
+        state.handle_key(KeyCode::Down);
+        assert_eq!(state.selected_index, 4);
+        state.handle_key(KeyCode::Right);
+        state.handle_key(KeyCode::Right);
+        assert_eq!(state.selected_index, 5);
+        state.handle_key(KeyCode::Tab);
+        assert_eq!((state.pack_index, state.selected_index), (1, 0));
+        state.handle_key(KeyCode::Tab);
+        assert_eq!(state.pack_index, 0);
+        state.handle_key(KeyCode::BackTab);
+        assert_eq!(state.pack_index, 1);
+    }
+
+    #[test]
+    fn enter_selects_sticker_ref() {
+        let mut state = StickerPickerState::default();
+        state.set_packs(vec![pack("a", true, 3)]);
+        state.handle_key(KeyCode::Right);
+        match state.handle_key(KeyCode::Enter) {
+            StickerPickerAction::Select(sticker) => {
+                assert_eq!(sticker.pack_id, state.packs[0].pack_id);
+                assert_eq!(sticker.sticker_id, 1);
+            }
+            _ => panic!("expected Select"),
+        }
+    }
+
+    #[test]
+    fn enter_without_packs_does_nothing() {
+        let mut state = StickerPickerState::default();
+        state.set_packs(Vec::new());
+        assert!(matches!(
+            state.handle_key(KeyCode::Enter),
+            StickerPickerAction::None
+        ));
+        assert!(matches!(
+            state.handle_key(KeyCode::Esc),
+            StickerPickerAction::Close
+        ));
+    }
+
+    #[test]
+    fn finds_pack_url_in_text() {
+        let url = "https://signal.art/addstickers/#pack_id=9acc9e8a&pack_key=5a6dff3";
+        assert_eq!(
+            sticker_pack_url(&format!("look at these: {url}.")),
+            Some(url)
+        );
+        assert_eq!(
+            sticker_pack_url("https://signal.art/addstickers/#pack_id=9acc"),
+            None
+        );
+        assert_eq!(
+            sticker_pack_url("https://signal.art/addstickers/#pack_id=zz&pack_key=00"),
+            None
+        );
+        assert_eq!(sticker_pack_url("no links here"), None);
+    }
+}
@@ -0,0 +1,272 @@
+//! Stories received from contacts and the stories overlay cursor.
+//!
+//! Stories are kept newest-last in one flat list; the overlay groups them
+//! by author. A story disappears [`STORY_TTL_MS`] after it was posted, both
+//! from memory (via `App::sweep_expired_stories`) and from the database.
+
+use ratatui::text::Line;
+
+/// How long a story stays visible after it was posted.
+pub const STORY_TTL_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// A story held in memory and in the `stories` table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Story {
+    pub author: String,
+    pub timestamp_ms: i64,
+    pub group_id: Option<String>,
+    /// Body of a text story
+    pub text: Option<String>,
+    /// MIME type of a media story's attachment
+    pub content_type: Option<String>,
+    /// Local path of a media story's attachment, once downloaded
+    pub attachment_path: Option<String>,
+    pub allows_replies: bool,
+    pub viewed: bool,
+}
+
+impl Story {
+    pub fn expires_at_ms(&self) -> i64 {
+        self.timestamp_ms + STORY_TTL_MS
+    }
+
+    pub fn is_expired(&self, now_ms: i64) -> bool {
+        self.expires_at_ms() <= now_ms
+    }
+
+    pub fn is_image(&self) -> bool {
+        self.content_type
+            .as_deref()
+            .is_some_and(|t| t.starts_with("image/"))
+    }
+
+    /// One-line summary used for the reply quote and the author list.
+    pub fn snippet(&self) -> String {
+        match (&self.text, &self.content_type) {
+            (Some(text), _) => text.clone(),
+            (None, Some(_)) if self.is_image() => "[image]".to_string(),
+            (None, Some(_)) => "[video]".to_string(),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+/// One row of the stories overlay's author list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoryAuthor {
+    pub author: String,
+    pub total: usize,
+    pub unviewed: usize,
+    pub latest_ms: i64,
+}
+
+/// State for the stories overlay.
+#[derive(Default)]
+pub struct StoriesState {
+    /// All live stories, oldest first
+    pub stories: Vec<Story>,
+    /// Cursor position in the author list
+    pub index: usize,
+    /// Story being viewed: (author, position among that author's stories)
+    pub viewing: Option<(String, usize)>,
+    /// Halfblock rendering of the viewed story's image, if any
+    pub image_lines: Option<Vec<Line<'static>>>,
+}
+
+impl StoriesState {
+    /// Add a story, ignoring duplicates. Returns false if it was already known.
+    pub fn insert(&mut self, story: Story) -> bool {
+        if self
+            .stories
+            .iter()
+            .any(|s| s.author == story.author && s.timestamp_ms == story.timestamp_ms)
+        {
+            return false;
+        }
+        let pos = self
+            .stories
+            .partition_point(|s| s.timestamp_ms <= story.timestamp_ms);
+        self.stories.insert(pos, story);
+        true
+    }
+
+    /// Authors with live stories: those with unviewed stories first, then
+    /// by most recent story.
+    pub fn authors(&self) -> Vec<StoryAuthor> {
+        let mut authors: Vec<StoryAuthor> = Vec::new();
+        for story in &self.stories {
+            let entry = match authors.iter_mut().find(|a| a.author == story.author) {
+                Some(entry) => entry,
+                None => {
+                    authors.push(StoryAuthor {
+                        author: story.author.clone(),
+                        total: 0,
+                        unviewed: 0,
+                        latest_ms: 0,
+                    });
+                    authors.last_mut().expect("just pushed")
+                }
+            };
+            entry.total += 1;
+            entry.unviewed += usize::from(!story.viewed);
+            entry.latest_ms = entry.latest_ms.max(story.timestamp_ms);
+        }
+        authors.sort_by(|a, b| {
+            (b.unviewed > 0)
+                .cmp(&(a.unviewed > 0))
+                .then(b.latest_ms.cmp(&a.latest_ms))
+        });
+        authors
+    }
+
+    /// Number of stories not yet viewed.
+    pub fn unviewed_count(&self) -> usize {
+        self.stories.iter().filter(|s| !s.viewed).count()
+    }
+
+    fn by_author(&self, author: &str) -> Vec<usize> {
+        self.stories
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.author == author)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Start viewing `author`'s stories at the first unviewed one.
+    pub fn open(&mut self, author: &str) {
+        let indices = self.by_author(author);
+        if indices.is_empty() {
+            return;
+        }
+        let pos = indices
+            .iter()
+            .position(|&i| !self.stories[i].viewed)
+            .unwrap_or(0);
+        self.viewing = Some((author.to_string(), pos));
+    }
+
+    /// Leave the viewer and go back to the author list.
+    pub fn close_viewer(&mut self) {
+        self.viewing = None;
+        self.image_lines = None;
+    }
+
+    /// The story being viewed and how many stories its author has.
+    pub fn current(&self) -> Option<(&Story, usize, usize)> {
+        let (author, pos) = self.viewing.as_ref()?;
+        let indices = self.by_author(author);
+        let &i = indices.get(*pos)?;
+        Some((&self.stories[i], *pos, indices.len()))
+    }
+
+    pub fn current_mut(&mut self) -> Option<&mut Story> {
+        let (author, pos) = self.viewing.as_ref()?;
+        let &i = self.by_author(author).get(*pos)?;
+        self.stories.get_mut(i)
+    }
+
+    /// Move to the author's next (`forward`) or previous story. Returns
+    /// false at either end.
+    pub fn step(&mut self, forward: bool) -> bool {
+        let Some((author, pos)) = self.viewing.clone() else {
+            return false;
+        };
+        let len = self.by_author(&author).len();
+        let next = if forward {
+            pos + 1
+        } else {
+            match pos.checked_sub(1) {
+                Some(p) => p,
+                None => return false,
+            }
+        };
+        if next >= len {
+            return false;
+        }
+        self.viewing = Some((author, next));
+        true
+    }
+
+    /// Drop expired stories. Returns how many were removed.
+    pub fn retain_live(&mut self, now_ms: i64) -> usize {
+        let before = self.stories.len();
+        self.stories.retain(|s| !s.is_expired(now_ms));
+        let removed = before - self.stories.len();
+        if removed > 0 && self.current().is_none() {
+            self.close_viewer();
+        }
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn story(author: &str, ts: i64, viewed: bool) -> Story {
+        Story {
+            author: author.to_string(),
+            timestamp_ms: ts,
+            group_id: None,
+            text: Some(format!("story {ts}")),
+            content_type: None,
+            attachment_path: None,
+            allows_replies: true,
+            viewed,
+        }
+    }
+
+    #[test]
+    fn insert_dedups_and_keeps_order() {
+        let mut state = StoriesState::default();
+        assert!(state.insert(story("+1", 200, false)));
+        assert!(state.insert(story("+2", 100, false)));
+        assert!(!state.insert(story("+1", 200, false)));
+        let ts: Vec<i64> = state.stories.iter().map(|s| s.timestamp_ms).collect();
+        assert_eq!(ts, vec![100, 200]);
+    }
+
+    #[test]
+    fn authors_put_unviewed_first() {
+        let mut state = StoriesState::default();
+        state.insert(story("+1", 300, true));
+        state.insert(story("+2", 100, false));
+        state.insert(story("+2", 150, true));
+        let authors = state.authors();
+        assert_eq!(authors[0].author, "+2");
+        assert_eq!((authors[0].total, authors[0].unviewed), (2, 1));
+        assert_eq!(authors[1].author, "+1");
+        assert_eq!(state.unviewed_count(), 1);
+    }
+
+    #[test]
+    fn open_starts_at_first_unviewed_and_steps() {
+        let mut state = StoriesState::default();
+        state.insert(story("+1", 100, true));
+        state.insert(story("+1", 200, false));
+        state.insert(story("+1", 300, false));
+        state.open("+1");
+        assert_eq!(state.current().map(|(s, ..)| s.timestamp_ms), Some(200));
+        assert!(state.step(true));
+        assert_eq!(
+            state.current().map(|(s, i, n)| (s.timestamp_ms, i, n)),
+            Some((300, 2, 3))
+        );
+        assert!(!state.step(true));
+        assert!(state.step(false));
+        assert!(state.step(false));
+        assert!(!state.step(false));
+    }
+
+    #[test]
+    fn retain_live_drops_expired_and_closes_viewer() {
+        let mut state = StoriesState::default();
+        state.insert(story("+1", 0, false));
+        state.insert(story("+2", STORY_TTL_MS, false));
+        state.open("+1");
+        assert_eq!(state.retain_live(STORY_TTL_MS + 1), 1);
+        assert_eq!(state.stories.len(), 1);
+        assert!(state.viewing.is_none());
+    }
+}
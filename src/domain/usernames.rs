@@ -0,0 +1,76 @@
+//! Signal usernames: a nickname plus a numeric discriminator, `alice.42`.
+//!
+//! Usernames are case-insensitive, so both parsers normalize to lowercase
+//! and drop a leading `@`. Looking someone up needs the full username;
+//! setting our own may leave the discriminator off and let signal-cli pick
+//! one.
+
+const NICKNAME_MIN: usize = 3;
+const NICKNAME_MAX: usize = 32;
+
+/// Nickname rules: 3-32 of `a-z`, `0-9` and `_`, not starting with a digit.
+fn valid_nickname(nickname: &str) -> bool {
+    (NICKNAME_MIN..=NICKNAME_MAX).contains(&nickname.len())
+        && nickname
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        && !nickname.starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Discriminator rules: at least two digits, never `00`.
+fn valid_discriminator(discriminator: &str) -> bool {
+    discriminator.len() >= 2
+        && discriminator.chars().all(|c| c.is_ascii_digit())
+        && discriminator != "00"
+}
+
+fn normalize(text: &str) -> String {
+    text.trim().trim_start_matches('@').to_lowercase()
+}
+
+/// Parse a username to look up, e.g. `@Alice.42` -> `alice.42`.
+pub fn parse_username(text: &str) -> Option<String> {
+    let username = normalize(text);
+    let (nickname, discriminator) = username.rsplit_once('.')?;
+    (valid_nickname(nickname) && valid_discriminator(discriminator)).then_some(username)
+}
+
+/// Parse a username to claim for ourselves. The discriminator is optional.
+pub fn parse_own_username(text: &str) -> Option<String> {
+    let username = normalize(text);
+    if username.contains('.') {
+        parse_username(&username)
+    } else {
+        valid_nickname(&username).then_some(username)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("alice.42", Some("alice.42"))]
+    #[case("@Alice.42", Some("alice.42"))]
+    #[case(" bob_smith.007 ", Some("bob_smith.007"))]
+    #[case("alice", None)]
+    #[case("alice.4", None)]
+    #[case("alice.00", None)]
+    #[case("al.42", None)]
+    #[case("1alice.42", None)]
+    #[case("ali-ce.42", None)]
+    #[case("+15551234567", None)]
+    fn username_lookup(#[case] input: &str, #[case] expected: Option<&str>) {
+        assert_eq!(parse_username(input).as_deref(), expected);
+    }
+
+    #[rstest]
+    #[case("alice", Some("alice"))]
+    #[case("@Alice.42", Some("alice.42"))]
+    #[case("alice.x", None)]
+    #[case("a", None)]
+    fn own_username(#[case] input: &str, #[case] expected: Option<&str>) {
+        assert_eq!(parse_own_username(input).as_deref(), expected);
+    }
+}
@@ -0,0 +1,123 @@
+//! View-once media: per-message state and the reveal overlay.
+//!
+//! A view-once message shows as a placeholder in the chat pane. Its media
+//! stays on disk only until the user reveals it in the fullscreen overlay;
+//! closing the overlay (or a `viewOnceOpen` sync from another of our
+//! devices) wipes the file with [`purge_media`] and marks the message
+//! viewed, so it can never be opened a second time.
+
+use std::io::{self, Write};
+use std::path::Path;
+
+use ratatui::text::Line;
+use serde::{Deserialize, Serialize};
+
+/// Chat pane body once a view-once message has been viewed and purged.
+pub const VIEW_ONCE_VIEWED_BODY: &str = "[View-once message: viewed]";
+
+/// View-once state of a message, persisted as JSON in `messages.view_once`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum ViewOnce {
+    /// Not revealed yet. `path` is the downloaded media, if any.
+    Unopened {
+        path: Option<String>,
+        content_type: Option<String>,
+    },
+    /// Revealed here or on another device; the media is gone.
+    Viewed,
+}
+
+impl ViewOnce {
+    pub fn is_unopened(&self) -> bool {
+        matches!(self, Self::Unopened { .. })
+    }
+
+    /// Local path of the media while it is still unopened.
+    pub fn unopened_path(&self) -> Option<&str> {
+        match self {
+            Self::Unopened { path, .. } => path.as_deref(),
+            Self::Viewed => None,
+        }
+    }
+}
+
+/// State for the view-once reveal overlay.
+#[derive(Default)]
+pub struct ViewOnceState {
+    /// Message being revealed: (conv_id, timestamp_ms)
+    pub target: Option<(String, i64)>,
+    /// Sender display name, for the overlay title
+    pub sender: String,
+    /// Media being shown; purged when the overlay closes
+    pub path: Option<String>,
+    pub content_type: Option<String>,
+    /// Halfblock rendering of the media, for images
+    pub image_lines: Option<Vec<Line<'static>>>,
+}
+
+impl ViewOnceState {
+    pub fn is_image(&self) -> bool {
+        self.content_type
+            .as_deref()
+            .is_some_and(|t| t.starts_with("image/"))
+    }
+
+    /// Forget the revealed message. Returns what was being shown.
+    pub fn take(&mut self) -> Option<(String, i64)> {
+        self.image_lines = None;
+        self.path = None;
+        self.content_type = None;
+        self.target.take()
+    }
+}
+
+/// Overwrite a file with zeros, flush it to disk, then delete it. A file
+/// that is already gone is not an error.
+pub fn purge_media(path: &Path) -> io::Result<()> {
+    let mut file = match std::fs::OpenOptions::new().write(true).open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    let zeros = [0u8; 8192];
+    let mut remaining = file.metadata()?.len();
+    while remaining > 0 {
+        let n = remaining.min(zeros.len() as u64) as usize;
+        file.write_all(&zeros[..n])?;
+        remaining -= n as u64;
+    }
+    file.sync_all()?;
+    drop(file);
+    std::fs::remove_file(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn purge_media_removes_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secret.jpg");
+        std::fs::write(&path, vec![0xAB; 20_000]).unwrap();
+        purge_media(&path).unwrap();
+        assert!(!path.exists());
+        // Second purge of a missing file is a no-op
+        purge_media(&path).unwrap();
+    }
+
+    #[test]
+    fn view_once_json_round_trip() {
+        let state = ViewOnce::Unopened {
+            path: Some("/tmp/a.jpg".to_string()),
+            content_type: Some("image/jpeg".to_string()),
+        };
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(serde_json::from_str::<ViewOnce>(&json).unwrap(), state);
+        assert_eq!(
+            serde_json::to_string(&ViewOnce::Viewed).unwrap(),
+            r#"{"state":"viewed"}"#
+        );
+    }
+}
@@ -11,11 +11,12 @@ use chrono::Utc;
 
 use crate::app::{App, GroupMenuState, OverlayKind, SendRequest, WireQuote};
 use crate::conversation_store::{DisplayMessage, Quote, db_warn};
-use crate::domain::EmojiPickerSource;
-use crate::image_render;
+use crate::domain::{EmojiPickerSource, MessageAttachment, strip_markup};
 use crate::input::{self, InputAction};
 use crate::mute::MuteState;
-use crate::signal::types::{IdentityInfo, Mention, MessageStatus, PollData, PollOption};
+use crate::signal::types::{
+    AvatarRef, IdentityInfo, Mention, MessageStatus, PollData, PollOption, StickerRef, StyleType,
+};
 
 /// Handle a line of user input; returns Some(SendRequest) if a message
 /// must be sent to signal-cli.
@@ -32,6 +33,7 @@ pub fn handle_input(app: &mut App) -> Option<SendRequest> {
     let action = input::parse_input(&input);
     match action {
         InputAction::SendText(raw_text) => send_text(app, raw_text),
+        InputAction::Join(target) if target.starts_with('@') => app.join_username(&target),
         InputAction::Join(target) => {
             app.join_conversation(&target);
             None
@@ -41,7 +43,7 @@ pub fn handle_input(app: &mut App) -> Option<SendRequest> {
             app.active_conversation = None;
             app.scroll.offset = 0;
             app.scroll.focused_index = None;
-            app.pending_attachment = None;
+            app.pending_attachments.clear();
             app.reset_typing_with_stop();
             app.update_status();
             None
@@ -114,13 +116,45 @@ pub fn handle_input(app: &mut App) -> Option<SendRequest> {
             app.open_overlay(OverlayKind::Search);
             None
         }
-        InputAction::Contacts => {
+        InputAction::Contacts | InputAction::ShareContact => {
+            let sharing = matches!(action, InputAction::ShareContact);
+            if sharing && app.active_conversation.is_none() {
+                app.status_message = "No active conversation".to_string();
+                return None;
+            }
             app.open_overlay(OverlayKind::Contacts);
             app.contacts_overlay.index = 0;
             app.contacts_overlay.filter.clear();
+            app.contacts_overlay.mode = Default::default();
+            app.contacts_overlay.input.clear();
+            app.contacts_overlay.sharing = sharing;
             app.refresh_contacts_filter();
             None
         }
+        InputAction::Stories => {
+            app.open_stories();
+            None
+        }
+        InputAction::Calls => {
+            app.open_calls();
+            None
+        }
+        InputAction::Devices => app.open_devices(),
+        InputAction::Stickers => app.open_sticker_picker(),
+        InputAction::InstallStickerPack(url) => match crate::domain::sticker_pack_url(&url) {
+            Some(url) => {
+                app.status_message = "Installing sticker pack...".to_string();
+                Some(SendRequest::InstallStickerPack {
+                    url: url.to_string(),
+                })
+            }
+            None => {
+                app.status_message =
+                    "Not a sticker pack link (https://signal.art/addstickers/#pack_id=...)"
+                        .to_string();
+                None
+            }
+        },
         InputAction::Emoji(query) => {
             let filter = if query.is_empty() { None } else { Some(query) };
             app.emoji_picker.open(EmojiPickerSource::Input, filter);
@@ -143,13 +177,30 @@ pub fn handle_input(app: &mut App) -> Option<SendRequest> {
             app.group_menu.index = 0;
             app.group_menu.filter.clear();
             app.group_menu.input.clear();
+            if let Some(id) = app.active_conversation.clone() {
+                app.queue_conversation_avatar(&id);
+            }
             None
         }
+        InputAction::JoinGroup(link) => match crate::domain::group_invite_url(&link) {
+            Some(uri) => {
+                app.status_message = "Joining group...".to_string();
+                Some(SendRequest::JoinGroup {
+                    uri: uri.to_string(),
+                })
+            }
+            None => {
+                app.status_message =
+                    "Not a group invite link (https://signal.group/#...)".to_string();
+                None
+            }
+        },
         InputAction::Verify => verify(app),
         InputAction::Profile => {
             app.open_overlay(OverlayKind::Profile);
             app.profile.index = 0;
             app.profile.editing = false;
+            app.queue_avatar(AvatarRef::Contact(app.account.clone()));
             None
         }
         InputAction::About => {
@@ -184,13 +235,13 @@ pub fn handle_input(app: &mut App) -> Option<SendRequest> {
 }
 
 fn send_text(app: &mut App, raw_text: String) -> Option<SendRequest> {
-    let text = input::replace_shortcodes(&raw_text);
-    if text.is_empty() && app.pending_attachment.is_none() && app.editing_message.is_none() {
+    let (text, style_ranges) = strip_markup(&input::replace_shortcodes(&raw_text));
+    if text.is_empty() && app.pending_attachments.is_empty() && app.editing_message.is_none() {
         return None;
     }
 
     if let Some((edit_ts, edit_conv_id)) = app.editing_message.take() {
-        return try_send_edit(app, edit_ts, edit_conv_id, &text);
+        return try_send_edit(app, edit_ts, edit_conv_id, &text, style_ranges);
     }
 
     let Some(conv_id) = app.active_conversation.clone() else {
@@ -198,7 +249,7 @@ fn send_text(app: &mut App, raw_text: String) -> Option<SendRequest> {
         return None;
     };
 
-    let attachment = app.pending_attachment.take();
+    let attachments = std::mem::take(&mut app.pending_attachments);
     let is_group = app
         .store
         .conversations
@@ -206,18 +257,16 @@ fn send_text(app: &mut App, raw_text: String) -> Option<SendRequest> {
         .map(|c| c.is_group)
         .unwrap_or(false);
 
-    let (display_body, outgoing_image_lines, outgoing_image_path) =
-        build_outgoing_attachment_body(app, &text, attachment.as_deref());
-
     let mut mention_ranges = Vec::new();
     for (name, _uuid) in &app.autocomplete.pending_mentions {
         let needle = format!("@{name}");
-        if let Some(pos) = display_body.find(&needle) {
+        if let Some(pos) = text.find(&needle) {
             mention_ranges.push((pos, pos + needle.len()));
         }
     }
 
-    let (wire_body, wire_mentions) = app.prepare_outgoing_mentions(&text);
+    let (wire_body, wire_mentions, text_styles) =
+        app.prepare_outgoing_mentions(&text, &style_ranges);
     app.autocomplete.pending_mentions.clear();
 
     let now = Utc::now();
@@ -232,18 +281,24 @@ fn send_text(app: &mut App, raw_text: String) -> Option<SendRequest> {
         .unwrap_or(0);
     let out_expiry_start = if out_expires > 0 { local_ts_ms } else { 0 };
 
+    // Images render in the background (`ensure_active_images`)
     let outgoing_msg = DisplayMessage {
         sender: "you".to_string(),
         timestamp: now,
-        body: display_body,
+        body: text.clone(),
+        attachments: attachments
+            .iter()
+            .map(|path| MessageAttachment::from_path(path, None))
+            .collect(),
         is_system: false,
-        image_lines: outgoing_image_lines,
-        image_path: outgoing_image_path,
+        is_warning: false,
+        image_lines: None,
+        image_tiles: Vec::new(),
         status: Some(MessageStatus::Sending),
         timestamp_ms: local_ts_ms,
         reactions: Vec::new(),
         mention_ranges,
-        style_ranges: Vec::new(),
+        style_ranges,
         body_raw: if wire_mentions.is_empty() {
             None
         } else {
@@ -269,6 +324,8 @@ fn send_text(app: &mut App, raw_text: String) -> Option<SendRequest> {
         preview: None,
         preview_image_lines: None,
         preview_image_path: None,
+        view_once: None,
+        shared_contacts: Vec::new(),
     };
     app.on_message_added(
         &conv_id,
@@ -289,7 +346,8 @@ fn send_text(app: &mut App, raw_text: String) -> Option<SendRequest> {
         is_group,
         local_ts_ms,
         mentions: wire_mentions,
-        attachment,
+        text_styles,
+        attachments,
         quote_timestamp,
         quote_author,
         quote_body,
@@ -301,6 +359,7 @@ fn try_send_edit(
     edit_ts: i64,
     edit_conv_id: String,
     text: &str,
+    style_ranges: Vec<(usize, usize, StyleType)>,
 ) -> Option<SendRequest> {
     if text.is_empty() {
         return None;
@@ -314,22 +373,31 @@ fn try_send_edit(
         .and_then(|msg| msg.quote.as_ref())
         .map(|q| (q.timestamp_ms, q.author_id.clone(), q.body.clone()));
 
+    let (wire_body, wire_mentions, text_styles) =
+        app.prepare_outgoing_mentions(text, &style_ranges);
     let conv = app.store.conversations.get_mut(&edit_conv_id)?;
     if let Some(idx) = conv
         .find_msg_idx(edit_ts)
         .filter(|&idx| conv.messages[idx].is_outgoing())
     {
         conv.messages[idx].body = text.to_string();
+        conv.messages[idx].style_ranges = style_ranges.clone();
         conv.messages[idx].is_edited = true;
     }
     let is_group = conv.is_group;
-    let (wire_body, wire_mentions) = app.prepare_outgoing_mentions(text);
     app.autocomplete.pending_mentions.clear();
+    let now = Utc::now();
     app.db_warn_visible(
-        app.db.update_message_body(&edit_conv_id, edit_ts, text),
-        "update_message_body",
+        app.db.record_edit(
+            &edit_conv_id,
+            edit_ts,
+            now.timestamp_millis(),
+            text,
+            &style_ranges,
+            true,
+        ),
+        "record_edit",
     );
-    let now = Utc::now();
     Some(SendRequest::Edit {
         recipient: edit_conv_id,
         body: wire_body,
@@ -337,52 +405,13 @@ fn try_send_edit(
         edit_timestamp: edit_ts,
         local_ts_ms: now.timestamp_millis(),
         mentions: wire_mentions,
+        text_styles,
         quote_timestamp: original_quote.as_ref().map(|(ts, _, _)| *ts),
         quote_author: original_quote.as_ref().map(|(_, a, _)| a.clone()),
         quote_body: original_quote.map(|(_, _, b)| b),
     })
 }
 
-fn build_outgoing_attachment_body(
-    app: &App,
-    text: &str,
-    attachment: Option<&std::path::Path>,
-) -> (
-    String,
-    Option<Vec<ratatui::text::Line<'static>>>,
-    Option<String>,
-) {
-    let Some(path) = attachment else {
-        return (text.to_string(), None, None);
-    };
-    let fname = path
-        .file_name()
-        .map(|f| f.to_string_lossy().to_string())
-        .unwrap_or_else(|| "file".to_string());
-    let ext = path
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("")
-        .to_lowercase();
-    let is_image = matches!(ext.as_str(), "png" | "jpg" | "jpeg" | "gif" | "webp");
-    let prefix = if is_image { "image" } else { "attachment" };
-    let body = if text.is_empty() {
-        format!("[{prefix}: {fname}]")
-    } else {
-        format!("[{prefix}: {fname}] {text}")
-    };
-    let (img_lines, img_path) =
-        if is_image && app.image.image_mode != crate::domain::ImageMode::None {
-            (
-                image_render::render_image(path, 40),
-                Some(path.to_string_lossy().into_owned()),
-            )
-        } else {
-            (None, None)
-        };
-    (body, img_lines, img_path)
-}
-
 fn build_outgoing_quote(app: &App) -> (Option<Quote>, Option<i64>, Option<String>, Option<String>) {
     let Some((author_phone, body, ts)) = app.reply_target.as_ref() else {
         return (None, None, None, None);
@@ -399,11 +428,19 @@ fn build_outgoing_quote(app: &App) -> (Option<Quote>, Option<i64>, Option<String
                 author_phone.clone()
             }
         });
+    let style_ranges = app
+        .active_conversation
+        .as_ref()
+        .and_then(|id| app.store.conversations.get(id))
+        .and_then(|conv| conv.find_msg_idx(*ts).map(|idx| &conv.messages[idx]))
+        .map(|msg| msg.snippet_styles(body))
+        .unwrap_or_default();
     let quote = Quote {
         author: author_display,
         body: body.clone(),
         timestamp_ms: *ts,
         author_id: author_phone.clone(),
+        style_ranges,
     };
     (
         Some(quote),
@@ -667,8 +704,10 @@ fn create_poll(
         timestamp: now,
         body,
         is_system: false,
+        is_warning: false,
+        attachments: Vec::new(),
         image_lines: None,
-        image_path: None,
+        image_tiles: Vec::new(),
         status: Some(MessageStatus::Sending),
         timestamp_ms: local_ts_ms,
         reactions: Vec::new(),
@@ -688,6 +727,8 @@ fn create_poll(
         preview: None,
         preview_image_lines: None,
         preview_image_path: None,
+        view_once: None,
+        shared_contacts: Vec::new(),
     };
     app.on_message_added(&conv_id, poll_msg, WireQuote::default(), false);
     app.db_warn_visible(
@@ -706,3 +747,71 @@ fn create_poll(
         local_ts_ms,
     })
 }
+
+/// Send a sticker picked in the sticker picker to the active conversation.
+/// The local copy shows its `[Sticker: ...]` text until the image has been
+/// fetched, which `dispatch_send` starts alongside the send.
+pub(crate) fn send_sticker(app: &mut App, sticker: StickerRef) -> Option<SendRequest> {
+    let Some(conv_id) = app.active_conversation.clone() else {
+        app.status_message = "No active conversation".to_string();
+        return None;
+    };
+    let is_group = app
+        .store
+        .conversations
+        .get(&conv_id)
+        .map(|c| c.is_group)
+        .unwrap_or(false);
+    let now = Utc::now();
+    let local_ts_ms = now.timestamp_millis();
+    let out_expires = app
+        .store
+        .conversations
+        .get(&conv_id)
+        .map(|c| c.expiration_timer)
+        .unwrap_or(0);
+    let body = match sticker.emoji {
+        Some(ref emoji) => format!("[Sticker: {emoji}]"),
+        None => "[Sticker]".to_string(),
+    };
+    let msg = DisplayMessage {
+        sender: "you".to_string(),
+        timestamp: now,
+        body,
+        is_system: false,
+        is_warning: false,
+        attachments: Vec::new(),
+        image_lines: None,
+        image_tiles: Vec::new(),
+        status: Some(MessageStatus::Sending),
+        timestamp_ms: local_ts_ms,
+        reactions: Vec::new(),
+        mention_ranges: Vec::new(),
+        style_ranges: Vec::new(),
+        body_raw: None,
+        mentions: Vec::new(),
+        quote: None,
+        is_edited: false,
+        is_deleted: false,
+        is_pinned: false,
+        sender_id: app.account.clone(),
+        expires_in_seconds: out_expires,
+        expiration_start_ms: if out_expires > 0 { local_ts_ms } else { 0 },
+        poll_data: None,
+        poll_votes: Vec::new(),
+        preview: None,
+        preview_image_lines: None,
+        preview_image_path: None,
+        view_once: None,
+        shared_contacts: Vec::new(),
+    };
+    app.on_message_added(&conv_id, msg, WireQuote::default(), false);
+    app.scroll.offset = 0;
+    app.scroll.focused_index = None;
+    Some(SendRequest::Sticker {
+        recipient: conv_id,
+        is_group,
+        local_ts_ms,
+        sticker,
+    })
+}
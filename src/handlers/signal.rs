@@ -10,27 +10,31 @@ use std::path::Path;
 use std::time::Instant;
 
 use chrono::{DateTime, Utc};
-use ratatui::text::Line;
 
 use crate::app::{
     App, OverlayKind, PASTE_CLEANUP_DELAY_SECS, WireQuote, show_desktop_notification,
 };
 use crate::conversation_store::{Conversation, DisplayMessage, Quote, db_warn, short_name};
 use crate::db::Database;
+use crate::domain::{
+    CallOutcome, CallRecord, GroupChange, MessageAttachment, PendingGroupUpdate, Story, ViewOnce,
+    diff_groups, purge_media,
+};
 use crate::image_render;
 use crate::signal::types::{
-    Contact, Group, IdentityInfo, LinkPreview, Mention, MessageStatus, PollData, PollVote,
-    Reaction, SignalEvent, SignalMessage, StyleType,
+    CallSignal, Contact, Group, IdentityInfo, LinkPreview, Mention, MessageStatus, PollData,
+    PollVote, RateLimitFailure, Reaction, SharedContact, SignalEvent, SignalMessage, StickerRef,
+    StoryMessage, StyleType,
 };
 
-/// Convert a local file path to a file:/// URI (forward slashes, for terminal Ctrl+Click).
-fn path_to_file_uri(path: &str) -> String {
-    let normalized = path.replace('\\', "/");
-    if normalized.starts_with('/') {
-        format!("file://{normalized}")
-    } else {
-        format!("file:///{normalized}")
-    }
+/// Attachment standing in for a sticker whose image is on disk, so inline
+/// rendering and the reload path treat it like an image attachment.
+pub(crate) fn sticker_attachment(emoji: Option<&str>, path: &Path) -> MessageAttachment {
+    let label = match emoji {
+        Some(emoji) => format!("sticker {emoji}"),
+        None => "sticker".to_string(),
+    };
+    MessageAttachment::from_path(path, Some(label))
 }
 
 /// Dispatch a `SignalEvent` from the signal-cli backend to the appropriate handler.
@@ -41,8 +45,9 @@ pub fn handle_signal_event(app: &mut App, event: SignalEvent) {
             sender,
             receipt_type,
             timestamps,
+            when_ms,
         } => {
-            handle_receipt(app, &sender, &receipt_type, &timestamps);
+            handle_receipt(app, &sender, &receipt_type, &timestamps, when_ms);
         }
         SignalEvent::SendTimestamp { rpc_id, server_ts } => {
             handle_send_timestamp(app, &rpc_id, server_ts);
@@ -51,6 +56,21 @@ pub fn handle_signal_event(app: &mut App, event: SignalEvent) {
             app.status_message = "send failed".to_string();
             handle_send_failed(app, &rpc_id);
         }
+        SignalEvent::SendTimedOut { rpc_id } => handle_send_timed_out(app, &rpc_id),
+        SignalEvent::UntrustedRecipients {
+            rpc_id,
+            recipients,
+            partial,
+        } => {
+            handle_untrusted_recipients(app, rpc_id.as_deref(), &recipients, partial);
+        }
+        SignalEvent::RateLimited {
+            rpc_id,
+            failure,
+            partial,
+        } => {
+            handle_rate_limited(app, rpc_id.as_deref(), failure, partial);
+        }
         SignalEvent::TypingIndicator {
             sender,
             sender_name,
@@ -82,6 +102,7 @@ pub fn handle_signal_event(app: &mut App, event: SignalEvent) {
             target_author,
             target_timestamp,
             is_remove,
+            timestamp_ms,
         } => {
             app.store
                 .remember_contact_name(&sender, sender_name.as_deref());
@@ -93,6 +114,7 @@ pub fn handle_signal_event(app: &mut App, event: SignalEvent) {
                 &target_author,
                 target_timestamp,
                 is_remove,
+                timestamp_ms,
             );
         }
         SignalEvent::EditReceived {
@@ -101,12 +123,19 @@ pub fn handle_signal_event(app: &mut App, event: SignalEvent) {
             sender_name,
             target_timestamp,
             new_body,
-            new_timestamp: _,
-            is_outgoing: _,
+            new_timestamp,
+            is_outgoing,
         } => {
             app.store
                 .remember_contact_name(&sender, sender_name.as_deref());
-            handle_edit_received(app, &conv_id, target_timestamp, &new_body);
+            handle_edit_received(
+                app,
+                &conv_id,
+                target_timestamp,
+                &new_body,
+                new_timestamp,
+                is_outgoing,
+            );
         }
         SignalEvent::RemoteDeleteReceived {
             conv_id,
@@ -175,8 +204,21 @@ pub fn handle_signal_event(app: &mut App, event: SignalEvent) {
             body,
             timestamp,
             timestamp_ms,
+            is_warning,
         } => {
-            handle_system_message(app, &conv_id, &body, timestamp, timestamp_ms);
+            add_system_line(app, &conv_id, &body, timestamp, timestamp_ms, is_warning);
+        }
+        SignalEvent::GroupUpdated {
+            group_id,
+            editor,
+            editor_name,
+            name,
+            timestamp,
+            timestamp_ms,
+        } => {
+            app.store
+                .remember_contact_name(&editor, editor_name.as_deref());
+            handle_group_updated(app, group_id, editor, name, timestamp, timestamp_ms);
         }
         SignalEvent::ExpirationTimerChanged {
             conv_id,
@@ -203,6 +245,10 @@ pub fn handle_signal_event(app: &mut App, event: SignalEvent) {
             if let Some(conv) = app.store.conversations.get_mut(&conv_id) {
                 conv.expiration_timer = seconds;
             }
+            // Already announced: keep the next group diff from repeating it
+            if let Some(group) = app.store.groups.get_mut(&conv_id) {
+                group.expiration_seconds = seconds;
+            }
             app.db_warn_visible(
                 app.db.update_expiration_timer(&conv_id, seconds),
                 "update_expiration_timer",
@@ -213,9 +259,45 @@ pub fn handle_signal_event(app: &mut App, event: SignalEvent) {
         SignalEvent::ReadSyncReceived { read_messages } => {
             handle_read_sync(app, read_messages);
         }
+        SignalEvent::ViewOnceOpened { sender, timestamp } => {
+            handle_view_once_opened(app, &sender, timestamp);
+        }
+        SignalEvent::StoryReceived(story) => handle_story(app, story),
+        SignalEvent::CallSignal(call) => handle_call_signal(app, call),
         SignalEvent::ContactList(contacts) => handle_contact_list(app, contacts),
         SignalEvent::GroupList(groups) => handle_group_list(app, groups),
         SignalEvent::IdentityList(identities) => handle_identity_list(app, identities),
+        SignalEvent::IdentityTrusted {
+            recipient,
+            verified,
+            identities,
+        } => handle_identity_trusted(app, &recipient, verified, identities),
+        SignalEvent::StickerFetched {
+            conv_id,
+            timestamp_ms,
+            sticker,
+            result,
+        } => handle_sticker_fetched(app, &conv_id, timestamp_ms, &sticker, result),
+        SignalEvent::StickerPacks(Ok(packs)) => app.sticker_picker.set_packs(packs),
+        SignalEvent::StickerPacks(Err(e)) => app.sticker_picker.set_error(e),
+        SignalEvent::UsernameResolved(resolved) => app.open_username_conversation(&resolved),
+        SignalEvent::OwnUsername(account) => app.set_own_username(account.username, account.link),
+        SignalEvent::DeviceList(Ok(devices)) => app.devices.set_devices(devices),
+        SignalEvent::DeviceList(Err(e)) => {
+            app.devices.loading = false;
+            app.status_message = format!("devices error: {e}");
+        }
+        SignalEvent::ContactRemoved(number) => app.contact_removed(&number),
+        SignalEvent::AvatarFetched { id, result } => match result {
+            Ok(path) => app.set_avatar(&id, path.as_deref()),
+            Err(e) => {
+                crate::debug_log::logf(format_args!("avatar fetch error: {e}"));
+                app.avatars.fetch_failed(&id, Utc::now().timestamp_millis());
+            }
+        },
+        SignalEvent::ProfileAvatarSet(result) => handle_profile_avatar_set(app, result),
+        SignalEvent::CaptchaSubmitted(result) => handle_captcha_submitted(app, result),
+        SignalEvent::Notice(text) => app.status_message = text,
         SignalEvent::Error(ref err) => {
             crate::debug_log::logf(format_args!("signal event error: {err}"));
             app.status_message = format!("error: {err}");
@@ -231,13 +313,11 @@ struct ContactIdentity {
     source_name: Option<String>,
 }
 
-/// One pushable DisplayMessage worth of resolved data. A single incoming
-/// `SignalMessage` produces one entry for the text body (if any) plus one entry
-/// per attachment.
+/// The pushable DisplayMessage worth of resolved data: the text body (empty
+/// for attachment-only messages) together with every attachment.
 struct ResolvedEntry {
     body: String,
-    image_lines: Option<Vec<Line<'static>>>,
-    image_path: Option<String>,
+    attachments: Vec<MessageAttachment>,
     mention_ranges: Vec<(usize, usize)>,
     style_ranges: Vec<(usize, usize, StyleType)>,
     quote: Option<Quote>,
@@ -261,9 +341,9 @@ struct ResolvedMessage {
     msg_status: Option<MessageStatus>,
     msg_expires_in: i64,
     msg_expiration_start: i64,
-    /// One entry per DisplayMessage to append: text body first (if any),
-    /// then one entry per attachment, in push order.
-    entries: Vec<ResolvedEntry>,
+    /// The DisplayMessage to append, or `None` when the message carries
+    /// neither text nor attachments.
+    entry: Option<ResolvedEntry>,
     /// Raw body + mentions for `upsert_message_mentions` so the display body
     /// can be re-resolved when the contact/group list later fills in UUIDs.
     /// `None` when the message had no mentions.
@@ -278,6 +358,25 @@ struct ResolvedMessage {
     /// Source identity to fold into contact_names / uuid_to_name after the
     /// conversation is created. Only set for incoming messages.
     source_to_remember: Option<ContactIdentity>,
+    /// Sticker whose image still has to be fetched from signal-cli.
+    sticker_to_fetch: Option<StickerRef>,
+    /// View-once state for the placeholder entry, if this is view-once media.
+    view_once: Option<ViewOnce>,
+    /// Contact cards shared in the message, rendered below its body.
+    shared_contacts: Vec<SharedContact>,
+}
+
+/// Conversation id for a 1:1 peer. A conversation opened by username is
+/// keyed by ACI, so a peer who later shows up under their phone number is
+/// routed there rather than into a second conversation.
+fn direct_conv_id(app: &App, id: &str, uuid: Option<&str>) -> String {
+    if app.store.conversations.contains_key(id) {
+        return id.to_string();
+    }
+    uuid.or_else(|| app.store.number_to_uuid.get(id).map(String::as_str))
+        .filter(|aci| app.store.conversations.contains_key(*aci))
+        .unwrap_or(id)
+        .to_string()
 }
 
 /// Pure read-only resolution of an incoming `SignalMessage`. Returns `None`
@@ -287,9 +386,9 @@ fn resolve_incoming(app: &App, msg: &SignalMessage) -> Option<ResolvedMessage> {
         gid.clone()
     } else if msg.is_outgoing {
         // Outgoing 1:1 — conversation is keyed by recipient
-        msg.destination.as_ref()?.clone()
+        direct_conv_id(app, msg.destination.as_ref()?, None)
     } else {
-        msg.source.clone()
+        direct_conv_id(app, &msg.source, msg.source_uuid.as_deref())
     };
 
     let is_group = msg.group_id.is_some();
@@ -302,7 +401,7 @@ fn resolve_incoming(app: &App, msg: &SignalMessage) -> Option<ResolvedMessage> {
         .or(if is_group {
             None
         } else {
-            msg.source_name.as_deref()
+            app.store.nickname(&conv_id).or(msg.source_name.as_deref())
         })
         .unwrap_or_else(|| {
             app.store
@@ -316,8 +415,10 @@ fn resolve_incoming(app: &App, msg: &SignalMessage) -> Option<ResolvedMessage> {
     let sender_display = if msg.is_outgoing {
         "you".to_string()
     } else {
-        msg.source_name
-            .clone()
+        app.store
+            .nickname(&msg.source)
+            .map(str::to_string)
+            .or_else(|| msg.source_name.clone())
             .or_else(|| app.store.contact_names.get(&msg.source).cloned())
             .unwrap_or_else(|| short_name(&msg.source))
     };
@@ -360,9 +461,13 @@ fn resolve_incoming(app: &App, msg: &SignalMessage) -> Option<ResolvedMessage> {
                 .resolve_text_styles(resolved, &msg.text_styles, &msg.mentions)
         })
         .unwrap_or_default();
+    // Notifications never reveal spoilers
+    let notification_preview_body = resolved_body
+        .as_ref()
+        .map(|(resolved, _)| crate::domain::mask_spoilers(resolved, &resolved_styles));
 
     // Resolve quote from wire format
-    let msg_quote = msg.quote.as_ref().map(|(ts, author_phone, body)| {
+    let msg_quote = msg.quote.as_ref().map(|(ts, author_phone, body, styles)| {
         let author_display = app
             .store
             .contact_names
@@ -381,6 +486,7 @@ fn resolve_incoming(app: &App, msg: &SignalMessage) -> Option<ResolvedMessage> {
                 body: body.clone(),
                 timestamp_ms: *ts,
                 author_id: author_phone.clone(),
+                style_ranges: app.store.resolve_text_styles(body, styles, &[]),
             },
             author_phone.clone(),
             body.clone(),
@@ -395,68 +501,80 @@ fn resolve_incoming(app: &App, msg: &SignalMessage) -> Option<ResolvedMessage> {
     };
 
     let had_mentions = !msg.mentions.is_empty();
-    let mut entries: Vec<ResolvedEntry> = Vec::new();
 
-    // Text body entry (if present).
-    if let Some((resolved, ranges)) = resolved_body {
+    // View-once media stays out of the chat pane: the placeholder body is the
+    // only content, and the first attachment is kept for the one-time reveal.
+    let view_once = msg.view_once.then(|| match msg.attachments.first() {
+        Some(att) if !msg.is_outgoing => ViewOnce::Unopened {
+            path: att.local_path.clone(),
+            content_type: Some(att.content_type.clone()),
+        },
+        _ => ViewOnce::Viewed,
+    });
+    let attachments: Vec<MessageAttachment> = if msg.view_once {
+        Vec::new()
+    } else {
+        msg.attachments
+            .iter()
+            .map(MessageAttachment::from_wire)
+            .collect()
+    };
+
+    // A sticker whose image is already on disk renders like an image
+    // attachment; otherwise its `[Sticker: ...]` text stands in until the
+    // main loop fetches the image.
+    let mut sticker_to_fetch = None;
+    let sticker_image = match msg.sticker {
+        Some(ref sticker) if sticker.local_path.is_none() => {
+            sticker_to_fetch = Some(sticker.clone());
+            None
+        }
+        Some(ref sticker) => sticker.local_path.as_deref().map(|path| ResolvedEntry {
+            body: String::new(),
+            attachments: vec![sticker_attachment(
+                sticker.emoji.as_deref(),
+                Path::new(path),
+            )],
+            mention_ranges: Vec::new(),
+            style_ranges: Vec::new(),
+            quote: display_quote.clone(),
+            body_raw: None,
+            mentions: Vec::new(),
+        }),
+        None => None,
+    };
+
+    let entry = if let Some(entry) = sticker_image {
+        Some(entry)
+    } else if let Some((resolved, ranges)) = resolved_body {
         let raw_body_for_msg = if had_mentions { msg.body.clone() } else { None };
         let mentions_for_msg = if had_mentions {
             msg.mentions.clone()
         } else {
             Vec::new()
         };
-        entries.push(ResolvedEntry {
+        Some(ResolvedEntry {
             body: resolved,
-            image_lines: None,
-            image_path: None,
+            attachments,
             mention_ranges: ranges,
             style_ranges: resolved_styles,
             quote: display_quote,
             body_raw: raw_body_for_msg,
             mentions: mentions_for_msg,
-        });
-    }
-
-    // Attachment entries.
-    for att in &msg.attachments {
-        let label = att.filename.as_deref().unwrap_or(&att.content_type);
-        let is_image = matches!(
-            att.content_type.as_str(),
-            "image/jpeg" | "image/png" | "image/gif" | "image/webp"
-        );
-        let path_info = att
-            .local_path
-            .as_deref()
-            .map(|p| format!("({})", path_to_file_uri(p)))
-            .unwrap_or_default();
-        if is_image {
-            let rendered = att
-                .local_path
-                .as_deref()
-                .and_then(|p| image_render::render_image(Path::new(p), 40));
-            entries.push(ResolvedEntry {
-                body: format!("[image: {label}]{path_info}"),
-                image_lines: rendered,
-                image_path: att.local_path.clone(),
-                mention_ranges: Vec::new(),
-                style_ranges: Vec::new(),
-                quote: None,
-                body_raw: None,
-                mentions: Vec::new(),
-            });
-        } else {
-            entries.push(ResolvedEntry {
-                body: format!("[attachment: {label}]{path_info}"),
-                image_lines: None,
-                image_path: None,
-                mention_ranges: Vec::new(),
-                style_ranges: Vec::new(),
-                quote: None,
-                body_raw: None,
-                mentions: Vec::new(),
-            });
-        }
-    }
+        })
+    } else if !attachments.is_empty() {
+        Some(ResolvedEntry {
+            body: String::new(),
+            attachments,
+            mention_ranges: Vec::new(),
+            style_ranges: Vec::new(),
+            quote: display_quote,
+            body_raw: None,
+            mentions: Vec::new(),
+        })
+    } else {
+        None
+    };
 
     let raw_body_for_mentions_db = if had_mentions {
         msg.body
@@ -488,12 +606,15 @@ fn resolve_incoming(app: &App, msg: &SignalMessage) -> Option<ResolvedMessage> {
         msg_status,
         msg_expires_in,
         msg_expiration_start,
-        entries,
+        entry,
         raw_body_for_mentions_db,
         preview: msg.previews.first().cloned(),
         wire_quote,
-        notification_preview_body: msg.body.clone(),
+        notification_preview_body,
         source_to_remember,
+        sticker_to_fetch,
+        view_once,
+        shared_contacts: msg.shared_contacts.clone(),
     })
 }
 
@@ -512,7 +633,7 @@ fn push_resolved(app: &mut App, r: &ResolvedMessage, is_active: bool) -> bool {
     remember_sender_identity(app, r);
 
     let conv_accepted = accept_or_create_conversation(app, r);
-    append_entries(app, r);
+    append_entry(app, r);
     persist_message_extras(app, r);
 
     if is_active {
@@ -607,59 +728,56 @@ fn accept_or_create_conversation(app: &mut App, r: &ResolvedMessage) -> bool {
         .unwrap_or(true)
 }
 
-/// Append each `ResolvedEntry` as a `DisplayMessage` in push order. Two
-/// "first-entry only" fields are carried in `take()`-shaped locals so they
-/// land on the body row (or, when there is no body, the first attachment
-/// row) and not on subsequent attachment entries:
-/// - `deferred_poll`: a poll event that arrived before this message and was
-///   buffered; attaches once to avoid duplicate poll-data rendering.
-/// - `entry_wire_quote`: the message's quote payload, which historically
-///   got copy-persisted to every attachment row and produced duplicate
-///   quote rendering on reload.
-fn append_entries(app: &mut App, r: &ResolvedMessage) {
-    let mut deferred_poll = app
+/// Append the resolved entry as a `DisplayMessage`, picking up a poll event
+/// that arrived before this message and was buffered.
+fn append_entry(app: &mut App, r: &ResolvedMessage) {
+    let Some(entry) = &r.entry else {
+        return;
+    };
+    let poll_data = app
         .poll_vote
         .pending_polls
         .remove(&(r.conv_id.clone(), r.msg_ts_ms));
-    let mut entry_wire_quote = Some(r.wire_quote.clone());
-
-    for entry in &r.entries {
-        let display = DisplayMessage {
-            sender: r.sender_display.clone(),
-            timestamp: r.timestamp,
-            body: entry.body.clone(),
-            is_system: false,
-            image_lines: entry.image_lines.clone(),
-            image_path: entry.image_path.clone(),
-            status: r.msg_status,
-            timestamp_ms: r.msg_ts_ms,
-            reactions: Vec::new(),
-            mention_ranges: entry.mention_ranges.clone(),
-            style_ranges: entry.style_ranges.clone(),
-            body_raw: entry.body_raw.clone(),
-            mentions: entry.mentions.clone(),
-            quote: entry.quote.clone(),
-            is_edited: false,
-            is_deleted: false,
-            is_pinned: false,
-            sender_id: r.sender_id.clone(),
-            expires_in_seconds: r.msg_expires_in,
-            expiration_start_ms: r.msg_expiration_start,
-            poll_data: deferred_poll.take(),
-            poll_votes: Vec::new(),
-            preview: None,
-            preview_image_lines: None,
-            preview_image_path: None,
-        };
-        let wq = entry_wire_quote.take().unwrap_or_default();
-        app.on_message_added(&r.conv_id, display, wq, true);
-    }
+    // Images are left for `ensure_active_images` to render in the
+    // background; an album can be too slow to decode here.
+    let display = DisplayMessage {
+        sender: r.sender_display.clone(),
+        timestamp: r.timestamp,
+        body: entry.body.clone(),
+        attachments: entry.attachments.clone(),
+        is_system: false,
+        is_warning: false,
+        image_lines: None,
+        image_tiles: Vec::new(),
+        status: r.msg_status,
+        timestamp_ms: r.msg_ts_ms,
+        reactions: Vec::new(),
+        mention_ranges: entry.mention_ranges.clone(),
+        style_ranges: entry.style_ranges.clone(),
+        body_raw: entry.body_raw.clone(),
+        mentions: entry.mentions.clone(),
+        quote: entry.quote.clone(),
+        is_edited: false,
+        is_deleted: false,
+        is_pinned: false,
+        sender_id: r.sender_id.clone(),
+        expires_in_seconds: r.msg_expires_in,
+        expiration_start_ms: r.msg_expiration_start,
+        poll_data,
+        poll_votes: Vec::new(),
+        preview: None,
+        preview_image_lines: None,
+        preview_image_path: None,
+        view_once: r.view_once.clone(),
+        shared_contacts: r.shared_contacts.clone(),
+    };
+    app.on_message_added(&r.conv_id, display, r.wire_quote.clone(), true);
 }
 
-/// Persist the artifacts that hang off a message but live outside the
-/// per-entry rows: raw body + mention ranges (so the display body can be
+/// Persist the artifacts that hang off a message but live outside its
+/// row: raw body + mention ranges (so the display body can be
 /// re-resolved when the contact list later fills in unknown UUIDs), and the
-/// first link preview (decoded and attached to the body row, with the
+/// first link preview (decoded and attached to the message, with the
 /// preview row itself written to the DB).
 fn persist_message_extras(app: &mut App, r: &ResolvedMessage) {
     if let Some((raw, mentions)) = &r.raw_body_for_mentions_db {
@@ -669,6 +787,12 @@ fn persist_message_extras(app: &mut App, r: &ResolvedMessage) {
             "upsert_message_mentions",
         );
     }
+    if let Some(state) = &r.view_once {
+        db_warn(
+            app.db.set_view_once(&r.conv_id, r.msg_ts_ms, state),
+            "set_view_once",
+        );
+    }
 
     let Some(preview) = &r.preview else {
         return;
@@ -679,7 +803,7 @@ fn persist_message_extras(app: &mut App, r: &ResolvedMessage) {
             .messages
             .iter_mut()
             .rev()
-            .find(|m| m.timestamp_ms == r.msg_ts_ms && !m.body.starts_with('['))
+            .find(|m| m.timestamp_ms == r.msg_ts_ms)
     {
         let (img_lines, img_path) = if app.image.show_link_previews
             && app.image.image_mode != crate::domain::ImageMode::None
@@ -781,6 +905,7 @@ fn apply_notification_policy(
             r.is_group,
             notif_group.as_deref(),
             app.notifications.notification_preview,
+            app.accounts.label.as_deref(),
         );
     }
 }
@@ -796,6 +921,90 @@ fn handle_message(app: &mut App, msg: SignalMessage) {
         .unwrap_or(false);
     let conv_accepted = push_resolved(app, &resolved, is_active);
     apply_notification_policy(app, &resolved, is_active, conv_accepted);
+    if let Some(sticker) = resolved.sticker_to_fetch {
+        app.pending
+            .sticker_fetches
+            .push((resolved.conv_id, resolved.msg_ts_ms, sticker));
+    }
+    // Only the first attachment of incoming view-once media is kept for the
+    // reveal; anything else (including our own synced sends) goes now.
+    if msg.view_once {
+        let keep = usize::from(!msg.is_outgoing);
+        for path in msg
+            .attachments
+            .iter()
+            .skip(keep)
+            .filter_map(|a| a.local_path.as_deref())
+        {
+            if let Err(e) = purge_media(Path::new(path)) {
+                crate::debug_log::logf(format_args!("view-once purge: {e}"));
+            }
+        }
+    }
+}
+
+/// Another of our devices opened a view-once message: purge it here too.
+fn handle_view_once_opened(app: &mut App, sender: &str, timestamp: i64) {
+    let found = app.store.conversations.iter().find_map(|(conv_id, conv)| {
+        conv.messages
+            .iter()
+            .any(|m| {
+                m.timestamp_ms == timestamp
+                    && m.view_once.as_ref().is_some_and(ViewOnce::is_unopened)
+                    && app.store.is_same_person(&m.sender_id, sender)
+            })
+            .then(|| conv_id.clone())
+    });
+    match found {
+        Some(conv_id) => {
+            app.mark_view_once_viewed(&conv_id, timestamp);
+        }
+        None => crate::debug_log::logf(format_args!(
+            "view_once_open: no message found for sender={} ts={timestamp}",
+            crate::debug_log::mask_phone(sender)
+        )),
+    }
+}
+
+/// Fold a call signalling step into the call history. A call that ends
+/// without being answered also leaves a "Missed call" line in the chat.
+fn handle_call_signal(app: &mut App, call: CallSignal) {
+    if app.blocked_conversations.contains(&call.peer) {
+        return;
+    }
+    app.store
+        .remember_contact_name(&call.peer, call.peer_name.as_deref());
+    let record = match app.db.get_call(&call.peer, call.call_id).ok().flatten() {
+        Some(mut record) => {
+            if record.ended_ms.is_some() {
+                return;
+            }
+            record.apply(&call.kind, call.timestamp_ms);
+            record
+        }
+        None => {
+            match CallRecord::from_signal(&call.peer, call.call_id, &call.kind, call.timestamp_ms) {
+                Some(record) => record,
+                None => return,
+            }
+        }
+    };
+    db_warn(app.db.upsert_call(&record), "upsert_call");
+    if record.outcome == CallOutcome::Missed {
+        record_missed_call(app, &record);
+    }
+}
+
+/// Add the "Missed voice/video call" line to the caller's conversation.
+pub(crate) fn record_missed_call(app: &mut App, record: &CallRecord) {
+    let timestamp = DateTime::from_timestamp_millis(record.started_ms).unwrap_or_default();
+    handle_system_message(
+        app,
+        &record.peer,
+        &record.missed_body(),
+        timestamp,
+        record.started_ms,
+    );
 }
 
 pub(super) fn handle_system_message(
@@ -804,6 +1013,19 @@ pub(super) fn handle_system_message(
     body: &str,
     timestamp: DateTime<Utc>,
     timestamp_ms: i64,
+) {
+    add_system_line(app, conv_id, body, timestamp, timestamp_ms, false);
+}
+
+/// Add a system line to a conversation, creating the conversation if
+/// needed. `is_warning` lines are drawn to stand out.
+fn add_system_line(
+    app: &mut App,
+    conv_id: &str,
+    body: &str,
+    timestamp: DateTime<Utc>,
+    timestamp_ms: i64,
+    is_warning: bool,
 ) {
     let is_group = app
         .store
@@ -824,8 +1046,10 @@ pub(super) fn handle_system_message(
         timestamp,
         body: body.to_string(),
         is_system: true,
+        is_warning,
+        attachments: Vec::new(),
         image_lines: None,
-        image_path: None,
+        image_tiles: Vec::new(),
         status: None,
         timestamp_ms,
         reactions: Vec::new(),
@@ -845,10 +1069,13 @@ pub(super) fn handle_system_message(
         preview: None,
         preview_image_lines: None,
         preview_image_path: None,
+        view_once: None,
+        shared_contacts: Vec::new(),
     };
     app.on_message_added(conv_id, msg, WireQuote::default(), true);
 }
 
+#[allow(clippy::too_many_arguments)]
 fn handle_reaction(
     app: &mut App,
     conv_id: &str,
@@ -857,6 +1084,7 @@ fn handle_reaction(
     target_author: &str,
     target_timestamp: i64,
     is_remove: bool,
+    timestamp_ms: i64,
 ) {
     // Find the message in memory and update reactions.
     // Pre-resolve names to avoid borrow conflict with app.store.conversations.
@@ -913,29 +1141,64 @@ fn handle_reaction(
             "remove_reaction",
         );
     } else {
+        let reacted_at_ms = if timestamp_ms > 0 {
+            timestamp_ms
+        } else {
+            Utc::now().timestamp_millis()
+        };
         app.db_warn_visible(
-            app.db
-                .upsert_reaction(conv_id, target_timestamp, target_author, sender, emoji),
+            app.db.upsert_reaction(
+                conv_id,
+                target_timestamp,
+                target_author,
+                sender,
+                emoji,
+                reacted_at_ms,
+            ),
             "upsert_reaction",
         );
     }
+    app.refresh_message_info(conv_id, target_timestamp);
 }
 
-fn handle_edit_received(app: &mut App, conv_id: &str, target_timestamp: i64, new_body: &str) {
+fn handle_edit_received(
+    app: &mut App,
+    conv_id: &str,
+    target_timestamp: i64,
+    new_body: &str,
+    new_timestamp: i64,
+    is_outgoing: bool,
+) {
     if let Some(conv) = app.store.conversations.get_mut(conv_id)
         && let Some(idx) = conv.find_msg_idx(target_timestamp)
     {
         conv.messages[idx].body = new_body.to_string();
         conv.messages[idx].is_edited = true;
+        // Old style offsets don't apply to the new body
+        conv.messages[idx].style_ranges.clear();
     }
+    // Older signal-cli builds may leave out the edit's own timestamp
+    let revision_ms = if new_timestamp > 0 {
+        new_timestamp
+    } else {
+        Utc::now().timestamp_millis()
+    };
     app.db_warn_visible(
-        app.db
-            .update_message_body(conv_id, target_timestamp, new_body),
-        "update_message_body",
+        app.db.record_edit(
+            conv_id,
+            target_timestamp,
+            revision_ms,
+            new_body,
+            &[],
+            is_outgoing,
+        ),
+        "record_edit",
     );
+    app.refresh_edit_history(conv_id, target_timestamp);
 }
 
 fn handle_remote_delete(app: &mut App, conv_id: &str, target_timestamp: i64) {
+    app.purge_deleted_view_once(conv_id, target_timestamp);
     if let Some(conv) = app.store.conversations.get_mut(conv_id)
         && let Some(idx) = conv.find_msg_idx(target_timestamp)
     {
@@ -1124,7 +1387,11 @@ fn handle_read_sync(app: &mut App, read_messages: Vec<(String, i64)>) {
 fn handle_contact_list(app: &mut App, contacts: Vec<Contact>) {
     app.loading = false;
     app.startup_status.clear();
-    for contact in contacts {
+    for mut contact in contacts {
+        // A local nickname wins over the name signal-cli resolved
+        if let Some(nickname) = app.store.nickname(&contact.number) {
+            contact.name = Some(nickname.to_string());
+        }
         // Store name in lookup for future message resolution
         if let Some(ref name) = contact.name
             && !name.is_empty()
@@ -1180,9 +1447,112 @@ fn handle_contact_list(app: &mut App, contacts: Vec<Contact>) {
     // Re-resolve @mention display bodies: messages that arrived before the
     // contact list may have fallen back to truncated UUIDs. (#283)
     app.store.rebuild_mention_display(&app.db);
+
+    app.queue_sidebar_avatars();
+}
+
+/// Display name for a member in group change messages.
+fn member_name(app: &App, id: &str) -> String {
+    if id == app.account {
+        return "you".to_string();
+    }
+    app.store
+        .contact_names
+        .get(id)
+        .filter(|n| !n.is_empty())
+        .cloned()
+        .unwrap_or_else(|| id.to_string())
+}
+
+/// A group changed. A rename signal-cli reports inline is announced right
+/// away; everything else waits for the `listGroups` refresh queued here,
+/// where `describe_group_updates` diffs it against the cached group.
+fn handle_group_updated(
+    app: &mut App,
+    group_id: String,
+    editor: String,
+    name: Option<String>,
+    timestamp: DateTime<Utc>,
+    timestamp_ms: i64,
+) {
+    let renamed = name.filter(|name| {
+        app.store
+            .groups
+            .get(&group_id)
+            .is_some_and(|g| &g.name != name)
+    });
+    if let Some(ref name) = renamed {
+        let body = GroupChange::Renamed(name.clone()).describe(&editor, &|id| member_name(app, id));
+        if let Some(group) = app.store.groups.get_mut(&group_id) {
+            group.name = name.clone();
+        }
+        app.store
+            .contact_names
+            .insert(group_id.clone(), name.clone());
+        if let Some(conv) = app.store.conversations.get_mut(&group_id) {
+            conv.name = name.clone();
+        }
+        db_warn(
+            app.db.upsert_conversation(&group_id, name, true),
+            "upsert_conversation",
+        );
+        handle_system_message(app, &group_id, &body, timestamp, timestamp_ms);
+    }
+    app.pending.group_updates.push(PendingGroupUpdate {
+        group_id,
+        editor,
+        timestamp_ms,
+        described: renamed.is_some(),
+    });
+    app.pending.refresh_groups = true;
+}
+
+/// Turn the pending updates for `group` into system messages by diffing it
+/// against the cached copy. Call before the cache is replaced.
+fn describe_group_updates(app: &mut App, group: &Group) {
+    let (updates, rest): (Vec<_>, Vec<_>) = std::mem::take(&mut app.pending.group_updates)
+        .into_iter()
+        .partition(|u| u.group_id == group.id);
+    app.pending.group_updates = rest;
+    // Several updates folded into one refresh are attributed to the latest
+    let Some(latest) = updates.last() else {
+        return;
+    };
+    let changes = match app.store.groups.get(&group.id) {
+        Some(old) => diff_groups(old, group),
+        // Not cached yet: we were just added
+        None if group.members.contains(&app.account) => {
+            vec![GroupChange::MembersAdded(vec![app.account.clone()])]
+        }
+        None => Vec::new(),
+    };
+    let name_of = |id: &str| member_name(app, id);
+    let mut bodies: Vec<String> = changes
+        .iter()
+        .map(|c| c.describe(&latest.editor, &name_of))
+        .collect();
+    if bodies.is_empty() && !updates.iter().any(|u| u.described) {
+        bodies.push(format!("{} updated the group", name_of(&latest.editor)));
+    }
+    let timestamp = DateTime::from_timestamp_millis(latest.timestamp_ms).unwrap_or_default();
+    for body in bodies {
+        handle_system_message(app, &group.id, &body, timestamp, latest.timestamp_ms);
+    }
 }
 
 fn handle_group_list(app: &mut App, groups: Vec<Group>) {
+    for group in &groups {
+        describe_group_updates(app, group);
+    }
+    // Updates for groups missing from the list can't be diffed
+    for update in std::mem::take(&mut app.pending.group_updates) {
+        if !update.described {
+            let body = format!("{} updated the group", member_name(app, &update.editor));
+            let timestamp =
+                DateTime::from_timestamp_millis(update.timestamp_ms).unwrap_or_default();
+            handle_system_message(app, &update.group_id, &body, timestamp, update.timestamp_ms);
+        }
+    }
     for group in groups {
         // Store name in lookup for future message resolution
         if !group.name.is_empty() {
@@ -1228,6 +1598,27 @@ fn handle_group_list(app: &mut App, groups: Vec<Group>) {
     // Re-resolve @mention display bodies: group member names may now fill
     // in UUIDs that weren't known at message-receipt time. (#283)
     app.store.rebuild_mention_display(&app.db);
+
+    app.queue_sidebar_avatars();
+}
+
+fn handle_story(app: &mut App, story: StoryMessage) {
+    app.store
+        .remember_contact_name(&story.author, story.author_name.as_deref());
+    let (content_type, attachment_path) = match story.attachment {
+        Some(att) => (Some(att.content_type), att.local_path),
+        None => (None, None),
+    };
+    app.add_story(Story {
+        author: story.author,
+        timestamp_ms: story.timestamp_ms,
+        group_id: story.group_id,
+        text: story.text,
+        content_type,
+        attachment_path,
+        allows_replies: story.allows_replies,
+        viewed: false,
+    });
 }
 
 fn handle_identity_list(app: &mut App, identities: Vec<IdentityInfo>) {
@@ -1238,6 +1629,20 @@ fn handle_identity_list(app: &mut App, identities: Vec<IdentityInfo>) {
             app.identity_trust.insert(number.clone(), id.trust_level);
         }
     }
+    // Compare each key with the one stored last time to catch changes
+    for id in &identities {
+        let Some(ref number) = id.number else {
+            continue;
+        };
+        if id.fingerprint.is_empty() {
+            continue;
+        }
+        match app.db.record_identity_key(number, &id.fingerprint) {
+            Ok(true) => announce_identity_change(app, number),
+            Ok(false) => {}
+            Err(e) => app.db_warn_visible(Err::<(), _>(e), "record_identity_key"),
+        }
+    }
     // If verify overlay is open, refresh the displayed identities
     if app.is_overlay(OverlayKind::Verify)
         && let Some(ref conv_id) = app.active_conversation
@@ -1276,23 +1681,97 @@ fn handle_identity_list(app: &mut App, identities: Vec<IdentityInfo>) {
     }
 }
 
+/// A sticker image arrived. One we sent may have been re-keyed to its
+/// server timestamp while the fetch ran.
+fn handle_sticker_fetched(
+    app: &mut App,
+    conv_id: &str,
+    timestamp_ms: i64,
+    sticker: &StickerRef,
+    result: Result<std::path::PathBuf, String>,
+) {
+    let timestamp_ms = app
+        .pending
+        .outgoing_stickers
+        .remove(&(conv_id.to_string(), timestamp_ms))
+        .unwrap_or(timestamp_ms);
+    match result {
+        Ok(path) => app.apply_sticker_image(conv_id, timestamp_ms, sticker, &path),
+        Err(e) => crate::debug_log::logf(format_args!("sticker fetch error: {e}")),
+    }
+}
+
+/// Our profile avatar was changed: show the new one (or none) right away.
+fn handle_profile_avatar_set(app: &mut App, result: Result<Option<std::path::PathBuf>, String>) {
+    match result {
+        Err(e) => app.status_message = format!("avatar error: {e}"),
+        Ok(cached) => {
+            app.status_message = if cached.is_some() {
+                "Avatar updated".to_string()
+            } else {
+                "Avatar removed".to_string()
+            };
+            let account = app.account.clone();
+            app.set_avatar(&account, cached.as_deref());
+        }
+    }
+}
+
+/// A `trust` request went through: stop holding sends for the recipient,
+/// apply the refreshed identities and report success.
+fn handle_identity_trusted(
+    app: &mut App,
+    recipient: &str,
+    verified: bool,
+    identities: Result<Vec<IdentityInfo>, String>,
+) {
+    app.acknowledge_identity(recipient);
+    let name = app
+        .store
+        .contact_names
+        .get(recipient)
+        .map_or(recipient, String::as_str)
+        .to_string();
+    app.status_message = match (identities, verified) {
+        (Ok(identities), verified) => {
+            handle_identity_list(app, identities);
+            if verified {
+                format!("Verified {name}")
+            } else {
+                format!("Accepted new safety number for {name}")
+            }
+        }
+        (Err(e), true) => format!("verified, but refresh failed: {e}"),
+        (Err(e), false) => format!("accepted, but refresh failed: {e}"),
+    };
+}
+
 fn handle_send_timestamp(app: &mut App, rpc_id: &str, server_ts: i64) {
-    // Schedule any paste temp file for deletion after the delay (signal-cli has confirmed send)
-    if let Some((path, _)) = app.pending_paste_cleanups.remove(rpc_id) {
+    // Schedule any paste temp files for deletion after the delay (signal-cli has confirmed send)
+    if let Some((paths, _)) = app.pending_paste_cleanups.remove(rpc_id) {
         app.pending_paste_cleanups.insert(
             rpc_id.to_string(),
             (
-                path,
+                paths,
                 Instant::now() + std::time::Duration::from_secs(PASTE_CLEANUP_DELAY_SECS),
             ),
         );
     }
-    if let Some((conv_id, local_ts)) = app.pending.sends.remove(rpc_id) {
+    let undelivered = app.pending.undelivered.remove(rpc_id).unwrap_or_default();
+    if let Some((conv_id, local_ts)) = app.pending.resolve_send(rpc_id) {
         crate::debug_log::logf(format_args!(
             "send confirmed: conv={} local_ts={local_ts} server_ts={server_ts}",
             crate::debug_log::mask_phone(&conv_id)
         ));
+        app.confirm_outbox_send(&conv_id, local_ts);
         let effective_ts = if server_ts != 0 { server_ts } else { local_ts };
+        if let Some(ts) = app
+            .pending
+            .outgoing_stickers
+            .get_mut(&(conv_id.clone(), local_ts))
+        {
+            *ts = effective_ts;
+        }
         let mut found = false;
         if let Some(conv) = app.store.conversations.get_mut(&conv_id) {
             // Find the outgoing message with matching local timestamp
@@ -1317,29 +1796,213 @@ fn handle_send_timestamp(app: &mut App, rpc_id: &str, server_ts: i64) {
                 "update_message_timestamp_ms",
             );
         }
+        // Right under the message, which now sits at effective_ts
+        let timestamp = DateTime::from_timestamp_millis(effective_ts).unwrap_or_else(Utc::now);
+        for body in undelivered {
+            let ts = app
+                .store
+                .conversations
+                .get(&conv_id)
+                .map_or(effective_ts, |c| c.free_timestamp(effective_ts));
+            add_system_line(app, &conv_id, &body, timestamp, ts, true);
+        }
 
         // Replay any buffered receipts that may have arrived before this SendTimestamp
         if !app.pending.receipts.is_empty() {
             let receipts = std::mem::take(&mut app.pending.receipts);
-            for (sender, receipt_type, timestamps) in receipts {
-                handle_receipt(app, &sender, &receipt_type, &timestamps);
+            for (sender, receipt_type, timestamps, when_ms) in receipts {
+                handle_receipt(app, &sender, &receipt_type, &timestamps, when_ms);
             }
         }
     }
 }
 
+/// A send was refused because these recipients' safety numbers changed.
+/// When the whole send failed it is held, showing as queued, and goes out
+/// again once the change is acknowledged.
+fn handle_untrusted_recipients(
+    app: &mut App,
+    rpc_id: Option<&str>,
+    recipients: &[String],
+    partial: bool,
+) {
+    let mut names = Vec::new();
+    for recipient in recipients {
+        // A failure may name only the ACI; key the change by number when
+        // we know it, as identity refreshes and group members do
+        let number = app.store.number_for(recipient).to_string();
+        app.db_warn_visible(
+            app.db.mark_identity_changed(&number),
+            "mark_identity_changed",
+        );
+        announce_identity_change(app, &number);
+        names.push(recipient_name(app, &number));
+    }
+    let Some(rpc_id) = rpc_id else {
+        return;
+    };
+    if partial {
+        note_undelivered(
+            app,
+            rpc_id,
+            format!(
+                "\u{26A0} Not delivered to {}: safety number changed, review it in /verify",
+                names.join(", ")
+            ),
+        );
+        return;
+    }
+    match app.pending.payloads.get(rpc_id).cloned() {
+        Some(req) => {
+            app.pending.resolve_send(rpc_id);
+            // The retry registers any paste temp files under its own rpc id
+            app.pending_paste_cleanups.remove(rpc_id);
+            app.hold_send(req);
+        }
+        None => handle_send_failed(app, rpc_id),
+    }
+}
+
+/// Signal answered a rate-limit captcha: send what it held, or reopen the
+/// overlay so another token can be pasted for the same challenge.
+fn handle_captcha_submitted(app: &mut App, result: Result<(), String>) {
+    match result {
+        Ok(()) => {
+            let held = app.pending.rate_limited_sends.len();
+            app.retry_rate_limited();
+            app.status_message = format!("Captcha accepted, resending {held} held");
+        }
+        Err(e) => {
+            app.show_rate_limit(RateLimitFailure::default());
+            app.status_message = format!("Captcha rejected: {e}");
+        }
+    }
+}
+
+/// A send was refused by Signal's rate limit. When the whole send failed it
+/// is held as queued until the limit is cleared; when only some group
+/// members were refused, a line under the message names them. Either way
+/// the overlay explains what happened.
+fn handle_rate_limited(
+    app: &mut App,
+    rpc_id: Option<&str>,
+    failure: RateLimitFailure,
+    partial: bool,
+) {
+    match rpc_id {
+        Some(rpc_id) if partial => {
+            let names: Vec<String> = failure
+                .recipients
+                .iter()
+                .map(|r| recipient_name(app, r))
+                .collect();
+            note_undelivered(
+                app,
+                rpc_id,
+                format!(
+                    "\u{26A0} Not delivered to {}: rate limited by Signal",
+                    names.join(", ")
+                ),
+            );
+        }
+        Some(rpc_id) => match app.pending.payloads.get(rpc_id).cloned() {
+            Some(req) => {
+                app.pending.resolve_send(rpc_id);
+                // The retry registers any paste temp files under its own rpc id
+                app.pending_paste_cleanups.remove(rpc_id);
+                app.hold_rate_limited(req);
+            }
+            None => handle_send_failed(app, rpc_id),
+        },
+        None => {}
+    }
+    app.show_rate_limit(failure);
+}
+
+/// A send went out to a group without some of its members: say so under
+/// the message once its `SendTimestamp` lands.
+fn note_undelivered(app: &mut App, rpc_id: &str, body: String) {
+    app.pending
+        .undelivered
+        .entry(rpc_id.to_string())
+        .or_default()
+        .push(body);
+}
+
+/// The contact name for a recipient a send result named by number or ACI.
+fn recipient_name(app: &App, recipient: &str) -> String {
+    let number = app.store.number_for(recipient);
+    app.store
+        .contact_names
+        .get(number)
+        .cloned()
+        .unwrap_or_else(|| number.to_string())
+}
+
+/// Flag `number`'s safety number as changed and say so in their 1:1 chat
+/// and in every group they're in. Sends to them are held until the change
+/// is acknowledged. A number already flagged is left alone, so a refused
+/// send and the next identity refresh don't announce the same change twice.
+fn announce_identity_change(app: &mut App, number: &str) {
+    if !app.identity_changes.insert(number.to_string()) {
+        return;
+    }
+    let name = app
+        .store
+        .contact_names
+        .get(number)
+        .cloned()
+        .unwrap_or_else(|| number.to_string());
+    let mut conv_ids: Vec<String> = app
+        .store
+        .groups
+        .values()
+        .filter(|g| g.members.iter().any(|m| m == number))
+        .map(|g| g.id.clone())
+        .filter(|id| app.store.conversations.contains_key(id))
+        .collect();
+    conv_ids.sort();
+    if app.store.conversations.contains_key(number) {
+        conv_ids.insert(0, number.to_string());
+    }
+    let body = format!("\u{26A0} Safety number with {name} changed");
+    let now = Utc::now();
+    for conv_id in conv_ids {
+        // Never share a timestamp with a message, which is how sends,
+        // receipts and deletes find theirs
+        let ts = app
+            .store
+            .conversations
+            .get(&conv_id)
+            .map_or(now.timestamp_millis(), |c| {
+                c.free_timestamp(now.timestamp_millis())
+            });
+        add_system_line(app, &conv_id, &body, now, ts, true);
+    }
+    app.status_message = format!("Safety number with {name} changed, review it in /verify");
+}
+
 fn handle_send_failed(app: &mut App, rpc_id: &str) {
-    // Schedule any paste temp file for deletion after the delay (signal-cli has finished with it)
-    if let Some((path, _)) = app.pending_paste_cleanups.remove(rpc_id) {
+    app.pending.undelivered.remove(rpc_id);
+    let resolved = app.pending.resolve_send(rpc_id);
+    // Outbox messages are retried, and keep their paste temp files for it
+    if let Some((conv_id, local_ts)) = &resolved
+        && app.retry_outbox_send(conv_id, *local_ts)
+    {
+        app.pending_paste_cleanups.remove(rpc_id);
+        return;
+    }
+    // Schedule any paste temp files for deletion after the delay (signal-cli has finished with it)
+    if let Some((paths, _)) = app.pending_paste_cleanups.remove(rpc_id) {
         app.pending_paste_cleanups.insert(
             rpc_id.to_string(),
             (
-                path,
+                paths,
                 Instant::now() + std::time::Duration::from_secs(PASTE_CLEANUP_DELAY_SECS),
             ),
         );
     }
-    if let Some((conv_id, local_ts)) = app.pending.sends.remove(rpc_id) {
+    if let Some((conv_id, local_ts)) = resolved {
         let mut found = false;
         if let Some(conv) = app.store.conversations.get_mut(&conv_id)
             && let Some(idx) = conv
@@ -1359,19 +2022,38 @@ fn handle_send_failed(app: &mut App, rpc_id: &str) {
     }
 }
 
-/// Try to upgrade an outgoing message's status in a single conversation.
-/// Returns true if a match was found for `ts`.
+/// signal-cli didn't answer a send in time. It may still have gone out, so
+/// it is not retried: the message shows as failed for a manual resend, and
+/// stays tracked so a late `SendTimestamp` still confirms it.
+fn handle_send_timed_out(app: &mut App, rpc_id: &str) {
+    // A respawn must not replay it either
+    app.pending.payloads.remove(rpc_id);
+    let Some((conv_id, local_ts)) = app.pending.sends.get(rpc_id).cloned() else {
+        return;
+    };
+    app.send_timed_out(&conv_id, local_ts);
+}
+
+/// Try to upgrade an outgoing message's status in a single conversation,
+/// and store `recipient`'s time for it. Returns true if a match was found
+/// for `ts`.
 fn try_upgrade_receipt(
     db: &Database,
     conv_id: &str,
     conv: &mut Conversation,
     ts: i64,
     new_status: MessageStatus,
+    recipient: &str,
+    when_ms: i64,
 ) -> bool {
     if let Some(idx) = conv
         .find_msg_idx(ts)
         .filter(|&idx| conv.messages[idx].is_outgoing())
     {
+        db_warn(
+            db.record_receipt(conv_id, ts, recipient, new_status, when_ms),
+            "record_receipt",
+        );
         if let Some(current) = conv.messages[idx].status
             && new_status > current
         {
@@ -1386,7 +2068,13 @@ fn try_upgrade_receipt(
     false
 }
 
-fn handle_receipt(app: &mut App, sender: &str, receipt_type: &str, timestamps: &[i64]) {
+fn handle_receipt(
+    app: &mut App,
+    sender: &str,
+    receipt_type: &str,
+    timestamps: &[i64],
+    when_ms: i64,
+) {
     let receipt_upper = receipt_type.to_uppercase();
     let new_status = match receipt_upper.as_str() {
         "DELIVERY" => MessageStatus::Delivered,
@@ -1394,6 +2082,11 @@ fn handle_receipt(app: &mut App, sender: &str, receipt_type: &str, timestamps: &
         "VIEWED" => MessageStatus::Viewed,
         _ => return,
     };
+    let when_ms = if when_ms > 0 {
+        when_ms
+    } else {
+        Utc::now().timestamp_millis()
+    };
 
     let mut matched_any = false;
 
@@ -1401,7 +2094,7 @@ fn handle_receipt(app: &mut App, sender: &str, receipt_type: &str, timestamps: &
     let conv_id = sender.to_string();
     if let Some(conv) = app.store.conversations.get_mut(&conv_id) {
         for ts in timestamps {
-            if try_upgrade_receipt(&app.db, &conv_id, conv, *ts, new_status) {
+            if try_upgrade_receipt(&app.db, &conv_id, conv, *ts, new_status, sender, when_ms) {
                 matched_any = true;
             }
         }
@@ -1412,7 +2105,7 @@ fn handle_receipt(app: &mut App, sender: &str, receipt_type: &str, timestamps: &
     if !matched_any {
         for ts in timestamps {
             for (cid, conv) in &mut app.store.conversations {
-                if try_upgrade_receipt(&app.db, cid, conv, *ts, new_status) {
+                if try_upgrade_receipt(&app.db, cid, conv, *ts, new_status, sender, when_ms) {
                     matched_any = true;
                     break;
                 }
@@ -1431,11 +2124,18 @@ fn handle_receipt(app: &mut App, sender: &str, receipt_type: &str, timestamps: &
             sender.to_string(),
             receipt_type.to_string(),
             timestamps.to_vec(),
+            when_ms,
         ));
     } else if matched_any {
         crate::debug_log::logf(format_args!(
             "receipt: {receipt_type} from {} -> {new_status:?}",
             crate::debug_log::mask_phone(sender)
         ));
+        // The matching conversation isn't tracked here; the timestamp is
+        // enough to tell whether the open message info overlay is affected
+        let info_conv = app.message_info.conv_id.clone();
+        for ts in timestamps {
+            app.refresh_message_info(&info_conv, *ts);
+        }
     }
 }
@@ -3,7 +3,8 @@
 //! Detects the host terminal's image protocol ([`ImageProtocol`]: Kitty,
 //! iTerm2, Sixel, or Halfblock fallback) and provides encoders for each:
 //! [`encode_native_png`] for Kitty/iTerm2, [`encode_sixel`] for Sixel, and
-//! [`render_image`] for Unicode halfblock approximation.
+//! [`render_image`] for Unicode halfblock approximation, which
+//! [`render_attachment_images`] also lays out as a thumbnail grid for albums.
 
 use std::io::Cursor;
 use std::path::Path;
@@ -432,6 +433,103 @@ pub fn kitty_id_color(image_id: u32) -> Color {
 /// can pin a `spawn_blocking` thread for minutes inside the resize step, and
 /// the output is at most a 30-cell column anyway. See issue #408.
 pub fn render_image(path: &Path, max_width: u32) -> Option<Vec<Line<'static>>> {
+    let rgba = decode_scaled(path, max_width, 60)?; // 30 cell-rows × 2 pixels per row
+    Some(halfblock_lines(&rgba, "  "))
+}
+
+/// Thumbnail box for album grids: cells wide, and pixels (half-rows) tall.
+const THUMB_WIDTH: u32 = 18;
+const THUMB_HEIGHT: u32 = 18;
+
+/// Where one image sits inside the lines built by
+/// [`render_attachment_images`], so native protocols can be placed over
+/// the halfblock cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageTile {
+    pub path: String,
+    /// First line of the image within the rendered lines
+    pub row: u16,
+    /// First column, counted after the two-cell indent
+    pub col: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+/// Render the image attachments of one message. A single image is drawn
+/// like [`render_image`]; an album becomes a grid of thumbnails, two or
+/// three to a row. Images that fail to decode are left out. Returns `None`
+/// when nothing could be drawn.
+pub fn render_attachment_images(paths: &[&str]) -> Option<(Vec<Line<'static>>, Vec<ImageTile>)> {
+    if let [path] = paths {
+        let lines = render_image(Path::new(path), 40)?;
+        let tile = ImageTile {
+            path: path.to_string(),
+            row: 0,
+            col: 0,
+            width: lines.first().map_or(0, |l| l.width().saturating_sub(2)) as u16,
+            height: lines.len() as u16,
+        };
+        return Some((lines, vec![tile]));
+    }
+
+    let thumbs: Vec<(&str, Vec<Line<'static>>)> = paths
+        .iter()
+        .filter_map(|path| {
+            let rgba = decode_scaled(Path::new(path), THUMB_WIDTH, THUMB_HEIGHT)?;
+            Some((*path, halfblock_lines(&rgba, "")))
+        })
+        .collect();
+    if thumbs.is_empty() {
+        return None;
+    }
+    let columns = if thumbs.len() == 2 || thumbs.len() == 4 {
+        2
+    } else {
+        3
+    };
+
+    let mut lines: Vec<Line<'static>> = Vec::new();
+    let mut tiles = Vec::with_capacity(thumbs.len());
+    for (row_idx, row) in thumbs.chunks(columns).enumerate() {
+        if row_idx > 0 {
+            lines.push(Line::from(""));
+        }
+        let top = lines.len() as u16;
+        let height = row.iter().map(|(_, t)| t.len()).max().unwrap_or(0);
+        for y in 0..height {
+            let mut spans = vec![Span::raw("  ")];
+            for (i, (_, thumb)) in row.iter().enumerate() {
+                let width = match thumb.get(y) {
+                    Some(line) => {
+                        spans.extend(line.spans.iter().cloned());
+                        line.width()
+                    }
+                    None => 0,
+                };
+                // Pad every thumbnail but the last to the box width plus a gap
+                if i + 1 < row.len() {
+                    let pad = THUMB_WIDTH as usize - width + 1;
+                    spans.push(Span::raw(" ".repeat(pad)));
+                }
+            }
+            lines.push(Line::from(spans));
+        }
+        for (i, (path, thumb)) in row.iter().enumerate() {
+            tiles.push(ImageTile {
+                path: path.to_string(),
+                row: top,
+                col: (i as u32 * (THUMB_WIDTH + 1)) as u16,
+                width: thumb.first().map_or(0, Line::width) as u16,
+                height: thumb.len() as u16,
+            });
+        }
+    }
+    Some((lines, tiles))
+}
+
+/// Decode an image and scale it down (never up) to fit within `max_width`
+/// by `max_height` pixels.
+fn decode_scaled(path: &Path, max_width: u32, max_height: u32) -> Option<image::RgbaImage> {
     /// Largest input dimension we'll attempt to decode. Anything over this
     /// is treated as a broken / hostile image and silently skipped.
     const MAX_INPUT_DIM: u32 = 8192;
@@ -460,7 +558,7 @@ pub fn render_image(path: &Path, max_width: u32) -> Option<Vec<Line<'static>>> {
     };
 
     let cap_width = max_width;
-    let cap_height: u32 = 60; // 30 cell-rows × 2 pixels per row
+    let cap_height = max_height;
 
     let (orig_w, orig_h) = img.dimensions();
     if orig_w == 0 || orig_h == 0 {
@@ -480,45 +578,6 @@ pub fn render_image(path: &Path, max_width: u32) -> Option<Vec<Line<'static>>> {
     let resized = img.resize_exact(new_w, new_h, image::imageops::FilterType::Triangle);
     let rgba = resized.to_rgba8();
 
-    let (w, h) = rgba.dimensions();
-    // Process pixel rows in pairs (top/bottom per cell row)
-    let row_pairs = h.div_ceil(2);
-
-    let mut lines: Vec<Line<'static>> = Vec::with_capacity(row_pairs as usize);
-
-    for row in 0..row_pairs {
-        let y_top = row * 2;
-        let y_bot = y_top + 1;
-
-        let mut spans: Vec<Span<'static>> = Vec::with_capacity(w as usize + 1);
-        // 2-space indent for visual separation
-        spans.push(Span::raw("  "));
-
-        for x in 0..w {
-            let top_pixel = rgba.get_pixel(x, y_top);
-            let fg = if top_pixel[3] < 128 {
-                Color::Reset
-            } else {
-                Color::Rgb(top_pixel[0], top_pixel[1], top_pixel[2])
-            };
-
-            let bg = if y_bot < h {
-                let bot_pixel = rgba.get_pixel(x, y_bot);
-                if bot_pixel[3] < 128 {
-                    Color::Reset
-                } else {
-                    Color::Rgb(bot_pixel[0], bot_pixel[1], bot_pixel[2])
-                }
-            } else {
-                Color::Reset
-            };
-
-            spans.push(Span::styled("▀", Style::default().fg(fg).bg(bg)));
-        }
-
-        lines.push(Line::from(spans));
-    }
-
     let elapsed_ms = start.elapsed().as_millis();
     crate::debug_log::logf(format_args!(
         "render_image done: path={} elapsed_ms={elapsed_ms} src={}x{} out={}x{}",
@@ -542,7 +601,89 @@ pub fn render_image(path: &Path, max_width: u32) -> Option<Vec<Line<'static>>> {
             new_h
         ));
     }
-    Some(lines)
+    Some(rgba)
+}
+
+/// Turn an image into halfblock lines, one line per two pixel rows, each
+/// starting with `indent`. Transparent pixels keep the terminal background.
+fn halfblock_lines(rgba: &image::RgbaImage, indent: &'static str) -> Vec<Line<'static>> {
+    let color = |pixel: &image::Rgba<u8>| {
+        if pixel[3] < 128 {
+            Color::Reset
+        } else {
+            Color::Rgb(pixel[0], pixel[1], pixel[2])
+        }
+    };
+    let (w, h) = rgba.dimensions();
+    // Process pixel rows in pairs (top/bottom per cell row)
+    let row_pairs = h.div_ceil(2);
+
+    let mut lines: Vec<Line<'static>> = Vec::with_capacity(row_pairs as usize);
+    for row in 0..row_pairs {
+        let y_top = row * 2;
+        let y_bot = y_top + 1;
+
+        let mut spans: Vec<Span<'static>> = Vec::with_capacity(w as usize + 1);
+        if !indent.is_empty() {
+            spans.push(Span::raw(indent));
+        }
+        for x in 0..w {
+            let fg = color(rgba.get_pixel(x, y_top));
+            let bg = if y_bot < h {
+                color(rgba.get_pixel(x, y_bot))
+            } else {
+                Color::Reset
+            };
+            spans.push(Span::styled("▀", Style::default().fg(fg).bg(bg)));
+        }
+        lines.push(Line::from(spans));
+    }
+    lines
+}
+
+/// Decode an avatar and crop it to its centred square.
+pub fn decode_avatar(path: &Path) -> Option<image::DynamicImage> {
+    /// Avatars are small; anything bigger than this is not one.
+    const MAX_INPUT_DIM: u32 = 4096;
+
+    let mut reader = ImageReader::open(path).ok()?.with_guessed_format().ok()?;
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_INPUT_DIM);
+    limits.max_image_height = Some(MAX_INPUT_DIM);
+    reader.limits(limits);
+    let img = reader.decode().ok()?;
+
+    let (w, h) = img.dimensions();
+    let side = w.min(h);
+    if side == 0 {
+        return None;
+    }
+    Some(img.crop_imm((w - side) / 2, (h - side) / 2, side, side))
+}
+
+/// Render a square avatar from [`decode_avatar`] as halfblocks `width`
+/// cells wide and `width / 2` rows tall, which looks square with the
+/// usual 1:2 cell shape.
+pub fn avatar_lines(square: &image::DynamicImage, width: u32) -> Vec<Line<'static>> {
+    let resized = square.resize_exact(width, width, image::imageops::FilterType::Triangle);
+    halfblock_lines(&resized.to_rgba8(), "")
+}
+
+/// Encode a square avatar from [`decode_avatar`] as a PNG filling `width`
+/// cells by `width / 2` rows, for the native protocols. Returns the same
+/// `(base64_data, pixel_width, pixel_height)` as [`encode_native_png`].
+pub fn encode_avatar_png(square: &image::DynamicImage, width: u32) -> Option<(String, u32, u32)> {
+    // ~8px per cell width, matching encode_native_png
+    let side = width * 8;
+    let resized = square.resize_exact(side, side, image::imageops::FilterType::Triangle);
+    let mut buf = Cursor::new(Vec::new());
+    resized.write_to(&mut buf, image::ImageFormat::Png).ok()?;
+    use base64::Engine;
+    Some((
+        base64::engine::general_purpose::STANDARD.encode(buf.into_inner()),
+        side,
+        side,
+    ))
 }
 
 /// Threshold (ms) above which `render_image` emits an always-on warning,
@@ -738,4 +879,102 @@ mod tests {
         let wrapped = wrap_for_tmux("plain ascii");
         assert_eq!(wrapped, "\x1bPtmux;plain ascii\x1b\\");
     }
+
+    #[test]
+    fn avatar_is_cropped_to_square() {
+        // Wide image: red left third, green middle, blue right third
+        let img = image::RgbaImage::from_fn(30, 10, |x, _| match x / 10 {
+            0 => image::Rgba([255, 0, 0, 255]),
+            1 => image::Rgba([0, 255, 0, 255]),
+            _ => image::Rgba([0, 0, 255, 255]),
+        });
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("avatar.png");
+        img.save(&path).unwrap();
+
+        let square = decode_avatar(&path).unwrap();
+        assert_eq!(square.dimensions(), (10, 10));
+        let lines = avatar_lines(&square, 4);
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|l| l.width() == 4));
+        // Only the green centre survives the crop
+        let green = Color::Rgb(0, 255, 0);
+        assert!(
+            lines
+                .iter()
+                .flat_map(|l| &l.spans)
+                .all(|s| s.style.fg == Some(green) && s.style.bg == Some(green))
+        );
+    }
+
+    /// Write `count` solid-colour PNGs of the given size into `dir`.
+    fn write_images(dir: &Path, count: usize, w: u32, h: u32) -> Vec<String> {
+        (0..count)
+            .map(|i| {
+                let path = dir.join(format!("img{i}.png"));
+                image::RgbaImage::from_pixel(w, h, image::Rgba([200, 100, 50, 255]))
+                    .save(&path)
+                    .unwrap();
+                path.to_string_lossy().into_owned()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn single_attachment_image_is_one_full_size_tile() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = write_images(dir.path(), 1, 80, 40);
+        let refs: Vec<&str> = paths.iter().map(String::as_str).collect();
+        let (lines, tiles) = render_attachment_images(&refs).unwrap();
+        assert_eq!(lines.len(), 10);
+        assert_eq!(
+            tiles,
+            vec![ImageTile {
+                path: paths[0].clone(),
+                row: 0,
+                col: 0,
+                width: 40,
+                height: 10,
+            }]
+        );
+    }
+
+    #[test]
+    fn album_is_laid_out_as_a_grid() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = write_images(dir.path(), 4, 36, 36);
+        let refs: Vec<&str> = paths.iter().map(String::as_str).collect();
+        let (lines, tiles) = render_attachment_images(&refs).unwrap();
+
+        // Four images make two rows of two, with a blank line between rows
+        let placed: Vec<(u16, u16)> = tiles.iter().map(|t| (t.row, t.col)).collect();
+        assert_eq!(placed, vec![(0, 0), (0, 19), (10, 0), (10, 19)]);
+        assert!(tiles.iter().all(|t| t.width == 18 && t.height == 9));
+        assert_eq!(lines.len(), 19);
+        assert_eq!(lines[0].width(), 2 + 18 + 1 + 18);
+        assert_eq!(lines[9].width(), 0);
+    }
+
+    #[test]
+    fn album_skips_images_that_fail_to_decode() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut paths = write_images(dir.path(), 2, 36, 36);
+        let broken = dir.path().join("broken.png");
+        std::fs::write(&broken, b"not an image").unwrap();
+        paths.insert(1, broken.to_string_lossy().into_owned());
+        let refs: Vec<&str> = paths.iter().map(String::as_str).collect();
+
+        let (_, tiles) = render_attachment_images(&refs).unwrap();
+        let placed: Vec<&str> = tiles.iter().map(|t| t.path.as_str()).collect();
+        assert_eq!(placed, vec![paths[0].as_str(), paths[2].as_str()]);
+        assert!(render_attachment_images(&[refs[1]]).is_none());
+    }
+
+    #[test]
+    fn decode_avatar_rejects_non_images() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("avatar.png");
+        std::fs::write(&path, b"not an image").unwrap();
+        assert!(decode_avatar(&path).is_none());
+    }
 }
@@ -18,7 +18,7 @@ pub const COMMANDS: &[CommandInfo] = &[
         name: "/join",
         alias: "/j",
         args: "<name>",
-        description: "Switch to a conversation",
+        description: "Switch to a conversation, or join a group via link",
     },
     CommandInfo {
         name: "/part",
@@ -86,6 +86,30 @@ pub const COMMANDS: &[CommandInfo] = &[
         args: "",
         description: "Browse contacts",
     },
+    CommandInfo {
+        name: "/share-contact",
+        alias: "",
+        args: "",
+        description: "Send a contact card",
+    },
+    CommandInfo {
+        name: "/stories",
+        alias: "",
+        args: "",
+        description: "View contacts' stories",
+    },
+    CommandInfo {
+        name: "/calls",
+        alias: "",
+        args: "",
+        description: "Browse recent calls",
+    },
+    CommandInfo {
+        name: "/devices",
+        alias: "",
+        args: "",
+        description: "Manage linked devices",
+    },
     CommandInfo {
         name: "/settings",
         alias: "",
@@ -146,6 +170,12 @@ pub const COMMANDS: &[CommandInfo] = &[
         args: "[search]",
         description: "Open emoji picker",
     },
+    CommandInfo {
+        name: "/sticker",
+        alias: "",
+        args: "[install <url>]",
+        description: "Send a sticker, or install a sticker pack",
+    },
     CommandInfo {
         name: "/export",
         alias: "",
@@ -211,6 +241,14 @@ pub enum InputAction {
     Settings,
     /// Open contacts overlay
     Contacts,
+    /// Pick a contact to send as a card
+    ShareContact,
+    /// Open stories overlay
+    Stories,
+    /// Open call history overlay
+    Calls,
+    /// Open linked devices overlay
+    Devices,
     /// Open file browser to attach a file
     Attach,
     /// Paste clipboard contents (image, file path, or text)
@@ -221,6 +259,8 @@ pub enum InputAction {
     SetDisappearing(String),
     /// Open group management menu
     Group,
+    /// Join a group from a signal.group invite link
+    JoinGroup(String),
     /// Open theme picker
     Theme,
     /// Create a poll
@@ -239,6 +279,10 @@ pub enum InputAction {
     Keybindings,
     /// Open the emoji picker overlay (optional initial search filter)
     Emoji(String),
+    /// Open the sticker picker overlay
+    Stickers,
+    /// Install a sticker pack from a signal.art link
+    InstallStickerPack(String),
     /// Export chat history to a text file (optional: last N messages)
     Export(Option<usize>),
     /// Unknown command
@@ -268,6 +312,8 @@ pub fn parse_input(input: &str) -> InputAction {
         "/join" | "/j" => {
             if arg.is_empty() {
                 InputAction::Unknown("/join requires a contact or group name".to_string())
+            } else if arg.starts_with("https://signal.group/") {
+                InputAction::JoinGroup(arg)
             } else {
                 InputAction::Join(arg)
             }
@@ -304,6 +350,10 @@ pub fn parse_input(input: &str) -> InputAction {
             }
         }
         "/contacts" | "/c" => InputAction::Contacts,
+        "/share-contact" => InputAction::ShareContact,
+        "/stories" => InputAction::Stories,
+        "/calls" => InputAction::Calls,
+        "/devices" => InputAction::Devices,
         "/settings" => InputAction::Settings,
         "/disappearing" | "/dm" => {
             if arg.is_empty() {
@@ -327,6 +377,13 @@ pub fn parse_input(input: &str) -> InputAction {
             ),
         },
         "/emoji" | "/e" => InputAction::Emoji(arg),
+        "/sticker" => match arg.split_once(' ') {
+            _ if arg.is_empty() => InputAction::Stickers,
+            Some(("install", url)) if !url.trim().is_empty() => {
+                InputAction::InstallStickerPack(url.trim().to_string())
+            }
+            _ => InputAction::Unknown("Usage: /sticker [install <url>]".to_string()),
+        },
         "/verify" | "/v" => InputAction::Verify,
         "/profile" => InputAction::Profile,
         "/about" => InputAction::About,
@@ -520,6 +577,11 @@ mod tests {
     #[case("/pa", InputAction::Paste)]
     #[case("/contacts", InputAction::Contacts)]
     #[case("/c", InputAction::Contacts)]
+    #[case("/share-contact", InputAction::ShareContact)]
+    #[case("/stories", InputAction::Stories)]
+    #[case("/calls", InputAction::Calls)]
+    #[case("/devices", InputAction::Devices)]
+    #[case("/sticker", InputAction::Stickers)]
     #[case("/help", InputAction::Help)]
     #[case("/h", InputAction::Help)]
     #[case("/block", InputAction::Block)]
@@ -543,6 +605,7 @@ mod tests {
 
     #[rstest]
     #[case("/join Alice", InputAction::Join("Alice".to_string()))]
+    #[case("/join @alice.42", InputAction::Join("@alice.42".to_string()))]
     #[case("/j +1234567890", InputAction::Join("+1234567890".to_string()))]
     #[case("/search hello", InputAction::Search("hello".to_string()))]
     #[case("/s world", InputAction::Search("world".to_string()))]
@@ -554,6 +617,20 @@ mod tests {
     #[case("/mute 1d", InputAction::Mute(Some("1d".to_string())))]
     #[case("/emoji smile", InputAction::Emoji("smile".to_string()))]
     #[case("/e rocket", InputAction::Emoji("rocket".to_string()))]
+    #[case(
+        "/sticker install https://signal.art/addstickers/#pack_id=ab&pack_key=cd",
+        InputAction::InstallStickerPack(
+            "https://signal.art/addstickers/#pack_id=ab&pack_key=cd".to_string()
+        )
+    )]
+    #[case(
+        "/join https://signal.group/#CjQKIA",
+        InputAction::JoinGroup("https://signal.group/#CjQKIA".to_string())
+    )]
+    #[case(
+        "/sticker send",
+        InputAction::Unknown("Usage: /sticker [install <url>]".to_string())
+    )]
     fn command_with_argument(#[case] input: &str, #[case] expected: InputAction) {
         assert_eq!(parse_input(input), expected);
     }
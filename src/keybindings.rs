@@ -20,6 +20,7 @@ pub enum KeyAction {
     // Global
     Quit,
     Lock,
+    SwitchAccount,
     NextConversation,
     PrevConversation,
     ResizeSidebarLeft,
@@ -66,6 +67,7 @@ pub enum KeyAction {
     JumpToQuote,
     JumpBack,
     SidebarSearch,
+    RevealSpoiler,
     // Insert
     ExitInsert,
     SendMessage,
@@ -414,6 +416,7 @@ pub fn parse_key_combo(s: &str) -> Result<KeyCombo, String> {
 /// Actions in display order for the keybindings overlay.
 pub const GLOBAL_ACTIONS: &[KeyAction] = &[
     KeyAction::Quit,
+    KeyAction::SwitchAccount,
     KeyAction::NextConversation,
     KeyAction::PrevConversation,
     KeyAction::ResizeSidebarLeft,
@@ -460,6 +463,7 @@ pub const NORMAL_ACTIONS: &[KeyAction] = &[
     KeyAction::JumpToQuote,
     KeyAction::JumpBack,
     KeyAction::SidebarSearch,
+    KeyAction::RevealSpoiler,
 ];
 
 pub const INSERT_ACTIONS: &[KeyAction] = &[
@@ -474,6 +478,7 @@ pub fn action_label(action: KeyAction) -> &'static str {
     match action {
         KeyAction::Quit => "Quit",
         KeyAction::Lock => "Lock session",
+        KeyAction::SwitchAccount => "Switch account",
         KeyAction::NextConversation => "Next conversation",
         KeyAction::PrevConversation => "Previous conversation",
         KeyAction::ResizeSidebarLeft => "Shrink sidebar",
@@ -515,6 +520,7 @@ pub fn action_label(action: KeyAction) -> &'static str {
         KeyAction::OpenActionMenu => "Action menu",
         KeyAction::PinMessage => "Pin/unpin message",
         KeyAction::JumpToQuote => "Jump to quoted message",
+        KeyAction::RevealSpoiler => "Reveal/hide spoiler",
         KeyAction::JumpBack => "Jump back",
         KeyAction::SidebarSearch => "Filter sidebar",
         KeyAction::ExitInsert => "Normal mode",
@@ -571,6 +577,7 @@ const DEFAULT_BINDINGS: &[BindingRow] = {
         // Global
         (Global, M::CONTROL, Char('c'), Quit),
         (Global, M::CONTROL, Char('l'), Lock),
+        (Global, M::ALT, Char('a'), SwitchAccount),
         (Global, M::NONE, Tab, NextConversation),
         (Global, M::SHIFT, BackTab, PrevConversation),
         (Global, M::CONTROL, KeyCode::Left, ResizeSidebarLeft),
@@ -615,6 +622,7 @@ const DEFAULT_BINDINGS: &[BindingRow] = {
         (Normal, M::NONE, Char('Q'), JumpToQuote),
         (Normal, M::CONTROL, Char('o'), JumpBack),
         (Normal, M::NONE, Char('s'), SidebarSearch),
+        (Normal, M::NONE, Char('z'), RevealSpoiler),
         // Insert
         (Insert, M::NONE, Esc, ExitInsert),
         (Insert, M::NONE, Enter, SendMessage),
@@ -639,6 +647,7 @@ const EMACS_BINDINGS: &[BindingRow] = {
         // Global
         (Global, M::CONTROL, Char('c'), Quit),
         (Global, M::CONTROL, Char('l'), Lock),
+        (Global, M::ALT, Char('a'), SwitchAccount),
         (Global, M::NONE, Tab, NextConversation),
         (Global, M::SHIFT, BackTab, PrevConversation),
         (Global, M::CONTROL, KeyCode::Left, ResizeSidebarLeft),
@@ -676,6 +685,7 @@ const EMACS_BINDINGS: &[BindingRow] = {
         (Insert, M::ALT, Char('p'), PrevSearchResult),
         (Insert, M::ALT, Char('m'), OpenActionMenu),
         (Insert, M::ALT, Char('Q'), JumpToQuote),
+        (Insert, M::ALT, Char('z'), RevealSpoiler),
         (Insert, M::CONTROL, Char('o'), JumpBack),
     ]
 };
@@ -696,6 +706,7 @@ const MINIMAL_BINDINGS: &[BindingRow] = {
         (Global, M::CONTROL, Char('q'), Quit),
         (Global, M::CONTROL, Char('c'), Quit),
         (Global, M::CONTROL, Char('l'), Lock),
+        (Global, M::ALT, Char('a'), SwitchAccount),
         (Global, M::NONE, Tab, NextConversation),
         (Global, M::SHIFT, BackTab, PrevConversation),
         (Global, M::CONTROL, KeyCode::Left, ResizeSidebarLeft),
@@ -724,6 +735,7 @@ const MINIMAL_BINDINGS: &[BindingRow] = {
         (Insert, M::NONE, F(8), OpenActionMenu),
         (Insert, M::NONE, F(9), JumpToQuote),
         (Insert, M::NONE, F(10), JumpBack),
+        (Insert, M::NONE, F(11), RevealSpoiler),
     ]
 };
 
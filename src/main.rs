@@ -20,6 +20,7 @@ mod keybindings;
 mod link;
 mod list_overlay;
 mod mute;
+mod register;
 mod settings_profile;
 mod setup;
 mod signal;
@@ -55,8 +56,13 @@ use ratatui::{
 
 use app::{App, InputMode, SendRequest};
 use config::Config;
+use domain::{AccountBadge, account_label};
 use setup::SetupResult;
-use signal::client::SignalClient;
+use signal::client::{SignalClient, SignalRpc};
+use signal::supervisor::Supervisor;
+use signal::types::{
+    AccountUsername, AvatarRef, GroupEdit, GroupLinkAction, SignalEvent, StickerRef,
+};
 
 /// Keyboard polling interval for the main event loop.
 const POLL_TIMEOUT: Duration = Duration::from_millis(50);
@@ -274,6 +280,7 @@ async fn run_main_flow(
             database,
             false,
             config_path,
+            BackgroundAccounts::default(),
         )
         .await;
     }
@@ -327,10 +334,10 @@ async fn run_main_flow(
         None
     };
 
-    // Spawn signal-cli backend directly (skip the old pre-flight check that spawned
-    // a throwaway JVM process). If the account isn't registered, signal-cli will exit
-    // quickly and we fall back to the linking flow.
-    let mut signal_client = match SignalClient::spawn(config).await {
+    // Spawn signal-cli backend directly, or attach to the configured daemon (skip the
+    // old pre-flight check that spawned a throwaway JVM process). If the account isn't
+    // registered, signal-cli will exit quickly and we fall back to the linking flow.
+    let mut signal_client = match SignalClient::connect(config).await {
         Ok(client) => client,
         Err(e) => {
             let msg = format!("{e}");
@@ -365,7 +372,7 @@ async fn run_main_flow(
         }
 
         // Re-spawn after successful linking
-        signal_client = match SignalClient::spawn(config).await {
+        signal_client = match SignalClient::connect(config).await {
             Ok(client) => client,
             Err(e) => {
                 let msg = format!("{e}");
@@ -375,6 +382,23 @@ async fn run_main_flow(
         };
     }
 
+    // Start any extra accounts; each runs its own signal-cli and database
+    let mut background = BackgroundAccounts::default();
+    for account in config.all_accounts().into_iter().skip(1) {
+        match start_account_session(config, &account, incognito, config_path).await {
+            Ok(session) => background.sessions.push(session),
+            Err(e) => {
+                debug_log::logf(format_args!(
+                    "account {} failed to start: {e}",
+                    debug_log::mask_phone(&account)
+                ));
+                background
+                    .failures
+                    .push(format!("{}: {e}", account_label(&account)));
+            }
+        }
+    }
+
     // Run the app
     let result = run_app(
         terminal,
@@ -383,6 +407,7 @@ async fn run_main_flow(
         database,
         incognito,
         config_path,
+        background,
     )
     .await;
 
@@ -573,6 +598,9 @@ fn emit_passthrough_seq(
 /// For iTerm2: overlay pre-resized images on top of the halfblock placeholders
 /// using cursor-positioned inline image sequences.
 ///
+/// For iTerm2 and Sixel, images placed by an overlay (`overlay_images`)
+/// replace the chat's for that frame.
+///
 /// When running inside tmux (`$TMUX` set), every Kitty (`\x1b_G…\x1b\\`) and
 /// iTerm2 (`\x1b]1337;…\x07`) escape is wrapped in tmux's DCS passthrough
 /// envelope so the outer terminal can still see it. Requires `tmux 3.3+` with
@@ -629,9 +657,16 @@ fn emit_native_images(backend: &mut CrosstermBackend<io::Stdout>, app: &mut App)
         return Ok(());
     }
 
+    // An overlay showing its own image (a detail avatar) covers the chat,
+    // so draw that instead of the chat's images.
+    let overlay_image = !app.image.overlay_images.is_empty();
+    if overlay_image {
+        app.image.visible_images = std::mem::take(&mut app.image.overlay_images);
+    }
+
     // Sixel: slice the cached full Sixel to the visible region (instant string op).
     if protocol == image_render::ImageProtocol::Sixel {
-        if app.has_overlay() || app.image.visible_images.is_empty() {
+        if (app.has_overlay() && !overlay_image) || app.image.visible_images.is_empty() {
             app.image.visible_images.clear();
             return Ok(());
         }
@@ -740,6 +775,13 @@ fn emit_native_images(backend: &mut CrosstermBackend<io::Stdout>, app: &mut App)
 
 /// Dispatch a SendRequest to signal-cli.
 async fn dispatch_send(signal_client: &mut SignalClient, app: &mut App, req: SendRequest) {
+    // Keep a copy of sends we track until confirmation, so they can be
+    // replayed if signal-cli dies before answering.
+    let replay = matches!(
+        req,
+        SendRequest::Message { .. } | SendRequest::PollCreate { .. } | SendRequest::Sticker { .. }
+    )
+    .then(|| req.clone());
     match req {
         SendRequest::Message {
             recipient,
@@ -747,12 +789,12 @@ async fn dispatch_send(signal_client: &mut SignalClient, app: &mut App, req: Sen
             is_group,
             local_ts_ms,
             mentions,
-            attachment,
+            text_styles,
+            attachments,
             quote_timestamp,
             quote_author,
             quote_body,
         } => {
-            let attachments: Vec<std::path::PathBuf> = attachment.into_iter().collect();
             let quote = match (quote_author, quote_timestamp, quote_body) {
                 (Some(author), Some(ts), Some(body_text)) => Some((author, ts, body_text)),
                 _ => None,
@@ -764,6 +806,7 @@ async fn dispatch_send(signal_client: &mut SignalClient, app: &mut App, req: Sen
                     &body,
                     is_group,
                     &mentions,
+                    &text_styles,
                     &att_refs,
                     quote.as_ref().map(|(a, t, b)| (a.as_str(), *t, b.as_str())),
                 )
@@ -777,26 +820,29 @@ async fn dispatch_send(signal_client: &mut SignalClient, app: &mut App, req: Sen
                     app.pending
                         .sends
                         .insert(rpc_id.clone(), (recipient.to_string(), local_ts_ms));
-                    // Register any paste temp file for deferred deletion. The actual delete is
-                    // triggered after send confirmation; this sentinel keeps it alive until then.
-                    // Only one paste attachment per send is expected; break after the first match.
-                    for path in &attachments {
-                        if path.starts_with(&app.paste_temp_path) {
-                            let sentinel = Instant::now()
-                                + Duration::from_secs(app::PASTE_CLEANUP_SENTINEL_SECS);
-                            app.pending_paste_cleanups
-                                .insert(rpc_id.clone(), (path.clone(), sentinel));
-                            break;
-                        }
+                    if let Some(req) = replay {
+                        app.pending.payloads.insert(rpc_id.clone(), req);
+                    }
+                    // Register any paste temp files for deferred deletion. The actual delete is
+                    // triggered after send confirmation; this sentinel keeps them alive until then.
+                    let pasted: Vec<std::path::PathBuf> = attachments
+                        .iter()
+                        .filter(|path| path.starts_with(&app.paste_temp_path))
+                        .cloned()
+                        .collect();
+                    if !pasted.is_empty() {
+                        let sentinel =
+                            Instant::now() + Duration::from_secs(app::PASTE_CLEANUP_SENTINEL_SECS);
+                        app.pending_paste_cleanups
+                            .insert(rpc_id.clone(), (pasted, sentinel));
                     }
                 }
                 Err(e) => {
-                    app.status_message = format!("send error: {e}");
-                    // RPC failed to send — delete temp file immediately (signal-cli never saw it)
-                    for path in &attachments {
-                        if path.starts_with(&app.paste_temp_path) {
-                            let _ = std::fs::remove_file(path);
-                        }
+                    debug_log::logf(format_args!("send error: {e}"));
+                    // Retried from the outbox, which keeps any paste temp files
+                    // until the message is sent or discarded
+                    if !app.retry_outbox_send(&recipient, local_ts_ms) {
+                        app.status_message = format!("send error: {e}");
                     }
                 }
             }
@@ -830,6 +876,7 @@ async fn dispatch_send(signal_client: &mut SignalClient, app: &mut App, req: Sen
             edit_timestamp,
             local_ts_ms,
             mentions,
+            text_styles,
             quote_timestamp,
             quote_author,
             quote_body,
@@ -845,6 +892,7 @@ async fn dispatch_send(signal_client: &mut SignalClient, app: &mut App, req: Sen
                     is_group,
                     edit_timestamp,
                     &mentions,
+                    &text_styles,
                     quote.as_ref().map(|(a, t, b)| (a.as_str(), *t, b.as_str())),
                 )
                 .await
@@ -1072,7 +1120,12 @@ async fn dispatch_send(signal_client: &mut SignalClient, app: &mut App, req: Sen
                 .await
             {
                 Ok(rpc_id) => {
-                    app.pending.sends.insert(rpc_id, (recipient, local_ts_ms));
+                    app.pending
+                        .sends
+                        .insert(rpc_id.clone(), (recipient, local_ts_ms));
+                    if let Some(req) = replay {
+                        app.pending.payloads.insert(rpc_id, req);
+                    }
                 }
                 Err(e) => {
                     app.status_message = format!("poll error: {e}");
@@ -1134,33 +1187,148 @@ async fn dispatch_send(signal_client: &mut SignalClient, app: &mut App, req: Sen
                 }
             }
         }
-        SendRequest::ListIdentities => {
-            let _ = signal_client.list_identities().await;
-        }
-        SendRequest::TrustIdentity {
+        SendRequest::Sticker {
             recipient,
-            safety_number,
+            is_group,
+            local_ts_ms,
+            sticker,
         } => {
+            app.pending
+                .outgoing_stickers
+                .insert((recipient.clone(), local_ts_ms), local_ts_ms);
+            fetch_sticker_image(
+                signal_client,
+                recipient.clone(),
+                local_ts_ms,
+                sticker.clone(),
+            );
             match signal_client
-                .trust_identity(&recipient, &safety_number)
+                .send_sticker(&recipient, is_group, &sticker.pack_id, sticker.sticker_id)
                 .await
             {
+                Ok(rpc_id) => {
+                    app.pending
+                        .sends
+                        .insert(rpc_id.clone(), (recipient, local_ts_ms));
+                    if let Some(req) = replay {
+                        app.pending.payloads.insert(rpc_id, req);
+                    }
+                }
+                Err(e) => {
+                    app.status_message = format!("send error: {e}");
+                }
+            }
+        }
+        SendRequest::ListStickerPacks => signal_client.detach(|rpc| async move {
+            let packs = rpc.list_sticker_packs().await.map_err(|e| e.to_string());
+            rpc.post(SignalEvent::StickerPacks(packs)).await;
+        }),
+        SendRequest::InstallStickerPack { url } => signal_client.detach(|rpc| async move {
+            match rpc.add_sticker_pack(&url).await {
+                Err(e) => {
+                    rpc.post(SignalEvent::Notice(format!("sticker pack error: {e}")))
+                        .await;
+                }
+                Ok(()) => {
+                    rpc.post(SignalEvent::Notice("Sticker pack installed".to_string()))
+                        .await;
+                    if let Ok(packs) = rpc.list_sticker_packs().await {
+                        rpc.post(SignalEvent::StickerPacks(Ok(packs))).await;
+                    }
+                }
+            }
+        }),
+        SendRequest::FetchSticker {
+            conv_id,
+            timestamp_ms,
+            sticker,
+        } => fetch_sticker_image(signal_client, conv_id, timestamp_ms, sticker),
+        SendRequest::ViewedReceipt {
+            recipient,
+            timestamp_ms,
+        } => {
+            if let Err(e) = signal_client
+                .send_viewed_receipt(&recipient, timestamp_ms)
+                .await
+            {
+                debug_log::logf(format_args!("viewed receipt error: {e}"));
+            }
+        }
+        SendRequest::ListIdentities => {
+            let _ = signal_client.list_identities().await;
+        }
+        SendRequest::ListGroups => {
+            let _ = signal_client.list_groups().await;
+        }
+        SendRequest::EditGroup { group_id, edit } => {
+            match signal_client.edit_group(&group_id, &edit).await {
                 Err(e) => {
-                    app.status_message = format!("trust error: {e}");
+                    app.status_message = format!("edit group error: {e}");
                 }
                 _ => {
-                    app.status_message = format!(
-                        "Verified {}",
-                        app.store
-                            .contact_names
-                            .get(&recipient)
-                            .unwrap_or(&recipient)
-                    );
-                    // Re-fetch identities to update trust levels
-                    let _ = signal_client.list_identities().await;
+                    app.status_message = match &edit {
+                        GroupEdit::Admin { member, admin } => {
+                            let name = app
+                                .store
+                                .contact_names
+                                .get(member)
+                                .cloned()
+                                .unwrap_or_else(|| member.clone());
+                            if *admin {
+                                format!("Made {name} an admin")
+                            } else {
+                                format!("Removed {name} as admin")
+                            }
+                        }
+                        GroupEdit::Permission { policy, .. } => {
+                            format!("Updated \"{}\" permission", policy.label())
+                        }
+                        GroupEdit::Description(_) => "Updated group description".to_string(),
+                        GroupEdit::Avatar(_) => "Updated group avatar".to_string(),
+                        GroupEdit::Link(GroupLinkAction::Enable) => {
+                            "Invite link enabled".to_string()
+                        }
+                        GroupEdit::Link(GroupLinkAction::Reset) => "Invite link reset".to_string(),
+                        GroupEdit::Link(GroupLinkAction::Disable) => {
+                            "Invite link disabled".to_string()
+                        }
+                    };
+                    if let GroupEdit::Avatar(file) = &edit {
+                        let avatar = AvatarRef::Group(group_id.clone());
+                        match signal_client.cache_avatar(&avatar, std::path::Path::new(file)) {
+                            Ok(path) => app.set_avatar(&group_id, Some(&path)),
+                            Err(e) => debug_log::logf(format_args!("avatar cache error: {e}")),
+                        }
+                    }
+                    let _ = signal_client.list_groups().await;
                 }
             }
         }
+        SendRequest::JoinGroup { uri } => match signal_client.join_group(&uri).await {
+            Err(e) => {
+                app.status_message = format!("join group error: {e}");
+            }
+            _ => {
+                app.status_message = "Joined group".to_string();
+                let _ = signal_client.list_groups().await;
+            }
+        },
+        SendRequest::TrustIdentity {
+            recipient,
+            safety_number,
+        } => signal_client.detach(|rpc| trust_and_refresh(rpc, recipient, Some(safety_number))),
+        SendRequest::AcknowledgeIdentity { recipient } => {
+            signal_client.detach(|rpc| trust_and_refresh(rpc, recipient, None))
+        }
+        SendRequest::SubmitRateLimitChallenge { challenge, captcha } => {
+            signal_client.detach(|rpc| async move {
+                let result = rpc
+                    .submit_rate_limit_challenge(&challenge, &captcha)
+                    .await
+                    .map_err(|e| e.to_string());
+                rpc.post(SignalEvent::CaptchaSubmitted(result)).await;
+            })
+        }
         SendRequest::UpdateProfile {
             given_name,
             family_name,
@@ -1179,6 +1347,169 @@ async fn dispatch_send(signal_client: &mut SignalClient, app: &mut App, req: Sen
                 }
             }
         }
+        SendRequest::ResolveUsername { username } => signal_client.detach(|rpc| async move {
+            let event = match rpc.resolve_username(&username).await {
+                Err(e) => SignalEvent::Notice(format!("username lookup error: {e}")),
+                Ok(None) => {
+                    SignalEvent::Notice(format!("No Signal account has the username {username}"))
+                }
+                Ok(Some(resolved)) => SignalEvent::UsernameResolved(resolved),
+            };
+            rpc.post(event).await;
+        }),
+        SendRequest::SetUsername { username } => signal_client.detach(|rpc| async move {
+            let event = match rpc.set_username(&username).await {
+                Err(e) => SignalEvent::Notice(format!("username error: {e}")),
+                Ok(account) => SignalEvent::OwnUsername(AccountUsername {
+                    // signal-cli echoes the final username; fall back to the one we asked for
+                    username: account.username.or(Some(username)),
+                    link: account.link,
+                }),
+            };
+            rpc.post(event).await;
+        }),
+        SendRequest::DeleteUsername => signal_client.detach(|rpc| async move {
+            let event = match rpc.delete_username().await {
+                Err(e) => SignalEvent::Notice(format!("username error: {e}")),
+                Ok(()) => SignalEvent::OwnUsername(AccountUsername::default()),
+            };
+            rpc.post(event).await;
+        }),
+        SendRequest::FetchAvatars { avatars } => signal_client.detach(|rpc| async move {
+            for avatar in avatars {
+                let result = rpc.fetch_avatar(&avatar).await.map_err(|e| e.to_string());
+                let id = avatar.id().to_string();
+                rpc.post(SignalEvent::AvatarFetched { id, result }).await;
+            }
+        }),
+        SendRequest::SetProfileAvatar { path } => signal_client.detach(|rpc| async move {
+            let result = rpc
+                .set_profile_avatar(path.as_deref())
+                .await
+                .map_err(|e| e.to_string());
+            rpc.post(SignalEvent::ProfileAvatarSet(result)).await;
+        }),
+        SendRequest::ListContacts => signal_client.detach(|rpc| async move {
+            if let Err(e) = rpc.list_contacts().await {
+                rpc.post(SignalEvent::Notice(format!("contact error: {e}")))
+                    .await;
+            }
+        }),
+        SendRequest::UpdateContact { number, name } => signal_client.detach(|rpc| async move {
+            let notice = match rpc.update_contact(&number, &name).await {
+                Err(e) => format!("contact error: {e}"),
+                Ok(()) => {
+                    let _ = rpc.list_contacts().await;
+                    format!("Saved contact {name}")
+                }
+            };
+            rpc.post(SignalEvent::Notice(notice)).await;
+        }),
+        SendRequest::RemoveContact { number } => signal_client.detach(|rpc| async move {
+            match rpc.remove_contact(&number).await {
+                Err(e) => {
+                    rpc.post(SignalEvent::Notice(format!("contact error: {e}")))
+                        .await;
+                }
+                Ok(()) => {
+                    rpc.post(SignalEvent::ContactRemoved(number)).await;
+                    let _ = rpc.list_contacts().await;
+                }
+            }
+        }),
+        SendRequest::ImportContacts { contacts } => signal_client.detach(|rpc| async move {
+            let mut failed = 0;
+            for (number, name) in &contacts {
+                if rpc.update_contact(number, name).await.is_err() {
+                    failed += 1;
+                }
+            }
+            let imported = contacts.len() - failed;
+            let notice = if failed == 0 {
+                format!("Imported {imported} contacts")
+            } else {
+                format!("Imported {imported} contacts, {failed} failed")
+            };
+            let _ = rpc.list_contacts().await;
+            rpc.post(SignalEvent::Notice(notice)).await;
+        }),
+        SendRequest::ListDevices => signal_client.detach(|rpc| async move {
+            refresh_devices(&rpc).await;
+        }),
+        SendRequest::RemoveDevice { device_id } => signal_client.detach(|rpc| async move {
+            let result = rpc.remove_device(device_id).await;
+            let done = format!("Unlinked device {device_id}");
+            report_device_change(&rpc, result, "unlink", done).await;
+        }),
+        SendRequest::AddDevice { uri } => signal_client.detach(|rpc| async move {
+            let result = rpc.add_device(&uri).await;
+            report_device_change(&rpc, result, "link", "Device linked".to_string()).await;
+        }),
+        SendRequest::RenameDevice { name } => signal_client.detach(|rpc| async move {
+            let result = rpc.rename_device(&name).await;
+            let done = format!("Renamed this device to {name}");
+            report_device_change(&rpc, result, "rename", done).await;
+        }),
+    }
+}
+
+/// Fetch a sticker's image on its own task. It comes back as
+/// `SignalEvent::StickerFetched` for the message at `timestamp_ms`.
+fn fetch_sticker_image(
+    signal_client: &SignalClient,
+    conv_id: String,
+    timestamp_ms: i64,
+    sticker: StickerRef,
+) {
+    signal_client.detach(|rpc| async move {
+        let result = rpc.fetch_sticker(&sticker).await.map_err(|e| e.to_string());
+        rpc.post(SignalEvent::StickerFetched {
+            conv_id,
+            timestamp_ms,
+            sticker,
+            result,
+        })
+        .await;
+    });
+}
+
+/// Trust `recipient`'s key, verifying `safety_number` when given or else
+/// accepting whatever key is current, then re-fetch identities so trust
+/// levels reflect the change before success is reported.
+async fn trust_and_refresh(rpc: SignalRpc, recipient: String, safety_number: Option<String>) {
+    let trusted = match &safety_number {
+        Some(number) => rpc.trust_identity(&recipient, number).await,
+        None => rpc.trust_identity_unverified(&recipient).await,
+    };
+    let event = match trusted {
+        Err(e) => SignalEvent::Notice(format!("trust error: {e}")),
+        Ok(()) => SignalEvent::IdentityTrusted {
+            recipient,
+            verified: safety_number.is_some(),
+            identities: rpc.fetch_identities().await.map_err(|e| e.to_string()),
+        },
+    };
+    rpc.post(event).await;
+}
+
+/// Re-fetch the account's devices into the `/devices` overlay.
+async fn refresh_devices(rpc: &SignalRpc) {
+    let devices = rpc.list_devices().await.map_err(|e| e.to_string());
+    rpc.post(SignalEvent::DeviceList(devices)).await;
+}
+
+/// Report how a device change went, refreshing the `/devices` overlay
+/// when it worked.
+async fn report_device_change(rpc: &SignalRpc, result: Result<()>, what: &str, done: String) {
+    match result {
+        Err(e) => {
+            rpc.post(SignalEvent::Notice(format!("{what} error: {e}")))
+                .await;
+        }
+        Ok(()) => {
+            rpc.post(SignalEvent::Notice(done)).await;
+            refresh_devices(rpc).await;
+        }
     }
 }
 
@@ -1189,7 +1520,19 @@ enum MessagingBackend<'a> {
 
 impl MessagingBackend<'_> {
     async fn dispatch(&mut self, app: &mut App, req: SendRequest) {
-        if let MessagingBackend::Signal(sc) = self {
+        let MessagingBackend::Signal(sc) = self else {
+            return;
+        };
+        // Messages wait in the outbox until Signal confirms them
+        let Some(req) = app.admit_send(req) else {
+            return;
+        };
+        // Sends to someone whose safety number changed wait until it's
+        // acknowledged, and nothing goes out while Signal rate-limits us
+        if let Some(req) = app
+            .hold_for_identity_change(req)
+            .and_then(|req| app.hold_for_rate_limit(req))
+        {
             dispatch_send(sc, app, req).await;
         }
     }
@@ -1232,17 +1575,75 @@ impl MessagingBackend<'_> {
         }
         changed
     }
+
+    /// Respawn signal-cli once the supervisor's backoff allows it, then
+    /// reload contacts/groups/identities and replay sends the old process
+    /// never confirmed. Returns true if app state changed.
+    async fn supervise(
+        &mut self,
+        app: &mut App,
+        config: &Config,
+        supervisor: &mut Supervisor,
+    ) -> bool {
+        let MessagingBackend::Signal(sc) = self else {
+            return false;
+        };
+        if app.connected {
+            return false;
+        }
+        let now = Instant::now();
+        supervisor.on_disconnect(now);
+        if !supervisor.due(now) {
+            return false;
+        }
+
+        let new_client = match SignalClient::connect(config).await {
+            Ok(client) => client,
+            Err(e) => {
+                let delay = supervisor.on_spawn_failed(now);
+                debug_log::logf(format_args!("respawn failed: {e}"));
+                app.connection_error = Some(format!(
+                    "signal-cli restart failed, retrying in {}s",
+                    delay.as_secs()
+                ));
+                return true;
+            }
+        };
+        let mut old = std::mem::replace(&mut **sc, new_client);
+        let _ = old.shutdown().await;
+        supervisor.on_spawned(Instant::now());
+        debug_log::log("respawned signal-cli");
+
+        app.connection_error = None;
+        app.set_connected();
+        let _ = sc.list_contacts().await;
+        let _ = sc.list_groups().await;
+        let _ = sc.list_identities().await;
+
+        let replay = app.pending.take_replayable();
+        let count = replay.len();
+        for (old_rpc_id, req) in replay {
+            // Paste temp files are re-registered under the new rpc id by dispatch_send
+            app.pending_paste_cleanups.remove(&old_rpc_id);
+            dispatch_send(sc, app, req).await;
+        }
+        if count > 0 {
+            app.status_message = format!("reconnected | resent {count} pending message(s)");
+        }
+        true
+    }
 }
 
-async fn run_app(
-    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
-    mut backend: MessagingBackend<'_>,
+/// Build an `App` for one account from config and its database. Shared by
+/// the foreground account and every background account session.
+fn build_app(
     config: &Config,
     db: db::Database,
     incognito: bool,
     config_path: &std::path::Path,
-) -> Result<()> {
+) -> Result<App> {
     let mut app = App::new(config.account.clone(), db, config_path);
+    app.incognito = incognito;
     app.notifications.notify_direct = config.notify_direct;
     app.notifications.notify_group = config.notify_group;
     app.notifications.desktop_notifications = config.desktop_notifications;
@@ -1250,7 +1651,6 @@ async fn run_app(
     app.notifications.clipboard_clear_seconds = config.clipboard_clear_seconds;
     app.image.image_mode = config.image_mode.unwrap_or_default();
     app.image.show_link_previews = config.show_link_previews;
-    app.incognito = incognito;
     app.date_separators = config.date_separators;
     app.show_receipts = config.show_receipts;
     app.color_receipts = config.color_receipts;
@@ -1261,6 +1661,7 @@ async fn run_app(
     app.send_read_receipts = config.send_read_receipts;
     app.mouse.enabled = config.mouse_enabled;
     app.sidebar_on_right = config.sidebar_on_right;
+    app.avatars.sidebar = config.sidebar_avatars;
     app.sidebar_width = config.sidebar_width.clamp(14, 40);
     if config.cell_pixel_width > 0 && config.cell_pixel_height > 0 {
         app.image.cell_px = (config.cell_pixel_width, config.cell_pixel_height);
@@ -1282,6 +1683,208 @@ async fn run_app(
         .flat_map(|c| &c.messages)
         .filter(|m| m.expires_in_seconds > 0)
         .count();
+    Ok(app)
+}
+
+/// A configured account running in the background: its own `App` (store
+/// and database), signal-cli client and restart policy. The foreground
+/// account lives in `run_app`'s locals; switching swaps it with one of these.
+struct AccountSession {
+    app: App,
+    client: SignalClient,
+    supervisor: Supervisor,
+    config: Config,
+}
+
+/// Extra accounts from `Config::accounts`, plus a message for each one
+/// that could not be started.
+#[derive(Default)]
+struct BackgroundAccounts {
+    sessions: Vec<AccountSession>,
+    failures: Vec<String>,
+}
+
+/// Database file for an extra account. The primary account keeps
+/// `siggy.db`; the others get `siggy-<digits>.db` alongside it.
+fn account_db_file_name(account: &str) -> String {
+    let digits: String = account
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect();
+    format!("siggy-{digits}.db")
+}
+
+/// Open the database and signal-cli backend for an extra account and run
+/// the same initial sync as the primary. Unlike the primary there is no
+/// linking fallback: an unregistered extra account is reported and skipped.
+async fn start_account_session(
+    base: &Config,
+    account: &str,
+    incognito: bool,
+    config_path: &std::path::Path,
+) -> Result<AccountSession> {
+    let config = base.for_account(account);
+    let database = if incognito {
+        db::Database::open_in_memory()?
+    } else {
+        let data_root = dirs::data_dir().unwrap_or_else(|| std::path::PathBuf::from("."));
+        let db_dir = data_root.join("siggy");
+        std::fs::create_dir_all(&db_dir)?;
+        set_dir_permissions(&db_dir);
+        let db_path = db_dir.join(account_db_file_name(account));
+        let database = db::Database::open(&db_path)?;
+        set_file_permissions(&db_path);
+        database
+    };
+    let mut client = SignalClient::connect(&config).await?;
+    if !client
+        .wait_for_ready(std::time::Duration::from_millis(500))
+        .await
+    {
+        let stderr = client.stderr_output();
+        client.shutdown().await?;
+        let reason = stderr.lines().last().unwrap_or("signal-cli exited");
+        anyhow::bail!("{reason}");
+    }
+    let mut app = build_app(&config, database, incognito, config_path)?;
+    app.set_connected();
+    app.sweep_expired_messages();
+    let _ = client.send_sync_request().await;
+    let _ = client.list_contacts().await;
+    let _ = client.list_groups().await;
+    let _ = client.list_identities().await;
+    Ok(AccountSession {
+        app,
+        client,
+        supervisor: Supervisor::new(Instant::now()),
+        config,
+    })
+}
+
+/// Drive a background account for one loop tick: drain its events, respawn
+/// its backend if needed, and flush the sends it queues on its own (typing
+/// stops, read receipts, outbox retries). Returns true if its state changed.
+async fn pump_background(session: &mut AccountSession) -> bool {
+    let mut backend = MessagingBackend::Signal(&mut session.client);
+    let mut changed = backend.drain_events(&mut session.app);
+    changed |= backend
+        .supervise(&mut session.app, &session.config, &mut session.supervisor)
+        .await;
+    if session.app.sync.active && session.app.sync.should_end() {
+        session.app.end_sync();
+        changed = true;
+    }
+    if let Some(typing_stop) = session.app.pending.typing_stop.take() {
+        backend.dispatch(&mut session.app, typing_stop).await;
+    }
+    for (recipient, timestamps) in std::mem::take(&mut session.app.pending.read_receipts) {
+        backend
+            .dispatch(
+                &mut session.app,
+                SendRequest::ReadReceipt {
+                    recipient,
+                    timestamps,
+                },
+            )
+            .await;
+    }
+    if session.app.connected {
+        for req in session
+            .app
+            .take_due_sends(chrono::Utc::now().timestamp_millis())
+        {
+            backend.dispatch(&mut session.app, req).await;
+            changed = true;
+        }
+    }
+    if std::mem::take(&mut session.app.pending.refresh_groups) {
+        backend
+            .dispatch(&mut session.app, SendRequest::ListGroups)
+            .await;
+    }
+    for (conv_id, timestamp_ms, sticker) in std::mem::take(&mut session.app.pending.sticker_fetches)
+    {
+        backend
+            .dispatch(
+                &mut session.app,
+                SendRequest::FetchSticker {
+                    conv_id,
+                    timestamp_ms,
+                    sticker,
+                },
+            )
+            .await;
+    }
+    if let Some(avatars) = take_avatar_fetches(&mut session.app) {
+        backend
+            .dispatch(&mut session.app, SendRequest::FetchAvatars { avatars })
+            .await;
+    }
+    changed
+}
+
+/// Take every queued avatar fetch, or `None` if there are none.
+fn take_avatar_fetches(app: &mut App) -> Option<Vec<AvatarRef>> {
+    let avatars = std::mem::take(&mut app.pending.avatar_fetches);
+    (!avatars.is_empty()).then_some(avatars)
+}
+
+/// Every account's unread count in switcher order, for the sidebar and
+/// status bar badges.
+fn account_badges(
+    order: &[String],
+    active: &App,
+    background: &[AccountSession],
+) -> Vec<AccountBadge> {
+    order
+        .iter()
+        .filter_map(|acct| {
+            if *acct == active.account {
+                return Some(AccountBadge {
+                    account: acct.clone(),
+                    unread: active.store.total_unread(),
+                    is_self: true,
+                });
+            }
+            background
+                .iter()
+                .find(|s| s.app.account == *acct)
+                .map(|s| AccountBadge {
+                    account: acct.clone(),
+                    unread: s.app.store.total_unread(),
+                    is_self: false,
+                })
+        })
+        .collect()
+}
+
+async fn run_app(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    mut backend: MessagingBackend<'_>,
+    config: &Config,
+    db: db::Database,
+    incognito: bool,
+    config_path: &std::path::Path,
+    background: BackgroundAccounts,
+) -> Result<()> {
+    let mut app = build_app(config, db, incognito, config_path)?;
+    let BackgroundAccounts {
+        sessions: mut background,
+        failures,
+    } = background;
+    // Switcher order is config order, restricted to accounts that started
+    let account_order: Vec<String> = config
+        .all_accounts()
+        .into_iter()
+        .filter(|a| *a == app.account || background.iter().any(|s| s.app.account == *a))
+        .collect();
+    if !background.is_empty() {
+        app.accounts.label = Some(account_label(&app.account));
+        for session in &mut background {
+            session.app.accounts.label = Some(account_label(&session.app.account));
+        }
+    }
+    let mut active_config = config.clone();
     if let MessagingBackend::Signal(sc) = &mut backend {
         app.set_connected();
 
@@ -1304,7 +1907,11 @@ async fn run_app(
         app.status_message = "connected | demo mode".to_string();
         app.populate_demo_data(chrono::Utc::now().date_naive());
     }
+    if !failures.is_empty() {
+        app.status_message = failures.join(" | ");
+    }
 
+    let mut supervisor = Supervisor::new(Instant::now());
     let mut last_expiry_sweep = Instant::now();
     let mut last_sync_redraw = Instant::now();
     // Initialise far enough in the past that the spinner ticks on the very
@@ -1400,6 +2007,9 @@ async fn run_app(
         if app.ensure_active_images() {
             needs_redraw = true;
         }
+        if app.apply_rendered_avatars() {
+            needs_redraw = true;
+        }
 
         // Animate the loading spinner on a wall-clock cadence so its speed
         // is decoupled from event-loop iteration rate. The drain loop above
@@ -1479,6 +2089,41 @@ async fn run_app(
             had_terminal_event = event::poll(Duration::ZERO)?;
         }
 
+        // Account switch: park the foreground account (flushing what it queued
+        // on its own client), then swap in the next background session.
+        if std::mem::take(&mut app.accounts.pending_switch) && !background.is_empty() {
+            if let Some(receipt) = app.finish_view_once() {
+                backend.dispatch(&mut app, receipt).await;
+            }
+            app.park_account();
+            if let Some(typing_stop) = app.pending.typing_stop.take() {
+                backend.dispatch(&mut app, typing_stop).await;
+            }
+            for (recipient, timestamps) in std::mem::take(&mut app.pending.read_receipts) {
+                backend
+                    .dispatch(
+                        &mut app,
+                        SendRequest::ReadReceipt {
+                            recipient,
+                            timestamps,
+                        },
+                    )
+                    .await;
+            }
+            let next = &mut background[0];
+            std::mem::swap(&mut app, &mut next.app);
+            if let MessagingBackend::Signal(sc) = &mut backend {
+                std::mem::swap(&mut **sc, &mut next.client);
+            }
+            std::mem::swap(&mut supervisor, &mut next.supervisor);
+            std::mem::swap(&mut active_config, &mut next.config);
+            background.rotate_left(1);
+            app.resume_account();
+            app.clear_kitty_state();
+            terminal.clear()?;
+            needs_redraw = true;
+        }
+
         // Drain signal events (non-blocking), detect disconnect
         if backend.drain_events(&mut app) {
             diag_signal_event = diag_signal_event.wrapping_add(1);
@@ -1493,6 +2138,29 @@ async fn run_app(
             }
         }
 
+        // Background accounts keep receiving while another one is on screen
+        for session in &mut background {
+            pump_background(session).await;
+            if std::mem::take(&mut session.app.notifications.pending_bell) {
+                app.notifications.pending_bell = true;
+            }
+        }
+        if !background.is_empty() {
+            let badges = account_badges(&account_order, &app, &background);
+            if badges != app.accounts.badges {
+                app.accounts.badges = badges;
+                needs_redraw = true;
+            }
+        }
+
+        // Respawn signal-cli if it went away (backoff handled by the supervisor)
+        if backend
+            .supervise(&mut app, &active_config, &mut supervisor)
+            .await
+        {
+            needs_redraw = true;
+        }
+
         // Check if initial sync burst has ended
         if app.sync.active && app.sync.should_end() {
             app.end_sync();
@@ -1512,6 +2180,52 @@ async fn run_app(
                 .await;
         }
 
+        // Send what was held for a safety number change now acknowledged,
+        // or for a rate limit now cleared
+        app.release_rate_limited(chrono::Utc::now().timestamp_millis());
+        for req in std::mem::take(&mut app.pending.released_sends) {
+            backend.dispatch(&mut app, req).await;
+            needs_redraw = true;
+        }
+
+        // Retry outbox messages whose backoff has run out
+        if app.connected {
+            for req in app.take_due_sends(chrono::Utc::now().timestamp_millis()) {
+                backend.dispatch(&mut app, req).await;
+                needs_redraw = true;
+            }
+        }
+
+        // Re-fetch groups so pending group updates can be described
+        if std::mem::take(&mut app.pending.refresh_groups) {
+            backend.dispatch(&mut app, SendRequest::ListGroups).await;
+        }
+
+        // Fetch images for stickers that arrived without one
+        let sticker_fetches = std::mem::take(&mut app.pending.sticker_fetches);
+        if !sticker_fetches.is_empty() {
+            for (conv_id, timestamp_ms, sticker) in sticker_fetches {
+                backend
+                    .dispatch(
+                        &mut app,
+                        SendRequest::FetchSticker {
+                            conv_id,
+                            timestamp_ms,
+                            sticker,
+                        },
+                    )
+                    .await;
+            }
+            needs_redraw = true;
+        }
+
+        // Fetch queued avatars in the background; each redraws as it lands
+        if let Some(avatars) = take_avatar_fetches(&mut app) {
+            backend
+                .dispatch(&mut app, SendRequest::FetchAvatars { avatars })
+                .await;
+        }
+
         // Expire stale typing indicators
         if app.typing.cleanup() {
             needs_redraw = true;
@@ -1526,10 +2240,19 @@ async fn run_app(
             backend.dispatch(&mut app, typing_stop).await;
         }
 
-        // Periodic sweep of expired disappearing messages and timed mutes (every 10s)
+        // Periodic sweep of expired disappearing messages, stories, timed
+        // mutes and unanswered calls (every 10s)
         if last_expiry_sweep.elapsed() >= Duration::from_secs(10) {
             app.sweep_expired_messages();
+            app.sweep_expired_stories();
             app.sweep_expired_mutes();
+            app.sweep_stale_calls();
+            for session in &mut background {
+                session.app.sweep_expired_messages();
+                session.app.sweep_expired_stories();
+                session.app.sweep_expired_mutes();
+                session.app.sweep_stale_calls();
+            }
             last_expiry_sweep = Instant::now();
             needs_redraw = true;
         }
@@ -1562,7 +2285,7 @@ async fn run_app(
         // Update terminal title with unread count, but only when it actually changes.
         // Emitting SetTitle every tick (~20Hz) is a write+flush per iteration the terminal
         // has to parse, which compounds with mouse-move event storms. See issue #408.
-        let unread = app.store.total_unread();
+        let unread = app.store.total_unread() + app.accounts.other_unread();
         let title = if app.lock.is_locked() {
             "siggy".to_string()
         } else if unread > 0 {
@@ -1601,8 +2324,17 @@ async fn run_app(
         }
     }
 
+    // A view-once reveal left open is purged rather than kept past the session
+    if let Some(receipt) = app.finish_view_once() {
+        backend.dispatch(&mut app, receipt).await;
+    }
+
     // Restore terminal title on exit
     execute!(terminal.backend_mut(), crossterm::terminal::SetTitle("")).ok();
 
+    for session in &mut background {
+        session.client.shutdown().await?;
+    }
+
     Ok(())
 }
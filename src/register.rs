@@ -0,0 +1,672 @@
+//! Primary-device registration for numbers with no phone behind them.
+//!
+//! For dedicated team or bot numbers, signal-cli itself becomes the primary
+//! device. This drives `signal-cli register` (SMS or voice call), takes a
+//! pasted captcha token when Signal asks for one, submits the verification
+//! code with `verify` and optionally sets a registration-lock PIN with
+//! `setPin`. Every signal-cli error is shown on the registration screen so
+//! the user can fix the input and retry.
+
+use std::io;
+use std::time::Duration;
+
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::{
+    Terminal,
+    backend::CrosstermBackend,
+    layout::{Constraint, Flex, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Paragraph, Wrap},
+};
+use tokio::process::Command;
+
+use crate::config::Config;
+
+/// How long a single signal-cli command may take before we give up.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Where Signal's registration captcha is solved.
+const CAPTCHA_URL: &str = "https://signalcaptchas.org/registration/generate.html";
+
+/// Minimum length Signal accepts for a PIN.
+const PIN_MIN_LEN: usize = 4;
+
+/// Result of a registration flow.
+pub enum RegisterResult {
+    /// The number is registered and verified.
+    Success,
+    /// User backed out (Esc / Ctrl+C).
+    Cancelled,
+}
+
+/// How Signal delivers the verification code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyChannel {
+    Sms,
+    Voice,
+}
+
+impl VerifyChannel {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Sms => "by SMS",
+            Self::Voice => "in a voice call",
+        }
+    }
+}
+
+/// What the registration screen is waiting for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    /// Ready to ask Signal for a code
+    Request,
+    /// Signal wants a captcha token before it sends a code
+    Captcha,
+    /// Waiting for the verification code
+    Code,
+    /// The number has a registration lock; waiting for its PIN
+    LockPin,
+    /// Verified; optionally set a registration-lock PIN
+    SetPin,
+}
+
+/// A step for the driver to carry out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Request {
+    Register { captcha: Option<String> },
+    Verify { code: String, pin: Option<String> },
+    SetPin(String),
+    Finish,
+    Cancel,
+}
+
+/// Registration screen state. Key handling and signal-cli results move it
+/// between phases; the driver in [`run_registration_flow`] runs the
+/// requests it hands back.
+struct Registration {
+    channel: VerifyChannel,
+    phase: Phase,
+    input: String,
+    /// Captcha token, kept for re-requesting a code
+    captcha: Option<String>,
+    /// Verification code, kept for retrying with a lock PIN
+    code: String,
+    error: Option<String>,
+}
+
+impl Registration {
+    fn new(channel: VerifyChannel) -> Self {
+        Self {
+            channel,
+            phase: Phase::Request,
+            input: String::new(),
+            captcha: None,
+            code: String::new(),
+            error: None,
+        }
+    }
+
+    fn handle_key(&mut self, modifiers: KeyModifiers, code: KeyCode) -> Option<Request> {
+        match (modifiers, code) {
+            (KeyModifiers::CONTROL, KeyCode::Char('c')) => Some(Request::Cancel),
+            (_, KeyCode::Esc) if self.phase == Phase::SetPin => Some(Request::Finish),
+            (_, KeyCode::Esc) => Some(Request::Cancel),
+            (_, KeyCode::Enter) => self.submit(),
+            (_, KeyCode::Backspace) => {
+                self.input.pop();
+                None
+            }
+            (_, KeyCode::Char(c)) if self.phase != Phase::Request => {
+                self.input.push(c);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Bracketed paste: captcha tokens are far too long to type.
+    fn paste(&mut self, text: &str) {
+        if self.phase != Phase::Request {
+            self.input.push_str(text.trim());
+        }
+    }
+
+    fn submit(&mut self) -> Option<Request> {
+        let input = self.input.trim().to_string();
+        match self.phase {
+            Phase::Request => Some(Request::Register {
+                captcha: self.captcha.clone(),
+            }),
+            Phase::Captcha => match parse_captcha(&input) {
+                Some(token) => {
+                    self.captcha = Some(token.clone());
+                    Some(Request::Register {
+                        captcha: Some(token),
+                    })
+                }
+                None => {
+                    self.error = Some("Paste the whole signalcaptcha:// link".to_string());
+                    None
+                }
+            },
+            Phase::Code => match parse_verification_code(&input) {
+                Some(code) => {
+                    self.code = code.clone();
+                    Some(Request::Verify { code, pin: None })
+                }
+                None => {
+                    self.error = Some("The code is 6 digits".to_string());
+                    None
+                }
+            },
+            Phase::SetPin if input.is_empty() => Some(Request::Finish),
+            Phase::LockPin | Phase::SetPin => {
+                if input.chars().count() < PIN_MIN_LEN {
+                    self.error = Some(format!("PINs are at least {PIN_MIN_LEN} characters"));
+                    return None;
+                }
+                if self.phase == Phase::SetPin {
+                    Some(Request::SetPin(input))
+                } else {
+                    Some(Request::Verify {
+                        code: self.code.clone(),
+                        pin: Some(input),
+                    })
+                }
+            }
+        }
+    }
+
+    /// Fold a signal-cli result into the screen. Returns `Finish` once the
+    /// flow is complete.
+    fn apply(&mut self, request: &Request, result: Result<()>) -> Option<Request> {
+        self.error = None;
+        match (request, result) {
+            (Request::Register { .. }, Ok(())) => self.enter(Phase::Code),
+            (Request::Register { .. }, Err(e)) if is_captcha_error(&e.to_string()) => {
+                if self.phase == Phase::Captcha {
+                    self.error = Some("Signal rejected the captcha; solve a new one".to_string());
+                }
+                self.captcha = None;
+                self.enter(Phase::Captcha);
+            }
+            (Request::Verify { pin: Some(_), .. }, Ok(())) => return Some(Request::Finish),
+            (Request::Verify { .. }, Ok(())) => self.enter(Phase::SetPin),
+            (Request::Verify { pin: None, .. }, Err(e)) if is_locked_error(&e.to_string()) => {
+                self.enter(Phase::LockPin);
+            }
+            (Request::SetPin(_), Ok(())) => return Some(Request::Finish),
+            (_, Err(e)) => self.error = Some(e.to_string()),
+            _ => {}
+        }
+        None
+    }
+
+    fn enter(&mut self, phase: Phase) {
+        self.phase = phase;
+        self.input.clear();
+    }
+}
+
+/// Captcha tokens come from the "Open Signal" link on the captcha page,
+/// `signalcaptcha://signal-hcaptcha.<site>.registration.<token>`. signal-cli
+/// strips the scheme itself, so the link is passed through whole.
+fn parse_captcha(text: &str) -> Option<String> {
+    let token = text.trim();
+    let body = token.strip_prefix("signalcaptcha://").unwrap_or(token);
+    (body.contains('.') && !token.chars().any(char::is_whitespace)).then(|| token.to_string())
+}
+
+/// Verification codes are six digits, sometimes shown as `123-456`.
+fn parse_verification_code(text: &str) -> Option<String> {
+    let code: String = text
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect();
+    (code.len() == 6 && code.chars().all(|c| c.is_ascii_digit())).then_some(code)
+}
+
+fn is_captcha_error(message: &str) -> bool {
+    message.to_lowercase().contains("captcha")
+}
+
+fn is_locked_error(message: &str) -> bool {
+    let message = message.to_lowercase();
+    message.contains("locked with a pin") || message.contains("--pin")
+}
+
+/// Run `signal-cli -a <account> <args>`. On failure the error carries
+/// signal-cli's own message from stderr, minus its log lines.
+async fn run_signal_cli(config: &Config, args: &[&str]) -> Result<()> {
+    let command = args.first().copied().unwrap_or("signal-cli");
+    let output = tokio::time::timeout(
+        COMMAND_TIMEOUT,
+        Command::new(&config.signal_cli_path)
+            .arg("-a")
+            .arg(&config.account)
+            .args(args)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::piped())
+            .output(),
+    )
+    .await
+    .map_err(|_| anyhow::anyhow!("signal-cli {command} timed out"))?
+    .map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            anyhow::anyhow!(
+                "'{}' not found. Is signal-cli installed and in your PATH?",
+                config.signal_cli_path
+            )
+        } else {
+            anyhow::anyhow!("Failed to run '{}': {}", config.signal_cli_path, e)
+        }
+    })?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let detail: Vec<&str> = stderr
+        .lines()
+        .map(str::trim)
+        .filter(|l| {
+            !l.is_empty()
+                && !["INFO", "WARN", "DEBUG"]
+                    .iter()
+                    .any(|lvl| l.starts_with(lvl))
+        })
+        .collect();
+    if detail.is_empty() {
+        anyhow::bail!(
+            "signal-cli {command} failed (exit code: {:?})",
+            output.status.code()
+        );
+    }
+    anyhow::bail!("{}", detail.join(" "))
+}
+
+/// Carry out a register, verify or setPin request.
+async fn execute(config: &Config, channel: VerifyChannel, request: &Request) -> Result<()> {
+    match request {
+        Request::Register { captcha } => {
+            let mut args = vec!["register"];
+            if channel == VerifyChannel::Voice {
+                args.push("--voice");
+            }
+            if let Some(token) = captcha {
+                args.extend(["--captcha", token]);
+            }
+            run_signal_cli(config, &args).await
+        }
+        Request::Verify { code, pin } => {
+            let mut args = vec!["verify", code.as_str()];
+            if let Some(pin) = pin {
+                args.extend(["--pin", pin]);
+            }
+            run_signal_cli(config, &args).await
+        }
+        Request::SetPin(pin) => run_signal_cli(config, &["setPin", pin]).await,
+        Request::Finish | Request::Cancel => Ok(()),
+    }
+}
+
+/// What the screen says while a request runs.
+fn busy_text(request: &Request, channel: VerifyChannel) -> String {
+    match request {
+        Request::Register { .. } => format!("Asking Signal to send a code {}...", channel.label()),
+        Request::Verify { .. } => "Verifying...".to_string(),
+        Request::SetPin(_) => "Setting PIN...".to_string(),
+        Request::Finish | Request::Cancel => String::new(),
+    }
+}
+
+/// Run the interactive registration flow for `config.account` as a
+/// primary device.
+pub async fn run_registration_flow(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    config: &Config,
+    channel: VerifyChannel,
+) -> Result<RegisterResult> {
+    let mut reg = Registration::new(channel);
+    let mut next: Option<Request> = None;
+
+    loop {
+        match next.take() {
+            Some(Request::Cancel) => return Ok(RegisterResult::Cancelled),
+            Some(Request::Finish) => {
+                terminal.draw(|frame| draw_registration(frame, &reg, config, None, true))?;
+                tokio::time::sleep(Duration::from_secs(2)).await;
+                return Ok(RegisterResult::Success);
+            }
+            Some(request) => {
+                let busy = busy_text(&request, channel);
+                terminal
+                    .draw(|frame| draw_registration(frame, &reg, config, Some(&busy), false))?;
+                let result = execute(config, channel, &request).await;
+                next = reg.apply(&request, result);
+                continue;
+            }
+            None => {}
+        }
+
+        terminal.draw(|frame| draw_registration(frame, &reg, config, None, false))?;
+
+        if event::poll(Duration::from_millis(50))? {
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    next = reg.handle_key(key.modifiers, key.code);
+                }
+                Event::Paste(text) => reg.paste(&text),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn draw_registration(
+    frame: &mut ratatui::Frame,
+    reg: &Registration,
+    config: &Config,
+    busy: Option<&str>,
+    done: bool,
+) {
+    let area = frame.area();
+
+    let [_, content_area, _] = Layout::vertical([
+        Constraint::Min(1),
+        Constraint::Length(18),
+        Constraint::Min(1),
+    ])
+    .flex(Flex::Center)
+    .areas(area);
+
+    let [content] = Layout::horizontal([Constraint::Percentage(60)])
+        .flex(Flex::Center)
+        .areas(content_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Register ")
+        .title_style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        );
+    let inner = block.inner(content);
+    frame.render_widget(block, content);
+
+    let gray = Style::default().fg(Color::Gray);
+    let muted = Style::default().fg(Color::DarkGray);
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("  Register {} as a primary device", config.account),
+            Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+
+    if done {
+        lines.push(Line::from(Span::styled(
+            "  Registered and verified!",
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+        )));
+        frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+        return;
+    }
+
+    let (help, footer): (Vec<String>, &str) = match reg.phase {
+        Phase::Request => (
+            vec![
+                format!(
+                    "  Signal will send a verification code {}.",
+                    reg.channel.label()
+                ),
+                "  Registering makes siggy the primary device for this".to_string(),
+                "  number; any phone registered to it is signed out.".to_string(),
+            ],
+            "  Enter to request a code | Esc to go back",
+        ),
+        Phase::Captcha => (
+            vec![
+                "  Signal wants a captcha before it sends a code:".to_string(),
+                format!("  1. Open {CAPTCHA_URL}"),
+                "  2. Solve it and copy the \"Open Signal\" link".to_string(),
+                "  3. Paste the link (signalcaptcha://...) below".to_string(),
+            ],
+            "  Enter to submit | Esc to go back",
+        ),
+        Phase::Code => (
+            vec![format!(
+                "  Enter the 6-digit code sent {}.",
+                reg.channel.label()
+            )],
+            "  Enter to verify | Esc to go back",
+        ),
+        Phase::LockPin => (
+            vec![
+                "  This number has a registration lock.".to_string(),
+                "  Enter its Signal PIN to finish verifying.".to_string(),
+            ],
+            "  Enter to verify | Esc to go back",
+        ),
+        Phase::SetPin => (
+            vec![
+                "  Verified! Optionally set a Signal PIN. It acts as a".to_string(),
+                "  registration lock: the number can't be registered".to_string(),
+                "  elsewhere without it.".to_string(),
+            ],
+            "  Enter to set | Enter on empty or Esc to skip",
+        ),
+    };
+    lines.extend(help.into_iter().map(|l| Line::from(Span::styled(l, gray))));
+
+    let mut input_line_idx = None;
+    if reg.phase != Phase::Request {
+        let shown = if matches!(reg.phase, Phase::LockPin | Phase::SetPin) {
+            "\u{2022}".repeat(reg.input.chars().count())
+        } else {
+            reg.input.clone()
+        };
+        // Keep the tail of long pasted tokens in view
+        let room = inner.width.saturating_sub(5) as usize;
+        let len = shown.chars().count();
+        let shown: String = shown.chars().skip(len.saturating_sub(room)).collect();
+        lines.push(Line::from(""));
+        input_line_idx = Some((lines.len(), shown.chars().count()));
+        lines.push(Line::from(vec![
+            Span::styled("  > ", Style::default().fg(Color::Cyan)),
+            Span::raw(shown),
+        ]));
+    }
+
+    if let Some(busy) = busy {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("  {busy}"),
+            Style::default().fg(Color::Yellow),
+        )));
+    } else if let Some(err) = &reg.error {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("  {err}"),
+            Style::default().fg(Color::Red),
+        )));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(footer, muted)));
+
+    frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+
+    if busy.is_none()
+        && let Some((idx, col)) = input_line_idx
+    {
+        frame.set_cursor_position((inner.x + 4 + col as u16, inner.y + idx as u16));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn key(reg: &mut Registration, code: KeyCode) -> Option<Request> {
+        reg.handle_key(KeyModifiers::NONE, code)
+    }
+
+    fn type_text(reg: &mut Registration, text: &str) {
+        for c in text.chars() {
+            key(reg, KeyCode::Char(c));
+        }
+    }
+
+    #[rstest]
+    #[case("123456", Some("123456"))]
+    #[case(" 123-456 ", Some("123456"))]
+    #[case("12345", None)]
+    #[case("12345a", None)]
+    fn verification_code(#[case] input: &str, #[case] expected: Option<&str>) {
+        assert_eq!(parse_verification_code(input).as_deref(), expected);
+    }
+
+    #[rstest]
+    #[case("signalcaptcha://signal-hcaptcha.abc.registration.xyz", true)]
+    #[case("signal-hcaptcha.abc.registration.xyz", true)]
+    #[case("signalcaptcha://", false)]
+    #[case("not a token", false)]
+    fn captcha_token(#[case] input: &str, #[case] valid: bool) {
+        assert_eq!(parse_captcha(input).is_some(), valid);
+    }
+
+    #[test]
+    fn set_pin_is_optional() {
+        let mut reg = Registration::new(VerifyChannel::Sms);
+        reg.phase = Phase::SetPin;
+        type_text(&mut reg, "12");
+        assert_eq!(key(&mut reg, KeyCode::Enter), None);
+        assert!(reg.error.is_some());
+        reg.input.clear();
+        assert_eq!(key(&mut reg, KeyCode::Enter), Some(Request::Finish));
+        assert_eq!(key(&mut reg, KeyCode::Esc), Some(Request::Finish));
+        type_text(&mut reg, "4321");
+        assert_eq!(
+            key(&mut reg, KeyCode::Enter),
+            Some(Request::SetPin("4321".to_string()))
+        );
+    }
+
+    #[test]
+    fn errors_stay_on_screen() {
+        let mut reg = Registration::new(VerifyChannel::Voice);
+        let request = key(&mut reg, KeyCode::Enter).unwrap();
+        let next = reg.apply(&request, Err(anyhow::anyhow!("Rate limit exceeded: 413")));
+        assert_eq!(next, None);
+        assert_eq!(reg.phase, Phase::Request);
+        assert_eq!(reg.error.as_deref(), Some("Rate limit exceeded: 413"));
+    }
+
+    /// A stand-in signal-cli that logs its arguments and scripts Signal's
+    /// answers: a captcha is required, and the number is PIN-locked.
+    #[cfg(unix)]
+    fn stub_signal_cli(dir: &std::path::Path) -> Config {
+        use std::os::unix::fs::PermissionsExt;
+        let script = dir.join("signal-cli");
+        let log = dir.join("calls.log");
+        std::fs::write(
+            &script,
+            format!(
+                r#"#!/bin/sh
+echo "$@" >> "{log}"
+case "$*" in
+  *"register --captcha signalcaptcha://signal-hcaptcha.site.registration.tok") exit 0 ;;
+  *register*) echo "INFO  Manager - starting" >&2
+              echo "Captcha required for verification, use --captcha CAPTCHA" >&2; exit 1 ;;
+  *"verify 123456 --pin 4321") exit 0 ;;
+  *verify*) echo "Verification failed! This number is locked with a pin." >&2
+            echo "Use '--pin PIN_CODE' to specify the registration lock PIN" >&2; exit 3 ;;
+esac
+exit 2
+"#,
+                log = log.display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        Config {
+            account: "+15550001234".to_string(),
+            signal_cli_path: script.display().to_string(),
+            ..Config::default()
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn registers_through_captcha_and_lock_pin() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = stub_signal_cli(dir.path());
+        let mut reg = Registration::new(VerifyChannel::Sms);
+
+        async fn run(reg: &mut Registration, config: &Config, request: Request) -> Option<Request> {
+            let result = execute(config, reg.channel, &request).await;
+            reg.apply(&request, result)
+        }
+
+        let request = key(&mut reg, KeyCode::Enter).unwrap();
+        assert_eq!(run(&mut reg, &config, request).await, None);
+        assert_eq!(reg.phase, Phase::Captcha);
+        assert_eq!(reg.error, None);
+
+        reg.paste(" signalcaptcha://signal-hcaptcha.site.registration.tok\n");
+        let request = key(&mut reg, KeyCode::Enter).unwrap();
+        assert_eq!(run(&mut reg, &config, request).await, None);
+        assert_eq!(reg.phase, Phase::Code);
+
+        type_text(&mut reg, "123-456");
+        let request = key(&mut reg, KeyCode::Enter).unwrap();
+        assert_eq!(run(&mut reg, &config, request).await, None);
+        assert_eq!(reg.phase, Phase::LockPin);
+
+        type_text(&mut reg, "4321");
+        let request = key(&mut reg, KeyCode::Enter).unwrap();
+        assert_eq!(run(&mut reg, &config, request).await, Some(Request::Finish));
+
+        let log = std::fs::read_to_string(dir.path().join("calls.log")).unwrap();
+        assert_eq!(
+            log.lines().collect::<Vec<_>>(),
+            [
+                "-a +15550001234 register",
+                "-a +15550001234 register --captcha signalcaptcha://signal-hcaptcha.site.registration.tok",
+                "-a +15550001234 verify 123456",
+                "-a +15550001234 verify 123456 --pin 4321",
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn signal_cli_errors_drop_log_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = stub_signal_cli(dir.path());
+        let err = run_signal_cli(&config, &["register", "--voice"])
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Captcha required for verification, use --captcha CAPTCHA"
+        );
+        let err = run_signal_cli(&config, &["setPin", "1"]).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "signal-cli setPin failed (exit code: Some(2))"
+        );
+    }
+}
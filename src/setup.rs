@@ -2,7 +2,8 @@
 //!
 //! Multi-step flow that detects signal-cli on PATH, prompts for the user's
 //! E.164 phone, and hands off to [`crate::link`] for QR-code device
-//! linking. Persists the resolved signal-cli path back to [`crate::config`]
+//! linking, or to [`crate::register`] to register the number as a primary
+//! device. Persists the resolved signal-cli path back to [`crate::config`]
 //! so subsequent launches skip detection.
 
 use std::io;
@@ -22,6 +23,7 @@ use tokio::process::Command;
 
 use crate::config::Config;
 use crate::link;
+use crate::register::{self, VerifyChannel};
 
 pub enum SetupResult {
     /// Wizard finished successfully, use this config.
@@ -36,7 +38,12 @@ pub enum SetupResult {
 enum Step {
     SignalCli,
     Account,
-    Linking,
+    /// Skip ahead if the account is already registered
+    Connect,
+    /// Choose between linking and registering
+    Method,
+    Link,
+    Register(VerifyChannel),
     Preferences,
     Done,
 }
@@ -179,7 +186,7 @@ pub async fn run_setup(
                             Ok(()) => {
                                 working_config.account = phone_input.clone();
                                 phone_error = None;
-                                step = Step::Linking;
+                                step = Step::Connect;
                             }
                             Err(msg) => {
                                 phone_error = Some(msg);
@@ -208,7 +215,7 @@ pub async fn run_setup(
                 }
             }
 
-            Step::Linking => {
+            Step::Connect => {
                 // Check if already registered
                 let registered = link::check_account_registered(&working_config)
                     .await
@@ -220,16 +227,51 @@ pub async fn run_setup(
                     })?;
                     tokio::time::sleep(Duration::from_secs(1)).await;
                     step = Step::Preferences;
-                    continue;
+                } else {
+                    step = Step::Method;
+                }
+            }
+
+            Step::Method => {
+                terminal.draw(|frame| {
+                    draw_method_step(frame, &working_config.account);
+                })?;
+
+                if event::poll(Duration::from_millis(50))?
+                    && let Event::Key(key) = event::read()?
+                {
+                    if key.kind != KeyEventKind::Press {
+                        continue;
+                    }
+                    match (key.modifiers, key.code) {
+                        (KeyModifiers::CONTROL, KeyCode::Char('c')) => {
+                            return Ok(SetupResult::Cancelled);
+                        }
+                        (_, KeyCode::Esc) => {
+                            step = Step::Account;
+                        }
+                        (_, KeyCode::Char('l') | KeyCode::Enter) => {
+                            step = Step::Link;
+                        }
+                        (_, KeyCode::Char('s')) => {
+                            step = Step::Register(VerifyChannel::Sms);
+                        }
+                        (_, KeyCode::Char('v')) => {
+                            step = Step::Register(VerifyChannel::Voice);
+                        }
+                        _ => {}
+                    }
                 }
+            }
 
+            Step::Link => {
                 // Run linking flow
                 match link::run_linking_flow(terminal, &working_config).await {
                     Ok(link::LinkResult::Success) => {
                         step = Step::Preferences;
                     }
                     Ok(link::LinkResult::Cancelled) => {
-                        step = Step::Account;
+                        step = Step::Method;
                     }
                     Err(e) => {
                         let msg = format!("{e}");
@@ -251,7 +293,7 @@ pub async fn run_setup(
                                             break;
                                         }
                                         KeyCode::Esc => {
-                                            step = Step::Account;
+                                            step = Step::Method;
                                             break;
                                         }
                                         _ => {}
@@ -263,6 +305,17 @@ pub async fn run_setup(
                 }
             }
 
+            Step::Register(channel) => {
+                // Errors from signal-cli are handled on the registration
+                // screen itself; only terminal I/O errors surface here.
+                step = match register::run_registration_flow(terminal, &working_config, channel)
+                    .await?
+                {
+                    register::RegisterResult::Success => Step::Preferences,
+                    register::RegisterResult::Cancelled => Step::Method,
+                };
+            }
+
             Step::Preferences => {
                 terminal.draw(|frame| {
                     draw_preferences_step(frame, &working_config);
@@ -410,7 +463,7 @@ fn step_label(current: Step) -> &'static str {
     match current {
         Step::SignalCli => "Step 1 of 4",
         Step::Account => "Step 2 of 4",
-        Step::Linking => "Step 3 of 4",
+        Step::Connect | Step::Method | Step::Link | Step::Register(_) => "Step 3 of 4",
         Step::Preferences => "Step 4 of 4",
         Step::Done => "Complete",
     }
@@ -618,6 +671,80 @@ fn draw_account_step(
     frame.set_cursor_position((cursor_x, cursor_y));
 }
 
+fn draw_method_step(frame: &mut ratatui::Frame, account: &str) {
+    let area = frame.area();
+
+    let [_, content_area, _] = Layout::vertical([
+        Constraint::Min(1),
+        Constraint::Length(16),
+        Constraint::Min(1),
+    ])
+    .flex(Flex::Center)
+    .areas(area);
+
+    let [content] = Layout::horizontal([Constraint::Percentage(60)])
+        .flex(Flex::Center)
+        .areas(content_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Setup ")
+        .title_style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        );
+    let inner = block.inner(content);
+    frame.render_widget(block, content);
+
+    let key = Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD);
+    let label = Style::default().fg(Color::White);
+
+    let lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("  {}: Connect {account}", step_label(Step::Method)),
+            Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  l ", key),
+            Span::styled("Link to the Signal app on my phone (QR code)", label),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            "  No phone for this number (team or bot accounts)?",
+            Style::default().fg(Color::Gray),
+        )),
+        Line::from(Span::styled(
+            "  Register siggy as its primary device instead:",
+            Style::default().fg(Color::Gray),
+        )),
+        Line::from(vec![
+            Span::styled("  s ", key),
+            Span::styled("Register, verification code by SMS", label),
+        ]),
+        Line::from(vec![
+            Span::styled("  v ", key),
+            Span::styled("Register, verification code by voice call", label),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            "  Enter to link | Esc to go back",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, inner);
+}
+
 fn draw_registered_screen(frame: &mut ratatui::Frame, account: &str) {
     let area = frame.area();
 
@@ -1,42 +1,139 @@
-//! signal-cli child process bridge over JSON-RPC.
+//! signal-cli bridge over JSON-RPC.
 //!
-//! [`SignalClient`] spawns signal-cli and runs two tokio tasks: a stdout
-//! reader that parses JSON-RPC frames into [`SignalEvent`]s, and a stdin
-//! writer that sends [`JsonRpcRequest`]s. The `pending_requests` map
+//! [`SignalClient`] either spawns signal-cli as a child process or attaches
+//! to a running `signal-cli daemon` over a Unix socket or TCP (see
+//! [`Transport`]). Either way it runs two tokio tasks: a reader that parses
+//! JSON-RPC frames into [`SignalEvent`]s, and a writer that sends
+//! [`JsonRpcRequest`]s. The `pending_requests` map
 //! correlates response IDs with the originating method so the reader can
 //! emit the right event variant. Notifications (incoming messages, typing,
 //! receipts) and RPC results both flow through the same mpsc channel.
 
 use anyhow::{Context, Result};
-use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::process::{Child, Command};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::process::{Child, ChildStderr, Command};
 use tokio::sync::mpsc;
 use uuid::Uuid;
 
 /// Maximum size of the stderr capture buffer (~1 MB).
 const MAX_STDERR_LEN: usize = 1_000_000;
 
-use super::parse::{parse_rpc_result, parse_signal_event};
+/// How long a cached avatar is used before it is fetched again.
+const AVATAR_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// signal-cli's JSON-RPC error code for a send refused by a rate limit.
+const RATE_LIMIT_ERROR_CODE: i64 = -5;
+
+use super::parse::{
+    parse_account_username, parse_devices, parse_identity_failures, parse_image_data,
+    parse_rate_limit_failure, parse_rpc_result, parse_signal_event, parse_sticker_packs,
+    parse_user_status,
+};
+use super::rpc::{
+    AWAITED_RPC_TIMEOUT, PendingMap, PendingRpc, RPC_TIMEOUT, RpcCall, RpcError, RpcMethod,
+    SEND_RPC_TIMEOUT, fail_waiters, lock_pending, spawn_deadline,
+};
 use crate::config::Config;
 use crate::signal::types::*;
 
-/// Maximum age for pending RPC entries before they are considered stale.
-const PENDING_REQUEST_TTL: Duration = Duration::from_secs(60);
+/// How siggy reaches signal-cli. Selected by [`Transport::from_config`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transport {
+    /// Fork `signal-cli jsonRpc` and talk over its stdin/stdout.
+    Process,
+    /// Attach to `signal-cli daemon --socket <path>`.
+    UnixSocket(PathBuf),
+    /// Attach to `signal-cli daemon --tcp <host:port>`.
+    Tcp(String),
+}
+
+impl Transport {
+    /// Pick the transport from config. `signal_cli_socket` wins over
+    /// `signal_cli_tcp`; with neither set we spawn our own child process.
+    pub fn from_config(config: &Config) -> Self {
+        if !config.signal_cli_socket.is_empty() {
+            Self::UnixSocket(PathBuf::from(&config.signal_cli_socket))
+        } else if !config.signal_cli_tcp.is_empty() {
+            Self::Tcp(config.signal_cli_tcp.clone())
+        } else {
+            Self::Process
+        }
+    }
+}
 
 pub struct SignalClient {
-    child: Child,
-    stdin_tx: mpsc::Sender<String>,
+    /// The signal-cli process we spawned. `None` when attached to a daemon.
+    child: Option<Child>,
     pub event_rx: mpsc::Receiver<SignalEvent>,
-    account: String,
-    pending_requests: Arc<Mutex<HashMap<String, (String, Instant)>>>,
     stderr_buffer: Arc<Mutex<String>>,
+    rpc: SignalRpc,
+}
+
+/// The request side of a [`SignalClient`], which derefs to it. Cheap to
+/// clone, so a request whose answer we wait for can run on its own task
+/// (see [`SignalRpc::detach`]) instead of holding up the main loop.
+#[derive(Clone)]
+pub struct SignalRpc {
+    stdin_tx: mpsc::Sender<String>,
+    account: String,
+    pending_requests: PendingMap,
+    /// Lets per-request deadline timers report `SendFailed` without keeping
+    /// the event channel open after the reader task ends.
+    event_weak: mpsc::WeakSender<SignalEvent>,
+    /// Where fetched sticker images are cached (see [`StickerRef::cache_path`]).
+    download_dir: PathBuf,
+}
+
+impl std::ops::Deref for SignalClient {
+    type Target = SignalRpc;
+
+    fn deref(&self) -> &SignalRpc {
+        &self.rpc
+    }
 }
 
 impl SignalClient {
+    /// Open a JSON-RPC session using whichever [`Transport`] the config selects.
+    pub async fn connect(config: &Config) -> Result<Self> {
+        match Transport::from_config(config) {
+            Transport::Process => Self::spawn(config).await,
+            Transport::UnixSocket(path) => Self::connect_unix(config, &path).await,
+            Transport::Tcp(addr) => {
+                let stream = TcpStream::connect(&addr)
+                    .await
+                    .with_context(|| format!("Failed to connect to signal-cli daemon at {addr}"))?;
+                let (reader, writer) = stream.into_split();
+                Ok(Self::attach(config, reader, writer, None, None))
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    async fn connect_unix(config: &Config, path: &Path) -> Result<Self> {
+        let stream = tokio::net::UnixStream::connect(path)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to connect to signal-cli daemon socket {}",
+                    path.display()
+                )
+            })?;
+        let (reader, writer) = stream.into_split();
+        Ok(Self::attach(config, reader, writer, None, None))
+    }
+
+    #[cfg(not(unix))]
+    async fn connect_unix(_config: &Config, path: &Path) -> Result<Self> {
+        anyhow::bail!(
+            "Unix sockets are not supported on this platform ({}); use signal_cli_tcp instead",
+            path.display()
+        )
+    }
+
     pub async fn spawn(config: &Config) -> Result<Self> {
         let mut cmd = Command::new(&config.signal_cli_path);
         if !config.account.is_empty() {
@@ -61,19 +158,40 @@ impl SignalClient {
         let stdin = child.stdin.take().context("Failed to capture stdin")?;
         let stderr = child.stderr.take().context("Failed to capture stderr")?;
 
+        Ok(Self::attach(
+            config,
+            stdout,
+            stdin,
+            Some(child),
+            Some(stderr),
+        ))
+    }
+
+    /// Start the reader/writer tasks over any byte stream pair. The stderr
+    /// capture task only runs when we own the child process.
+    fn attach<R, W>(
+        config: &Config,
+        reader: R,
+        writer: W,
+        child: Option<Child>,
+        stderr: Option<ChildStderr>,
+    ) -> Self
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+        W: AsyncWrite + Unpin + Send + 'static,
+    {
         let (event_tx, event_rx) = mpsc::channel::<SignalEvent>(256);
         let (stdin_tx, mut stdin_rx) = mpsc::channel::<String>(64);
 
         let download_dir = config.download_dir.clone();
-        let pending_requests: Arc<Mutex<HashMap<String, (String, Instant)>>> =
-            Arc::new(Mutex::new(HashMap::new()));
+        let pending_requests: PendingMap = Arc::default();
         let pending_clone = Arc::clone(&pending_requests);
+        let event_weak = event_tx.downgrade();
 
-        // Stdout reader task — parse JSON-RPC messages from signal-cli
+        // Reader task — parse JSON-RPC messages from signal-cli
         tokio::spawn(async move {
-            let reader = BufReader::new(stdout);
+            let reader = BufReader::new(reader);
             let mut lines = reader.lines();
-
             while let Ok(Some(line)) = lines.next_line().await {
                 if line.trim().is_empty() {
                     continue;
@@ -83,34 +201,104 @@ impl SignalClient {
                     Ok(resp) => {
                         // Check if this is a response to a pending request
                         let rpc_id = resp.id.clone();
-                        let pending_method = rpc_id.as_ref().and_then(|id| {
-                            pending_clone.lock().ok().and_then(|mut map| {
-                                let method = map.remove(id).map(|(m, _)| m);
-                                // Sweep stale entries (signal-cli never responded)
-                                map.retain(|_, (_, ts)| ts.elapsed() < PENDING_REQUEST_TTL);
-                                method
-                            })
-                        });
-
-                        let event = if let Some(method) = pending_method {
-                            if let Some(ref err) = resp.error {
-                                crate::debug_log::logf(format_args!(
-                                    "rpc error: method={method} error={err:?}"
-                                ));
-                                // RPC error — emit SendFailed for send requests,
-                                // surface other errors to the status bar
-                                if method == "send" {
-                                    rpc_id.map(|id| SignalEvent::SendFailed { rpc_id: id })
+                        let pending = rpc_id
+                            .as_ref()
+                            .and_then(|id| lock_pending(&pending_clone).remove(id));
+
+                        let event = match pending {
+                            // Someone is awaiting this call: hand them the outcome
+                            Some(entry) if entry.has_waiter() => {
+                                let outcome = match resp.error {
+                                    Some(ref err) => Err(RpcError::from(err)),
+                                    None => Ok(resp.result.clone().unwrap_or_default()),
+                                };
+                                let _ = entry.reply.send(outcome);
+                                None
+                            }
+                            Some(entry) => {
+                                let method = entry.method;
+                                if let Some(ref err) = resp.error {
+                                    crate::debug_log::logf(format_args!(
+                                        "rpc error: method={method} error={err:?}"
+                                    ));
+                                    // RPC error — emit SendFailed for send requests,
+                                    // surface other errors to the status bar. A send
+                                    // refused over changed safety numbers or a rate
+                                    // limit is reported as such so it can be held and
+                                    // retried.
+                                    let untrusted = err
+                                        .data
+                                        .as_ref()
+                                        .map(parse_identity_failures)
+                                        .unwrap_or_default();
+                                    let rate_limit = err
+                                        .data
+                                        .as_ref()
+                                        .and_then(parse_rate_limit_failure)
+                                        .or_else(|| {
+                                            (err.code == RATE_LIMIT_ERROR_CODE)
+                                                .then(RateLimitFailure::default)
+                                        });
+                                    if method.is_send() && !untrusted.is_empty() {
+                                        Some(SignalEvent::UntrustedRecipients {
+                                            rpc_id,
+                                            recipients: untrusted,
+                                            partial: false,
+                                        })
+                                    } else if method.is_send()
+                                        && let Some(failure) = rate_limit
+                                    {
+                                        Some(SignalEvent::RateLimited {
+                                            rpc_id,
+                                            failure,
+                                            partial: false,
+                                        })
+                                    } else if method.is_send() {
+                                        rpc_id.map(|id| SignalEvent::SendFailed { rpc_id: id })
+                                    } else {
+                                        Some(SignalEvent::Error(format!(
+                                            "{method}: {}",
+                                            err.message
+                                        )))
+                                    }
                                 } else {
-                                    Some(SignalEvent::Error(format!("{method}: {}", err.message)))
+                                    // Some recipients of a group send may have been
+                                    // refused even though the send went through
+                                    if method.is_send()
+                                        && let Some(result) = resp.result.as_ref()
+                                    {
+                                        let untrusted = parse_identity_failures(result);
+                                        if !untrusted.is_empty()
+                                            && event_tx
+                                                .send(SignalEvent::UntrustedRecipients {
+                                                    rpc_id: rpc_id.clone(),
+                                                    recipients: untrusted,
+                                                    partial: true,
+                                                })
+                                                .await
+                                                .is_err()
+                                        {
+                                            break;
+                                        }
+                                        if let Some(failure) = parse_rate_limit_failure(result)
+                                            && event_tx
+                                                .send(SignalEvent::RateLimited {
+                                                    rpc_id: rpc_id.clone(),
+                                                    failure,
+                                                    partial: true,
+                                                })
+                                                .await
+                                                .is_err()
+                                        {
+                                            break;
+                                        }
+                                    }
+                                    resp.result.as_ref().and_then(|result| {
+                                        parse_rpc_result(method.as_str(), result, rpc_id.as_deref())
+                                    })
                                 }
-                            } else {
-                                resp.result.as_ref().and_then(|result| {
-                                    parse_rpc_result(&method, result, rpc_id.as_deref())
-                                })
                             }
-                        } else {
-                            parse_signal_event(&resp, &download_dir)
+                            None => parse_signal_event(&resp, &download_dir),
                         };
 
                         if let Some(ref event) = event {
@@ -138,11 +326,14 @@ impl SignalClient {
                     }
                 }
             }
+            // signal-cli is gone: anyone still awaiting a response hears so
+            // now rather than at their deadline
+            fail_waiters(&pending_clone);
         });
 
-        // Stdin writer task — send JSON-RPC requests to signal-cli
+        // Writer task — send JSON-RPC requests to signal-cli
         tokio::spawn(async move {
-            let mut stdin = stdin;
+            let mut stdin = writer;
             while let Some(msg) = stdin_rx.recv().await {
                 if stdin.write_all(msg.as_bytes()).await.is_err() {
                     break;
@@ -158,33 +349,60 @@ impl SignalClient {
 
         // Stderr reader task — capture signal-cli error output
         let stderr_buffer: Arc<Mutex<String>> = Arc::new(Mutex::new(String::new()));
-        let stderr_clone = Arc::clone(&stderr_buffer);
-        tokio::spawn(async move {
-            let reader = BufReader::new(stderr);
-            let mut lines = reader.lines();
-            while let Ok(Some(line)) = lines.next_line().await {
-                crate::debug_log::logf(format_args!("signal-cli stderr: {line}"));
-                if let Ok(mut buf) = stderr_clone.lock() {
-                    if !buf.is_empty() {
-                        buf.push('\n');
-                    }
-                    buf.push_str(&line);
-                    if buf.len() > MAX_STDERR_LEN {
-                        let drain_to = buf.len() - MAX_STDERR_LEN / 2;
-                        buf.drain(..drain_to);
+        if let Some(stderr) = stderr {
+            let stderr_clone = Arc::clone(&stderr_buffer);
+            tokio::spawn(async move {
+                let reader = BufReader::new(stderr);
+                let mut lines = reader.lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    crate::debug_log::logf(format_args!("signal-cli stderr: {line}"));
+                    if let Ok(mut buf) = stderr_clone.lock() {
+                        if !buf.is_empty() {
+                            buf.push('\n');
+                        }
+                        buf.push_str(&line);
+                        if buf.len() > MAX_STDERR_LEN {
+                            let drain_to = buf.len() - MAX_STDERR_LEN / 2;
+                            buf.drain(..drain_to);
+                        }
                     }
                 }
-            }
-        });
+            });
+        }
 
-        Ok(Self {
+        Self {
             child,
-            stdin_tx,
             event_rx,
-            account: config.account.clone(),
-            pending_requests,
             stderr_buffer,
-        })
+            rpc: SignalRpc {
+                stdin_tx,
+                account: config.account.clone(),
+                pending_requests,
+                event_weak,
+                download_dir: config.download_dir.clone(),
+            },
+        }
+    }
+}
+
+impl SignalRpc {
+    /// Run `task` on its own tokio task with a clone of this handle. Used
+    /// for requests we wait on, so the main loop keeps drawing; the task
+    /// reports back with [`SignalRpc::post`].
+    pub fn detach<F, Fut>(&self, task: F)
+    where
+        F: FnOnce(SignalRpc) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        tokio::spawn(task(self.clone()));
+    }
+
+    /// Queue `event` for the main loop as if signal-cli had sent it. Dropped
+    /// once the reader task has ended.
+    pub async fn post(&self, event: SignalEvent) {
+        if let Some(tx) = self.event_weak.upgrade() {
+            let _ = tx.send(event).await;
+        }
     }
 
     /// Set the target field on params for recipient/groupId based on is_group.
@@ -200,20 +418,71 @@ impl SignalClient {
         }
     }
 
+    /// Attach `textStyle` ranges in signal-cli's "start:length:STYLE" form.
+    /// Offsets are UTF-16 units on the wire body.
+    fn set_text_styles(params: &mut serde_json::Value, text_styles: &[TextStyle]) {
+        if text_styles.is_empty() {
+            return;
+        }
+        params["textStyle"] = text_styles
+            .iter()
+            .map(|s| format!("{}:{}:{}", s.start, s.length, s.style.wire_name()))
+            .collect();
+    }
+
     /// Build the JSON-RPC envelope, send to signal-cli's stdin, and register the
     /// rpc id with `method` so the stdout reader can correlate the response.
     /// Returns the rpc id so callers that need to track the send (send_message,
-    /// send_edit_message) can correlate the result.
-    async fn send_rpc(&self, method: &str, params: serde_json::Value) -> Result<String> {
-        send_rpc_impl(&self.stdin_tx, &self.pending_requests, method, params).await
+    /// send_edit_message) can correlate the result. The response arrives later
+    /// as a [`SignalEvent`].
+    async fn send_rpc(&self, method: RpcMethod, params: serde_json::Value) -> Result<String> {
+        let timeout = if method.is_send() {
+            SEND_RPC_TIMEOUT
+        } else {
+            RPC_TIMEOUT
+        };
+        let call = self.request(method, params, timeout).await?;
+        Ok(call.id)
+    }
+
+    /// Send a request and wait for its result instead of receiving it as an
+    /// event. Errors carry signal-cli's message, or say the request timed out.
+    async fn call(
+        &self,
+        method: RpcMethod,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let call = self.request(method, params, AWAITED_RPC_TIMEOUT).await?;
+        call.response()
+            .await
+            .map_err(|e| anyhow::anyhow!("{method}: {e}"))
     }
 
+    async fn request(
+        &self,
+        method: RpcMethod,
+        params: serde_json::Value,
+        timeout: Duration,
+    ) -> Result<RpcCall> {
+        send_rpc_impl(
+            &self.stdin_tx,
+            &self.pending_requests,
+            &self.event_weak,
+            method,
+            params,
+            timeout,
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub async fn send_message(
         &self,
         recipient: &str,
         body: &str,
         is_group: bool,
         mentions: &[(usize, String)],
+        text_styles: &[TextStyle],
         attachments: &[&Path],
         quote: Option<(&str, i64, &str)>,
     ) -> Result<String> {
@@ -231,6 +500,7 @@ impl SignalClient {
                 .collect();
             params["mention"] = serde_json::Value::Array(mention_arr);
         }
+        Self::set_text_styles(&mut params, text_styles);
 
         if !attachments.is_empty() {
             let att_arr: Vec<serde_json::Value> = attachments
@@ -246,10 +516,11 @@ impl SignalClient {
             params["quoteMessage"] = serde_json::json!(body_text);
         }
 
-        let id = self.send_rpc("send", params).await?;
+        let id = self.send_rpc(RpcMethod::Send, params).await?;
         Ok(id)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn send_edit_message(
         &self,
         recipient: &str,
@@ -257,6 +528,7 @@ impl SignalClient {
         is_group: bool,
         edit_timestamp: i64,
         mentions: &[(usize, String)],
+        text_styles: &[TextStyle],
         quote: Option<(&str, i64, &str)>,
     ) -> Result<String> {
         let mut params = serde_json::json!({
@@ -273,6 +545,7 @@ impl SignalClient {
                 .collect();
             params["mention"] = serde_json::Value::Array(mention_arr);
         }
+        Self::set_text_styles(&mut params, text_styles);
 
         if let Some((author, timestamp, body_text)) = quote {
             params["quoteTimestamp"] = serde_json::json!(timestamp);
@@ -280,7 +553,7 @@ impl SignalClient {
             params["quoteMessage"] = serde_json::json!(body_text);
         }
 
-        let id = self.send_rpc("send", params).await?;
+        let id = self.send_rpc(RpcMethod::Send, params).await?;
         Ok(id)
     }
 
@@ -295,7 +568,7 @@ impl SignalClient {
             "account": self.account,
         });
         Self::set_target(&mut params, recipient, is_group);
-        self.send_rpc("remoteDelete", params).await?;
+        self.send_rpc(RpcMethod::RemoteDelete, params).await?;
         Ok(())
     }
 
@@ -314,7 +587,7 @@ impl SignalClient {
             "account": self.account,
         });
         Self::set_target(&mut params, recipient, is_group);
-        self.send_rpc("sendPinMessage", params).await?;
+        self.send_rpc(RpcMethod::SendPinMessage, params).await?;
         Ok(())
     }
 
@@ -332,19 +605,22 @@ impl SignalClient {
             "account": self.account,
         });
         Self::set_target(&mut params, recipient, is_group);
-        self.send_rpc("sendUnpinMessage", params).await?;
+        self.send_rpc(RpcMethod::SendUnpinMessage, params).await?;
         Ok(())
     }
 
     pub async fn list_groups(&self) -> Result<()> {
-        self.send_rpc("listGroups", serde_json::json!({ "account": self.account }))
-            .await?;
+        self.send_rpc(
+            RpcMethod::ListGroups,
+            serde_json::json!({ "account": self.account }),
+        )
+        .await?;
         Ok(())
     }
 
     pub async fn list_contacts(&self) -> Result<()> {
         self.send_rpc(
-            "listContacts",
+            RpcMethod::ListContacts,
             serde_json::json!({ "account": self.account }),
         )
         .await?;
@@ -353,30 +629,72 @@ impl SignalClient {
 
     pub async fn list_identities(&self) -> Result<()> {
         self.send_rpc(
-            "listIdentities",
+            RpcMethod::ListIdentities,
             serde_json::json!({ "account": self.account }),
         )
         .await?;
         Ok(())
     }
 
+    /// Fetch the identity list and wait for it, rather than receiving it as
+    /// a `SignalEvent::IdentityList`.
+    pub async fn fetch_identities(&self) -> Result<Vec<IdentityInfo>> {
+        let result = self
+            .call(
+                RpcMethod::ListIdentities,
+                serde_json::json!({ "account": self.account }),
+            )
+            .await?;
+        match parse_rpc_result(RpcMethod::ListIdentities.as_str(), &result, None) {
+            Some(SignalEvent::IdentityList(ids)) => Ok(ids),
+            _ => Err(anyhow::anyhow!("listIdentities: unexpected response")),
+        }
+    }
+
+    /// Mark a recipient's safety number as verified. Resolves once signal-cli
+    /// has accepted it, so callers can refresh trust state afterwards.
     pub async fn trust_identity(&self, recipient: &str, safety_number: &str) -> Result<()> {
         let params = serde_json::json!({
             "recipient": [recipient],
             "verifiedSafetyNumber": safety_number,
             "account": self.account,
         });
-        self.send_rpc("trust", params).await?;
+        self.call(RpcMethod::Trust, params).await?;
+        Ok(())
+    }
+
+    /// Trust every key currently known for `recipient` without verifying
+    /// the safety number, accepting a changed key.
+    pub async fn trust_identity_unverified(&self, recipient: &str) -> Result<()> {
+        let params = serde_json::json!({
+            "recipient": [recipient],
+            "trustAllKnownKeys": true,
+            "account": self.account,
+        });
+        self.call(RpcMethod::Trust, params).await?;
+        Ok(())
+    }
+
+    /// Answer a proof-required rate-limit challenge with a captcha token
+    /// from signalcaptchas.org.
+    pub async fn submit_rate_limit_challenge(&self, challenge: &str, captcha: &str) -> Result<()> {
+        let params = serde_json::json!({
+            "challenge": challenge,
+            "captcha": captcha,
+            "account": self.account,
+        });
+        self.call(RpcMethod::SubmitRateLimitChallenge, params)
+            .await?;
         Ok(())
     }
 
     /// Note: previously this method did not register in pending_requests. After this
-    /// refactor it goes through send_rpc and will be registered. The entry will be
-    /// swept by the existing TTL cleanup if signal-cli never sends a correlated
+    /// refactor it goes through send_rpc and will be registered. The entry is
+    /// dropped by its deadline if signal-cli never sends a correlated
     /// response, and the parser falls through to default for unknown methods.
     pub async fn send_sync_request(&self) -> Result<()> {
         self.send_rpc(
-            "sendSyncRequest",
+            RpcMethod::SendSyncRequest,
             serde_json::json!({ "account": self.account }),
         )
         .await?;
@@ -401,7 +719,7 @@ impl SignalClient {
             target_timestamp,
             remove,
         );
-        self.send_rpc("sendReaction", params).await?;
+        self.send_rpc(RpcMethod::SendReaction, params).await?;
         Ok(())
     }
 
@@ -411,15 +729,23 @@ impl SignalClient {
         if stop {
             params["stop"] = serde_json::json!(true);
         }
-        self.send_rpc("sendTypingIndicator", params).await?;
+        self.send_rpc(RpcMethod::SendTypingIndicator, params)
+            .await?;
         Ok(())
     }
 
     /// Send a read receipt to a single recipient for one or more message timestamps.
     /// Fire-and-forget — no useful result is expected from signal-cli.
     pub async fn send_read_receipt(&self, recipient: &str, timestamps: &[i64]) -> Result<()> {
-        let params = build_send_read_receipt_params(&self.account, recipient, timestamps);
-        self.send_rpc("sendReceipt", params).await?;
+        let params = build_send_receipt_params(&self.account, recipient, "read", timestamps);
+        self.send_rpc(RpcMethod::SendReceipt, params).await?;
+        Ok(())
+    }
+
+    /// Tell the sender a view-once message was opened. Fire-and-forget.
+    pub async fn send_viewed_receipt(&self, recipient: &str, timestamp: i64) -> Result<()> {
+        let params = build_send_receipt_params(&self.account, recipient, "viewed", &[timestamp]);
+        self.send_rpc(RpcMethod::SendReceipt, params).await?;
         Ok(())
     }
 
@@ -435,7 +761,8 @@ impl SignalClient {
             "account": self.account,
         });
         Self::set_target(&mut params, recipient, is_group);
-        self.send_rpc("sendMessageRequestResponse", params).await?;
+        self.send_rpc(RpcMethod::SendMessageRequestResponse, params)
+            .await?;
         Ok(())
     }
 
@@ -446,7 +773,30 @@ impl SignalClient {
         seconds: i64,
     ) -> Result<()> {
         let params = build_update_contact_expiration_params(&self.account, recipient, seconds);
-        self.send_rpc("updateContact", params).await?;
+        self.send_rpc(RpcMethod::UpdateContact, params).await?;
+        Ok(())
+    }
+
+    /// Add a contact to signal-cli's contact list, or rename one.
+    pub async fn update_contact(&self, number: &str, name: &str) -> Result<()> {
+        let params = serde_json::json!({
+            "recipient": number,
+            "name": name,
+            "account": self.account,
+        });
+        self.call(RpcMethod::UpdateContact, params).await?;
+        Ok(())
+    }
+
+    /// Remove a contact's details and hide them from the contact list. The
+    /// conversation and its messages are kept.
+    pub async fn remove_contact(&self, number: &str) -> Result<()> {
+        let params = serde_json::json!({
+            "recipient": number,
+            "hide": true,
+            "account": self.account,
+        });
+        self.call(RpcMethod::RemoveContact, params).await?;
         Ok(())
     }
 
@@ -459,7 +809,7 @@ impl SignalClient {
         if !members.is_empty() {
             params["members"] = serde_json::json!(members);
         }
-        self.send_rpc("updateGroup", params).await?;
+        self.send_rpc(RpcMethod::UpdateGroup, params).await?;
         Ok(())
     }
 
@@ -470,7 +820,7 @@ impl SignalClient {
             "members": members,
             "account": self.account,
         });
-        self.send_rpc("updateGroup", params).await?;
+        self.send_rpc(RpcMethod::UpdateGroup, params).await?;
         Ok(())
     }
 
@@ -481,7 +831,7 @@ impl SignalClient {
             "removeMembers": members,
             "account": self.account,
         });
-        self.send_rpc("updateGroup", params).await?;
+        self.send_rpc(RpcMethod::UpdateGroup, params).await?;
         Ok(())
     }
 
@@ -492,7 +842,97 @@ impl SignalClient {
             "name": name,
             "account": self.account,
         });
-        self.send_rpc("updateGroup", params).await?;
+        self.send_rpc(RpcMethod::UpdateGroup, params).await?;
+        Ok(())
+    }
+
+    /// Apply an admin-level change (roles, permissions, description,
+    /// avatar or invite link) to an existing group.
+    pub async fn edit_group(&self, group_id: &str, edit: &GroupEdit) -> Result<()> {
+        let params = build_edit_group_params(&self.account, group_id, edit);
+        self.send_rpc(RpcMethod::UpdateGroup, params).await?;
+        Ok(())
+    }
+
+    /// Join a group from a `https://signal.group/#...` invite link.
+    pub async fn join_group(&self, uri: &str) -> Result<()> {
+        let params = serde_json::json!({
+            "uri": uri,
+            "account": self.account,
+        });
+        self.send_rpc(RpcMethod::JoinGroup, params).await?;
+        Ok(())
+    }
+
+    /// Look up the account behind a username (`nickname.discriminator`).
+    /// `None` when no registered account has that username.
+    pub async fn resolve_username(&self, username: &str) -> Result<Option<ResolvedUsername>> {
+        let params = serde_json::json!({
+            "username": [username],
+            "account": self.account,
+        });
+        let result = self.call(RpcMethod::GetUserStatus, params).await?;
+        Ok(parse_user_status(username, &result))
+    }
+
+    /// Set or change our username. signal-cli picks a discriminator when
+    /// `username` has none, and answers with the final username and a fresh
+    /// username link.
+    pub async fn set_username(&self, username: &str) -> Result<AccountUsername> {
+        let params = serde_json::json!({
+            "username": username,
+            "account": self.account,
+        });
+        let result = self.call(RpcMethod::UpdateAccount, params).await?;
+        Ok(parse_account_username(&result))
+    }
+
+    /// Delete our username, which also invalidates its username link.
+    pub async fn delete_username(&self) -> Result<()> {
+        let params = serde_json::json!({
+            "deleteUsername": true,
+            "account": self.account,
+        });
+        self.call(RpcMethod::UpdateAccount, params).await?;
+        Ok(())
+    }
+
+    /// List the devices on this account, the primary first.
+    pub async fn list_devices(&self) -> Result<Vec<Device>> {
+        let params = serde_json::json!({ "account": self.account });
+        let result = self.call(RpcMethod::ListDevices, params).await?;
+        Ok(parse_devices(&result))
+    }
+
+    /// Unlink a device from the account. Only the primary device may do
+    /// this for devices other than itself.
+    pub async fn remove_device(&self, device_id: i64) -> Result<()> {
+        let params = serde_json::json!({
+            "deviceId": device_id,
+            "account": self.account,
+        });
+        self.call(RpcMethod::RemoveDevice, params).await?;
+        Ok(())
+    }
+
+    /// Link a new device from its `sgnl://linkdevice` URI. signal-cli
+    /// rejects this unless we are the primary device.
+    pub async fn add_device(&self, uri: &str) -> Result<()> {
+        let params = serde_json::json!({
+            "uri": uri,
+            "account": self.account,
+        });
+        self.call(RpcMethod::AddDevice, params).await?;
+        Ok(())
+    }
+
+    /// Rename the device signal-cli is running as.
+    pub async fn rename_device(&self, name: &str) -> Result<()> {
+        let params = serde_json::json!({
+            "deviceName": name,
+            "account": self.account,
+        });
+        self.call(RpcMethod::UpdateAccount, params).await?;
         Ok(())
     }
 
@@ -511,21 +951,21 @@ impl SignalClient {
             "about": about,
             "aboutEmoji": about_emoji,
         });
-        self.send_rpc("updateProfile", params).await?;
+        self.send_rpc(RpcMethod::UpdateProfile, params).await?;
         Ok(())
     }
 
     /// Block a contact or group.
     pub async fn block_contact(&self, recipient: &str, is_group: bool) -> Result<()> {
         let params = build_block_params(&self.account, recipient, is_group);
-        self.send_rpc("block", params).await?;
+        self.send_rpc(RpcMethod::Block, params).await?;
         Ok(())
     }
 
     /// Unblock a contact or group.
     pub async fn unblock_contact(&self, recipient: &str, is_group: bool) -> Result<()> {
         let params = build_block_params(&self.account, recipient, is_group);
-        self.send_rpc("unblock", params).await?;
+        self.send_rpc(RpcMethod::Unblock, params).await?;
         Ok(())
     }
 
@@ -535,7 +975,7 @@ impl SignalClient {
             "groupId": group_id,
             "account": self.account,
         });
-        self.send_rpc("quitGroup", params).await?;
+        self.send_rpc(RpcMethod::QuitGroup, params).await?;
         Ok(())
     }
 
@@ -546,7 +986,7 @@ impl SignalClient {
             "expiration": seconds,
             "account": self.account,
         });
-        self.send_rpc("updateGroup", params).await?;
+        self.send_rpc(RpcMethod::UpdateGroup, params).await?;
         Ok(())
     }
 
@@ -574,7 +1014,7 @@ impl SignalClient {
             params["noMulti"] = serde_json::json!(true);
         }
 
-        let id = self.send_rpc("sendPollCreate", params).await?;
+        let id = self.send_rpc(RpcMethod::SendPollCreate, params).await?;
         Ok(id)
     }
 
@@ -602,7 +1042,7 @@ impl SignalClient {
             params["voteCount"] = serde_json::json!(vote_count);
         }
 
-        self.send_rpc("sendPollVote", params).await?;
+        self.send_rpc(RpcMethod::SendPollVote, params).await?;
         Ok(())
     }
 
@@ -617,11 +1057,145 @@ impl SignalClient {
             "account": self.account,
         });
         Self::set_target(&mut params, recipient, is_group);
-        self.send_rpc("sendPollTerminate", params).await?;
+        self.send_rpc(RpcMethod::SendPollTerminate, params).await?;
+        Ok(())
+    }
+
+    /// Send a sticker from an installed pack. Tracked like `send_message`.
+    pub async fn send_sticker(
+        &self,
+        recipient: &str,
+        is_group: bool,
+        pack_id: &str,
+        sticker_id: u32,
+    ) -> Result<String> {
+        let mut params = serde_json::json!({
+            "sticker": format!("{pack_id}:{sticker_id}"),
+            "account": self.account,
+        });
+        Self::set_target(&mut params, recipient, is_group);
+        self.send_rpc(RpcMethod::Send, params).await
+    }
+
+    /// List the sticker packs signal-cli knows about, installed or not.
+    pub async fn list_sticker_packs(&self) -> Result<Vec<StickerPack>> {
+        let result = self
+            .call(
+                RpcMethod::ListStickerPacks,
+                serde_json::json!({ "account": self.account }),
+            )
+            .await?;
+        Ok(parse_sticker_packs(&result))
+    }
+
+    /// Install a pack from its `https://signal.art/addstickers/#pack_id=...`
+    /// link. Resolves once signal-cli has downloaded it.
+    pub async fn add_sticker_pack(&self, uri: &str) -> Result<()> {
+        let params = serde_json::json!({
+            "uri": uri,
+            "account": self.account,
+        });
+        self.call(RpcMethod::AddStickerPack, params).await?;
         Ok(())
     }
 
+    /// Fetch a sticker's image from signal-cli and cache it under the
+    /// download directory. Returns the cached path; a sticker cached by an
+    /// earlier fetch is returned without asking signal-cli again.
+    pub async fn fetch_sticker(&self, sticker: &StickerRef) -> Result<PathBuf> {
+        let path = sticker
+            .cache_path(&self.download_dir)
+            .with_context(|| format!("invalid sticker pack id: {}", sticker.pack_id))?;
+        if path.exists() {
+            return Ok(path);
+        }
+        let params = serde_json::json!({
+            "packId": sticker.pack_id,
+            "stickerId": sticker.sticker_id,
+            "account": self.account,
+        });
+        let result = self.call(RpcMethod::GetSticker, params).await?;
+        let data = parse_image_data(&result).context("getSticker: no image data")?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&path, data)?;
+        Ok(path)
+    }
+
+    /// Fetch an avatar from signal-cli and cache it under the download
+    /// directory. Contacts are tried for their profile avatar first, then
+    /// for one set on the contact entry. A cached copy younger than
+    /// [`AVATAR_MAX_AGE`] is returned without asking signal-cli. `None`
+    /// when there is no avatar, in which case a stale copy is dropped.
+    pub async fn fetch_avatar(&self, avatar: &AvatarRef) -> Result<Option<PathBuf>> {
+        let path = avatar.cache_path(&self.download_dir);
+        let fresh = std::fs::metadata(&path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.elapsed().ok())
+            .is_some_and(|age| age < AVATAR_MAX_AGE);
+        if fresh {
+            return Ok(Some(path));
+        }
+        let lookups: &[&str] = match avatar {
+            AvatarRef::Contact(_) => &["profile", "contact"],
+            AvatarRef::Group(_) => &["groupId"],
+        };
+        for key in lookups {
+            let mut params = serde_json::json!({ "account": self.account });
+            params[*key] = serde_json::Value::from(avatar.id());
+            // signal-cli answers with an error when there is no avatar
+            let Ok(result) = self.call(RpcMethod::GetAvatar, params).await else {
+                continue;
+            };
+            if let Some(data) = parse_image_data(&result) {
+                if let Some(dir) = path.parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                std::fs::write(&path, data)?;
+                return Ok(Some(path));
+            }
+        }
+        let _ = std::fs::remove_file(&path);
+        Ok(None)
+    }
+
+    /// Set our profile avatar from an image file, or remove it with `None`.
+    /// On success the new avatar (or its absence) is written straight to
+    /// the avatar cache. Returns the cached path.
+    pub async fn set_profile_avatar(&self, file: Option<&Path>) -> Result<Option<PathBuf>> {
+        let mut params = serde_json::json!({ "account": self.account });
+        match file {
+            Some(file) => params["avatar"] = file.display().to_string().into(),
+            None => params["removeAvatar"] = true.into(),
+        }
+        self.call(RpcMethod::UpdateProfile, params).await?;
+        let avatar = AvatarRef::Contact(self.account.clone());
+        match file {
+            Some(file) => Ok(Some(self.cache_avatar(&avatar, file)?)),
+            None => {
+                let _ = std::fs::remove_file(avatar.cache_path(&self.download_dir));
+                Ok(None)
+            }
+        }
+    }
+
+    /// Copy an avatar we just uploaded into the avatar cache, so it shows
+    /// without waiting for the next fetch. Returns the cached path.
+    pub fn cache_avatar(&self, avatar: &AvatarRef, file: &Path) -> Result<PathBuf> {
+        let path = avatar.cache_path(&self.download_dir);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::copy(file, &path)?;
+        Ok(path)
+    }
+}
+
+impl SignalClient {
     /// Returns accumulated stderr output from the signal-cli process.
+    /// Empty when attached to a daemon, whose stderr we never see.
     pub fn stderr_output(&self) -> String {
         self.stderr_buffer
             .lock()
@@ -630,8 +1204,9 @@ impl SignalClient {
     }
 
     /// Non-blocking check: returns `Some(exit_code)` if the child has exited.
+    /// Always `None` when attached to a daemon (there is no child to reap).
     pub fn try_child_exit(&mut self) -> Option<Option<i32>> {
-        match self.child.try_wait() {
+        match self.child.as_mut()?.try_wait() {
             Ok(Some(status)) => Some(status.code()),
             _ => None,
         }
@@ -639,8 +1214,11 @@ impl SignalClient {
 
     /// Wait up to `timeout` for signal-cli to either stay alive (ready) or exit early
     /// (likely unregistered). Returns `true` if the process is still running, `false`
-    /// if it exited during the window.
+    /// if it exited during the window. A daemon connection is ready as soon as it opens.
     pub async fn wait_for_ready(&mut self, timeout: Duration) -> bool {
+        if self.child.is_none() {
+            return true;
+        }
         let start = Instant::now();
         while start.elapsed() < timeout {
             if self.try_child_exit().is_some() {
@@ -651,8 +1229,12 @@ impl SignalClient {
         true
     }
 
+    /// Kill the child process, if we own one. A daemon connection is left
+    /// for the daemon to clean up once our tasks drop the stream.
     pub async fn shutdown(&mut self) -> Result<()> {
-        let _ = self.child.kill().await;
+        if let Some(child) = self.child.as_mut() {
+            let _ = child.kill().await;
+        }
         Ok(())
     }
 }
@@ -692,17 +1274,18 @@ fn build_send_reaction_params(
     params
 }
 
-/// Build the params for `sendReceipt` (read receipts). `recipient` is wrapped
-/// in a single-element array; `targetTimestamp` is the array of message
-/// timestamps being acknowledged.
-fn build_send_read_receipt_params(
+/// Build the params for `sendReceipt`. `receipt_type` is `read` or `viewed`;
+/// `recipient` is wrapped in a single-element array; `targetTimestamp` is the
+/// array of message timestamps being acknowledged.
+fn build_send_receipt_params(
     account: &str,
     recipient: &str,
+    receipt_type: &str,
     timestamps: &[i64],
 ) -> serde_json::Value {
     serde_json::json!({
         "recipient": [recipient],
-        "type": "read",
+        "type": receipt_type,
         "targetTimestamp": timestamps,
         "account": account,
     })
@@ -722,6 +1305,54 @@ fn build_update_contact_expiration_params(
     })
 }
 
+/// Build the `updateGroup` params for one [`GroupEdit`]. Permissions take
+/// signal-cli's CLI spellings (`every-member` / `only-admins`); resetting the
+/// invite link is a separate `resetLink` flag rather than a `link` state.
+fn build_edit_group_params(account: &str, group_id: &str, edit: &GroupEdit) -> serde_json::Value {
+    let mut params = serde_json::json!({
+        "groupId": group_id,
+        "account": account,
+    });
+    match edit {
+        GroupEdit::Admin { member, admin } => {
+            let key = if *admin { "admin" } else { "removeAdmin" };
+            params[key] = serde_json::json!([member]);
+        }
+        GroupEdit::Permission {
+            policy,
+            only_admins,
+        } => {
+            let key = match policy {
+                GroupPolicy::AddMembers => "setPermissionAddMember",
+                GroupPolicy::EditDetails => "setPermissionEditDetails",
+                GroupPolicy::SendMessages => "setPermissionSendMessages",
+            };
+            let value = if *only_admins {
+                "only-admins"
+            } else {
+                "every-member"
+            };
+            params[key] = serde_json::json!(value);
+        }
+        GroupEdit::Description(description) => {
+            params["description"] = serde_json::json!(description);
+        }
+        GroupEdit::Avatar(path) => {
+            params["avatar"] = serde_json::json!(path);
+        }
+        GroupEdit::Link(GroupLinkAction::Enable) => {
+            params["link"] = serde_json::json!("enabled");
+        }
+        GroupEdit::Link(GroupLinkAction::Reset) => {
+            params["resetLink"] = serde_json::json!(true);
+        }
+        GroupEdit::Link(GroupLinkAction::Disable) => {
+            params["link"] = serde_json::json!("disabled");
+        }
+    }
+    params
+}
+
 /// Build the params for `block` and `unblock`. Both wrap the identifier in
 /// a single-element array (`groupId` for groups, `recipient` for contacts),
 /// unlike `sendReaction` and `updateContact` which use bare strings.
@@ -740,26 +1371,29 @@ fn build_block_params(account: &str, recipient: &str, is_group: bool) -> serde_j
 }
 
 /// Send a JSON-RPC envelope to signal-cli's stdin and register the rpc id
-/// with `method` for response correlation. Returns the rpc id.
+/// with `method` for response correlation. Returns the [`RpcCall`] handle;
+/// its deadline timer starts once the entry is registered.
 ///
 /// Ordering matters: the entry only lands in `pending_requests` after the
 /// stdin write succeeds. If we registered before the write, a serialize or
-/// channel failure would leak an orphaned entry that sat in the map until the
-/// 60s TTL sweep, and callers waiting on the correlated event (SendTimestamp
+/// channel failure would leak an orphaned entry that sat in the map until its
+/// deadline, and callers waiting on the correlated event (SendTimestamp
 /// / SendFailed) would silently never hear back. See issue #434.
 ///
-/// Extracted from `SignalClient::send_rpc` so it can be unit-tested without
+/// Extracted from `SignalRpc::send_rpc` so it can be unit-tested without
 /// spawning a real signal-cli process.
 async fn send_rpc_impl(
     stdin_tx: &mpsc::Sender<String>,
-    pending_requests: &Arc<Mutex<HashMap<String, (String, Instant)>>>,
-    method: &str,
+    pending_requests: &PendingMap,
+    events: &mpsc::WeakSender<SignalEvent>,
+    method: RpcMethod,
     params: serde_json::Value,
-) -> Result<String> {
+    timeout: Duration,
+) -> Result<RpcCall> {
     let id = Uuid::new_v4().to_string();
     let request = JsonRpcRequest {
         jsonrpc: "2.0".to_string(),
-        method: method.to_string(),
+        method: method.as_str().to_string(),
         id: id.clone(),
         params: Some(params),
     };
@@ -768,24 +1402,18 @@ async fn send_rpc_impl(
         .send(json)
         .await
         .with_context(|| format!("Failed to send {method} to signal-cli stdin"))?;
-    match pending_requests.lock() {
-        Ok(mut map) => {
-            map.insert(id.clone(), (method.to_string(), Instant::now()));
-        }
-        Err(poisoned) => {
-            // The mutex is poisoned (another task panicked while holding it).
-            // We've already written the request to stdin, so signal-cli will
-            // respond -- recover the inner data and insert anyway so the
-            // response is still correlatable. Log loudly so the next debug
-            // capture surfaces it.
-            crate::debug_log::logf(format_args!(
-                "send_rpc: pending_requests mutex poisoned, recovering and registering {method} (id={id})"
-            ));
-            let mut map = poisoned.into_inner();
-            map.insert(id.clone(), (method.to_string(), Instant::now()));
-        }
-    }
-    Ok(id)
+    // We've already written the request to stdin, so signal-cli will respond.
+    // lock_pending recovers from a poisoned mutex so the response is still
+    // correlatable even if another task panicked while holding the lock.
+    let (reply, rx) = tokio::sync::oneshot::channel();
+    lock_pending(pending_requests).insert(id.clone(), PendingRpc { method, reply });
+    spawn_deadline(
+        Arc::clone(pending_requests),
+        events.clone(),
+        id.clone(),
+        timeout,
+    );
+    Ok(RpcCall::new(id, rx))
 }
 
 #[cfg(test)]
@@ -797,19 +1425,28 @@ mod tests {
     #[tokio::test]
     async fn send_rpc_impl_registers_after_successful_send() {
         let (tx, mut rx) = mpsc::channel::<String>(8);
-        let pending = Arc::new(Mutex::new(HashMap::new()));
-
-        let id = send_rpc_impl(&tx, &pending, "listContacts", serde_json::json!({}))
-            .await
-            .expect("send_rpc_impl");
+        let (event_tx, _event_rx) = mpsc::channel::<SignalEvent>(8);
+        let pending: PendingMap = Arc::default();
+
+        let call = send_rpc_impl(
+            &tx,
+            &pending,
+            &event_tx.downgrade(),
+            RpcMethod::ListContacts,
+            serde_json::json!({}),
+            RPC_TIMEOUT,
+        )
+        .await
+        .expect("send_rpc_impl");
+        let id = call.id;
 
         let wire = rx.recv().await.expect("stdin payload");
         assert!(wire.contains("\"method\":\"listContacts\""));
         assert!(wire.contains(&id));
 
-        let map = pending.lock().unwrap();
-        let (method, _) = map.get(&id).expect("pending entry");
-        assert_eq!(method, "listContacts");
+        let map = lock_pending(&pending);
+        let entry = map.get(&id).expect("pending entry");
+        assert_eq!(entry.method, RpcMethod::ListContacts);
         assert_eq!(map.len(), 1);
     }
 
@@ -820,12 +1457,21 @@ mod tests {
     async fn send_rpc_impl_does_not_leak_on_send_failure() {
         let (tx, rx) = mpsc::channel::<String>(8);
         drop(rx); // close the channel so send() returns Err
-        let pending = Arc::new(Mutex::new(HashMap::new()));
-
-        let result = send_rpc_impl(&tx, &pending, "listContacts", serde_json::json!({})).await;
+        let (event_tx, _event_rx) = mpsc::channel::<SignalEvent>(8);
+        let pending: PendingMap = Arc::default();
+
+        let result = send_rpc_impl(
+            &tx,
+            &pending,
+            &event_tx.downgrade(),
+            RpcMethod::ListContacts,
+            serde_json::json!({}),
+            RPC_TIMEOUT,
+        )
+        .await;
 
         assert!(result.is_err(), "send must fail when receiver is dropped");
-        let map = pending.lock().unwrap();
+        let map = lock_pending(&pending);
         assert!(
             map.is_empty(),
             "pending_requests must stay empty when stdin send fails (got {} entries)",
@@ -840,8 +1486,8 @@ mod tests {
     #[tokio::test]
     async fn send_rpc_impl_recovers_from_poisoned_mutex() {
         let (tx, mut rx) = mpsc::channel::<String>(8);
-        let pending: Arc<Mutex<HashMap<String, (String, Instant)>>> =
-            Arc::new(Mutex::new(HashMap::new()));
+        let (event_tx, _event_rx) = mpsc::channel::<SignalEvent>(8);
+        let pending: PendingMap = Arc::default();
 
         // Poison the mutex by panicking while holding the lock.
         let pending_clone = Arc::clone(&pending);
@@ -852,9 +1498,17 @@ mod tests {
         let _ = poison.join();
         assert!(pending.is_poisoned(), "mutex should be poisoned");
 
-        let id = send_rpc_impl(&tx, &pending, "listContacts", serde_json::json!({}))
-            .await
-            .expect("send_rpc_impl should succeed even with poisoned map");
+        let id = send_rpc_impl(
+            &tx,
+            &pending,
+            &event_tx.downgrade(),
+            RpcMethod::ListContacts,
+            serde_json::json!({}),
+            RPC_TIMEOUT,
+        )
+        .await
+        .expect("send_rpc_impl should succeed even with poisoned map")
+        .id;
 
         let _ = rx.recv().await;
         let map = pending.lock().unwrap_or_else(|e| e.into_inner());
@@ -863,6 +1517,396 @@ mod tests {
             "pending_requests must contain the entry even after mutex was poisoned"
         );
     }
+
+    /// A `signal-cli daemon` stand-in: writes one canned `receive`
+    /// notification, then answers the first request it reads with a canned
+    /// `listGroups` result carrying the request's id.
+    async fn serve_canned<S>(stream: S)
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let (reader, mut writer) = tokio::io::split(stream);
+        let notification = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "receive",
+            "params": {"envelope": {
+                "sourceNumber": "+15551234567",
+                "timestamp": 1_700_000_000_000_i64,
+                "dataMessage": {"timestamp": 1_700_000_000_000_i64, "message": "hello"}
+            }}
+        });
+        writer
+            .write_all(format!("{notification}\n").as_bytes())
+            .await
+            .unwrap();
+        let mut lines = BufReader::new(reader).lines();
+        let request: serde_json::Value =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        let response = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "result": [{"id": "group1", "name": "Family"}]
+        });
+        writer
+            .write_all(format!("{response}\n").as_bytes())
+            .await
+            .unwrap();
+        writer.flush().await.unwrap();
+    }
+
+    /// Drive a connected client against `serve_canned`: the notification and
+    /// the correlated RPC result both surface as events, then EOF closes the
+    /// channel. No child process means no stderr and no exit code.
+    async fn assert_canned_session(mut client: SignalClient) {
+        match client.event_rx.recv().await.expect("notification") {
+            SignalEvent::MessageReceived(msg) => {
+                assert_eq!(msg.source, "+15551234567");
+                assert_eq!(msg.body.as_deref(), Some("hello"));
+            }
+            other => panic!("expected MessageReceived, got {other:?}"),
+        }
+        client.list_groups().await.expect("list_groups");
+        match client.event_rx.recv().await.expect("rpc result") {
+            SignalEvent::GroupList(groups) => assert_eq!(groups[0].id, "group1"),
+            other => panic!("expected GroupList, got {other:?}"),
+        }
+        assert!(client.event_rx.recv().await.is_none(), "EOF closes channel");
+        assert_eq!(client.stderr_output(), "");
+        assert_eq!(client.try_child_exit(), None);
+        assert!(client.wait_for_ready(Duration::from_secs(5)).await);
+        client.shutdown().await.unwrap();
+    }
+
+    #[test]
+    fn transport_from_config_prefers_socket_then_tcp() {
+        let mut config = Config::default();
+        assert_eq!(Transport::from_config(&config), Transport::Process);
+        config.signal_cli_tcp = "127.0.0.1:7583".to_string();
+        assert_eq!(
+            Transport::from_config(&config),
+            Transport::Tcp("127.0.0.1:7583".to_string())
+        );
+        config.signal_cli_socket = "/run/signal-cli/socket".to_string();
+        assert_eq!(
+            Transport::from_config(&config),
+            Transport::UnixSocket(PathBuf::from("/run/signal-cli/socket"))
+        );
+    }
+
+    #[tokio::test]
+    async fn tcp_transport_round_trips_canned_frames() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            serve_canned(stream).await;
+        });
+        let config = Config {
+            account: "+15550000000".to_string(),
+            signal_cli_tcp: addr.to_string(),
+            download_dir: std::env::temp_dir(),
+            ..Config::default()
+        };
+        let client = SignalClient::connect(&config).await.expect("connect");
+        assert_canned_session(client).await;
+        server.await.unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn unix_socket_transport_round_trips_canned_frames() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("signal-cli.sock");
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            serve_canned(stream).await;
+        });
+        let config = Config {
+            account: "+15550000000".to_string(),
+            signal_cli_socket: path.to_string_lossy().to_string(),
+            download_dir: std::env::temp_dir(),
+            ..Config::default()
+        };
+        let client = SignalClient::connect(&config).await.expect("connect");
+        assert_canned_session(client).await;
+        server.await.unwrap();
+    }
+
+    /// Awaited calls resolve through their `RpcCall` instead of the event
+    /// stream: a rejected `trust` surfaces signal-cli's message, and
+    /// `fetch_identities` hands back the parsed list directly, or an error
+    /// for a reply it can't parse.
+    #[tokio::test]
+    async fn awaited_calls_resolve_without_events() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = tokio::io::split(stream);
+            let mut lines = BufReader::new(reader).lines();
+            let replies = [
+                serde_json::json!({"error": {"code": -1, "message": "Unknown recipient"}}),
+                serde_json::json!({"result": [{
+                    "number": "+15551234567",
+                    "safetyNumber": "1234",
+                    "trustLevel": "TRUSTED_VERIFIED"
+                }]}),
+                serde_json::json!({"result": {"unexpected": true}}),
+            ];
+            for mut reply in replies {
+                let request: serde_json::Value =
+                    serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+                reply["jsonrpc"] = "2.0".into();
+                reply["id"] = request["id"].clone();
+                writer
+                    .write_all(format!("{reply}\n").as_bytes())
+                    .await
+                    .unwrap();
+            }
+            writer.flush().await.unwrap();
+        });
+        let config = Config {
+            account: "+15550000000".to_string(),
+            signal_cli_tcp: addr.to_string(),
+            download_dir: std::env::temp_dir(),
+            ..Config::default()
+        };
+        let mut client = SignalClient::connect(&config).await.expect("connect");
+
+        let err = client
+            .trust_identity("+15551234567", "1234")
+            .await
+            .expect_err("trust rejected");
+        assert_eq!(err.to_string(), "trust: Unknown recipient");
+
+        let ids = client.fetch_identities().await.expect("identities");
+        assert_eq!(ids.len(), 1);
+        assert_eq!(ids[0].trust_level, TrustLevel::TrustedVerified);
+        assert!(client.fetch_identities().await.is_err());
+
+        server.await.unwrap();
+        assert!(
+            client.event_rx.recv().await.is_none(),
+            "awaited responses must not be emitted as events"
+        );
+        assert!(lock_pending(&client.pending_requests).is_empty());
+        client.shutdown().await.unwrap();
+    }
+
+    /// A send refused over a changed safety number is reported with the
+    /// recipients it failed for, both when the whole send fails and when
+    /// only some members of a group were refused.
+    #[tokio::test]
+    async fn identity_failures_surface_as_untrusted_recipients() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = tokio::io::split(stream);
+            let mut lines = BufReader::new(reader).lines();
+            let replies = [
+                serde_json::json!({"error": {
+                    "code": -4,
+                    "message": "Failed to send message due to untrusted identities",
+                    "data": {"response": {"timestamp": 1000, "results": [
+                        {"recipientAddress": {"number": "+15551234567"},
+                         "type": "IDENTITY_FAILURE"}
+                    ]}}
+                }}),
+                serde_json::json!({"result": {"timestamp": 2000, "results": [
+                    {"recipientAddress": {"number": "+15550000001"}, "type": "SUCCESS"},
+                    {"recipientAddress": {"number": "+15550000002"},
+                     "type": "IDENTITY_FAILURE"}
+                ]}}),
+            ];
+            for mut reply in replies {
+                let request: serde_json::Value =
+                    serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+                reply["jsonrpc"] = "2.0".into();
+                reply["id"] = request["id"].clone();
+                writer
+                    .write_all(format!("{reply}\n").as_bytes())
+                    .await
+                    .unwrap();
+            }
+            writer.flush().await.unwrap();
+        });
+        let config = Config {
+            account: "+15550000000".to_string(),
+            signal_cli_tcp: addr.to_string(),
+            download_dir: std::env::temp_dir(),
+            ..Config::default()
+        };
+        let mut client = SignalClient::connect(&config).await.expect("connect");
+
+        let failed_id = client
+            .send_message("+15551234567", "hi", false, &[], &[], &[], None)
+            .await
+            .unwrap();
+        match client.event_rx.recv().await.expect("failure") {
+            SignalEvent::UntrustedRecipients {
+                rpc_id,
+                recipients,
+                partial,
+            } => {
+                assert_eq!(rpc_id.as_deref(), Some(failed_id.as_str()));
+                assert_eq!(recipients, vec!["+15551234567"]);
+                assert!(!partial);
+            }
+            other => panic!("expected UntrustedRecipients, got {other:?}"),
+        }
+
+        let group_id = client
+            .send_message("Z3JvdXA=", "hi all", true, &[], &[], &[], None)
+            .await
+            .unwrap();
+        match client.event_rx.recv().await.expect("partial failure") {
+            SignalEvent::UntrustedRecipients {
+                rpc_id,
+                recipients,
+                partial,
+            } => {
+                assert_eq!(rpc_id.as_deref(), Some(group_id.as_str()));
+                assert_eq!(recipients, vec!["+15550000002"]);
+                assert!(partial);
+            }
+            other => panic!("expected UntrustedRecipients, got {other:?}"),
+        }
+        match client.event_rx.recv().await.expect("timestamp") {
+            SignalEvent::SendTimestamp { rpc_id, server_ts } => {
+                assert_eq!(rpc_id, group_id);
+                assert_eq!(server_ts, 2000);
+            }
+            other => panic!("expected SendTimestamp, got {other:?}"),
+        }
+
+        server.await.unwrap();
+        client.shutdown().await.unwrap();
+    }
+
+    /// A send refused by a rate limit is reported with its challenge, a bare
+    /// rate-limit error without per-recipient results still counts, and a
+    /// group send refused for some members says which ones.
+    #[tokio::test]
+    async fn rate_limit_errors_surface_as_rate_limited() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = tokio::io::split(stream);
+            let mut lines = BufReader::new(reader).lines();
+            let replies = [
+                serde_json::json!({"error": {
+                    "code": -5,
+                    "message": "Failed to send message due to rate limiting",
+                    "data": {"response": {"timestamp": 1000, "results": [
+                        {"recipientAddress": {"number": "+15551234567"},
+                         "type": "PROOF_REQUIRED_FAILURE",
+                         "token": "challenge-token", "retryAfterSeconds": 86400}
+                    ]}}
+                }}),
+                serde_json::json!({"error": {"code": -5, "message": "Rate limit exceeded"}}),
+                serde_json::json!({"result": {"timestamp": 3000, "results": [
+                    {"recipientAddress": {"number": "+15550000001"}, "type": "SUCCESS"},
+                    {"recipientAddress": {"number": "+15550000002"},
+                     "type": "RATE_LIMIT_FAILURE", "retryAfterSeconds": 30}
+                ]}}),
+            ];
+            for mut reply in replies {
+                let request: serde_json::Value =
+                    serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+                reply["jsonrpc"] = "2.0".into();
+                reply["id"] = request["id"].clone();
+                writer
+                    .write_all(format!("{reply}\n").as_bytes())
+                    .await
+                    .unwrap();
+            }
+            writer.flush().await.unwrap();
+        });
+        let config = Config {
+            account: "+15550000000".to_string(),
+            signal_cli_tcp: addr.to_string(),
+            download_dir: std::env::temp_dir(),
+            ..Config::default()
+        };
+        let mut client = SignalClient::connect(&config).await.expect("connect");
+
+        let first = client
+            .send_message("+15551234567", "hi", false, &[], &[], &[], None)
+            .await
+            .unwrap();
+        match client.event_rx.recv().await.expect("challenge") {
+            SignalEvent::RateLimited {
+                rpc_id,
+                failure,
+                partial,
+            } => {
+                assert_eq!(rpc_id.as_deref(), Some(first.as_str()));
+                assert!(!partial);
+                assert_eq!(failure.recipients, vec!["+15551234567"]);
+                assert_eq!(failure.challenge.as_deref(), Some("challenge-token"));
+                assert_eq!(failure.retry_after_seconds, Some(86400));
+            }
+            other => panic!("expected RateLimited, got {other:?}"),
+        }
+
+        let second = client
+            .send_message("+15551234567", "again", false, &[], &[], &[], None)
+            .await
+            .unwrap();
+        match client.event_rx.recv().await.expect("rate limit") {
+            SignalEvent::RateLimited {
+                rpc_id, failure, ..
+            } => {
+                assert_eq!(rpc_id.as_deref(), Some(second.as_str()));
+                assert_eq!(failure, RateLimitFailure::default());
+            }
+            other => panic!("expected RateLimited, got {other:?}"),
+        }
+
+        let group_id = client
+            .send_message("Z3JvdXA=", "hi all", true, &[], &[], &[], None)
+            .await
+            .unwrap();
+        match client.event_rx.recv().await.expect("partial rate limit") {
+            SignalEvent::RateLimited {
+                rpc_id,
+                failure,
+                partial,
+            } => {
+                assert_eq!(rpc_id.as_deref(), Some(group_id.as_str()));
+                assert!(partial);
+                assert_eq!(failure.recipients, vec!["+15550000002"]);
+                assert_eq!(failure.retry_after_seconds, Some(30));
+            }
+            other => panic!("expected RateLimited, got {other:?}"),
+        }
+        match client.event_rx.recv().await.expect("timestamp") {
+            SignalEvent::SendTimestamp { rpc_id, server_ts } => {
+                assert_eq!(rpc_id, group_id);
+                assert_eq!(server_ts, 3000);
+            }
+            other => panic!("expected SendTimestamp, got {other:?}"),
+        }
+
+        server.await.unwrap();
+        client.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn connect_to_missing_daemon_reports_address() {
+        let config = Config {
+            signal_cli_tcp: "127.0.0.1:1".to_string(),
+            ..Config::default()
+        };
+        let err = SignalClient::connect(&config)
+            .await
+            .err()
+            .expect("nothing listens on port 1");
+        assert!(err.to_string().contains("127.0.0.1:1"));
+    }
 }
 
 #[cfg(test)]
@@ -877,7 +1921,7 @@ mod wire_tests {
     #[test]
     fn set_target_wraps_recipient_in_array() {
         let mut params = json!({});
-        SignalClient::set_target(&mut params, "+15551234567", false);
+        SignalRpc::set_target(&mut params, "+15551234567", false);
         assert_eq!(
             params,
             json!({
@@ -890,7 +1934,7 @@ mod wire_tests {
     #[test]
     fn set_target_uses_bare_group_id() {
         let mut params = json!({});
-        SignalClient::set_target(&mut params, "Z0VlVnFLbE...", true);
+        SignalRpc::set_target(&mut params, "Z0VlVnFLbE...", true);
         assert_eq!(
             params,
             json!({
@@ -899,6 +1943,30 @@ mod wire_tests {
         );
     }
 
+    /// textStyle: each range becomes a "start:length:STYLE" string; no key
+    /// at all when the message is unstyled.
+    #[test]
+    fn set_text_styles_uses_colon_strings() {
+        let mut params = json!({});
+        SignalRpc::set_text_styles(&mut params, &[]);
+        assert_eq!(params, json!({}));
+
+        let styles = [
+            TextStyle {
+                start: 0,
+                length: 4,
+                style: StyleType::Bold,
+            },
+            TextStyle {
+                start: 6,
+                length: 3,
+                style: StyleType::Spoiler,
+            },
+        ];
+        SignalRpc::set_text_styles(&mut params, &styles);
+        assert_eq!(params, json!({"textStyle": ["0:4:BOLD", "6:3:SPOILER"]}));
+    }
+
     /// sendReaction (1:1): bare-string recipient (NOT array). signal-cli
     /// rejects the array form here. Distinct from set_target's behaviour.
     #[test]
@@ -967,9 +2035,10 @@ mod wire_tests {
     /// is the message-timestamp array, type=read.
     #[test]
     fn send_read_receipt_wire_shape() {
-        let params = build_send_read_receipt_params(
+        let params = build_send_receipt_params(
             "+15550000000",
             "+15551234567",
+            "read",
             &[1_700_000_000_000, 1_700_000_000_001],
         );
         assert_eq!(
@@ -983,6 +2052,73 @@ mod wire_tests {
         );
     }
 
+    /// sendReceipt for an opened view-once message: type=viewed.
+    #[test]
+    fn send_viewed_receipt_wire_shape() {
+        let params = build_send_receipt_params(
+            "+15550000000",
+            "+15551234567",
+            "viewed",
+            &[1_700_000_000_000],
+        );
+        assert_eq!(params["type"], json!("viewed"));
+        assert_eq!(params["targetTimestamp"], json!([1_700_000_000_000_i64]));
+    }
+
+    /// updateGroup admin edits: one key per edit, member lists as arrays.
+    #[test]
+    fn edit_group_wire_shapes() {
+        let params = |edit: GroupEdit| build_edit_group_params("+15550000000", "g1", &edit);
+        assert_eq!(
+            params(GroupEdit::Admin {
+                member: "+1".to_string(),
+                admin: true,
+            }),
+            json!({"groupId": "g1", "account": "+15550000000", "admin": ["+1"]})
+        );
+        assert_eq!(
+            params(GroupEdit::Admin {
+                member: "+1".to_string(),
+                admin: false,
+            })["removeAdmin"],
+            json!(["+1"])
+        );
+        assert_eq!(
+            params(GroupEdit::Permission {
+                policy: GroupPolicy::SendMessages,
+                only_admins: true,
+            })["setPermissionSendMessages"],
+            json!("only-admins")
+        );
+        assert_eq!(
+            params(GroupEdit::Permission {
+                policy: GroupPolicy::AddMembers,
+                only_admins: false,
+            })["setPermissionAddMember"],
+            json!("every-member")
+        );
+        assert_eq!(
+            params(GroupEdit::Description("Plans".to_string()))["description"],
+            json!("Plans")
+        );
+        assert_eq!(
+            params(GroupEdit::Avatar("/tmp/a.png".to_string()))["avatar"],
+            json!("/tmp/a.png")
+        );
+        assert_eq!(
+            params(GroupEdit::Link(GroupLinkAction::Enable))["link"],
+            json!("enabled")
+        );
+        assert_eq!(
+            params(GroupEdit::Link(GroupLinkAction::Reset))["resetLink"],
+            json!(true)
+        );
+        assert_eq!(
+            params(GroupEdit::Link(GroupLinkAction::Disable))["link"],
+            json!("disabled")
+        );
+    }
+
     /// updateContact (1:1 disappearing-message timer): bare-string recipient.
     #[test]
     fn update_contact_expiration_uses_bare_recipient() {
@@ -1,6 +1,9 @@
 //! signal-cli integration: child process bridge ([`client`]), JSON-RPC frame
-//! parsers ([`parse`]), and wire types ([`types`]).
+//! parsers ([`parse`]), typed requests and their completion ([`rpc`]),
+//! respawn policy ([`supervisor`]), and wire types ([`types`]).
 
 pub mod client;
 pub mod parse;
+pub mod rpc;
+pub mod supervisor;
 pub mod types;
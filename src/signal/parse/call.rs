@@ -0,0 +1,51 @@
+//! Call message parser. signal-cli forwards each 1:1 call signalling step
+//! as an envelope `callMessage` holding exactly one of `offerMessage`,
+//! `answerMessage`, `busyMessage`, `hangupMessage` or `iceUpdateMessages`.
+//! Every step carries the call's `id`, which ties the lifecycle together.
+
+use crate::signal::types::*;
+
+use super::envelope::envelope_source;
+
+pub(super) fn parse_call_message(
+    envelope: &serde_json::Value,
+    call: &serde_json::Value,
+) -> Option<SignalEvent> {
+    let (step, kind) = if let Some(offer) = call.get("offerMessage") {
+        let video = offer.get("type").and_then(|v| v.as_str()) == Some("VIDEO_CALL");
+        (offer, CallSignalKind::Offer { video })
+    } else if let Some(answer) = call.get("answerMessage") {
+        (answer, CallSignalKind::Answer)
+    } else if let Some(busy) = call.get("busyMessage") {
+        (busy, CallSignalKind::Busy)
+    } else if let Some(hangup) = call.get("hangupMessage") {
+        let hangup_type = hangup
+            .get("type")
+            .and_then(|v| v.as_str())
+            .unwrap_or("NORMAL")
+            .to_string();
+        (hangup, CallSignalKind::Hangup { hangup_type })
+    } else {
+        // ICE candidate updates: signalling noise
+        return None;
+    };
+    // Call ids are unsigned 64-bit on the wire; Java prints them signed
+    let id = step.get("id")?;
+    let call_id = id.as_i64().or_else(|| id.as_u64().map(|v| v as i64))?;
+    let peer_name = envelope
+        .get("sourceName")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string());
+    let timestamp_ms = envelope
+        .get("timestamp")
+        .and_then(|v| v.as_i64())
+        .unwrap_or(0);
+    Some(SignalEvent::CallSignal(CallSignal {
+        peer: envelope_source(envelope),
+        peer_name,
+        call_id,
+        timestamp_ms,
+        kind,
+    }))
+}
@@ -6,7 +6,9 @@ use chrono::DateTime;
 
 use crate::signal::types::*;
 
+use super::call::parse_call_message;
 use super::message::{parse_data_message, parse_edit_message, parse_sent_sync};
+use super::story::parse_story_message;
 
 pub fn parse_signal_event(
     resp: &JsonRpcResponse,
@@ -87,6 +89,7 @@ fn parse_receive_event(
                 body: "\u{26A0} Safety number changed".to_string(),
                 timestamp,
                 timestamp_ms,
+                is_warning: true,
             });
         }
         return Some(SignalEvent::Error(format!("signal-cli: {msg}")));
@@ -100,34 +103,11 @@ fn parse_receive_event(
     if envelope.get("receiptMessage").is_some() {
         return parse_receipt_message(envelope);
     }
-    // Call messages (missed calls)
     if let Some(call_msg) = envelope.get("callMessage") {
-        if call_msg.get("offerMessage").is_some() {
-            let call_type = call_msg
-                .get("offerMessage")
-                .and_then(|o| o.get("type"))
-                .and_then(|v| v.as_str())
-                .unwrap_or("AUDIO_CALL");
-            let kind = if call_type == "VIDEO_CALL" {
-                "video"
-            } else {
-                "voice"
-            };
-            let conv_id = envelope_source(envelope);
-            let timestamp_ms = envelope
-                .get("timestamp")
-                .and_then(|v| v.as_i64())
-                .unwrap_or(0);
-            let timestamp = DateTime::from_timestamp_millis(timestamp_ms).unwrap_or_default();
-            return Some(SignalEvent::SystemMessage {
-                conv_id,
-                body: format!("Missed {kind} call"),
-                timestamp,
-                timestamp_ms,
-            });
-        }
-        // Ignore ICE candidates, hangup, busy (call signaling noise)
-        return None;
+        return parse_call_message(envelope, call_msg);
+    }
+    if let Some(story) = envelope.get("storyMessage") {
+        return parse_story_message(envelope, story, download_dir);
     }
     // Check for editMessage (top-level envelope field) before dataMessage
     if let Some(edit_msg) = envelope.get("editMessage") {
@@ -146,6 +126,9 @@ fn parse_receive_event(
         if let Some(event) = parse_read_sync(sync) {
             return Some(event);
         }
+        if let Some(event) = parse_view_once_open(sync) {
+            return Some(event);
+        }
         return None;
     }
 
@@ -173,6 +156,18 @@ fn parse_read_sync(sync: &serde_json::Value) -> Option<SignalEvent> {
     })
 }
 
+/// `viewOnceOpen` sync: another of our devices revealed a view-once message.
+fn parse_view_once_open(sync: &serde_json::Value) -> Option<SignalEvent> {
+    let open = sync.get("viewOnceOpen")?;
+    let sender = ["senderNumber", "sender", "senderUuid"]
+        .iter()
+        .find_map(|key| open.get(*key).and_then(|v| v.as_str()))
+        .filter(|s| !s.is_empty())?
+        .to_string();
+    let timestamp = open.get("timestamp").and_then(|v| v.as_i64())?;
+    Some(SignalEvent::ViewOnceOpened { sender, timestamp })
+}
+
 fn parse_typing_indicator(envelope: &serde_json::Value) -> Option<SignalEvent> {
     let typing = envelope.get("typingMessage")?;
     let sender = envelope_source(envelope);
@@ -233,9 +228,15 @@ fn parse_receipt_message(envelope: &serde_json::Value) -> Option<SignalEvent> {
         .and_then(|v| v.as_array())
         .map(|arr| arr.iter().filter_map(|v| v.as_i64()).collect())
         .unwrap_or_default();
+    let when_ms = receipt
+        .get("when")
+        .or_else(|| envelope.get("timestamp"))
+        .and_then(|v| v.as_i64())
+        .unwrap_or(0);
     Some(SignalEvent::ReceiptReceived {
         sender,
         receipt_type,
         timestamps,
+        when_ms,
     })
 }
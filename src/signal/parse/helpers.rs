@@ -6,6 +6,24 @@ use crate::signal::types::*;
 pub(super) fn parse_attachment(
     value: &serde_json::Value,
     download_dir: &std::path::Path,
+) -> Option<Attachment> {
+    import_attachment(value, download_dir, false)
+}
+
+/// Like [`parse_attachment`], but moves the file out of signal-cli's storage
+/// instead of copying it, so a view-once attachment exists exactly once on
+/// disk until it is revealed and purged.
+pub(super) fn parse_view_once_attachment(
+    value: &serde_json::Value,
+    download_dir: &std::path::Path,
+) -> Option<Attachment> {
+    import_attachment(value, download_dir, true)
+}
+
+fn import_attachment(
+    value: &serde_json::Value,
+    download_dir: &std::path::Path,
+    take: bool,
 ) -> Option<Attachment> {
     let id = value.get("id").and_then(|v| v.as_str())?.to_string();
     let content_type = value
@@ -84,23 +102,80 @@ pub(super) fn parse_attachment(
                 let _ =
                     std::fs::set_permissions(download_dir, std::fs::Permissions::from_mode(0o700));
             }
-            match std::fs::copy(&src, &dest) {
-                Ok(_) => Some(dest.to_string_lossy().to_string()),
-                Err(_) => Some(src.to_string_lossy().to_string()),
+            let moved = take && std::fs::rename(&src, &dest).is_ok();
+            if moved {
+                Some(dest.to_string_lossy().to_string())
+            } else {
+                match std::fs::copy(&src, &dest) {
+                    Ok(_) => {
+                        if take {
+                            let _ = std::fs::remove_file(&src);
+                        }
+                        Some(dest.to_string_lossy().to_string())
+                    }
+                    Err(_) => Some(src.to_string_lossy().to_string()),
+                }
             }
         } else {
             None
         }
     };
 
+    let dimension = |key: &str| {
+        value
+            .get(key)
+            .and_then(|v| v.as_u64())
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|&v| v > 0)
+    };
     Some(Attachment {
         id,
         content_type,
         filename: Some(effective_name),
         local_path,
+        size: value.get("size").and_then(|v| v.as_u64()),
+        width: dimension("width"),
+        height: dimension("height"),
     })
 }
 
+/// Parse the `sticker` object of a data message. The image comes from the
+/// attachment signal-cli downloaded with it, or from a copy cached by an
+/// earlier `getSticker` fetch; failing both, `local_path` stays `None` and
+/// the app fetches it later.
+pub(super) fn parse_sticker(
+    value: &serde_json::Value,
+    download_dir: &std::path::Path,
+) -> Option<StickerRef> {
+    let pack_id = value.get("packId").and_then(|v| v.as_str())?.to_string();
+    let sticker_id = value
+        .get("stickerId")
+        .and_then(|v| v.as_u64())
+        .and_then(|v| u32::try_from(v).ok())?;
+    let emoji = value
+        .get("emoji")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string());
+    let mut sticker = StickerRef {
+        pack_id,
+        sticker_id,
+        emoji,
+        local_path: None,
+    };
+    sticker.local_path = value
+        .get("attachment")
+        .and_then(|a| parse_attachment(a, download_dir))
+        .and_then(|a| a.local_path)
+        .or_else(|| {
+            sticker
+                .cache_path(download_dir)
+                .filter(|p| p.exists())
+                .map(|p| p.to_string_lossy().into_owned())
+        });
+    Some(sticker)
+}
+
 /// Parse link previews from a dataMessage / sentMessage object.
 pub(super) fn parse_link_previews(
     data: &serde_json::Value,
@@ -139,6 +214,68 @@ pub(super) fn parse_link_previews(
         .collect()
 }
 
+/// Parse the `sharedContacts` array of a dataMessage / sentMessage object.
+/// Contacts with neither a name nor a number or email are dropped.
+pub(super) fn parse_shared_contacts(data: &serde_json::Value) -> Vec<SharedContact> {
+    let Some(arr) = data.get("sharedContacts").and_then(|v| v.as_array()) else {
+        return Vec::new();
+    };
+    let text = |v: &serde_json::Value, key: &str| {
+        v.get(key)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+    };
+    // Custom entries carry their own label; the rest are named by type
+    let points = |c: &serde_json::Value, key: &str| -> Vec<ContactPoint> {
+        c.get(key)
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|p| {
+                        let value = text(p, "value")?;
+                        let label = text(p, "label").or_else(|| {
+                            match p.get("type").and_then(|v| v.as_str()) {
+                                Some("HOME") => Some("Home".to_string()),
+                                Some("WORK") => Some("Work".to_string()),
+                                Some("MOBILE") => Some("Mobile".to_string()),
+                                _ => None,
+                            }
+                        });
+                        Some(ContactPoint { value, label })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    arr.iter()
+        .filter_map(|c| {
+            let phones = points(c, "phone");
+            let emails = points(c, "email");
+            let name = c
+                .get("name")
+                .and_then(|n| {
+                    text(n, "display").or_else(|| {
+                        let parts: Vec<String> = ["given", "family"]
+                            .iter()
+                            .filter_map(|k| text(n, k))
+                            .collect();
+                        (!parts.is_empty()).then(|| parts.join(" "))
+                    })
+                })
+                .or_else(|| phones.first().map(|p| p.value.clone()))
+                .or_else(|| emails.first().map(|e| e.value.clone()))?;
+            Some(SharedContact {
+                name,
+                organization: text(c, "organization"),
+                phones,
+                emails,
+            })
+        })
+        .collect()
+}
+
 /// Look for an attachment file in signal-cli's data directory by attachment ID.
 /// signal-cli stores attachments as `{data_dir}/attachments/{id}.{ext}`.
 ///
@@ -206,6 +343,15 @@ pub(super) fn format_expiration(seconds: i64) -> String {
     if seconds == 0 {
         return "Disappearing messages disabled".to_string();
     }
+    format!(
+        "Disappearing messages set to {}",
+        format_timer_duration(seconds)
+    )
+}
+
+/// Spell out a disappearing-message timer in its largest whole unit,
+/// e.g. "1 week" or "30 seconds".
+pub fn format_timer_duration(seconds: i64) -> String {
     let (n, unit) = if seconds < 60 {
         (seconds, "second")
     } else if seconds < 3600 {
@@ -218,7 +364,7 @@ pub(super) fn format_expiration(seconds: i64) -> String {
         (seconds / 604800, "week")
     };
     let plural = if n == 1 { "" } else { "s" };
-    format!("Disappearing messages set to {n} {unit}{plural}")
+    format!("{n} {unit}{plural}")
 }
 
 /// Parse mentions from a data/sync message.
@@ -251,6 +397,33 @@ pub(super) fn parse_mentions(data: &serde_json::Value) -> Vec<Mention> {
     .unwrap_or_default()
 }
 
+/// Drop the U+FFFC mention placeholders from `text`, moving the UTF-16
+/// offsets of `styles` to match.
+pub(super) fn strip_placeholders(text: &str, styles: Vec<TextStyle>) -> (String, Vec<TextStyle>) {
+    let mut placeholders = Vec::new();
+    let mut pos = 0;
+    for c in text.chars() {
+        if c == '\u{FFFC}' {
+            placeholders.push(pos);
+        }
+        pos += c.len_utf16();
+    }
+    let shift = |offset: usize| offset - placeholders.iter().filter(|&&p| p < offset).count();
+    let styles = styles
+        .into_iter()
+        .filter_map(|s| {
+            let start = shift(s.start);
+            let length = shift(s.start + s.length) - start;
+            (length > 0).then_some(TextStyle {
+                start,
+                length,
+                style: s.style,
+            })
+        })
+        .collect();
+    (text.replace('\u{FFFC}', ""), styles)
+}
+
 /// Parse text styles from a data message's textStyles array (or bodyRanges style entries).
 pub(super) fn parse_text_styles(data: &serde_json::Value) -> Vec<TextStyle> {
     // Try textStyles array first, then fall back to bodyRanges entries with "style" field
@@ -9,7 +9,9 @@ use crate::signal::types::*;
 
 use super::envelope::{envelope_source, sent_destination};
 use super::helpers::{
-    format_expiration, parse_attachment, parse_link_previews, parse_mentions, parse_text_styles,
+    format_expiration, parse_attachment, parse_link_previews, parse_mentions,
+    parse_shared_contacts, parse_sticker, parse_text_styles, parse_view_once_attachment,
+    strip_placeholders,
 };
 use super::poll::{parse_poll_create, parse_poll_terminate, parse_poll_vote};
 
@@ -107,6 +109,9 @@ pub(super) fn parse_data_message(
         quote: common.quote,
         expires_in_seconds: common.expires_in_seconds,
         previews: common.previews,
+        sticker: common.sticker,
+        view_once: common.view_once,
+        shared_contacts: common.shared_contacts,
     }))
 }
 
@@ -157,6 +162,9 @@ pub(super) fn parse_sent_sync(
         quote: common.quote,
         expires_in_seconds: common.expires_in_seconds,
         previews: common.previews,
+        sticker: common.sticker,
+        view_once: common.view_once,
+        shared_contacts: common.shared_contacts,
     }))
 }
 
@@ -171,9 +179,12 @@ struct CommonMessageFields {
     group_name: Option<String>,
     mentions: Vec<Mention>,
     text_styles: Vec<TextStyle>,
-    quote: Option<(i64, String, String)>,
+    quote: Option<(i64, String, String, Vec<TextStyle>)>,
     expires_in_seconds: i64,
     timestamp: DateTime<chrono::Utc>,
+    sticker: Option<StickerRef>,
+    view_once: bool,
+    shared_contacts: Vec<SharedContact>,
 }
 
 fn parse_common_message_fields(
@@ -183,19 +194,30 @@ fn parse_common_message_fields(
     let timestamp_ms = data.get("timestamp").and_then(|v| v.as_i64()).unwrap_or(0);
     let timestamp = DateTime::from_timestamp_millis(timestamp_ms).unwrap_or_default();
 
+    let mut sticker = data
+        .get("sticker")
+        .and_then(|s| parse_sticker(s, download_dir));
     let sticker_body =
         data.get("sticker").map(
-            |sticker| match sticker.get("emoji").and_then(|v| v.as_str()) {
+            |_| match sticker.as_ref().and_then(|s| s.emoji.as_deref()) {
                 Some(emoji) => format!("[Sticker: {}]", emoji),
                 None => "[Sticker]".to_string(),
             },
         );
 
+    // A contact card with no text reads as "[Contact: Name]" in previews
+    // and notifications; the card itself is rendered from `shared_contacts`.
+    let shared_contacts = parse_shared_contacts(data);
+    let contact_body = shared_contacts
+        .first()
+        .map(|c| format!("[Contact: {}]", c.name));
+
     let mut body = data
         .get("message")
         .and_then(|v| v.as_str())
         .map(|s| s.to_string())
-        .or(sticker_body);
+        .or(sticker_body)
+        .or(contact_body);
 
     let group_id = data
         .get("groupInfo")
@@ -209,43 +231,45 @@ fn parse_common_message_fields(
         .and_then(|v| v.as_str())
         .map(|s| s.to_string());
 
-    let mut attachments = data
+    let view_once = data
+        .get("viewOnce")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let import = if view_once {
+        parse_view_once_attachment
+    } else {
+        parse_attachment
+    };
+
+    let attachments = data
         .get("attachments")
         .and_then(|v| v.as_array())
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|a| parse_attachment(a, download_dir))
-                .collect()
-        })
+        .map(|arr| arr.iter().filter_map(|a| import(a, download_dir)).collect())
         .unwrap_or_default();
 
     let mut previews = parse_link_previews(data, download_dir);
 
-    // View-once messages: replace content with placeholder.
-    if data
-        .get("viewOnce")
-        .and_then(|v| v.as_bool())
-        .unwrap_or(false)
-    {
+    // View-once messages: the body becomes a placeholder and the media is
+    // kept only for the one-time reveal.
+    if view_once {
         body = Some("[View-once message]".to_string());
-        attachments = Vec::new();
         previews = Vec::new();
+        sticker = None;
     }
 
-    let mentions = parse_mentions(data);
-    let text_styles = parse_text_styles(data);
+    let (mentions, text_styles) = if view_once {
+        (Vec::new(), Vec::new())
+    } else {
+        (parse_mentions(data), parse_text_styles(data))
+    };
 
     // Strip U+FFFC mention placeholders from quote text.
     let quote = data.get("quote").and_then(|q| {
         let q_ts = q.get("id").and_then(|v| v.as_i64())?;
         let q_author = q.get("authorNumber").and_then(|v| v.as_str())?.to_string();
-        let q_body = q
-            .get("text")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .replace('\u{FFFC}', "")
-            .to_string();
-        Some((q_ts, q_author, q_body))
+        let q_text = q.get("text").and_then(|v| v.as_str()).unwrap_or("");
+        let (q_body, q_styles) = strip_placeholders(q_text, parse_text_styles(q));
+        Some((q_ts, q_author, q_body, q_styles))
     });
 
     let expires_in_seconds = data
@@ -264,6 +288,9 @@ fn parse_common_message_fields(
         quote,
         expires_in_seconds,
         timestamp,
+        sticker,
+        view_once,
+        shared_contacts,
     }
 }
 
@@ -374,7 +401,7 @@ fn parse_data_payload_event(
         });
     }
 
-    // Group update with no body/reaction/remoteDelete -> system message.
+    // Group update with no body/reaction/remoteDelete -> GroupUpdated.
     // conv_id here always comes from the group_id directly (never sender or
     // destination) since we are inside the groupInfo branch.
     if let Some(group_info) = data.get("groupInfo") {
@@ -386,17 +413,30 @@ fn parse_data_payload_event(
             && data.get("message").and_then(|v| v.as_str()).is_none()
             && data.get("reaction").is_none()
             && data.get("remoteDelete").is_none()
+            && data.get("sharedContacts").is_none()
         {
-            let conv_id = group_info
+            let group_id = group_info
                 .get("groupId")
                 .and_then(|v| v.as_str())
                 .unwrap_or("unknown")
                 .to_string();
+            let name = group_info
+                .get("groupName")
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string());
+            let editor_name = envelope
+                .get("sourceName")
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string());
             let timestamp_ms = data.get("timestamp").and_then(|v| v.as_i64()).unwrap_or(0);
             let timestamp = DateTime::from_timestamp_millis(timestamp_ms).unwrap_or_default();
-            return Some(SignalEvent::SystemMessage {
-                conv_id,
-                body: "Group updated".to_string(),
+            return Some(SignalEvent::GroupUpdated {
+                group_id,
+                editor: envelope_source(envelope),
+                editor_name,
+                name,
                 timestamp,
                 timestamp_ms,
             });
@@ -423,6 +463,10 @@ fn parse_reaction(
         .get("isRemove")
         .and_then(|v| v.as_bool())
         .unwrap_or(false);
+    let timestamp_ms = envelope
+        .get("timestamp")
+        .and_then(|v| v.as_i64())
+        .unwrap_or(0);
 
     let sender = envelope_source(envelope);
     let sender_name = envelope
@@ -443,6 +487,7 @@ fn parse_reaction(
         target_author,
         target_timestamp,
         is_remove,
+        timestamp_ms,
     })
 }
 
@@ -465,6 +510,11 @@ pub(super) fn parse_reaction_sync(
         .unwrap_or(false);
 
     let sender = envelope_source(envelope);
+    let timestamp_ms = sent
+        .get("timestamp")
+        .or_else(|| envelope.get("timestamp"))
+        .and_then(|v| v.as_i64())
+        .unwrap_or(0);
 
     let group_id = sent
         .get("groupInfo")
@@ -489,6 +539,7 @@ pub(super) fn parse_reaction_sync(
         target_author,
         target_timestamp,
         is_remove,
+        timestamp_ms,
     })
 }
 
@@ -4,27 +4,42 @@
 //! - [`envelope`] -- top-level dispatch, typing/receipt/read-sync routing
 //! - [`message`] -- data messages, sent-sync, reactions, edits
 //! - [`poll`] -- poll create / vote / terminate
+//! - [`story`] -- text and media stories
+//! - [`call`] -- call offers, answers, busy and hangup signalling
 //! - [`rpc`] -- correlated RPC response shape converters
 //! - [`helpers`] -- attachments, mentions, styles, expiration formatting
 //!
 //! Two parsers are public to the [`super::client`] module:
 //! [`parse_rpc_result`] handles correlated RPC responses, and
-//! [`parse_signal_event`] handles unsolicited notifications. The rest are
-//! private to the submodule tree.
+//! [`parse_signal_event`] handles unsolicited notifications. The sticker
+//! converters ([`parse_sticker_packs`], [`parse_image_data`]) and the
+//! username converters ([`parse_user_status`], [`parse_account_username`])
+//! and [`parse_devices`] serve the client's awaited calls,
+//! [`parse_identity_failures`] and [`parse_rate_limit_failure`] pick
+//! untrusted recipients and rate-limit challenges out of send results,
+//! and [`format_timer_duration`] is shared with the group change
+//! descriptions. The rest are private to the
+//! submodule tree.
 //!
 //! The parsers deliberately defend against signal-cli's quirky, version-
 //! dependent JSON shape: many of them fall through multiple field names
 //! (e.g. `profileName` -> `contactName` -> `name`) or accept both object
 //! and bare-string member representations.
 
+pub(super) mod call;
 pub(super) mod envelope;
 pub(super) mod helpers;
 pub(super) mod message;
 pub(super) mod poll;
 pub(super) mod rpc;
+pub(super) mod story;
 
 pub use envelope::parse_signal_event;
-pub use rpc::parse_rpc_result;
+pub use helpers::format_timer_duration;
+pub use rpc::{
+    parse_account_username, parse_devices, parse_identity_failures, parse_image_data,
+    parse_rate_limit_failure, parse_rpc_result, parse_sticker_packs, parse_user_status,
+};
 
 #[cfg(test)]
 mod tests {
@@ -154,6 +169,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_list_groups_details() {
+        let result = json!([
+            {
+                "id": "group1",
+                "name": "Family",
+                "description": "Weekend plans",
+                "messageExpirationTime": 86400,
+                "permissionAddMember": "ONLY_ADMINS",
+                "permissionEditDetails": "EVERY_MEMBER",
+                "permissionSendMessage": "ONLY_ADMINS",
+                "groupInviteLink": "https://signal.group/#CjQKIA",
+                "members": [{"number": "+1", "uuid": "uuid-1"}, {"number": "+2"}],
+                "admins": [{"number": "+1", "uuid": "uuid-1"}]
+            },
+            {"id": "group2", "name": "Work", "description": ""}
+        ]);
+        match parse_rpc_result("listGroups", &result, None).unwrap() {
+            SignalEvent::GroupList(groups) => {
+                assert_eq!(groups[0].description.as_deref(), Some("Weekend plans"));
+                assert_eq!(groups[0].expiration_seconds, 86400);
+                assert_eq!(groups[0].admins, vec!["+1"]);
+                assert_eq!(groups[0].permission_add_member, GroupPermission::OnlyAdmins);
+                assert_eq!(
+                    groups[0].permission_edit_details,
+                    GroupPermission::EveryMember
+                );
+                assert_eq!(
+                    groups[0].permission_send_message,
+                    GroupPermission::OnlyAdmins
+                );
+                assert_eq!(
+                    groups[0].invite_link.as_deref(),
+                    Some("https://signal.group/#CjQKIA")
+                );
+                assert!(groups[0].allows("+1", GroupPolicy::SendMessages));
+                assert!(!groups[0].allows("+2", GroupPolicy::SendMessages));
+                assert!(groups[0].allows("+2", GroupPolicy::EditDetails));
+                assert_eq!(groups[1].invite_link, None);
+                assert_eq!(groups[1].description, None);
+                assert!(groups[1].admins.is_empty());
+            }
+            other => panic!("Expected GroupList, got {other:?}"),
+        }
+    }
+
     #[test]
     fn parse_list_groups_skips_no_id() {
         let result = json!([
@@ -203,6 +264,155 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_sticker_packs_basic() {
+        let result = json!([
+            {
+                "packId": "9acc9e8aba563d26a4994e69263e3b25",
+                "url": "https://signal.art/addstickers/#pack_id=9acc9e8aba563d26a4994e69263e3b25&pack_key=00",
+                "installed": true,
+                "title": "Bandit",
+                "author": "Agnes Lee",
+                "stickers": [
+                    {"id": 0, "emoji": "\u{1F44B}", "contentType": "image/webp"},
+                    {"id": 1, "emoji": "", "contentType": "image/webp"}
+                ]
+            },
+            {"title": "no id"}
+        ]);
+        let packs = parse_sticker_packs(&result);
+        assert_eq!(packs.len(), 1);
+        assert_eq!(packs[0].title, "Bandit");
+        assert!(packs[0].installed);
+        assert_eq!(
+            packs[0].stickers,
+            vec![
+                StickerInfo {
+                    id: 0,
+                    emoji: Some("\u{1F44B}".to_string())
+                },
+                StickerInfo { id: 1, emoji: None },
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case(json!({"data": "UklGRg=="}))]
+    #[case(json!("UklGRg=="))]
+    fn parse_image_data_decodes_base64(#[case] result: serde_json::Value) {
+        assert_eq!(parse_image_data(&result).as_deref(), Some(&b"RIFF"[..]));
+    }
+
+    #[test]
+    fn parse_user_status_finds_registered_account() {
+        let result = json!([
+            {"recipient": "alice.42", "number": null, "uuid": null, "isRegistered": false},
+            {
+                "recipient": "alice.42",
+                "number": null,
+                "uuid": "a1b2c3d4-0000-4000-8000-000000000001",
+                "username": "alice.42",
+                "isRegistered": true
+            }
+        ]);
+        let resolved = parse_user_status("alice.42", &result).unwrap();
+        assert_eq!(resolved.aci, "a1b2c3d4-0000-4000-8000-000000000001");
+        assert_eq!(resolved.username, "alice.42");
+        assert_eq!(resolved.number, None);
+        assert_eq!(
+            parse_user_status("bob.01", &json!([{"isRegistered": false}])),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_account_username_reads_link() {
+        let result = json!({
+            "username": "me.07",
+            "usernameLink": "https://signal.me/#eu/abc"
+        });
+        assert_eq!(
+            parse_account_username(&result),
+            AccountUsername {
+                username: Some("me.07".to_string()),
+                link: Some("https://signal.me/#eu/abc".to_string()),
+            }
+        );
+        assert_eq!(
+            parse_account_username(&json!({})),
+            AccountUsername::default()
+        );
+    }
+
+    #[test]
+    fn parse_devices_sorts_by_id() {
+        let result = json!([
+            {"id": 3, "name": "siggy", "createdTimestamp": 3000, "lastSeenTimestamp": 4000},
+            {"id": 1, "name": null, "createdTimestamp": 1000, "lastSeenTimestamp": 5000,
+             "isThisDevice": true}
+        ]);
+        let devices = parse_devices(&result);
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].id, 1);
+        assert!(devices[0].is_primary());
+        assert!(devices[0].is_this_device);
+        assert_eq!(devices[0].name, None);
+        assert_eq!(devices[1].name.as_deref(), Some("siggy"));
+        assert_eq!(devices[1].last_seen_ms, 4000);
+        assert!(!devices[1].is_this_device);
+        assert!(parse_devices(&json!({})).is_empty());
+    }
+
+    #[test]
+    fn parse_identity_failures_from_results_and_error_data() {
+        let result = json!({
+            "timestamp": 1000,
+            "results": [
+                {"recipientAddress": {"uuid": "u1", "number": "+1"}, "type": "SUCCESS"},
+                {"recipientAddress": {"uuid": "u2", "number": "+2"}, "type": "IDENTITY_FAILURE"},
+                {"recipientAddress": {"uuid": "u3"}, "type": "IDENTITY_FAILURE"}
+            ]
+        });
+        assert_eq!(parse_identity_failures(&result), vec!["+2", "u3"]);
+        let data = json!({"response": {"timestamp": 1000, "results": [
+            {"recipientAddress": {"number": "+4"}, "type": "IDENTITY_FAILURE"}
+        ]}});
+        assert_eq!(parse_identity_failures(&data), vec!["+4"]);
+        assert!(parse_identity_failures(&json!({"timestamp": 1000})).is_empty());
+    }
+
+    #[test]
+    fn parse_rate_limit_failure_reads_challenge_and_delay() {
+        let data = json!({"response": {"timestamp": 1000, "results": [
+            {"recipientAddress": {"number": "+1"}, "type": "SUCCESS"},
+            {"recipientAddress": {"number": "+2"}, "type": "RATE_LIMIT_FAILURE", "retryAfterSeconds": 60},
+            {"recipientAddress": {"number": "+3"}, "type": "PROOF_REQUIRED_FAILURE",
+             "token": "tok-1", "retryAfterSeconds": 3600}
+        ]}});
+        assert_eq!(
+            parse_rate_limit_failure(&data),
+            Some(RateLimitFailure {
+                challenge: Some("tok-1".to_string()),
+                retry_after_seconds: Some(3600),
+                recipients: vec!["+2".to_string(), "+3".to_string()],
+            })
+        );
+        let plain = json!({"results": [
+            {"recipientAddress": {"number": "+2"}, "type": "RATE_LIMIT_FAILURE"}
+        ]});
+        assert_eq!(
+            parse_rate_limit_failure(&plain),
+            Some(RateLimitFailure {
+                recipients: vec!["+2".to_string()],
+                ..RateLimitFailure::default()
+            })
+        );
+        let data = json!({"response": {"results": [
+            {"recipientAddress": {"number": "+4"}, "type": "IDENTITY_FAILURE"}
+        ]}});
+        assert_eq!(parse_rate_limit_failure(&data), None);
+    }
+
     #[test]
     fn parse_send_result_extracts_timestamp() {
         let result = json!({"timestamp": 1700000000123_i64});
@@ -255,8 +465,10 @@ mod tests {
                 sender,
                 receipt_type,
                 timestamps,
+                when_ms,
             } => {
                 assert_eq!(sender, "+15551234567");
+                assert_eq!(when_ms, 1700000000000);
                 assert_eq!(receipt_type, expected_type);
                 assert_eq!(timestamps.len(), expected_count);
                 assert_eq!(timestamps[0], 1700000000001);
@@ -303,9 +515,11 @@ mod tests {
                 target_author,
                 target_timestamp,
                 is_remove,
+                timestamp_ms,
             } => {
                 assert_eq!(conv_id, "+15551234567");
                 assert_eq!(emoji, "👍");
+                assert_eq!(timestamp_ms, 1700000000000);
                 assert_eq!(sender, "+15551234567");
                 assert_eq!(sender_name.as_deref(), Some("Alice"));
                 assert_eq!(target_author, "+15559876543");
@@ -591,53 +805,64 @@ mod tests {
     // --- System message tests ---
 
     #[rstest]
-    #[case("AUDIO_CALL", "Missed voice call")]
-    #[case("VIDEO_CALL", "Missed video call")]
-    fn parse_call_message(#[case] call_type: &str, #[case] expected_body: &str) {
+    #[case(json!({"offerMessage": {"type": "AUDIO_CALL", "id": 12345}}), CallSignalKind::Offer { video: false })]
+    #[case(json!({"offerMessage": {"type": "VIDEO_CALL", "id": 12345}}), CallSignalKind::Offer { video: true })]
+    #[case(json!({"answerMessage": {"id": 12345}}), CallSignalKind::Answer)]
+    #[case(json!({"busyMessage": {"id": 12345}}), CallSignalKind::Busy)]
+    #[case(
+        json!({"hangupMessage": {"id": 12345, "type": "DECLINED"}}),
+        CallSignalKind::Hangup { hangup_type: "DECLINED".to_string() }
+    )]
+    fn parse_call_message(#[case] call: serde_json::Value, #[case] expected: CallSignalKind) {
         let resp = make_resp(json!({
             "envelope": {
                 "sourceNumber": "+15551234567",
                 "sourceName": "Alice",
                 "timestamp": 1700000000000_i64,
-                "callMessage": {
-                    "offerMessage": {
-                        "type": call_type,
-                        "id": 12345
-                    }
-                }
+                "callMessage": call
             }
         }));
         let event = parse_signal_event(&resp, std::path::Path::new("/tmp")).unwrap();
         match event {
-            SignalEvent::SystemMessage { conv_id, body, .. } => {
-                assert_eq!(conv_id, "+15551234567");
-                assert_eq!(body, expected_body);
+            SignalEvent::CallSignal(call) => {
+                assert_eq!(call.peer, "+15551234567");
+                assert_eq!(call.peer_name.as_deref(), Some("Alice"));
+                assert_eq!(call.call_id, 12345);
+                assert_eq!(call.timestamp_ms, 1700000000000);
+                assert_eq!(call.kind, expected);
             }
-            _ => panic!("Expected SystemMessage, got {:?}", event),
+            _ => panic!("Expected CallSignal, got {:?}", event),
         }
     }
 
     #[test]
-    fn parse_call_message_ignores_hangup() {
-        let resp = JsonRpcResponse {
-            jsonrpc: "2.0".to_string(),
-            id: None,
-            result: None,
-            error: None,
-            method: Some("receive".to_string()),
-            params: Some(json!({
-                "envelope": {
-                    "sourceNumber": "+15551234567",
-                    "timestamp": 1700000000000_i64,
-                    "callMessage": {
-                        "hangupMessage": {
-                            "id": 12345,
-                            "type": "NORMAL"
-                        }
-                    }
+    fn parse_call_message_unsigned_id() {
+        let resp = make_resp(json!({
+            "envelope": {
+                "sourceNumber": "+15551234567",
+                "timestamp": 1700000000000_i64,
+                "callMessage": {
+                    "hangupMessage": {"id": 18446744073709551615_u64, "type": "NORMAL"}
                 }
-            })),
-        };
+            }
+        }));
+        match parse_signal_event(&resp, std::path::Path::new("/tmp")) {
+            Some(SignalEvent::CallSignal(call)) => assert_eq!(call.call_id, -1),
+            other => panic!("Expected CallSignal, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_call_message_ignores_ice_updates() {
+        let resp = make_resp(json!({
+            "envelope": {
+                "sourceNumber": "+15551234567",
+                "timestamp": 1700000000000_i64,
+                "callMessage": {
+                    "iceUpdateMessages": [{"id": 12345, "opaque": "AAAA"}]
+                }
+            }
+        }));
         assert!(parse_signal_event(&resp, std::path::Path::new("/tmp")).is_none());
     }
 
@@ -662,9 +887,15 @@ mod tests {
         };
         let event = parse_signal_event(&resp, std::path::Path::new("/tmp")).unwrap();
         match event {
-            SignalEvent::SystemMessage { conv_id, body, .. } => {
+            SignalEvent::SystemMessage {
+                conv_id,
+                body,
+                is_warning,
+                ..
+            } => {
                 assert_eq!(conv_id, "+15551234567");
                 assert!(body.contains("Safety number changed"));
+                assert!(is_warning);
             }
             _ => panic!("Expected SystemMessage, got {:?}", event),
         }
@@ -672,33 +903,39 @@ mod tests {
 
     #[test]
     fn parse_group_update() {
-        let resp = JsonRpcResponse {
-            jsonrpc: "2.0".to_string(),
-            id: None,
-            result: None,
-            error: None,
-            method: Some("receive".to_string()),
-            params: Some(json!({
-                "envelope": {
-                    "sourceNumber": "+15551234567",
+        let resp = make_resp(json!({
+            "envelope": {
+                "sourceNumber": "+15551234567",
+                "sourceName": "Alice",
+                "timestamp": 1700000000000_i64,
+                "dataMessage": {
                     "timestamp": 1700000000000_i64,
-                    "dataMessage": {
-                        "timestamp": 1700000000000_i64,
-                        "groupInfo": {
-                            "groupId": "group123",
-                            "type": "UPDATE"
-                        }
+                    "groupInfo": {
+                        "groupId": "group123",
+                        "groupName": "Weekend",
+                        "revision": 4,
+                        "type": "UPDATE"
                     }
                 }
-            })),
-        };
+            }
+        }));
         let event = parse_signal_event(&resp, std::path::Path::new("/tmp")).unwrap();
         match event {
-            SignalEvent::SystemMessage { conv_id, body, .. } => {
-                assert_eq!(conv_id, "group123");
-                assert_eq!(body, "Group updated");
+            SignalEvent::GroupUpdated {
+                group_id,
+                editor,
+                editor_name,
+                name,
+                timestamp_ms,
+                ..
+            } => {
+                assert_eq!(group_id, "group123");
+                assert_eq!(editor, "+15551234567");
+                assert_eq!(editor_name.as_deref(), Some("Alice"));
+                assert_eq!(name.as_deref(), Some("Weekend"));
+                assert_eq!(timestamp_ms, 1700000000000);
             }
-            _ => panic!("Expected SystemMessage, got {:?}", event),
+            _ => panic!("Expected GroupUpdated, got {:?}", event),
         }
     }
 
@@ -847,11 +1084,97 @@ mod tests {
                 if is_sync {
                     assert_eq!(msg.destination.as_deref(), Some("+15559876543"));
                 }
+                let sticker = msg.sticker.expect("sticker ref");
+                assert_eq!(sticker.pack_id, "abc123");
+                assert_eq!(sticker.sticker_id, 5);
+                assert_eq!(sticker.emoji.as_deref(), emoji);
             }
             _ => panic!("Expected MessageReceived, got {:?}", event),
         }
     }
 
+    #[test]
+    fn parse_sticker_uses_cached_image() {
+        let dir = tempfile::tempdir().unwrap();
+        let cached = dir.path().join("stickers").join("abc123").join("5.webp");
+        std::fs::create_dir_all(cached.parent().unwrap()).unwrap();
+        std::fs::write(&cached, b"RIFF").unwrap();
+        let resp = make_resp(json!({
+            "envelope": {
+                "sourceNumber": "+15551234567",
+                "timestamp": 1700000000000_i64,
+                "dataMessage": {
+                    "timestamp": 1700000000000_i64,
+                    "sticker": { "packId": "abc123", "stickerId": 5 }
+                }
+            }
+        }));
+        match parse_signal_event(&resp, dir.path()) {
+            Some(SignalEvent::MessageReceived(msg)) => {
+                let sticker = msg.sticker.expect("sticker ref");
+                assert_eq!(
+                    sticker.local_path.as_deref(),
+                    Some(cached.to_string_lossy().as_ref())
+                );
+            }
+            other => panic!("Expected MessageReceived, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_shared_contact_card() {
+        let resp = make_resp(json!({
+            "envelope": {
+                "sourceNumber": "+15551234567",
+                "timestamp": 1700000000000_i64,
+                "dataMessage": {
+                    "timestamp": 1700000000000_i64,
+                    "sharedContacts": [{
+                        "name": { "given": "Dana", "family": "Scully" },
+                        "organization": "FBI",
+                        "phone": [
+                            { "value": "+15550001111", "type": "MOBILE" },
+                            { "value": "+15550002222", "type": "CUSTOM", "label": "Desk" }
+                        ],
+                        "email": [{ "value": "dana@example.com", "type": "WORK" }]
+                    }]
+                }
+            }
+        }));
+        match parse_signal_event(&resp, std::path::Path::new("/tmp")) {
+            Some(SignalEvent::MessageReceived(msg)) => {
+                assert_eq!(msg.body.as_deref(), Some("[Contact: Dana Scully]"));
+                let card = &msg.shared_contacts[0];
+                assert_eq!(card.organization.as_deref(), Some("FBI"));
+                assert_eq!(card.phones[0].label.as_deref(), Some("Mobile"));
+                assert_eq!(card.phones[1].label.as_deref(), Some("Desk"));
+                assert_eq!(card.emails[0].value, "dana@example.com");
+                assert_eq!(card.emails[0].label.as_deref(), Some("Work"));
+            }
+            other => panic!("Expected MessageReceived, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn sticker_cache_path_rejects_non_hex_pack() {
+        let sticker = StickerRef {
+            pack_id: "../etc".to_string(),
+            sticker_id: 1,
+            emoji: None,
+            local_path: None,
+        };
+        assert!(sticker.cache_path(std::path::Path::new("/tmp")).is_none());
+    }
+
+    #[rstest]
+    #[case(AvatarRef::Contact("+15551234567".to_string()), "contact--15551234567")]
+    #[case(AvatarRef::Group("ab/c+d==".to_string()), "group-ab_c-d==")]
+    #[case(AvatarRef::Group("../x".to_string()), "group-___x")]
+    fn avatar_cache_path_stays_in_avatars_dir(#[case] avatar: AvatarRef, #[case] name: &str) {
+        let path = avatar.cache_path(std::path::Path::new("/tmp"));
+        assert_eq!(path, std::path::Path::new("/tmp/avatars").join(name));
+    }
+
     // --- View-once message tests ---
 
     #[rstest]
@@ -915,9 +1238,7 @@ mod tests {
         match event {
             SignalEvent::MessageReceived(msg) => {
                 assert_eq!(msg.body.as_deref(), Some(expected_body));
-                if view_once {
-                    assert!(msg.attachments.is_empty());
-                }
+                assert_eq!(msg.view_once, view_once);
                 if is_sync {
                     assert!(msg.is_outgoing);
                 }
@@ -926,8 +1247,142 @@ mod tests {
         }
     }
 
+    #[test]
+    fn view_once_attachment_is_moved_not_copied() {
+        let src_dir = tempfile::tempdir().unwrap();
+        let download_dir = tempfile::tempdir().unwrap();
+        let src = src_dir.path().join("abcdef12");
+        std::fs::write(&src, b"secret").unwrap();
+        let resp = make_resp(json!({
+            "envelope": {
+                "sourceNumber": "+15551234567",
+                "timestamp": 1700000000000_i64,
+                "dataMessage": {
+                    "timestamp": 1700000000000_i64,
+                    "viewOnce": true,
+                    "attachments": [{
+                        "id": "abcdef12",
+                        "contentType": "image/jpeg",
+                        "filename": "photo.jpg",
+                        "file": src.to_string_lossy()
+                    }]
+                }
+            }
+        }));
+        let event = parse_signal_event(&resp, download_dir.path()).unwrap();
+        let SignalEvent::MessageReceived(msg) = event else {
+            panic!("Expected MessageReceived, got {event:?}");
+        };
+        assert!(msg.view_once);
+        let dest = download_dir.path().join("photo.jpg");
+        assert_eq!(
+            msg.attachments[0].local_path.as_deref(),
+            Some(dest.to_string_lossy().as_ref())
+        );
+        assert!(dest.exists());
+        assert!(!src.exists());
+    }
+
+    #[test]
+    fn attachment_size_and_dimensions_are_parsed() {
+        let resp = make_resp(json!({
+            "envelope": {
+                "sourceNumber": "+15551234567",
+                "timestamp": 1700000000000_i64,
+                "dataMessage": {
+                    "timestamp": 1700000000000_i64,
+                    "attachments": [{
+                        "id": "abcdef12",
+                        "contentType": "image/png",
+                        "filename": "shot.png",
+                        "size": 48213,
+                        "width": 1280,
+                        "height": 0
+                    }]
+                }
+            }
+        }));
+        let event = parse_signal_event(&resp, std::path::Path::new("/tmp")).unwrap();
+        let SignalEvent::MessageReceived(msg) = event else {
+            panic!("Expected MessageReceived, got {event:?}");
+        };
+        let att = &msg.attachments[0];
+        assert_eq!(att.size, Some(48213));
+        assert_eq!(att.width, Some(1280));
+        assert_eq!(att.height, None);
+    }
+
+    #[rstest]
+    #[case(json!({"senderNumber": "+15551234567", "timestamp": 1700000000001_i64}), Some("+15551234567"))]
+    #[case(json!({"senderUuid": "uuid-1", "timestamp": 1700000000001_i64}), Some("uuid-1"))]
+    #[case(json!({"timestamp": 1700000000001_i64}), None)]
+    fn parse_view_once_open_sync(
+        #[case] open: serde_json::Value,
+        #[case] expected_sender: Option<&str>,
+    ) {
+        let resp = make_resp(json!({
+            "envelope": {
+                "sourceNumber": "+10000000000",
+                "timestamp": 1700000000000_i64,
+                "syncMessage": { "viewOnceOpen": open }
+            }
+        }));
+        let event = parse_signal_event(&resp, std::path::Path::new("/tmp"));
+        match (event, expected_sender) {
+            (Some(SignalEvent::ViewOnceOpened { sender, timestamp }), Some(expected)) => {
+                assert_eq!(sender, expected);
+                assert_eq!(timestamp, 1700000000001);
+            }
+            (None, None) => {}
+            (event, _) => panic!("unexpected {event:?}"),
+        }
+    }
+
     // --- Text style parsing tests ---
 
+    #[test]
+    fn quote_styles_skip_stripped_mention_placeholders() {
+        let resp = JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            id: None,
+            result: None,
+            error: None,
+            method: Some("receive".to_string()),
+            params: Some(json!({
+                "envelope": {
+                    "sourceNumber": "+15551234567",
+                    "sourceName": "Alice",
+                    "timestamp": 1700000000000_i64,
+                    "dataMessage": {
+                        "timestamp": 1700000000000_i64,
+                        "message": "no way",
+                        "quote": {
+                            "id": 1699999990000_i64,
+                            "authorNumber": "+15559876543",
+                            "text": "\u{FFFC} the butler did it",
+                            "textStyles": [
+                                {"start": 6, "length": 6, "style": "SPOILER"}
+                            ]
+                        }
+                    }
+                }
+            })),
+        };
+        let event = parse_signal_event(&resp, std::path::Path::new("/tmp")).unwrap();
+        match event {
+            SignalEvent::MessageReceived(msg) => {
+                let (ts, author, body, styles) = msg.quote.expect("quote parsed");
+                assert_eq!(ts, 1699999990000);
+                assert_eq!(author, "+15559876543");
+                assert_eq!(body, " the butler did it");
+                assert_eq!(styles.len(), 1);
+                assert_eq!((styles[0].start, styles[0].length), (5, 6));
+                assert_eq!(styles[0].style, StyleType::Spoiler);
+            }
+            _ => panic!("Expected MessageReceived, got {:?}", event),
+        }
+    }
+
     #[test]
     fn parse_text_styles_basic() {
         let resp = JsonRpcResponse {
@@ -1270,4 +1725,68 @@ mod tests {
             _ => panic!("Expected TypingIndicator"),
         }
     }
+
+    // --- Story parsing ---
+
+    #[test]
+    fn parse_text_story() {
+        let params = json!({
+            "envelope": {
+                "sourceNumber": "+15551234567",
+                "sourceName": "Alice",
+                "timestamp": 1700000000000_i64,
+                "storyMessage": {
+                    "allowsReplies": false,
+                    "textAttachment": {"text": "Sunset", "style": "DEFAULT"}
+                }
+            }
+        });
+        let event = parse_signal_event(&make_resp(params), std::path::Path::new("/tmp")).unwrap();
+        match event {
+            SignalEvent::StoryReceived(story) => {
+                assert_eq!(story.author, "+15551234567");
+                assert_eq!(story.author_name.as_deref(), Some("Alice"));
+                assert_eq!(story.timestamp_ms, 1700000000000);
+                assert_eq!(story.text.as_deref(), Some("Sunset"));
+                assert!(story.attachment.is_none());
+                assert!(!story.allows_replies);
+            }
+            other => panic!("Expected StoryReceived, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_image_story() {
+        let params = json!({
+            "envelope": {
+                "sourceNumber": "+15551234567",
+                "timestamp": 1700000000000_i64,
+                "storyMessage": {
+                    "fileAttachment": {"id": "abcdef123456", "contentType": "image/jpeg"}
+                }
+            }
+        });
+        let event = parse_signal_event(&make_resp(params), std::path::Path::new("/tmp")).unwrap();
+        match event {
+            SignalEvent::StoryReceived(story) => {
+                let att = story.attachment.expect("attachment");
+                assert_eq!(att.content_type, "image/jpeg");
+                assert!(story.text.is_none());
+                assert!(story.allows_replies);
+            }
+            other => panic!("Expected StoryReceived, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_empty_story_is_dropped() {
+        let params = json!({
+            "envelope": {
+                "sourceNumber": "+15551234567",
+                "timestamp": 1700000000000_i64,
+                "storyMessage": {"allowsReplies": true}
+            }
+        });
+        assert!(parse_signal_event(&make_resp(params), std::path::Path::new("/tmp")).is_none());
+    }
 }
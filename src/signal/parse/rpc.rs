@@ -1,6 +1,8 @@
 //! RPC response parser: handles correlated responses for methods we sent
 //! (listContacts, listGroups, listIdentities, etc) and dispatches to the
-//! per-method shape converter.
+//! per-method shape converter. Results that callers await directly rather
+//! than receive as events (sticker packs, sticker data, username lookups,
+//! the device list) have their own converters.
 
 use crate::signal::types::*;
 
@@ -60,29 +62,37 @@ pub fn parse_rpc_result(
                         .and_then(|v| v.as_str())
                         .unwrap_or("")
                         .to_string();
-                    let mut members = Vec::new();
-                    let mut member_uuids = Vec::new();
-                    if let Some(arr) = obj.get("members").and_then(|v| v.as_array()) {
-                        for m in arr {
-                            // signal-cli returns members as objects: {"number": "+1...", "uuid": "..."}
-                            // Fall back to plain string for compatibility
-                            let phone = m
-                                .get("number")
-                                .and_then(|v| v.as_str())
-                                .or_else(|| m.as_str());
-                            if let Some(phone) = phone {
-                                members.push(phone.to_string());
-                                if let Some(uuid) = m.get("uuid").and_then(|v| v.as_str()) {
-                                    member_uuids.push((phone.to_string(), uuid.to_string()));
-                                }
-                            }
-                        }
-                    }
+                    let (members, member_uuids) = parse_member_list(obj.get("members"));
+                    let (admins, _) = parse_member_list(obj.get("admins"));
+                    let description = obj
+                        .get("description")
+                        .and_then(|v| v.as_str())
+                        .filter(|s| !s.is_empty())
+                        .map(|s| s.to_string());
+                    let expiration_seconds = obj
+                        .get("messageExpirationTime")
+                        .and_then(|v| v.as_i64())
+                        .unwrap_or(0);
+                    let permission = |key: &str| {
+                        GroupPermission::parse(obj.get(key).and_then(|v| v.as_str()).unwrap_or(""))
+                    };
+                    let invite_link = obj
+                        .get("groupInviteLink")
+                        .and_then(|v| v.as_str())
+                        .filter(|s| !s.is_empty())
+                        .map(|s| s.to_string());
                     Some(Group {
                         id: id.to_string(),
                         name,
                         members,
                         member_uuids,
+                        description,
+                        admins,
+                        expiration_seconds,
+                        permission_add_member: permission("permissionAddMember"),
+                        permission_edit_details: permission("permissionEditDetails"),
+                        permission_send_message: permission("permissionSendMessage"),
+                        invite_link,
                     })
                 })
                 .collect();
@@ -151,6 +161,7 @@ pub fn parse_rpc_result(
         | "updateContact"
         | "updateGroup"
         | "quitGroup"
+        | "joinGroup"
         | "sendMessageRequestResponse"
         | "block"
         | "unblock"
@@ -162,3 +173,220 @@ pub fn parse_rpc_result(
         _ => None,
     }
 }
+
+/// Phone numbers and (phone, uuid) pairs from a `listGroups` member array.
+/// signal-cli returns members as objects: {"number": "+1...", "uuid": "..."};
+/// plain strings are accepted for compatibility.
+fn parse_member_list(value: Option<&serde_json::Value>) -> (Vec<String>, Vec<(String, String)>) {
+    let mut phones = Vec::new();
+    let mut uuids = Vec::new();
+    for m in value.and_then(|v| v.as_array()).into_iter().flatten() {
+        let phone = m
+            .get("number")
+            .and_then(|v| v.as_str())
+            .or_else(|| m.as_str());
+        if let Some(phone) = phone {
+            phones.push(phone.to_string());
+            if let Some(uuid) = m.get("uuid").and_then(|v| v.as_str()) {
+                uuids.push((phone.to_string(), uuid.to_string()));
+            }
+        }
+    }
+    (phones, uuids)
+}
+
+/// Convert a `listStickerPacks` result into the packs signal-cli knows about.
+pub fn parse_sticker_packs(result: &serde_json::Value) -> Vec<StickerPack> {
+    let Some(arr) = result.as_array() else {
+        return Vec::new();
+    };
+    arr.iter()
+        .filter_map(|obj| {
+            let pack_id = obj.get("packId").and_then(|v| v.as_str())?.to_string();
+            let text = |key: &str| {
+                obj.get(key)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string()
+            };
+            let stickers = obj
+                .get("stickers")
+                .and_then(|v| v.as_array())
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|st| {
+                            let id = st.get("id").and_then(|v| v.as_u64())?;
+                            Some(StickerInfo {
+                                id: u32::try_from(id).ok()?,
+                                emoji: st
+                                    .get("emoji")
+                                    .and_then(|v| v.as_str())
+                                    .filter(|s| !s.is_empty())
+                                    .map(|s| s.to_string()),
+                            })
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            Some(StickerPack {
+                pack_id,
+                title: text("title"),
+                author: text("author"),
+                installed: obj
+                    .get("installed")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+                stickers,
+            })
+        })
+        .collect()
+}
+
+/// Decode a `getSticker` or `getAvatar` result: base64 image bytes, either
+/// bare or under `data` depending on the signal-cli version.
+pub fn parse_image_data(result: &serde_json::Value) -> Option<Vec<u8>> {
+    use base64::Engine;
+    let encoded = result
+        .get("data")
+        .and_then(|v| v.as_str())
+        .or_else(|| result.as_str())?;
+    base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .ok()
+}
+
+/// Find the registered account for `username` in a `getUserStatus` result.
+/// Accounts without an ACI can't be messaged and are skipped.
+pub fn parse_user_status(username: &str, result: &serde_json::Value) -> Option<ResolvedUsername> {
+    let text = |obj: &serde_json::Value, key: &str| {
+        obj.get(key)
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+    };
+    result.as_array()?.iter().find_map(|obj| {
+        let registered = obj
+            .get("isRegistered")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if !registered {
+            return None;
+        }
+        Some(ResolvedUsername {
+            username: text(obj, "username").unwrap_or_else(|| username.to_string()),
+            aci: text(obj, "uuid")?,
+            number: text(obj, "number"),
+        })
+    })
+}
+
+/// Read our username and its link from an `updateAccount` result.
+pub fn parse_account_username(result: &serde_json::Value) -> AccountUsername {
+    let text = |key: &str| {
+        result
+            .get(key)
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+    };
+    AccountUsername {
+        username: text("username"),
+        link: text("usernameLink"),
+    }
+}
+
+/// Read the account's devices from a `listDevices` result, in id order.
+pub fn parse_devices(result: &serde_json::Value) -> Vec<Device> {
+    let Some(arr) = result.as_array() else {
+        return Vec::new();
+    };
+    let mut devices: Vec<Device> = arr
+        .iter()
+        .filter_map(|obj| {
+            Some(Device {
+                id: obj.get("id")?.as_i64()?,
+                name: obj
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .filter(|s| !s.is_empty())
+                    .map(|s| s.to_string()),
+                created_ms: obj
+                    .get("createdTimestamp")
+                    .and_then(|v| v.as_i64())
+                    .unwrap_or(0),
+                last_seen_ms: obj
+                    .get("lastSeenTimestamp")
+                    .and_then(|v| v.as_i64())
+                    .unwrap_or(0),
+                is_this_device: obj
+                    .get("isThisDevice")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+            })
+        })
+        .collect();
+    devices.sort_by_key(|d| d.id);
+    devices
+}
+
+/// Recipients a send was refused for because their safety number changed.
+/// signal-cli lists per-recipient outcomes under `results`, both in a
+/// successful send (a group send where some members failed) and in the
+/// `data.response` of a send that failed outright.
+pub fn parse_identity_failures(value: &serde_json::Value) -> Vec<String> {
+    let results = value
+        .get("results")
+        .or_else(|| value.get("response").and_then(|r| r.get("results")))
+        .and_then(|v| v.as_array());
+    let Some(results) = results else {
+        return Vec::new();
+    };
+    results
+        .iter()
+        .filter(|r| r.get("type").and_then(|v| v.as_str()) == Some("IDENTITY_FAILURE"))
+        .filter_map(recipient_address)
+        .collect()
+}
+
+/// The number a per-recipient send result was for, or its ACI when
+/// signal-cli doesn't know the number.
+fn recipient_address(result: &serde_json::Value) -> Option<String> {
+    let address = result.get("recipientAddress")?;
+    address
+        .get("number")
+        .and_then(|v| v.as_str())
+        .or_else(|| address.get("uuid").and_then(|v| v.as_str()))
+        .map(|s| s.to_string())
+}
+
+/// Rate-limit and proof-required failures among the per-recipient `results`
+/// of a send, read the same way as [`parse_identity_failures`]. The first
+/// challenge token wins; the retry delay is the longest any recipient got.
+pub fn parse_rate_limit_failure(value: &serde_json::Value) -> Option<RateLimitFailure> {
+    let results = value
+        .get("results")
+        .or_else(|| value.get("response").and_then(|r| r.get("results")))
+        .and_then(|v| v.as_array())?;
+    let mut failure: Option<RateLimitFailure> = None;
+    for r in results {
+        if !matches!(
+            r.get("type").and_then(|v| v.as_str()),
+            Some("RATE_LIMIT_FAILURE" | "PROOF_REQUIRED_FAILURE")
+        ) {
+            continue;
+        }
+        let failure = failure.get_or_insert_with(RateLimitFailure::default);
+        failure.recipients.extend(recipient_address(r));
+        if failure.challenge.is_none() {
+            failure.challenge = r
+                .get("token")
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string());
+        }
+        if let Some(secs) = r.get("retryAfterSeconds").and_then(|v| v.as_i64()) {
+            failure.retry_after_seconds = Some(failure.retry_after_seconds.unwrap_or(0).max(secs));
+        }
+    }
+    failure
+}
@@ -0,0 +1,53 @@
+//! Story parser. signal-cli delivers stories as a top-level `storyMessage`
+//! on the envelope, carrying either a `textAttachment` (a text card) or a
+//! `fileAttachment` (image or video).
+
+use crate::signal::types::*;
+
+use super::envelope::envelope_source;
+use super::helpers::parse_attachment;
+
+pub(super) fn parse_story_message(
+    envelope: &serde_json::Value,
+    story: &serde_json::Value,
+    download_dir: &std::path::Path,
+) -> Option<SignalEvent> {
+    let text = story
+        .get("textAttachment")
+        .and_then(|t| t.get("text"))
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string());
+    let attachment = story
+        .get("fileAttachment")
+        .and_then(|a| parse_attachment(a, download_dir));
+    if text.is_none() && attachment.is_none() {
+        return None;
+    }
+    let author_name = envelope
+        .get("sourceName")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string());
+    let timestamp_ms = envelope
+        .get("timestamp")
+        .and_then(|v| v.as_i64())
+        .unwrap_or(0);
+    let group_id = story
+        .get("groupId")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    let allows_replies = story
+        .get("allowsReplies")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+    Some(SignalEvent::StoryReceived(StoryMessage {
+        author: envelope_source(envelope),
+        author_name,
+        timestamp_ms,
+        group_id,
+        text,
+        attachment,
+        allows_replies,
+    }))
+}
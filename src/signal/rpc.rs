@@ -0,0 +1,371 @@
+//! Typed JSON-RPC requests and their completion plumbing.
+//!
+//! Every request [`super::client::SignalClient`] writes is tagged with an
+//! [`RpcMethod`] and registered in a [`PendingMap`] alongside a `oneshot`
+//! sender. When the reader task sees the correlated response it either
+//! completes the [`RpcCall`] the caller is awaiting, or (if the caller
+//! dropped its handle) falls back to the fire-and-forget path and emits a
+//! [`SignalEvent`]. Each entry carries its own deadline: a timer task
+//! removes it on expiry and reports [`RpcError::Timeout`]. An unattended
+//! send is different: it may still go through, so its entry stays in the
+//! map and `SendTimedOut` is reported instead. When the connection closes,
+//! waiters are failed with [`RpcError::Disconnected`] straight away.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use tokio::sync::{mpsc, oneshot};
+
+use super::types::{JsonRpcError, SignalEvent};
+
+/// Deadline for fire-and-forget requests (replaces the old 60s TTL sweep).
+pub const RPC_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Deadline for unattended message sends. A large album or a big group
+/// can take minutes to upload and fan out.
+pub const SEND_RPC_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Deadline for requests the caller is awaiting. Shorter, since somebody
+/// is usually watching for the answer.
+pub const AWAITED_RPC_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Every JSON-RPC method siggy sends to signal-cli.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcMethod {
+    Send,
+    RemoteDelete,
+    SendPinMessage,
+    SendUnpinMessage,
+    ListGroups,
+    ListContacts,
+    ListIdentities,
+    Trust,
+    SendSyncRequest,
+    SendReaction,
+    SendTypingIndicator,
+    SendReceipt,
+    SendMessageRequestResponse,
+    UpdateContact,
+    RemoveContact,
+    UpdateGroup,
+    UpdateProfile,
+    Block,
+    Unblock,
+    QuitGroup,
+    JoinGroup,
+    SendPollCreate,
+    SendPollVote,
+    SendPollTerminate,
+    ListStickerPacks,
+    AddStickerPack,
+    GetSticker,
+    GetAvatar,
+    GetUserStatus,
+    UpdateAccount,
+    ListDevices,
+    RemoveDevice,
+    AddDevice,
+    SubmitRateLimitChallenge,
+}
+
+impl RpcMethod {
+    /// The method name on the wire.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Send => "send",
+            Self::RemoteDelete => "remoteDelete",
+            Self::SendPinMessage => "sendPinMessage",
+            Self::SendUnpinMessage => "sendUnpinMessage",
+            Self::ListGroups => "listGroups",
+            Self::ListContacts => "listContacts",
+            Self::ListIdentities => "listIdentities",
+            Self::Trust => "trust",
+            Self::SendSyncRequest => "sendSyncRequest",
+            Self::SendReaction => "sendReaction",
+            Self::SendTypingIndicator => "sendTypingIndicator",
+            Self::SendReceipt => "sendReceipt",
+            Self::SendMessageRequestResponse => "sendMessageRequestResponse",
+            Self::UpdateContact => "updateContact",
+            Self::RemoveContact => "removeContact",
+            Self::UpdateGroup => "updateGroup",
+            Self::UpdateProfile => "updateProfile",
+            Self::Block => "block",
+            Self::Unblock => "unblock",
+            Self::QuitGroup => "quitGroup",
+            Self::JoinGroup => "joinGroup",
+            Self::SendPollCreate => "sendPollCreate",
+            Self::SendPollVote => "sendPollVote",
+            Self::SendPollTerminate => "sendPollTerminate",
+            Self::ListStickerPacks => "listStickerPacks",
+            Self::AddStickerPack => "addStickerPack",
+            Self::GetSticker => "getSticker",
+            Self::GetAvatar => "getAvatar",
+            Self::GetUserStatus => "getUserStatus",
+            Self::UpdateAccount => "updateAccount",
+            Self::ListDevices => "listDevices",
+            Self::RemoveDevice => "removeDevice",
+            Self::AddDevice => "addDevice",
+            Self::SubmitRateLimitChallenge => "submitRateLimitChallenge",
+        }
+    }
+
+    /// Whether this request sends a message tracked in `PendingState::sends`,
+    /// so an error must be reported as `SendFailed` and a timeout as
+    /// `SendTimedOut`.
+    pub fn is_send(self) -> bool {
+        matches!(self, Self::Send | Self::SendPollCreate)
+    }
+}
+
+impl fmt::Display for RpcMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Why an awaited request did not produce a result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RpcError {
+    /// signal-cli answered with a JSON-RPC error object.
+    Rpc { code: i64, message: String },
+    /// No response before the deadline.
+    Timeout,
+    /// The connection closed (or the entry was dropped) before a response.
+    Disconnected,
+}
+
+impl From<&JsonRpcError> for RpcError {
+    fn from(err: &JsonRpcError) -> Self {
+        Self::Rpc {
+            code: err.code,
+            message: err.message.clone(),
+        }
+    }
+}
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rpc { message, .. } => f.write_str(message),
+            Self::Timeout => f.write_str("signal-cli did not respond in time"),
+            Self::Disconnected => f.write_str("signal-cli disconnected"),
+        }
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+/// What the reader hands back to an awaiting caller.
+pub type RpcOutcome = Result<serde_json::Value, RpcError>;
+
+/// One registered request awaiting its response.
+#[derive(Debug)]
+pub struct PendingRpc {
+    pub method: RpcMethod,
+    pub reply: oneshot::Sender<RpcOutcome>,
+}
+
+impl PendingRpc {
+    /// Whether somebody is still awaiting this request's [`RpcCall`].
+    pub fn has_waiter(&self) -> bool {
+        !self.reply.is_closed()
+    }
+}
+
+/// In-flight requests keyed by rpc id. Shared between the writer side
+/// (`send_rpc_impl`), the reader task, and the per-request timers.
+pub type PendingMap = Arc<Mutex<HashMap<String, PendingRpc>>>;
+
+/// Lock the pending map, recovering from poison: another task panicking
+/// while holding the lock must not strand every later request.
+pub fn lock_pending(pending: &PendingMap) -> MutexGuard<'_, HashMap<String, PendingRpc>> {
+    pending.lock().unwrap_or_else(|poisoned| {
+        crate::debug_log::log("pending_requests mutex poisoned, recovering");
+        poisoned.into_inner()
+    })
+}
+
+/// Handle to an in-flight request. Await [`RpcCall::response`] for the
+/// typed outcome, or drop it to let the response flow through the event
+/// stream as before.
+#[derive(Debug)]
+pub struct RpcCall {
+    pub id: String,
+    rx: oneshot::Receiver<RpcOutcome>,
+}
+
+impl RpcCall {
+    pub(super) fn new(id: String, rx: oneshot::Receiver<RpcOutcome>) -> Self {
+        Self { id, rx }
+    }
+
+    /// Wait for signal-cli's answer. Resolves with [`RpcError::Timeout`]
+    /// once the request's deadline passes.
+    pub async fn response(self) -> RpcOutcome {
+        self.rx.await.unwrap_or(Err(RpcError::Disconnected))
+    }
+}
+
+/// Fail every request somebody is awaiting with [`RpcError::Disconnected`].
+/// Called by the reader once the connection is gone, so callers don't sit
+/// out their deadline. Unattended entries are left to their timers.
+pub fn fail_waiters(pending: &PendingMap) {
+    let waiting: Vec<_> = lock_pending(pending)
+        .extract_if(|_, entry| entry.has_waiter())
+        .collect();
+    for (id, entry) in waiting {
+        crate::debug_log::logf(format_args!(
+            "rpc disconnected: method={} id={id}",
+            entry.method
+        ));
+        let _ = entry.reply.send(Err(RpcError::Disconnected));
+    }
+}
+
+/// Act on `id` after `timeout` if signal-cli hasn't answered it. A waiter
+/// gets [`RpcError::Timeout`]. An unattended send is reported as
+/// `SendTimedOut` but stays in `pending`: it may still have gone out, and
+/// a late answer must still confirm it rather than be dropped.
+pub fn spawn_deadline(
+    pending: PendingMap,
+    events: mpsc::WeakSender<SignalEvent>,
+    id: String,
+    timeout: Duration,
+) {
+    tokio::spawn(async move {
+        tokio::time::sleep(timeout).await;
+        let entry = {
+            let mut map = lock_pending(&pending);
+            match map.get(&id) {
+                None => return,
+                Some(entry) if entry.method.is_send() && !entry.has_waiter() => {
+                    crate::debug_log::logf(format_args!(
+                        "rpc timeout: method={} id={id} (kept for a late answer)",
+                        entry.method
+                    ));
+                    None
+                }
+                Some(_) => map.remove(&id),
+            }
+        };
+        match entry {
+            Some(entry) => {
+                crate::debug_log::logf(format_args!(
+                    "rpc timeout: method={} id={id}",
+                    entry.method
+                ));
+                let _ = entry.reply.send(Err(RpcError::Timeout));
+            }
+            None => {
+                if let Some(tx) = events.upgrade() {
+                    let _ = tx.send(SignalEvent::SendTimedOut { rpc_id: id }).await;
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHORT: Duration = Duration::from_millis(20);
+
+    fn register(pending: &PendingMap, id: &str, method: RpcMethod) -> RpcCall {
+        let (tx, rx) = oneshot::channel();
+        lock_pending(pending).insert(id.to_string(), PendingRpc { method, reply: tx });
+        RpcCall::new(id.to_string(), rx)
+    }
+
+    #[tokio::test]
+    async fn deadline_times_out_awaited_call() {
+        let pending: PendingMap = Arc::default();
+        let (tx, _rx) = mpsc::channel(4);
+        let call = register(&pending, "rpc-1", RpcMethod::Trust);
+        spawn_deadline(
+            Arc::clone(&pending),
+            tx.downgrade(),
+            "rpc-1".to_string(),
+            SHORT,
+        );
+        assert_eq!(call.response().await, Err(RpcError::Timeout));
+        assert!(lock_pending(&pending).is_empty());
+    }
+
+    #[tokio::test]
+    async fn deadline_keeps_unattended_send_for_a_late_answer() {
+        let pending: PendingMap = Arc::default();
+        let (tx, mut rx) = mpsc::channel(4);
+        drop(register(&pending, "rpc-2", RpcMethod::Send));
+        spawn_deadline(
+            Arc::clone(&pending),
+            tx.downgrade(),
+            "rpc-2".to_string(),
+            SHORT,
+        );
+        match rx.recv().await {
+            Some(SignalEvent::SendTimedOut { rpc_id }) => assert_eq!(rpc_id, "rpc-2"),
+            other => panic!("expected SendTimedOut, got {other:?}"),
+        }
+        assert!(lock_pending(&pending).contains_key("rpc-2"));
+    }
+
+    #[tokio::test]
+    async fn deadline_drops_unattended_non_send() {
+        let pending: PendingMap = Arc::default();
+        let (tx, mut rx) = mpsc::channel(4);
+        drop(register(&pending, "rpc-6", RpcMethod::SendReaction));
+        spawn_deadline(
+            Arc::clone(&pending),
+            tx.downgrade(),
+            "rpc-6".to_string(),
+            SHORT,
+        );
+        tokio::time::sleep(SHORT * 3).await;
+        assert!(lock_pending(&pending).is_empty());
+        drop(tx);
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn deadline_is_noop_after_response() {
+        let pending: PendingMap = Arc::default();
+        let (tx, mut rx) = mpsc::channel(4);
+        drop(register(&pending, "rpc-3", RpcMethod::Send));
+        spawn_deadline(
+            Arc::clone(&pending),
+            tx.downgrade(),
+            "rpc-3".to_string(),
+            SHORT,
+        );
+        // The reader answered first
+        lock_pending(&pending).remove("rpc-3");
+        tokio::time::sleep(SHORT * 3).await;
+        drop(tx);
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn disconnect_fails_waiters_only() {
+        let pending: PendingMap = Arc::default();
+        let call = register(&pending, "rpc-4", RpcMethod::ListDevices);
+        drop(register(&pending, "rpc-5", RpcMethod::Send));
+        fail_waiters(&pending);
+        assert_eq!(call.response().await, Err(RpcError::Disconnected));
+        let map = lock_pending(&pending);
+        assert_eq!(map.len(), 1);
+        assert!(map.contains_key("rpc-5"));
+    }
+
+    #[test]
+    fn method_names_match_wire() {
+        assert_eq!(RpcMethod::Send.to_string(), "send");
+        assert_eq!(RpcMethod::ListIdentities.as_str(), "listIdentities");
+        assert_eq!(
+            RpcMethod::SendMessageRequestResponse.as_str(),
+            "sendMessageRequestResponse"
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! Restart policy for the signal-cli child process.
+//!
+//! [`Supervisor`] does not own the [`super::client::SignalClient`]; the main
+//! loop reports disconnects and spawn outcomes, and asks [`Supervisor::due`]
+//! whether it is time to try again. Delays double from [`INITIAL_BACKOFF`] up
+//! to [`MAX_BACKOFF`]. A client that stayed up for at least [`STABLE_UPTIME`]
+//! is considered healthy, so its eventual crash starts over at the initial
+//! delay instead of inheriting the previous streak.
+
+use std::time::{Duration, Instant};
+
+/// Delay before the first respawn attempt after a disconnect.
+pub const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Upper bound on the delay between respawn attempts.
+pub const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Uptime after which a crash no longer counts towards the backoff streak.
+pub const STABLE_UPTIME: Duration = Duration::from_secs(30);
+
+/// Backoff bookkeeping for respawning signal-cli.
+#[derive(Debug)]
+pub struct Supervisor {
+    /// Consecutive failed (or short-lived) spawns since the last healthy run.
+    attempts: u32,
+    /// When the next respawn may be attempted. `None` while connected.
+    next_attempt: Option<Instant>,
+    /// When the current child was started, for the stable-uptime check.
+    spawned_at: Instant,
+}
+
+impl Supervisor {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            attempts: 0,
+            next_attempt: None,
+            spawned_at: now,
+        }
+    }
+
+    /// Delay before retry number `attempt` (0-based): 1s, 2s, 4s, ... capped
+    /// at [`MAX_BACKOFF`].
+    pub fn delay_for(attempt: u32) -> Duration {
+        INITIAL_BACKOFF
+            .checked_mul(1u32.checked_shl(attempt).unwrap_or(u32::MAX))
+            .map_or(MAX_BACKOFF, |d| d.min(MAX_BACKOFF))
+    }
+
+    /// Record that the child went away. Returns the delay until the next
+    /// attempt. Repeated calls while already waiting keep the existing
+    /// schedule.
+    pub fn on_disconnect(&mut self, now: Instant) -> Duration {
+        if let Some(at) = self.next_attempt {
+            return at.saturating_duration_since(now);
+        }
+        if now.duration_since(self.spawned_at) >= STABLE_UPTIME {
+            self.attempts = 0;
+        }
+        let delay = Self::delay_for(self.attempts);
+        self.next_attempt = Some(now + delay);
+        delay
+    }
+
+    /// Whether a respawn should be attempted now.
+    pub fn due(&self, now: Instant) -> bool {
+        self.next_attempt.is_some_and(|at| now >= at)
+    }
+
+    /// Record a successful spawn. The streak is kept until the new child
+    /// proves stable, so a process that dies immediately keeps backing off.
+    pub fn on_spawned(&mut self, now: Instant) {
+        self.attempts = self.attempts.saturating_add(1);
+        self.next_attempt = None;
+        self.spawned_at = now;
+    }
+
+    /// Record a failed spawn and schedule the next attempt. Returns the delay.
+    pub fn on_spawn_failed(&mut self, now: Instant) -> Duration {
+        self.attempts = self.attempts.saturating_add(1);
+        let delay = Self::delay_for(self.attempts);
+        self.next_attempt = Some(now + delay);
+        delay
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, 1)]
+    #[case(1, 2)]
+    #[case(2, 4)]
+    #[case(5, 32)]
+    #[case(6, 60)]
+    #[case(40, 60)]
+    fn delay_doubles_and_caps(#[case] attempt: u32, #[case] secs: u64) {
+        assert_eq!(Supervisor::delay_for(attempt), Duration::from_secs(secs));
+    }
+
+    #[test]
+    fn not_due_until_disconnect_delay_elapses() {
+        let t0 = Instant::now();
+        let mut sup = Supervisor::new(t0);
+        assert!(!sup.due(t0));
+        let delay = sup.on_disconnect(t0);
+        assert_eq!(delay, INITIAL_BACKOFF);
+        assert!(!sup.due(t0));
+        assert!(sup.due(t0 + delay));
+    }
+
+    #[test]
+    fn repeated_disconnect_keeps_schedule() {
+        let t0 = Instant::now();
+        let mut sup = Supervisor::new(t0);
+        sup.on_disconnect(t0);
+        let remaining = sup.on_disconnect(t0 + Duration::from_millis(400));
+        assert_eq!(remaining, Duration::from_millis(600));
+    }
+
+    #[test]
+    fn short_lived_child_keeps_backing_off() {
+        let t0 = Instant::now();
+        let mut sup = Supervisor::new(t0);
+        sup.on_disconnect(t0);
+        sup.on_spawned(t0 + Duration::from_secs(1));
+        // Dies two seconds later: second attempt waits longer
+        let delay = sup.on_disconnect(t0 + Duration::from_secs(3));
+        assert_eq!(delay, Duration::from_secs(2));
+    }
+
+    #[test]
+    fn stable_child_resets_streak() {
+        let t0 = Instant::now();
+        let mut sup = Supervisor::new(t0);
+        sup.on_disconnect(t0);
+        sup.on_spawn_failed(t0 + Duration::from_secs(1));
+        sup.on_spawn_failed(t0 + Duration::from_secs(3));
+        sup.on_spawned(t0 + Duration::from_secs(7));
+        let delay = sup.on_disconnect(t0 + Duration::from_secs(7) + STABLE_UPTIME);
+        assert_eq!(delay, INITIAL_BACKOFF);
+    }
+
+    #[test]
+    fn failed_spawn_schedules_longer_delay() {
+        let t0 = Instant::now();
+        let mut sup = Supervisor::new(t0);
+        sup.on_disconnect(t0);
+        let delay = sup.on_spawn_failed(t0 + INITIAL_BACKOFF);
+        assert_eq!(delay, Duration::from_secs(2));
+        assert!(!sup.due(t0 + INITIAL_BACKOFF + Duration::from_secs(1)));
+        assert!(sup.due(t0 + INITIAL_BACKOFF + delay));
+    }
+}
@@ -12,6 +12,7 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum MessageStatus {
     Failed,    // send failed
+    Queued,    // waiting in the outbox for a retry
     Sending,   // in transit to server
     Sent,      // server confirmed
     Delivered, // on recipient's device
@@ -29,6 +30,7 @@ impl MessageStatus {
             MessageStatus::Delivered => 4,
             MessageStatus::Read => 5,
             MessageStatus::Viewed => 6,
+            MessageStatus::Queued => 7,
         }
     }
 
@@ -41,6 +43,7 @@ impl MessageStatus {
             4 => Some(MessageStatus::Delivered),
             5 => Some(MessageStatus::Read),
             6 => Some(MessageStatus::Viewed),
+            7 => Some(MessageStatus::Queued),
             _ => None,
         }
     }
@@ -69,9 +72,6 @@ impl TrustLevel {
 #[derive(Debug, Clone)]
 pub struct IdentityInfo {
     pub number: Option<String>,
-    /// Only read by tests today; kept on the wire type for parity with
-    /// signal-cli's identity payload.
-    #[allow(dead_code)]
     pub uuid: Option<String>,
     pub fingerprint: String,
     pub safety_number: String,
@@ -80,6 +80,42 @@ pub struct IdentityInfo {
     pub added_timestamp: i64,
 }
 
+/// A Signal username resolved to its account by `getUserStatus`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedUsername {
+    pub username: String,
+    /// The account's ACI, usable as a recipient in place of a phone number
+    pub aci: String,
+    /// Phone number, only when the account shares it with us
+    pub number: Option<String>,
+}
+
+/// Our own username and its `signal.me` link, as returned by `updateAccount`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountUsername {
+    pub username: Option<String>,
+    pub link: Option<String>,
+}
+
+/// A device on our account, as returned by `listDevices`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Device {
+    /// Device id; the primary (phone) is always 1
+    pub id: i64,
+    pub name: Option<String>,
+    pub created_ms: i64,
+    pub last_seen_ms: i64,
+    /// Set when signal-cli marks the device it is running as
+    pub is_this_device: bool,
+}
+
+impl Device {
+    /// The primary device, which can't be unlinked.
+    pub fn is_primary(&self) -> bool {
+        self.id == 1
+    }
+}
+
 /// A single emoji reaction on a message.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Reaction {
@@ -121,6 +157,8 @@ pub enum SignalEvent {
         sender: String,
         receipt_type: String,
         timestamps: Vec<i64>,
+        /// When the recipient's device saw the messages (0 if not reported)
+        when_ms: i64,
     },
     SendTimestamp {
         rpc_id: String,
@@ -129,6 +167,28 @@ pub enum SignalEvent {
     SendFailed {
         rpc_id: String,
     },
+    /// signal-cli didn't answer a send in time. It may still have gone
+    /// out; a late `SendTimestamp` for the same `rpc_id` confirms it.
+    SendTimedOut {
+        rpc_id: String,
+    },
+    /// A send was refused for these recipients because their safety number
+    /// changed. `partial` is set when it still went out to everyone else, so
+    /// only these recipients missed it; otherwise the whole send failed and
+    /// can be retried once the change is acknowledged.
+    UntrustedRecipients {
+        rpc_id: Option<String>,
+        recipients: Vec<String>,
+        partial: bool,
+    },
+    /// A send was refused by Signal's rate limit. `partial` is set when it
+    /// still went out to everyone but `failure.recipients`; otherwise the
+    /// whole send failed and can be retried once the limit is cleared.
+    RateLimited {
+        rpc_id: Option<String>,
+        failure: RateLimitFailure,
+        partial: bool,
+    },
     TypingIndicator {
         sender: String,
         sender_name: Option<String>,
@@ -143,6 +203,8 @@ pub enum SignalEvent {
         target_author: String,
         target_timestamp: i64,
         is_remove: bool,
+        /// When the reaction was sent (0 if not reported)
+        timestamp_ms: i64,
     },
     EditReceived {
         conv_id: String,
@@ -150,9 +212,7 @@ pub enum SignalEvent {
         sender_name: Option<String>,
         target_timestamp: i64,
         new_body: String,
-        #[allow(dead_code)]
         new_timestamp: i64,
-        #[allow(dead_code)]
         is_outgoing: bool,
     },
     RemoteDeleteReceived {
@@ -199,6 +259,19 @@ pub enum SignalEvent {
         body: String,
         timestamp: DateTime<Utc>,
         timestamp_ms: i64,
+        /// Draw it as a warning (a changed safety number)
+        is_warning: bool,
+    },
+    /// A groupV2 `UPDATE`. signal-cli reports who made the change and, at
+    /// most, the new name; the rest is found by diffing a fresh `listGroups`.
+    GroupUpdated {
+        group_id: String,
+        editor: String,
+        editor_name: Option<String>,
+        /// Group name after the update, when signal-cli includes it
+        name: Option<String>,
+        timestamp: DateTime<Utc>,
+        timestamp_ms: i64,
     },
     ExpirationTimerChanged {
         conv_id: String,
@@ -210,9 +283,53 @@ pub enum SignalEvent {
     ReadSyncReceived {
         read_messages: Vec<(String, i64)>,
     },
+    /// Our phone opened a view-once message: (sender, message timestamp)
+    ViewOnceOpened {
+        sender: String,
+        timestamp: i64,
+    },
+    StoryReceived(StoryMessage),
+    /// A 1:1 call signalling step (offer, answer, busy or hangup)
+    CallSignal(CallSignal),
     ContactList(Vec<Contact>),
     GroupList(Vec<Group>),
     IdentityList(Vec<IdentityInfo>),
+    /// A `trust` request went through. `identities` is the list fetched
+    /// right after it, so trust levels already reflect the change.
+    IdentityTrusted {
+        recipient: String,
+        /// A verified safety number, rather than an accepted change
+        verified: bool,
+        identities: Result<Vec<IdentityInfo>, String>,
+    },
+    /// A sticker image fetched for the message at `timestamp_ms`
+    StickerFetched {
+        conv_id: String,
+        timestamp_ms: i64,
+        sticker: StickerRef,
+        result: Result<std::path::PathBuf, String>,
+    },
+    /// Installed sticker packs, for the sticker picker
+    StickerPacks(Result<Vec<StickerPack>, String>),
+    /// `getUserStatus` found the account behind a username
+    UsernameResolved(ResolvedUsername),
+    /// Our own username after setting (or, as `None`, deleting) it
+    OwnUsername(AccountUsername),
+    /// The devices linked to our account, for the `/devices` overlay
+    DeviceList(Result<Vec<Device>, String>),
+    /// Signal removed this contact, so its local details can go too
+    ContactRemoved(String),
+    /// An avatar fetched from signal-cli; `None` when it has none
+    AvatarFetched {
+        id: String,
+        result: Result<Option<std::path::PathBuf>, String>,
+    },
+    /// Our profile avatar was set (the cached copy) or removed (`None`)
+    ProfileAvatarSet(Result<Option<std::path::PathBuf>, String>),
+    /// Signal's answer to a submitted rate-limit captcha
+    CaptchaSubmitted(Result<(), String>),
+    /// Status-bar text from a request that ran off the main loop
+    Notice(String),
     Error(String),
 }
 
@@ -232,6 +349,7 @@ impl SignalEvent {
                 sender,
                 receipt_type,
                 timestamps,
+                ..
             } => format!(
                 "ReceiptReceived({receipt_type} from={}, count={})",
                 mask_phone(sender),
@@ -241,6 +359,36 @@ impl SignalEvent {
                 format!("SendTimestamp(rpc={rpc_id}, ts={server_ts})",)
             }
             Self::SendFailed { rpc_id } => format!("SendFailed(rpc={rpc_id})"),
+            Self::SendTimedOut { rpc_id } => format!("SendTimedOut(rpc={rpc_id})"),
+            Self::UntrustedRecipients {
+                rpc_id,
+                recipients,
+                partial,
+            } => format!(
+                "UntrustedRecipients(rpc={}, recipients=[{}], partial={partial})",
+                rpc_id.as_deref().unwrap_or("-"),
+                recipients
+                    .iter()
+                    .map(|r| mask_phone(r))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+            Self::RateLimited {
+                rpc_id,
+                failure,
+                partial,
+            } => format!(
+                "RateLimited(rpc={}, challenge={}, retry_after={:?}, recipients=[{}], partial={partial})",
+                rpc_id.as_deref().unwrap_or("-"),
+                failure.challenge.is_some(),
+                failure.retry_after_seconds,
+                failure
+                    .recipients
+                    .iter()
+                    .map(|r| mask_phone(r))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
             Self::TypingIndicator {
                 sender, is_typing, ..
             } => format!(
@@ -318,6 +466,13 @@ impl SignalEvent {
                 mask_phone(conv_id),
                 mask_body(body),
             ),
+            Self::GroupUpdated {
+                group_id, editor, ..
+            } => format!(
+                "GroupUpdated(group={}, editor={})",
+                mask_phone(group_id),
+                mask_phone(editor),
+            ),
             Self::ExpirationTimerChanged {
                 conv_id, seconds, ..
             } => format!(
@@ -327,9 +482,79 @@ impl SignalEvent {
             Self::ReadSyncReceived { read_messages } => {
                 format!("ReadSyncReceived(count={})", read_messages.len(),)
             }
+            Self::ViewOnceOpened { sender, timestamp } => format!(
+                "ViewOnceOpened(from={}, ts={timestamp})",
+                mask_phone(sender),
+            ),
+            Self::StoryReceived(story) => format!(
+                "StoryReceived(from={}, ts={}, text={}, attachment={})",
+                mask_phone(&story.author),
+                story.timestamp_ms,
+                story.text.is_some(),
+                story.attachment.is_some(),
+            ),
+            Self::CallSignal(call) => format!(
+                "CallSignal(peer={}, id={}, kind={:?})",
+                mask_phone(&call.peer),
+                call.call_id,
+                call.kind,
+            ),
             Self::ContactList(contacts) => format!("ContactList(count={})", contacts.len()),
             Self::GroupList(groups) => format!("GroupList(count={})", groups.len()),
             Self::IdentityList(ids) => format!("IdentityList(count={})", ids.len()),
+            Self::IdentityTrusted {
+                recipient,
+                verified,
+                identities,
+            } => format!(
+                "IdentityTrusted(recipient={}, verified={verified}, refreshed={})",
+                mask_phone(recipient),
+                identities.is_ok(),
+            ),
+            Self::StickerFetched {
+                conv_id,
+                timestamp_ms,
+                result,
+                ..
+            } => format!(
+                "StickerFetched(conv={}, ts={timestamp_ms}, ok={})",
+                mask_phone(conv_id),
+                result.is_ok(),
+            ),
+            Self::StickerPacks(packs) => match packs {
+                Ok(packs) => format!("StickerPacks(count={})", packs.len()),
+                Err(e) => format!("StickerPacks(error={e})"),
+            },
+            Self::UsernameResolved(resolved) => format!(
+                "UsernameResolved(aci={}, number={})",
+                mask_phone(&resolved.aci),
+                resolved.number.is_some(),
+            ),
+            Self::OwnUsername(account) => {
+                format!("OwnUsername(set={})", account.username.is_some())
+            }
+            Self::DeviceList(devices) => match devices {
+                Ok(devices) => format!("DeviceList(count={})", devices.len()),
+                Err(e) => format!("DeviceList(error={e})"),
+            },
+            Self::ContactRemoved(number) => format!("ContactRemoved({})", mask_phone(number)),
+            Self::AvatarFetched { id, result } => match result {
+                Ok(path) => format!(
+                    "AvatarFetched(id={}, found={})",
+                    mask_phone(id),
+                    path.is_some(),
+                ),
+                Err(e) => format!("AvatarFetched(id={}, error={e})", mask_phone(id)),
+            },
+            Self::ProfileAvatarSet(result) => match result {
+                Ok(cached) => format!("ProfileAvatarSet(set={})", cached.is_some()),
+                Err(e) => format!("ProfileAvatarSet(error={e})"),
+            },
+            Self::CaptchaSubmitted(result) => match result {
+                Ok(()) => "CaptchaSubmitted(ok)".to_string(),
+                Err(e) => format!("CaptchaSubmitted(error={e})"),
+            },
+            Self::Notice(text) => format!("Notice({})", mask_body(text)),
             Self::Error(e) => format!("Error({e})"),
         }
     }
@@ -353,12 +578,18 @@ pub struct SignalMessage {
     pub mentions: Vec<Mention>,
     /// Text style ranges from signal-cli (bold, italic, etc.)
     pub text_styles: Vec<TextStyle>,
-    /// Quoted reply context: (timestamp_ms, author_phone, body)
-    pub quote: Option<(i64, String, String)>,
+    /// Quoted reply context: (timestamp_ms, author_phone, body, text_styles)
+    pub quote: Option<(i64, String, String, Vec<TextStyle>)>,
     /// Disappearing message timer (seconds, 0 = no expiration)
     pub expires_in_seconds: i64,
     /// Link previews attached to this message
     pub previews: Vec<LinkPreview>,
+    /// Sticker sent in place of a body
+    pub sticker: Option<StickerRef>,
+    /// View-once media: attachments are revealed once, then purged
+    pub view_once: bool,
+    /// Contact cards shared in this message
+    pub shared_contacts: Vec<SharedContact>,
 }
 
 impl Default for SignalMessage {
@@ -382,10 +613,141 @@ impl Default for SignalMessage {
             quote: None,
             expires_in_seconds: 0,
             previews: Vec::new(),
+            sticker: None,
+            view_once: false,
+            shared_contacts: Vec::new(),
         }
     }
 }
 
+/// A sticker carried by a message: which pack, which sticker in it, and the
+/// emoji it stands for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StickerRef {
+    /// Hex-encoded pack id
+    pub pack_id: String,
+    pub sticker_id: u32,
+    pub emoji: Option<String>,
+    /// Local copy of the sticker image, if signal-cli already had one
+    pub local_path: Option<String>,
+}
+
+impl StickerRef {
+    /// Where a sticker image fetched with `getSticker` is cached. `None` for a
+    /// pack id that is not plain hex, so it can never escape `download_dir`.
+    pub fn cache_path(&self, download_dir: &std::path::Path) -> Option<std::path::PathBuf> {
+        if self.pack_id.is_empty() || !self.pack_id.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        Some(
+            download_dir
+                .join("stickers")
+                .join(&self.pack_id)
+                .join(format!("{}.webp", self.sticker_id)),
+        )
+    }
+}
+
+/// Whose avatar to fetch with `getAvatar`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AvatarRef {
+    /// A contact (or our own account), by number or UUID
+    Contact(String),
+    /// A group, by base64 group id
+    Group(String),
+}
+
+impl AvatarRef {
+    /// The conversation id the avatar belongs to.
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Contact(id) | Self::Group(id) => id,
+        }
+    }
+
+    /// Where the avatar is cached. Group ids are base64, so `/` and `+` are
+    /// swapped for their URL-safe forms to keep the name a single path
+    /// component.
+    pub fn cache_path(&self, download_dir: &std::path::Path) -> std::path::PathBuf {
+        let (prefix, id) = match self {
+            Self::Contact(id) => ("contact", id),
+            Self::Group(id) => ("group", id),
+        };
+        let name: String = id
+            .chars()
+            .map(|c| match c {
+                '/' => '_',
+                '+' => '-',
+                c if c.is_ascii_alphanumeric() || c == '=' || c == '-' => c,
+                _ => '_',
+            })
+            .collect();
+        download_dir
+            .join("avatars")
+            .join(format!("{prefix}-{name}"))
+    }
+}
+
+/// One sticker in an installed pack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StickerInfo {
+    pub id: u32,
+    pub emoji: Option<String>,
+}
+
+/// A sticker pack known to signal-cli, from `listStickerPacks`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StickerPack {
+    pub pack_id: String,
+    pub title: String,
+    pub author: String,
+    pub installed: bool,
+    pub stickers: Vec<StickerInfo>,
+}
+
+/// A story posted by a contact: either a text card or a single attachment.
+#[derive(Debug, Clone)]
+pub struct StoryMessage {
+    pub author: String,
+    pub author_name: Option<String>,
+    pub timestamp_ms: i64,
+    /// Set for stories posted to a group's story
+    pub group_id: Option<String>,
+    /// Body of a text story
+    pub text: Option<String>,
+    /// Image or video of a media story
+    pub attachment: Option<Attachment>,
+    pub allows_replies: bool,
+}
+
+/// One step of a 1:1 call's lifecycle, from an envelope's `callMessage`.
+/// ICE candidate updates carry nothing worth recording and are dropped by
+/// the parser.
+#[derive(Debug, Clone)]
+pub struct CallSignal {
+    /// The other party: the envelope source
+    pub peer: String,
+    pub peer_name: Option<String>,
+    /// Call id shared by every message of one call
+    pub call_id: i64,
+    pub timestamp_ms: i64,
+    pub kind: CallSignalKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallSignalKind {
+    Offer {
+        video: bool,
+    },
+    Answer,
+    Busy,
+    /// `hangup_type` is signal-cli's `NORMAL`, `ACCEPTED`, `DECLINED`,
+    /// `BUSY` or `NEED_PERMISSION`
+    Hangup {
+        hangup_type: String,
+    },
+}
+
 /// Link preview metadata attached to a message.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LinkPreview {
@@ -395,14 +757,37 @@ pub struct LinkPreview {
     pub image_path: Option<String>,
 }
 
+/// A contact card shared in a message (Signal's `sharedContacts`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SharedContact {
+    /// Display name, or given and family name joined when there is none
+    pub name: String,
+    pub organization: Option<String>,
+    pub phones: Vec<ContactPoint>,
+    pub emails: Vec<ContactPoint>,
+}
+
+/// One phone number or email address on a shared contact.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContactPoint {
+    pub value: String,
+    /// "Mobile", "Home", "Work" or a custom label
+    pub label: Option<String>,
+}
+
 /// An attachment on a message
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Attachment {
     #[allow(dead_code)]
     pub id: String,
     pub content_type: String,
     pub filename: Option<String>,
     pub local_path: Option<String>,
+    /// Size in bytes
+    pub size: Option<u64>,
+    /// Pixel dimensions, for images and videos
+    pub width: Option<u32>,
+    pub height: Option<u32>,
 }
 
 /// JSON-RPC request to signal-cli
@@ -432,6 +817,23 @@ pub struct JsonRpcResponse {
 pub struct JsonRpcError {
     pub code: i64,
     pub message: String,
+    /// Extra detail some errors carry, such as the per-recipient results of
+    /// a send that failed
+    #[serde(default)]
+    pub data: Option<serde_json::Value>,
+}
+
+/// Why Signal refused a send for rate limiting.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RateLimitFailure {
+    /// Token of the proof-required challenge, answered with a captcha via
+    /// `submitRateLimitChallenge`. `None` for a plain rate limit.
+    pub challenge: Option<String>,
+    /// How long Signal asks us to wait before sending again
+    pub retry_after_seconds: Option<i64>,
+    /// Recipients refused, by number or else ACI. Empty for a bare
+    /// rate-limit error that names nobody.
+    pub recipients: Vec<String>,
 }
 
 /// A body range mention from signal-cli's bodyRanges array.
@@ -451,7 +853,7 @@ pub struct TextStyle {
 }
 
 /// Type of text styling applied to a range.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum StyleType {
     Bold,
     Italic,
@@ -460,6 +862,19 @@ pub enum StyleType {
     Spoiler,
 }
 
+impl StyleType {
+    /// Name signal-cli uses for this style in `textStyle` ranges.
+    pub fn wire_name(self) -> &'static str {
+        match self {
+            StyleType::Bold => "BOLD",
+            StyleType::Italic => "ITALIC",
+            StyleType::Strikethrough => "STRIKETHROUGH",
+            StyleType::Monospace => "MONOSPACE",
+            StyleType::Spoiler => "SPOILER",
+        }
+    }
+}
+
 /// Contact info from signal-cli
 #[derive(Debug, Clone)]
 pub struct Contact {
@@ -469,7 +884,7 @@ pub struct Contact {
 }
 
 /// Group info from signal-cli
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct Group {
     pub id: String,
     pub name: String,
@@ -477,4 +892,101 @@ pub struct Group {
     pub members: Vec<String>,
     /// (phone, uuid) pairs for members where UUID is known
     pub member_uuids: Vec<(String, String)>,
+    pub description: Option<String>,
+    /// Phone numbers of group admins
+    pub admins: Vec<String>,
+    /// Disappearing message timer (seconds, 0 = off)
+    pub expiration_seconds: i64,
+    pub permission_add_member: GroupPermission,
+    pub permission_edit_details: GroupPermission,
+    pub permission_send_message: GroupPermission,
+    /// `https://signal.group/#...` link, when the invite link is enabled
+    pub invite_link: Option<String>,
+}
+
+impl Group {
+    pub fn is_admin(&self, number: &str) -> bool {
+        self.admins.iter().any(|a| a == number)
+    }
+
+    pub fn permission(&self, policy: GroupPolicy) -> GroupPermission {
+        match policy {
+            GroupPolicy::AddMembers => self.permission_add_member,
+            GroupPolicy::EditDetails => self.permission_edit_details,
+            GroupPolicy::SendMessages => self.permission_send_message,
+        }
+    }
+
+    /// Whether `number` may do what `policy` governs: admins always can,
+    /// everyone else only while the policy is open to every member.
+    pub fn allows(&self, number: &str, policy: GroupPolicy) -> bool {
+        self.is_admin(number) || self.permission(policy) == GroupPermission::EveryMember
+    }
+}
+
+/// Who may perform a restricted group action.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GroupPermission {
+    #[default]
+    EveryMember,
+    OnlyAdmins,
+}
+
+impl GroupPermission {
+    /// Parse signal-cli's `EVERY_MEMBER` / `ONLY_ADMINS`.
+    pub fn parse(s: &str) -> Self {
+        if s == "ONLY_ADMINS" {
+            Self::OnlyAdmins
+        } else {
+            Self::EveryMember
+        }
+    }
+}
+
+/// The group actions an admin can restrict to admins only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupPolicy {
+    AddMembers,
+    EditDetails,
+    /// Restricted, this makes an announcement group
+    SendMessages,
+}
+
+impl GroupPolicy {
+    pub const ALL: [GroupPolicy; 3] = [Self::AddMembers, Self::EditDetails, Self::SendMessages];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::AddMembers => "Add members",
+            Self::EditDetails => "Edit group details",
+            Self::SendMessages => "Send messages",
+        }
+    }
+}
+
+/// What to do with a group's invite link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupLinkAction {
+    Enable,
+    /// Replace the link with a new one, invalidating the old
+    Reset,
+    Disable,
+}
+
+/// One admin-level change to a group, sent with `updateGroup`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupEdit {
+    Admin {
+        member: String,
+        admin: bool,
+    },
+    Permission {
+        policy: GroupPolicy,
+        only_admins: bool,
+    },
+    /// Empty clears the description
+    Description(String),
+    /// Local path of the new avatar image
+    Avatar(String),
+    Link(GroupLinkAction),
 }
@@ -4,15 +4,15 @@
 //! into messages + composer; `draw_messages` is the per-conversation
 //! message list with its long pipeline of body wrapping, scroll
 //! window, sender/timestamp/status decoration, quotes, mentions,
-//! polls, attachments, image previews, reactions, link styling, and
-//! the inline typing indicator. `patch_kitty_placeholders` runs after
-//! the buffer is filled to swap halfblock cells for Kitty Unicode
-//! Placeholder symbols so terminals with the Kitty graphics protocol
-//! render image data inline. `emoji_to_text` rewrites emoji as text
+//! polls, contact cards, attachments, image previews, reactions, link
+//! styling, and the inline typing indicator. `patch_kitty_placeholders`
+//! runs after the buffer is filled to swap halfblock cells for Kitty
+//! Unicode Placeholder symbols so terminals with the Kitty graphics
+//! protocol render image data inline. `emoji_to_text` rewrites emoji as text
 //! emoticons or `:shortcode:` form when the user enables that setting;
-//! `build_reaction_summary` and `build_poll_display` produce the
-//! per-message reaction badge and poll bars consumed inside
-//! `draw_messages`.
+//! `build_reaction_summary`, `build_poll_display` and
+//! `build_contact_card` produce the per-message reaction badge, poll bars
+//! and shared-contact blocks consumed inside `draw_messages`.
 
 use ratatui::{
     Frame,
@@ -26,13 +26,14 @@ use ratatui::{
 };
 
 use super::composer::draw_input;
-use super::links::{split_spans_by_newline, styled_uri_spans};
+use super::links::{attachment_spans, split_spans_by_newline, styled_uri_spans};
 use super::welcome::draw_welcome;
 use super::{MSG_WINDOW_MULTIPLIER, build_separator, sender_color, status_symbol, truncate};
 use crate::app::{App, InputMode, VisibleImage};
+use crate::domain::ImageState;
 use crate::image_render::{self, ImageProtocol};
 use crate::input::format_compact_duration;
-use crate::signal::types::{PollData, PollVote, Reaction, TrustLevel};
+use crate::signal::types::{PollData, PollVote, Reaction, SharedContact, StyleType, TrustLevel};
 use crate::theme::Theme;
 use ratatui::layout::Alignment;
 
@@ -235,7 +236,12 @@ fn draw_messages(frame: &mut Frame, app: &mut App, area: Rect) {
             1 => {
                 let m = pinned[0];
                 // Collapse newlines to spaces for the single-line banner.
-                let body: String = m.body.replace('\n', " ").chars().take(80).collect();
+                let body: String = m
+                    .text_with_attachments()
+                    .replace('\n', " ")
+                    .chars()
+                    .take(80)
+                    .collect();
                 Some(format!("\u{1f4cc} {}: {body}", m.sender))
             }
             n => Some(format!("\u{1f4cc} {n} pinned messages")),
@@ -306,10 +312,11 @@ fn draw_messages(frame: &mut Frame, app: &mut App, area: Rect) {
     // Map each line to its source message index (None for separators/markers)
     let mut line_msg_idx: Vec<Option<usize>> = Vec::new();
 
-    // Track images for native protocol overlay: (first_line_index, line_count, path)
+    // Track images for native protocol overlay:
+    // (first_line_index, line_count, column after the indent, width, path)
     let use_native = app.image.image_mode == crate::domain::ImageMode::Native
         && app.image.image_protocol != ImageProtocol::Halfblock;
-    let mut image_records: Vec<(usize, usize, String)> = Vec::new();
+    let mut image_records: Vec<(usize, usize, u16, u16, String)> = Vec::new();
 
     for (i, msg) in visible.iter().enumerate() {
         let msg_index = start + i;
@@ -359,18 +366,25 @@ fn draw_messages(frame: &mut Frame, app: &mut App, area: Rect) {
             } else {
                 msg.body.clone()
             };
-            lines.push(Line::from(Span::styled(
-                format!("  {body}"),
-                Style::default().fg(theme.system_msg),
-            )));
+            // Warnings such as a changed safety number stand out
+            let style = if msg.is_warning {
+                Style::default()
+                    .fg(theme.warning)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.system_msg)
+            };
+            lines.push(Line::from(Span::styled(format!("  {body}"), style)));
             line_msg_idx.push(Some(msg_index));
         } else {
             // Render quoted reply line above message
             if let Some(ref quote) = msg.quote {
+                let quote_body =
+                    quote.shown_body(app.spoiler_revealed(conv_id, quote.timestamp_ms));
                 let raw_body = if app.reactions.emoji_to_text {
-                    emoji_to_text(&quote.body)
+                    emoji_to_text(&quote_body)
                 } else {
-                    quote.body.clone()
+                    quote_body
                 };
                 // Quotes render on a single line; collapse any newlines to spaces.
                 let raw_body = raw_body.replace('\n', " ");
@@ -453,15 +467,28 @@ fn draw_messages(frame: &mut Frame, app: &mut App, area: Rect) {
                 lines.push(Line::from(spans));
                 line_msg_idx.push(Some(msg_index));
             } else {
-                // Style URIs and @mentions
-                let (body_spans, hidden_url) =
-                    styled_uri_spans(&msg.body, &msg.mention_ranges, &msg.style_ranges, theme);
-                if let Some(url) = hidden_url {
-                    // Collect display text for link_url_map lookup
-                    let display_text: String =
-                        body_spans.iter().map(|s| s.content.as_ref()).collect();
-                    app.image.link_url_map.insert(display_text, url);
+                // Attachment labels, then the body with URIs and @mentions styled
+                let (mut body_spans, hidden_urls) = attachment_spans(&msg.attachments, theme);
+                // Labels carry no URI; the OSC 8 pass looks them up by text
+                app.image.link_url_map.extend(hidden_urls);
+                let text_spans = if app
+                    .revealed_spoilers
+                    .contains(&(conv_id.clone(), msg.timestamp_ms))
+                {
+                    let unmasked: Vec<_> = msg
+                        .style_ranges
+                        .iter()
+                        .copied()
+                        .filter(|&(_, _, style)| style != StyleType::Spoiler)
+                        .collect();
+                    styled_uri_spans(&msg.body, &msg.mention_ranges, &unmasked, theme)
+                } else {
+                    styled_uri_spans(&msg.body, &msg.mention_ranges, &msg.style_ranges, theme)
+                };
+                if !body_spans.is_empty() && !text_spans.is_empty() {
+                    body_spans.push(Span::raw(" "));
                 }
+                body_spans.extend(text_spans);
                 let body_spans: Vec<Span<'static>> = if app.reactions.emoji_to_text {
                     body_spans
                         .into_iter()
@@ -489,20 +516,28 @@ fn draw_messages(frame: &mut Frame, app: &mut App, area: Rect) {
                 }
             }
 
-            // Render inline image preview if available (skip for deleted, skip if images disabled)
+            // Render inline images if available: one image, or an album's
+            // thumbnail grid (skip for deleted, skip if images disabled)
             if !msg.is_deleted
                 && app.image.image_mode != crate::domain::ImageMode::None
                 && let Some(ref image_lines) = msg.image_lines
             {
                 let first_idx = lines.len();
-                let count = image_lines.len();
                 for line in image_lines {
                     lines.push(line.clone());
                     line_msg_idx.push(Some(msg_index));
                 }
-                // Record for native protocol overlay
-                if use_native && let Some(ref path) = msg.image_path {
-                    image_records.push((first_idx, count, path.clone()));
+                // Record each image for native protocol overlay
+                if use_native {
+                    for tile in &msg.image_tiles {
+                        image_records.push((
+                            first_idx + tile.row as usize,
+                            tile.height as usize,
+                            tile.col,
+                            tile.width,
+                            tile.path.clone(),
+                        ));
+                    }
                 }
             }
 
@@ -551,7 +586,19 @@ fn draw_messages(frame: &mut Frame, app: &mut App, area: Rect) {
                         line_msg_idx.push(Some(msg_index));
                     }
                     if use_native && let Some(ref path) = msg.preview_image_path {
-                        image_records.push((first_idx, count, path.clone()));
+                        // Image width = first image line width minus 2-char indent
+                        let width = img_lines.first().map_or(0, |l| l.width().saturating_sub(2));
+                        image_records.push((first_idx, count, 0, width as u16, path.clone()));
+                    }
+                }
+            }
+
+            // Render shared contact cards
+            if !msg.is_deleted {
+                for contact in &msg.shared_contacts {
+                    for line in build_contact_card(contact, theme) {
+                        lines.push(line);
+                        line_msg_idx.push(Some(msg_index));
                     }
                 }
             }
@@ -719,6 +766,15 @@ fn draw_messages(frame: &mut Frame, app: &mut App, area: Rect) {
         render_focus = None;
     };
 
+    // Record which message sits on each visible row, for mouse clicks
+    app.mouse.message_rows = line_heights
+        .iter()
+        .zip(&line_msg_idx)
+        .flat_map(|(&h, &idx)| std::iter::repeat_n(idx, h))
+        .skip(scroll_y)
+        .take(available_height)
+        .collect();
+
     // Compute screen positions for native protocol image overlay (before lines is consumed)
     if !image_records.is_empty() {
         // Build cumulative wrapped-line positions from the pre-computed heights so
@@ -730,7 +786,7 @@ fn draw_messages(frame: &mut Frame, app: &mut App, area: Rect) {
             cumulative += h;
         }
 
-        for (first_idx, count, path) in &image_records {
+        for (first_idx, count, col, img_width, path) in &image_records {
             let img_start = wrapped_positions[*first_idx];
             let img_end = if first_idx + count < wrapped_positions.len() {
                 wrapped_positions[first_idx + count]
@@ -751,20 +807,13 @@ fn draw_messages(frame: &mut Frame, app: &mut App, area: Rect) {
             let vis_end = (screen_end.min(available_height as i64)) as u16;
 
             if vis_start < vis_end {
-                // Image width = first image line width minus 2-char indent
-                let img_width = if *first_idx < lines.len() {
-                    (lines[*first_idx].width()).saturating_sub(2) as u16
-                } else {
-                    0
-                };
-
                 let full_height = (img_end - img_start) as u16;
                 let crop_top = (vis_start as i64 - screen_start) as u16;
 
                 app.image.visible_images.push(VisibleImage {
-                    x: inner.x + 2, // account for 2-char indent
+                    x: inner.x + 2 + col, // account for 2-char indent
                     y: inner.y + vis_start,
-                    width: img_width,
+                    width: *img_width,
                     height: vis_end - vis_start,
                     full_height,
                     crop_top,
@@ -824,38 +873,43 @@ fn draw_messages(frame: &mut Frame, app: &mut App, area: Rect) {
 /// Replaces the halfblock cells with U+10EEEE + row/column diacritics so the
 /// terminal renders image data at the cell level (instead of GPU overlays).
 pub(super) fn patch_kitty_placeholders(frame: &mut Frame, app: &mut App) {
-    for img in &app.image.visible_images {
-        let id = if let Some(&existing) = app.image.kitty_image_ids.get(&img.path) {
-            existing
-        } else {
-            let new_id = app.image.next_kitty_image_id;
-            app.image.next_kitty_image_id += 1;
-            app.image.kitty_image_ids.insert(img.path.clone(), new_id);
-            new_id
-        };
-        let fg = image_render::kitty_id_color(id);
-
-        for row_offset in 0..img.height {
-            let image_row = (img.crop_top + row_offset) as usize;
-            for col in 0..img.width {
-                let symbol = image_render::placeholder_symbol(image_row, col as usize);
-                let pos = Position::new(img.x + col, img.y + row_offset);
-                if let Some(cell) = frame.buffer_mut().cell_mut(pos) {
-                    cell.reset();
-                    cell.set_symbol(&symbol);
-                    cell.set_fg(fg);
-                }
+    let images = std::mem::take(&mut app.image.visible_images);
+    for img in &images {
+        patch_kitty_image(frame, &mut app.image, img);
+    }
+    app.image.visible_images = images;
+}
+
+/// Patch the placeholder cells of one image, queueing its transmission if
+/// the terminal doesn't have it yet.
+pub(super) fn patch_kitty_image(frame: &mut Frame, image: &mut ImageState, img: &VisibleImage) {
+    let id = if let Some(&existing) = image.kitty_image_ids.get(&img.path) {
+        existing
+    } else {
+        let new_id = image.next_kitty_image_id;
+        image.next_kitty_image_id += 1;
+        image.kitty_image_ids.insert(img.path.clone(), new_id);
+        new_id
+    };
+    let fg = image_render::kitty_id_color(id);
+
+    for row_offset in 0..img.height {
+        let image_row = (img.crop_top + row_offset) as usize;
+        for col in 0..img.width {
+            let symbol = image_render::placeholder_symbol(image_row, col as usize);
+            let pos = Position::new(img.x + col, img.y + row_offset);
+            if let Some(cell) = frame.buffer_mut().cell_mut(pos) {
+                cell.reset();
+                cell.set_symbol(&symbol);
+                cell.set_fg(fg);
             }
         }
+    }
 
-        if !app.image.kitty_transmitted.contains(&id) {
-            app.image.kitty_pending_transmits.push((
-                id,
-                img.path.clone(),
-                img.width,
-                img.full_height,
-            ));
-        }
+    if !image.kitty_transmitted.contains(&id) {
+        image
+            .kitty_pending_transmits
+            .push((id, img.path.clone(), img.width, img.full_height));
     }
 }
 
@@ -912,6 +966,41 @@ fn build_reaction_summary(
     }
 }
 
+/// Build the block for a shared contact: name, organization, then each
+/// number and email with its label.
+fn build_contact_card(contact: &SharedContact, theme: &Theme) -> Vec<Line<'static>> {
+    let border = Style::default().fg(theme.accent);
+    let mut rows: Vec<Vec<Span<'static>>> = vec![vec![Span::styled(
+        truncate(&contact.name, 60),
+        Style::default().fg(theme.fg).add_modifier(Modifier::BOLD),
+    )]];
+    if let Some(ref org) = contact.organization {
+        rows.push(vec![Span::styled(
+            truncate(org, 60),
+            Style::default().fg(theme.fg_muted),
+        )]);
+    }
+    for point in contact.phones.iter().chain(&contact.emails) {
+        let label = point.label.as_deref().unwrap_or("Other");
+        rows.push(vec![
+            Span::styled(
+                format!("{:<8}", truncate(label, 8)),
+                Style::default().fg(theme.fg_muted),
+            ),
+            Span::styled(truncate(&point.value, 50), Style::default().fg(theme.fg)),
+        ]);
+    }
+    let last = rows.len() - 1;
+    rows.into_iter()
+        .enumerate()
+        .map(|(i, mut spans)| {
+            let corner = if i == last { "\u{2570}" } else { "\u{251C}" };
+            spans.insert(0, Span::styled(format!("  {corner} "), border));
+            Line::from(spans)
+        })
+        .collect()
+}
+
 /// Build the per-poll display lines (option bars, vote totals, mode footer).
 fn build_poll_display(
     poll: &PollData,
@@ -3,12 +3,15 @@
 //! Draws the bordered text input pane at the bottom of the chat area:
 //! mode-coloured border, optional `replying:` / `editing…` title,
 //! optional attachment badge, the buffer text with horizontal +
-//! vertical scrolling tied to the cursor position, and the placeholder
+//! vertical scrolling tied to the cursor position and a live preview of
+//! its `*bold*`-style formatting markup, and the placeholder
 //! shown when both buffer and badge are empty. Sets the terminal
 //! cursor position only in Insert mode and writes
 //! `app.mouse.input_prefix_len` so click-to-position routing knows
 //! where the prefix ends and the editable text begins.
 
+use std::path::Path;
+
 use ratatui::{
     Frame,
     layout::Rect,
@@ -19,6 +22,9 @@ use ratatui::{
 
 use super::truncate;
 use crate::app::{App, InputMode};
+use crate::domain::{Markup, parse_markup};
+use crate::signal::types::StyleType;
+use crate::theme::Theme;
 
 pub(super) fn draw_input(frame: &mut Frame, app: &mut App, area: Rect) {
     let theme = &app.theme;
@@ -50,26 +56,25 @@ pub(super) fn draw_input(frame: &mut Frame, app: &mut App, area: Rect) {
         )));
     }
 
-    // Build attachment badge if present
-    let badge = app.pending_attachment.as_ref().map(|path| {
-        let fname = path
-            .file_name()
-            .map(|f| f.to_string_lossy().to_string())
-            .unwrap_or_else(|| "file".to_string());
-        // Detect type hint from extension
-        let ext = path
-            .extension()
-            .map(|e| e.to_string_lossy().to_lowercase())
-            .unwrap_or_default();
-        let type_hint = match ext.as_str() {
-            "jpg" | "jpeg" | "png" | "gif" | "webp" | "bmp" | "svg" => "image",
-            "mp4" | "mov" | "avi" | "mkv" | "webm" => "video",
-            "mp3" | "ogg" | "flac" | "wav" | "m4a" | "aac" => "audio",
-            "pdf" | "doc" | "docx" | "txt" | "md" => "doc",
-            _ => "file",
-        };
-        format!("[{type_hint}: {fname}] ")
-    });
+    // Build attachment badge if present: the file for one, a count for several
+    let badge = match app.pending_attachments.as_slice() {
+        [] => None,
+        [path] => {
+            let fname = path
+                .file_name()
+                .map(|f| f.to_string_lossy().to_string())
+                .unwrap_or_else(|| "file".to_string());
+            Some(format!("[{}: {fname}] ", attachment_type_hint(path)))
+        }
+        paths => {
+            let kind = if paths.iter().all(|p| attachment_type_hint(p) == "image") {
+                "images"
+            } else {
+                "files"
+            };
+            Some(format!("[{} {kind}] ", paths.len()))
+        }
+    };
     let badge_len = badge.as_ref().map(|b| b.len()).unwrap_or(0);
 
     // Available width inside the border (minus border cells on each side)
@@ -100,6 +105,8 @@ pub(super) fn draw_input(frame: &mut Frame, app: &mut App, area: Rect) {
             0
         };
 
+        let markup = parse_markup(&app.input.buffer);
+        let mut line_start = 0;
         let mut text_lines: Vec<Line> = Vec::new();
         for (i, line_str) in lines.iter().enumerate() {
             let mut spans: Vec<Span> = Vec::new();
@@ -120,18 +127,20 @@ pub(super) fn draw_input(frame: &mut Frame, app: &mut App, area: Rect) {
                 ));
             }
 
-            if i == cursor_line {
-                let char_scroll = cursor_col.saturating_sub(text_width);
-                let visible_text: String = line_str
-                    .chars()
-                    .skip(char_scroll)
-                    .take(text_width)
-                    .collect();
-                spans.push(Span::styled(visible_text, Style::default().fg(theme.fg)));
+            let char_scroll = if i == cursor_line {
+                cursor_col.saturating_sub(text_width)
             } else {
-                let visible_text: String = line_str.chars().take(text_width).collect();
-                spans.push(Span::styled(visible_text, Style::default().fg(theme.fg)));
-            }
+                0
+            };
+            spans.extend(preview_spans(
+                line_str,
+                line_start,
+                char_scroll,
+                text_width,
+                &markup,
+                theme,
+            ));
+            line_start += line_str.len() + 1;
             text_lines.push(Line::from(spans));
         }
 
@@ -156,3 +165,70 @@ pub(super) fn draw_input(frame: &mut Frame, app: &mut App, area: Rect) {
         frame.set_cursor_position((cursor_x, cursor_y));
     }
 }
+
+/// Visible part of one buffer line, styled as it will be sent: formatted runs
+/// take their style and the markup characters themselves are dimmed.
+/// `line_start` is the line's byte offset in the buffer the markup covers.
+fn preview_spans(
+    line: &str,
+    line_start: usize,
+    skip: usize,
+    take: usize,
+    markup: &Markup,
+    theme: &Theme,
+) -> Vec<Span<'static>> {
+    let marker_style = Style::default()
+        .fg(theme.fg_muted)
+        .add_modifier(Modifier::DIM);
+    let style_at = |pos: usize| -> Style {
+        if markup
+            .marker_ranges()
+            .iter()
+            .any(|&(start, end)| start <= pos && pos < end)
+        {
+            return marker_style;
+        }
+        let mut style = Style::default().fg(theme.fg);
+        for &(start, end, kind) in markup.preview_ranges() {
+            if start <= pos && pos < end {
+                style = match kind {
+                    StyleType::Bold => style.add_modifier(Modifier::BOLD),
+                    StyleType::Italic => style.add_modifier(Modifier::ITALIC),
+                    StyleType::Strikethrough => style.add_modifier(Modifier::CROSSED_OUT),
+                    StyleType::Monospace => style.fg(theme.fg_muted),
+                    StyleType::Spoiler => style.bg(theme.bg_selected),
+                };
+            }
+        }
+        style
+    };
+
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut run = String::new();
+    let mut run_style = Style::default().fg(theme.fg);
+    for (offset, ch) in line.char_indices().skip(skip).take(take) {
+        let style = style_at(line_start + offset);
+        if style != run_style && !run.is_empty() {
+            spans.push(Span::styled(std::mem::take(&mut run), run_style));
+        }
+        run_style = style;
+        run.push(ch);
+    }
+    spans.push(Span::styled(run, run_style));
+    spans
+}
+
+/// Rough kind of a queued file, from its extension.
+fn attachment_type_hint(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "jpg" | "jpeg" | "png" | "gif" | "webp" | "bmp" | "svg" => "image",
+        "mp4" | "mov" | "avi" | "mkv" | "webm" => "video",
+        "mp3" | "ogg" | "flac" | "wav" | "m4a" | "aac" => "audio",
+        "pdf" | "doc" | "docx" | "txt" | "md" => "doc",
+        _ => "file",
+    }
+}
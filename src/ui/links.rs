@@ -10,6 +10,8 @@
 //!   after rendering to find link runs (handles wrapped lines).
 //! - `split_spans_by_newline` is a generic "split spans on `\n`"
 //!   helper used by chat_pane to wrap multi-line bodies.
+//! - `attachment_spans` builds the `[image: ...]` / `[attachment: ...]`
+//!   labels for a message's attachments, with their hidden `file:///` URIs.
 //! - `styled_uri_spans` builds the styled `Vec<Span>` for a message
 //!   body, layering URI/mention/spoiler/bold/italic styles together.
 
@@ -20,6 +22,7 @@ use ratatui::{
     text::Span,
 };
 
+use crate::domain::MessageAttachment;
 use crate::signal::types::StyleType;
 use crate::theme::Theme;
 
@@ -174,18 +177,42 @@ pub(in crate::ui) fn split_spans_by_newline(spans: Vec<Span<'static>>) -> Vec<Ve
     lines
 }
 
+/// Label spans for a message's attachments, space-separated. A label whose
+/// file is on disk is link-styled, and its `file:///` URI is returned keyed
+/// by the label text so the OSC 8 pass can link it without showing the URI.
+pub(in crate::ui) fn attachment_spans(
+    attachments: &[MessageAttachment],
+    theme: &Theme,
+) -> (Vec<Span<'static>>, Vec<(String, String)>) {
+    let link_style = Style::default()
+        .fg(theme.link)
+        .add_modifier(Modifier::UNDERLINED);
+    let mut spans = Vec::new();
+    let mut hidden_urls = Vec::new();
+    for (i, attachment) in attachments.iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::raw(" "));
+        }
+        let label = attachment.label();
+        match attachment.file_uri() {
+            Some(uri) => {
+                hidden_urls.push((label.clone(), uri));
+                spans.push(Span::styled(label, link_style));
+            }
+            None => spans.push(Span::raw(label)),
+        }
+    }
+    (spans, hidden_urls)
+}
+
 /// Split a message body into spans, styling any URI (https://, http://, file:///) as
 /// underlined blue text. Non-URI text is rendered as plain spans.
-///
-/// Returns `(spans, Option<hidden_url>)`. For attachment bodies like
-/// `[image: label](file:///path)`, the bracket text is the visible link and
-/// the URI inside parens is returned separately (not displayed).
 pub(in crate::ui) fn styled_uri_spans(
     body: &str,
     mention_ranges: &[(usize, usize)],
     style_ranges: &[(usize, usize, StyleType)],
     theme: &Theme,
-) -> (Vec<Span<'static>>, Option<String>) {
+) -> Vec<Span<'static>> {
     let link_style = Style::default()
         .fg(theme.link)
         .add_modifier(Modifier::UNDERLINED);
@@ -193,33 +220,6 @@ pub(in crate::ui) fn styled_uri_spans(
         .fg(theme.mention)
         .add_modifier(Modifier::BOLD);
 
-    // Attachment/image patterns: extract bracket text as display, URI as hidden metadata
-    if body.starts_with("[image:") || body.starts_with("[attachment:") {
-        // Extract the bracket portion: [image: label] or [attachment: label]
-        if let Some(bracket_end) = body.find(']') {
-            let display_text = &body[..=bracket_end]; // e.g. "[image: photo.jpg]"
-
-            // Extract URI from either new format ](file:///...) or old format ] file:///...
-            let hidden_url = if let Some(uri_pos) = body.find("file:///") {
-                let uri_start = &body[uri_pos..];
-                // End at whitespace, closing paren, or end of string
-                let uri_end = uri_start
-                    .find(|c: char| c.is_whitespace() || c == ')')
-                    .unwrap_or(uri_start.len());
-                Some(uri_start[..uri_end].to_string())
-            } else {
-                None
-            };
-
-            if hidden_url.is_some() {
-                return (
-                    vec![Span::styled(display_text.to_string(), link_style)],
-                    hidden_url,
-                );
-            }
-        }
-    }
-
     // Build a sorted list of styled regions: mentions and URIs
     // Each region: (byte_start, byte_end, style)
     let mut regions: Vec<(usize, usize, Style)> = Vec::new();
@@ -278,7 +278,7 @@ pub(in crate::ui) fn styled_uri_spans(
         if pos < body.len() {
             spans.push(Span::raw(body[pos..].to_string()));
         }
-        return (spans, None);
+        return spans;
     }
 
     // With text styles: collect all boundary points and build segments where
@@ -359,7 +359,7 @@ pub(in crate::ui) fn styled_uri_spans(
         }
     }
 
-    (spans, None)
+    spans
 }
 
 #[cfg(test)]
@@ -20,24 +20,33 @@ pub use links::LinkRegion;
 use links::collect_link_regions;
 use overlays::about::draw_about;
 use overlays::action_menu::{draw_action_menu, draw_delete_confirm};
+use overlays::attachments::draw_attachments;
+use overlays::calls::draw_calls;
 use overlays::contacts::draw_contacts;
 use overlays::delete_conversation_confirm::draw_delete_conversation_confirm;
+use overlays::devices::draw_devices;
+use overlays::edit_history::draw_edit_history;
 use overlays::emoji_picker::draw_emoji_picker;
 use overlays::file_browser::draw_file_browser;
 use overlays::forward::draw_forward;
 use overlays::group_menu::draw_group_menu;
 use overlays::help::draw_help;
 use overlays::keybindings::draw_keybindings;
+use overlays::message_info::draw_message_info;
 use overlays::message_request::draw_message_request;
 use overlays::pin_duration::draw_pin_duration_picker;
 use overlays::poll_vote::draw_poll_vote_overlay;
 use overlays::profile::draw_profile;
+use overlays::rate_limit::draw_rate_limit;
 use overlays::reaction_picker::draw_reaction_picker;
 use overlays::search::draw_search;
 use overlays::settings::{draw_customize, draw_settings};
 use overlays::settings_profile::draw_settings_profile_manager;
+use overlays::sticker_picker::draw_sticker_picker;
+use overlays::stories::draw_stories;
 use overlays::theme_picker::draw_theme_picker;
 use overlays::verify::draw_verify;
+use overlays::view_once::draw_view_once;
 use sidebar::draw_sidebar;
 use status_bar::draw_status_bar;
 
@@ -49,7 +58,9 @@ use ratatui::{
     widgets::{Block, BorderType, Borders, Clear},
 };
 
-use crate::app::{App, OverlayKind};
+use crate::app::{App, OverlayKind, VisibleImage};
+use crate::domain::{AVATAR_DETAIL_WIDTH, ImageMode};
+use crate::image_render::ImageProtocol;
 use crate::signal::types::MessageStatus;
 use crate::theme::Theme;
 
@@ -60,11 +71,12 @@ pub(super) const MSG_WINDOW_MULTIPLIER: usize = 10;
 
 // Popup dimensions
 pub(super) const SETTINGS_POPUP_WIDTH: u16 = 50;
-pub(super) const SETTINGS_POPUP_HEIGHT: u16 = 25;
+pub(super) const SETTINGS_POPUP_HEIGHT: u16 = 26;
 pub(super) const CONTACTS_POPUP_WIDTH: u16 = 50;
 pub(super) const CONTACTS_MAX_VISIBLE: usize = 20;
 pub(super) const FILE_BROWSER_POPUP_WIDTH: u16 = 60;
 pub(super) const FILE_BROWSER_MAX_VISIBLE: usize = 20;
+pub(super) const ATTACHMENTS_POPUP_WIDTH: u16 = 56;
 pub(super) const SEARCH_POPUP_WIDTH: u16 = 60;
 pub(super) const SEARCH_MAX_VISIBLE: usize = 15;
 pub(super) const GROUP_MENU_POPUP_WIDTH: u16 = 40;
@@ -73,6 +85,15 @@ pub(super) const ABOUT_POPUP_WIDTH: u16 = 50;
 pub(super) const PROFILE_POPUP_WIDTH: u16 = 50;
 pub(super) const EMOJI_POPUP_WIDTH: u16 = 52;
 pub(super) const EMOJI_POPUP_HEIGHT: u16 = 20;
+pub(super) const STORIES_POPUP_WIDTH: u16 = 60;
+pub(super) const CALLS_POPUP_WIDTH: u16 = 64;
+pub(super) const DEVICES_POPUP_WIDTH: u16 = 72;
+pub(super) const EDIT_HISTORY_POPUP_WIDTH: u16 = 64;
+pub(super) const EDIT_HISTORY_MAX_VISIBLE: usize = 6;
+pub(super) const MESSAGE_INFO_POPUP_WIDTH: u16 = 64;
+pub(super) const RATE_LIMIT_POPUP_WIDTH: u16 = 60;
+pub(super) const STICKER_POPUP_WIDTH: u16 = 52;
+pub(super) const STICKER_POPUP_HEIGHT: u16 = 16;
 
 /// Map a MessageStatus to its display symbol and color.
 pub(crate) fn status_symbol(
@@ -83,6 +104,7 @@ pub(crate) fn status_symbol(
 ) -> (&'static str, Color) {
     let (unicode_sym, nerd_sym, colored) = match status {
         MessageStatus::Failed => ("\u{2717}", "\u{f055c}", theme.receipt_failed),
+        MessageStatus::Queued => ("\u{25f7}", "\u{f0150}", theme.receipt_sending),
         MessageStatus::Sending => ("\u{25cc}", "\u{f0996}", theme.receipt_sending),
         MessageStatus::Sent => ("\u{25cb}", "\u{f0954}", theme.receipt_sent),
         MessageStatus::Delivered => ("\u{2713}", "\u{f012c}", theme.receipt_delivered),
@@ -129,6 +151,69 @@ pub(crate) fn build_separator(label: &str, width: usize, style: Style) -> Line<'
     ))
 }
 
+/// Width the avatar column takes in detail views: indent plus the avatar.
+pub(super) const AVATAR_COLUMN_WIDTH: usize = 2 + AVATAR_DETAIL_WIDTH as usize;
+
+/// Lay `text` out to the right of an avatar in a detail view. The text
+/// lines keep their own indent, which doubles as the gap after the avatar.
+/// Without an avatar `text` is returned as it is.
+pub(super) fn beside_avatar(
+    avatar: Option<&Vec<Line<'static>>>,
+    text: Vec<Line<'static>>,
+) -> Vec<Line<'static>> {
+    let Some(avatar) = avatar else {
+        return text;
+    };
+    let rows = avatar.len().max(text.len());
+    let mut text = text.into_iter();
+    (0..rows)
+        .map(|row| {
+            let mut spans = vec![Span::raw("  ")];
+            match avatar.get(row) {
+                Some(line) => spans.extend(line.spans.iter().cloned()),
+                None => spans.push(Span::raw(" ".repeat(AVATAR_DETAIL_WIDTH as usize))),
+            }
+            if let Some(line) = text.next() {
+                spans.extend(line.spans);
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// Show `id`'s avatar with the native image protocol over its halfblock
+/// rows, which [`beside_avatar`] put at the top left of `inner`. Does
+/// nothing in halfblock mode, before the native rendering is ready, or
+/// when the avatar doesn't fit.
+pub(super) fn place_avatar(frame: &mut Frame, app: &mut App, id: &str, inner: Rect) {
+    if app.image.image_mode != ImageMode::Native
+        || app.image.image_protocol == ImageProtocol::Halfblock
+    {
+        return;
+    }
+    let Some(path) = app.avatars.native.get(id) else {
+        return;
+    };
+    let width = AVATAR_DETAIL_WIDTH as u16;
+    let height = width / 2;
+    if inner.width < 2 + width || inner.height < height {
+        return;
+    }
+    let img = VisibleImage {
+        x: inner.x + 2,
+        y: inner.y,
+        width,
+        height,
+        full_height: height,
+        crop_top: 0,
+        path: path.clone(),
+    };
+    if app.image.image_protocol == ImageProtocol::Kitty {
+        chat_pane::patch_kitty_image(frame, &mut app.image, &img);
+    }
+    app.image.overlay_images.push(img);
+}
+
 /// Create a centered popup overlay: clears the area, returns the Rect and a styled Block.
 /// Preferred width/height are clamped to fit within the terminal.
 pub(super) fn centered_popup(
@@ -166,6 +251,7 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
     }
     app.image.link_url_map.clear();
     app.image.visible_images.clear();
+    app.image.overlay_images.clear();
     let size = frame.area();
     let terminal_width = size.width;
 
@@ -247,6 +333,21 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
         draw_contacts(frame, app, size);
     }
 
+    // Stories overlay
+    if app.is_overlay(OverlayKind::Stories) {
+        draw_stories(frame, app, size);
+    }
+
+    // Call history overlay
+    if app.is_overlay(OverlayKind::Calls) {
+        draw_calls(frame, app, size);
+    }
+
+    // Linked devices overlay
+    if app.is_overlay(OverlayKind::Devices) {
+        draw_devices(frame, app, size);
+    }
+
     // Verify identity overlay
     if app.is_overlay(OverlayKind::Verify) {
         draw_verify(frame, app, size);
@@ -277,6 +378,26 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
         draw_action_menu(frame, app, size);
     }
 
+    // Attachment list overlay (from the action menu on an album)
+    if app.is_overlay(OverlayKind::Attachments) {
+        draw_attachments(frame, app, size);
+    }
+
+    // Edit history overlay (from the action menu on an edited message)
+    if app.is_overlay(OverlayKind::EditHistory) {
+        draw_edit_history(frame, app, size);
+    }
+
+    // Message info overlay (receipts and reactions, from the action menu)
+    if app.is_overlay(OverlayKind::MessageInfo) {
+        draw_message_info(frame, app, size);
+    }
+
+    // Rate-limit overlay (a send was refused by Signal's rate limit)
+    if app.is_overlay(OverlayKind::RateLimit) {
+        draw_rate_limit(frame, app, size);
+    }
+
     // Reaction picker overlay
     if app.is_overlay(OverlayKind::ReactionPicker) {
         draw_reaction_picker(frame, app, size);
@@ -287,6 +408,11 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
         draw_emoji_picker(frame, app, size);
     }
 
+    // Sticker picker overlay
+    if app.is_overlay(OverlayKind::StickerPicker) {
+        draw_sticker_picker(frame, app, size);
+    }
+
     // Delete confirmation overlay
     if app.is_overlay(OverlayKind::DeleteConfirm) {
         draw_delete_confirm(frame, app, size);
@@ -337,6 +463,11 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
         draw_forward(frame, app, size);
     }
 
+    // View-once reveal (full frame)
+    if app.is_overlay(OverlayKind::ViewOnce) {
+        draw_view_once(frame, app, size);
+    }
+
     // Collect link regions from the rendered buffer for OSC 8 injection
     let area = frame.area();
     app.image.link_regions = collect_link_regions(frame.buffer_mut(), area, app.theme.link);
@@ -398,6 +529,7 @@ mod tests {
 
     #[rstest]
     #[case(MessageStatus::Failed, "\u{2717}")]
+    #[case(MessageStatus::Queued, "\u{25f7}")]
     #[case(MessageStatus::Sending, "\u{25cc}")]
     #[case(MessageStatus::Sent, "\u{25cb}")]
     #[case(MessageStatus::Delivered, "\u{2713}")]
@@ -512,8 +644,10 @@ mod snapshot_tests {
                 timestamp: chrono::Utc::now(),
                 body: "line one\nline two".to_string(),
                 is_system: false,
+                is_warning: false,
+                attachments: Vec::new(),
                 image_lines: None,
-                image_path: None,
+                image_tiles: Vec::new(),
                 status: None,
                 timestamp_ms: 1_700_000_000_000,
                 reactions: Vec::new(),
@@ -533,6 +667,8 @@ mod snapshot_tests {
                 preview: None,
                 preview_image_lines: None,
                 preview_image_path: None,
+                view_once: None,
+                shared_contacts: Vec::new(),
             });
         }
         let output = render_to_string(&mut app, 100, 30);
@@ -548,6 +684,354 @@ mod snapshot_tests {
         );
     }
 
+    #[test]
+    fn attachments_render_as_labels_before_body() {
+        use crate::domain::{DownloadState, MessageAttachment};
+        let mut app = demo_app();
+        let conv_id = app.active_conversation.clone().unwrap();
+        let attachment = |content_type: &str, name: &str, path: Option<&str>| MessageAttachment {
+            content_type: content_type.to_string(),
+            filename: Some(name.to_string()),
+            size: None,
+            local_path: path.map(str::to_string),
+            width: None,
+            height: None,
+            state: if path.is_some() {
+                DownloadState::Downloaded
+            } else {
+                DownloadState::Pending
+            },
+        };
+        if let Some(conv) = app.store.conversations.get_mut(&conv_id) {
+            let msg = conv.messages.last_mut().unwrap();
+            msg.body = "see attached".to_string();
+            msg.attachments = vec![
+                attachment("application/pdf", "doc.pdf", Some("/tmp/doc.pdf")),
+                attachment("image/png", "shot.png", None),
+            ];
+            msg.mention_ranges.clear();
+            msg.style_ranges.clear();
+        }
+        let output = render_to_string(&mut app, 100, 30);
+        assert!(
+            output.contains("[attachment: doc.pdf] [image: shot.png] see attached"),
+            "expected attachment labels ahead of the body; got:\n{output}"
+        );
+        assert_eq!(
+            app.image
+                .link_url_map
+                .get("[attachment: doc.pdf]")
+                .map(String::as_str),
+            Some("file:///tmp/doc.pdf")
+        );
+        assert!(!app.image.link_url_map.contains_key("[image: shot.png]"));
+    }
+
+    #[test]
+    fn album_renders_as_thumbnail_grid_with_native_tiles() {
+        use crate::domain::MessageAttachment;
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = demo_app();
+        let conv_id = app.active_conversation.clone().unwrap();
+        if let Some(conv) = app.store.conversations.get_mut(&conv_id) {
+            let msg = conv.messages.last_mut().unwrap();
+            msg.body = "trip".to_string();
+            msg.mention_ranges.clear();
+            msg.style_ranges.clear();
+            msg.attachments = (0..3)
+                .map(|i| {
+                    let path = dir.path().join(format!("{i}.png"));
+                    image::RgbaImage::from_pixel(36, 36, image::Rgba([0, 120, 200, 255]))
+                        .save(&path)
+                        .unwrap();
+                    MessageAttachment::from_path(&path, None)
+                })
+                .collect();
+            msg.render_images();
+        }
+        app.image.image_mode = crate::domain::ImageMode::Native;
+        app.image.image_protocol = ImageProtocol::Sixel;
+
+        let output = render_to_string(&mut app, 100, 30);
+        assert!(output.contains("[image: 0.png] [image: 1.png] [image: 2.png] trip"));
+        // Three thumbnails side by side, one native placement each
+        let placed: Vec<(u16, u16, u16)> = app
+            .image
+            .visible_images
+            .iter()
+            .map(|img| (img.y, img.width, img.height))
+            .collect();
+        assert_eq!(placed.len(), 3);
+        assert!(placed.iter().all(|&p| p == placed[0]));
+        let xs: Vec<u16> = app.image.visible_images.iter().map(|img| img.x).collect();
+        assert_eq!(xs[1] - xs[0], 19);
+        assert_eq!(xs[2] - xs[1], 19);
+    }
+
+    #[test]
+    fn composer_previews_markup() {
+        let mut app = demo_app();
+        app.input.buffer = "say *hi* now".to_string();
+        app.input.cursor = app.input.buffer.len();
+        let backend = TestBackend::new(100, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|frame| draw(frame, &mut app)).unwrap();
+        let buffer = terminal.backend().buffer();
+
+        // Find the composer line and check the styled run and its markers
+        let y = (0..buffer.area.height)
+            .find(|&y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+                    .contains("say *hi* now")
+            })
+            .expect("composer line");
+        let row: Vec<_> = (0..buffer.area.width).map(|x| &buffer[(x, y)]).collect();
+        let star = row.iter().position(|c| c.symbol() == "*").unwrap();
+        assert!(row[star].modifier.contains(Modifier::DIM));
+        assert!(row[star + 1].modifier.contains(Modifier::BOLD));
+        assert!(row[star + 2].modifier.contains(Modifier::BOLD));
+        assert!(row[star + 3].modifier.contains(Modifier::DIM));
+        assert!(!row[star - 2].modifier.contains(Modifier::BOLD));
+    }
+
+    #[test]
+    fn edit_history_shows_versions_and_word_diff() {
+        use crate::domain::MessageRevision;
+        let mut app = demo_app();
+        let conv_id = app.active_conversation.clone().unwrap();
+        let revision = |revision_ms: i64, body: &str| MessageRevision {
+            revision_ms,
+            body: body.to_string(),
+            style_ranges: Vec::new(),
+            is_outgoing: true,
+        };
+        app.edit_history.conv_id = conv_id;
+        app.edit_history.revisions = vec![
+            revision(1_700_000_000_000, "meet at the cafe"),
+            revision(1_700_000_060_000, "meet at the park"),
+        ];
+        app.edit_history.select(1);
+        app.open_overlay(OverlayKind::EditHistory);
+
+        let backend = TestBackend::new(100, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|frame| draw(frame, &mut app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let rows: Vec<String> = (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect()
+            })
+            .collect();
+        assert!(
+            rows.iter()
+                .any(|r| r.contains("Original") && r.contains("you"))
+        );
+        assert!(rows.iter().any(|r| r.contains("Edit 1")));
+
+        // Removed word struck through, added word in bold
+        let y = rows
+            .iter()
+            .position(|r| r.contains("meet at the cafepark"))
+            .expect("diff line") as u16;
+        let row = &rows[y as usize];
+        let x = row[..row.find("cafepark").unwrap()].chars().count() as u16;
+        assert!(buffer[(x, y)].modifier.contains(Modifier::CROSSED_OUT));
+        assert!(buffer[(x + 4, y)].modifier.contains(Modifier::BOLD));
+        assert!(!buffer[(x - 2, y)].modifier.contains(Modifier::CROSSED_OUT));
+    }
+
+    #[test]
+    fn message_info_shows_receipts_and_reactions() {
+        use crate::domain::{ReactionDetail, RecipientReceipt};
+        let mut app = demo_app();
+        let conv_id = app.active_conversation.clone().unwrap();
+        let sent_ms = app.store.conversations[&conv_id]
+            .messages
+            .last()
+            .unwrap()
+            .timestamp_ms;
+        app.message_info.conv_id = conv_id.clone();
+        app.message_info.timestamp_ms = sent_ms;
+        app.message_info.is_outgoing = true;
+        app.message_info.receipts = vec![
+            RecipientReceipt {
+                recipient: conv_id.clone(),
+                delivered_ms: Some(sent_ms),
+                read_ms: Some(sent_ms),
+                viewed_ms: None,
+            },
+            RecipientReceipt {
+                recipient: "+19995550000".to_string(),
+                ..Default::default()
+            },
+        ];
+        app.message_info.reactions = vec![ReactionDetail {
+            sender: "you".to_string(),
+            emoji: "\u{1f44d}".to_string(),
+            reacted_at_ms: None,
+        }];
+        app.open_overlay(OverlayKind::MessageInfo);
+
+        let output = render_to_string(&mut app, 100, 30);
+        let name = app.conversation_name(&conv_id).to_string();
+        let lines: Vec<&str> = output.lines().collect();
+        let header = lines
+            .iter()
+            .position(|l| l.contains("Recipient"))
+            .unwrap_or_else(|| panic!("no receipts header:\n{output}"));
+        let row = lines[header + 1];
+        assert!(row.contains(&name), "got: {row}");
+        // Delivered and read times filled in, viewed still pending
+        let cells: Vec<&str> = row.split('\u{2502}').collect();
+        let popup_row = cells.iter().find(|c| c.contains(&name)).unwrap();
+        assert_eq!(popup_row.matches(':').count(), 2, "got: {row}");
+        assert!(popup_row.trim_end().ends_with('-'), "got: {row}");
+        assert!(lines[header + 2].contains("+19995550000"));
+        assert!(output.contains("Reactions"));
+        assert!(output.contains("you"));
+    }
+
+    #[test]
+    fn verify_flags_a_changed_safety_number() {
+        use crate::signal::types::{IdentityInfo, TrustLevel};
+        let mut app = demo_app();
+        let conv_id = app.active_conversation.clone().unwrap();
+        app.verify.identities = vec![IdentityInfo {
+            number: Some(conv_id.clone()),
+            uuid: None,
+            fingerprint: String::new(),
+            safety_number: "1".repeat(60),
+            trust_level: TrustLevel::TrustedUnverified,
+            added_timestamp: 0,
+        }];
+        app.open_overlay(OverlayKind::Verify);
+        let output = render_to_string(&mut app, 100, 30);
+        assert!(!output.contains("Safety number changed"), "got:\n{output}");
+
+        app.identity_changes.insert(conv_id);
+        let output = render_to_string(&mut app, 100, 30);
+        assert!(output.contains("Safety number changed"), "got:\n{output}");
+        assert!(output.contains("a: acknowledge"), "got:\n{output}");
+    }
+
+    #[test]
+    fn shared_contact_renders_as_card() {
+        use crate::signal::types::{ContactPoint, SharedContact};
+        let mut app = demo_app();
+        let conv_id = app.active_conversation.clone().unwrap();
+        if let Some(conv) = app.store.conversations.get_mut(&conv_id) {
+            let msg = conv.messages.last_mut().unwrap();
+            msg.body = "[Contact: Dana Scully]".to_string();
+            msg.mention_ranges.clear();
+            msg.style_ranges.clear();
+            msg.shared_contacts = vec![SharedContact {
+                name: "Dana Scully".to_string(),
+                organization: Some("FBI".to_string()),
+                phones: vec![ContactPoint {
+                    value: "+15550001111".to_string(),
+                    label: Some("Mobile".to_string()),
+                }],
+                emails: vec![ContactPoint {
+                    value: "dana@example.com".to_string(),
+                    label: None,
+                }],
+            }];
+        }
+        let output = render_to_string(&mut app, 100, 30);
+        let lines: Vec<&str> = output.lines().collect();
+        let top = lines
+            .iter()
+            .position(|l| l.contains("\u{251C} Dana Scully"))
+            .unwrap_or_else(|| panic!("no card:\n{output}"));
+        assert!(lines[top + 1].contains("\u{251C} FBI"), "got:\n{output}");
+        assert!(
+            lines[top + 2].contains("\u{251C} Mobile  +15550001111"),
+            "got:\n{output}"
+        );
+        assert!(
+            lines[top + 3].contains("\u{2570} Other   dana@example.com"),
+            "got:\n{output}"
+        );
+    }
+
+    #[test]
+    fn composer_badge_counts_several_attachments() {
+        let mut app = demo_app();
+        app.pending_attachments = vec![
+            std::path::PathBuf::from("/tmp/a.jpg"),
+            std::path::PathBuf::from("/tmp/b.png"),
+        ];
+        let output = render_to_string(&mut app, 100, 30);
+        assert!(output.contains("[2 images]"), "got:\n{output}");
+
+        app.pending_attachments
+            .push(std::path::PathBuf::from("/tmp/notes.pdf"));
+        let output = render_to_string(&mut app, 100, 30);
+        assert!(output.contains("[3 files]"), "got:\n{output}");
+    }
+
+    #[test]
+    fn spoilers_stay_masked_until_revealed() {
+        use crate::signal::types::StyleType;
+        let mut app = demo_app();
+        let conv_id = app.active_conversation.clone().unwrap();
+        let styles = vec![(4, 10, StyleType::Spoiler)];
+        let timestamp_ms = {
+            let conv = app.store.conversations.get_mut(&conv_id).unwrap();
+            let msg = conv.messages.last_mut().unwrap();
+            msg.body = "the butler did it".to_string();
+            msg.mention_ranges.clear();
+            msg.style_ranges = styles.clone();
+            msg.timestamp_ms
+        };
+
+        let output = render_to_string(&mut app, 100, 30);
+        assert!(output.contains("the \u{2588}\u{2588}\u{2588}\u{2588}\u{2588}\u{2588} did it"));
+        assert!(!output.contains("butler"));
+
+        // Search results are masked too
+        app.db
+            .upsert_conversation(&conv_id, "Alice", false)
+            .unwrap();
+        app.db
+            .insert_message(
+                &conv_id,
+                "Alice",
+                "2025-01-01T00:00:00Z",
+                "the butler did it",
+                false,
+                None,
+                timestamp_ms,
+            )
+            .unwrap();
+        app.db
+            .set_message_styles(&conv_id, timestamp_ms, &styles)
+            .unwrap();
+        app.search.query = "butler".to_string();
+        app.search.run(Some(&conv_id), &app.db);
+        assert_eq!(app.search.results.len(), 1);
+        app.open_overlay(OverlayKind::Search);
+        let output = render_to_string(&mut app, 100, 30);
+        assert!(
+            output.contains("Alice: the \u{2588}\u{2588}\u{2588}\u{2588}\u{2588}\u{2588} did it"),
+            "got:\n{output}"
+        );
+
+        app.revealed_spoilers.insert((conv_id, timestamp_ms));
+        let output = render_to_string(&mut app, 100, 30);
+        assert!(
+            output.contains("Alice: the butler did it"),
+            "got:\n{output}"
+        );
+        app.close_overlay();
+        let output = render_to_string(&mut app, 100, 30);
+        assert!(output.contains("the butler did it"), "got:\n{output}");
+    }
+
     #[test]
     fn test_normal_vs_insert_mode() {
         let mut app = demo_app();
@@ -699,6 +1183,27 @@ mod snapshot_tests {
         insta::assert_snapshot!(output);
     }
 
+    #[test]
+    fn test_multi_account_badges() {
+        use crate::domain::AccountBadge;
+        let mut app = demo_app();
+        app.accounts.label = Some("\u{2026}9999".to_string());
+        app.accounts.badges = vec![
+            AccountBadge {
+                account: "+15559999999".to_string(),
+                unread: 0,
+                is_self: true,
+            },
+            AccountBadge {
+                account: "+15558884321".to_string(),
+                unread: 3,
+                is_self: false,
+            },
+        ];
+        let output = render_to_string(&mut app, 100, 30);
+        insta::assert_snapshot!(output);
+    }
+
     #[test]
     fn test_theme_picker_overlay() {
         let mut app = demo_app();
@@ -746,6 +1251,188 @@ mod snapshot_tests {
         insta::assert_snapshot!(output);
     }
 
+    #[test]
+    fn test_contact_menu() {
+        let mut app = demo_app();
+        app.open_overlay(OverlayKind::Contacts);
+        app.contacts_overlay.filtered = vec![("+15551234567".to_string(), "Mom".to_string())];
+        app.store.local_contacts.insert(
+            "+15551234567".to_string(),
+            crate::domain::LocalContact {
+                nickname: Some("Mom".to_string()),
+                note: Some("Call on Sundays".to_string()),
+            },
+        );
+        app.contacts_overlay.mode = crate::domain::ContactsMode::Note;
+        app.contacts_overlay.input = "Call on Sundays".to_string();
+        let output = render_to_string(&mut app, 100, 30);
+        insta::assert_snapshot!(output);
+    }
+
+    #[test]
+    fn test_contact_menu_avatar() {
+        let mut app = demo_app();
+        app.open_overlay(OverlayKind::Contacts);
+        app.contacts_overlay.filtered = vec![("+15551234567".to_string(), "Alice".to_string())];
+        app.contacts_overlay.mode = crate::domain::ContactsMode::Menu;
+        let row = Line::from("\u{2580}".repeat(AVATAR_DETAIL_WIDTH as usize));
+        app.avatars
+            .detail
+            .insert("+15551234567".to_string(), vec![row; 6]);
+        let output = render_to_string(&mut app, 100, 30);
+        insta::assert_snapshot!(output);
+    }
+
+    #[test]
+    fn test_sidebar_avatars() {
+        let mut app = demo_app();
+        app.avatars.sidebar = true;
+        let id = app.store.conversation_order[0].clone();
+        app.avatars
+            .glyphs
+            .insert(id, Line::from("\u{2580}\u{2580}"));
+        let output = render_to_string(&mut app, 100, 30);
+        insta::assert_snapshot!(output);
+    }
+
+    #[test]
+    fn test_stories_overlay() {
+        use crate::domain::Story;
+        let mut app = demo_app();
+        for (author, ts, viewed) in [
+            ("+15550001111", 1_000, false),
+            ("+15550001111", 2_000, true),
+            ("+15550002222", 3_000, true),
+        ] {
+            app.stories.insert(Story {
+                author: author.to_string(),
+                timestamp_ms: ts,
+                group_id: None,
+                text: Some("hello".to_string()),
+                content_type: None,
+                attachment_path: None,
+                allows_replies: true,
+                viewed,
+            });
+        }
+        app.open_stories();
+        let output = render_to_string(&mut app, 100, 30);
+        insta::assert_snapshot!(output);
+    }
+
+    #[test]
+    fn test_calls_overlay() {
+        use crate::domain::{CallDirection, CallOutcome, CallRecord};
+        let mut app = demo_app();
+        let calls = [
+            (
+                1,
+                CallDirection::Incoming,
+                Some(false),
+                Some(63_000),
+                CallOutcome::Answered,
+            ),
+            (
+                2,
+                CallDirection::Outgoing,
+                None,
+                Some(4_000),
+                CallOutcome::Declined,
+            ),
+            (
+                3,
+                CallDirection::Incoming,
+                Some(true),
+                Some(2_000),
+                CallOutcome::Missed,
+            ),
+        ];
+        for (id, direction, is_video, ended_ms, outcome) in calls {
+            app.db
+                .upsert_call(&CallRecord {
+                    peer: "+15550001111".to_string(),
+                    call_id: id,
+                    direction,
+                    is_video,
+                    started_ms: 1_700_000_000_000 + id * 60_000,
+                    ended_ms: ended_ms.map(|d| 1_700_000_000_000 + id * 60_000 + d),
+                    outcome,
+                })
+                .unwrap();
+        }
+        app.open_calls();
+        let output = render_to_string(&mut app, 100, 30);
+        insta::assert_snapshot!(output);
+    }
+
+    #[test]
+    fn test_devices_overlay() {
+        use crate::signal::types::Device;
+        let mut app = demo_app();
+        app.open_devices();
+        let day = 86_400_000;
+        app.devices.set_devices(vec![
+            Device {
+                id: 1,
+                name: Some("Pixel 7".to_string()),
+                created_ms: 1_700_000_000_000,
+                last_seen_ms: 1_700_000_000_000 + 30 * day,
+                is_this_device: false,
+            },
+            Device {
+                id: 2,
+                name: None,
+                created_ms: 1_700_000_000_000 + day,
+                last_seen_ms: 1_700_000_000_000 + 2 * day,
+                is_this_device: false,
+            },
+            Device {
+                id: 3,
+                name: Some("siggy".to_string()),
+                created_ms: 1_700_000_000_000 + 20 * day,
+                last_seen_ms: 1_700_000_000_000 + 30 * day,
+                is_this_device: true,
+            },
+        ]);
+        app.handle_devices_key(crossterm::event::KeyCode::Char('j'));
+        let output = render_to_string(&mut app, 100, 30);
+        insta::assert_snapshot!(output);
+    }
+
+    #[test]
+    fn test_group_permissions_overlay() {
+        use crate::app::GroupMenuState;
+        use crate::signal::types::GroupPermission;
+        let mut app = demo_app();
+        app.active_conversation = Some("group_rustdevs".to_string());
+        if let Some(group) = app.store.groups.get_mut("group_rustdevs") {
+            group.admins = vec![app.account.clone()];
+            group.permission_edit_details = GroupPermission::OnlyAdmins;
+        }
+        app.open_overlay(OverlayKind::GroupMenu);
+        app.group_menu.state = Some(GroupMenuState::Permissions);
+        app.group_menu.index = 1;
+        let output = render_to_string(&mut app, 100, 30);
+        insta::assert_snapshot!(output);
+    }
+
+    #[test]
+    fn test_profile_overlay_username() {
+        let mut app = demo_app();
+        app.profile.fields = [
+            "Demo".to_string(),
+            "User".to_string(),
+            "Testing siggy".to_string(),
+            String::new(),
+        ];
+        app.profile.username = Some("demo.42".to_string());
+        app.profile.username_link = Some("https://signal.me/#eu/q0p9X8y7W6v5U4t3S2r1".to_string());
+        app.profile.index = crate::app::PROFILE_USERNAME_INDEX;
+        app.open_overlay(OverlayKind::Profile);
+        let output = render_to_string(&mut app, 100, 30);
+        insta::assert_snapshot!(output);
+    }
+
     #[test]
     fn test_forward_overlay() {
         let mut app = demo_app();
@@ -760,6 +1447,19 @@ mod snapshot_tests {
         insta::assert_snapshot!(output);
     }
 
+    #[test]
+    fn test_rate_limit_overlay() {
+        let mut app = demo_app();
+        app.pending
+            .rate_limited_sends
+            .push(crate::app::SendRequest::ListGroups);
+        app.rate_limit.challenge = Some("chal".to_string());
+        app.rate_limit.input = "signalcaptcha://signal-hcaptcha.5fad".to_string();
+        app.open_overlay(OverlayKind::RateLimit);
+        let output = render_to_string(&mut app, 100, 30);
+        insta::assert_snapshot!(output);
+    }
+
     #[test]
     fn test_emoji_picker_overlay() {
         let mut app = demo_app();
@@ -769,6 +1469,52 @@ mod snapshot_tests {
         insta::assert_snapshot!(output);
     }
 
+    #[test]
+    fn test_sticker_picker_overlay() {
+        use crate::signal::types::{StickerInfo, StickerPack};
+        let mut app = demo_app();
+        app.sticker_picker.open();
+        app.sticker_picker.set_packs(vec![
+            StickerPack {
+                pack_id: "9acc9e8aba563d26a4994e69263e3b25".to_string(),
+                title: "Bandit the Cat".to_string(),
+                author: "Agnes Lee".to_string(),
+                installed: true,
+                stickers: ["\u{1F44B}", "\u{1F602}", "\u{2764}\u{FE0F}"]
+                    .iter()
+                    .enumerate()
+                    .map(|(id, emoji)| StickerInfo {
+                        id: id as u32,
+                        emoji: Some(emoji.to_string()),
+                    })
+                    .collect(),
+            },
+            StickerPack {
+                pack_id: "00ff".to_string(),
+                title: "Zozo".to_string(),
+                author: String::new(),
+                installed: true,
+                stickers: vec![StickerInfo { id: 0, emoji: None }],
+            },
+        ]);
+        app.sticker_picker.selected_index = 1;
+        app.open_overlay(OverlayKind::StickerPicker);
+        let output = render_to_string(&mut app, 100, 30);
+        insta::assert_snapshot!(output);
+    }
+
+    #[test]
+    fn test_view_once_overlay() {
+        let mut app = demo_app();
+        app.view_once.target = Some(("+15550001111".to_string(), 1));
+        app.view_once.sender = "Alice".to_string();
+        app.view_once.path = Some("/tmp/clip.mp4".to_string());
+        app.view_once.content_type = Some("video/mp4".to_string());
+        app.open_overlay(OverlayKind::ViewOnce);
+        let output = render_to_string(&mut app, 100, 30);
+        insta::assert_snapshot!(output);
+    }
+
     #[test]
     fn test_lock_screen() {
         let mut app = demo_app();
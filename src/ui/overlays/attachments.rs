@@ -0,0 +1,80 @@
+//! Attachment list overlay.
+//!
+//! Opened from the action menu on a message with several attachments.
+//! Lists the focused message's files in order with their size, so each
+//! image of an album can be opened or saved on its own. Files that are
+//! not on disk are muted.
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+use super::super::{ATTACHMENTS_POPUP_WIDTH, centered_popup, truncate};
+use super::file_browser::format_file_size;
+use crate::app::App;
+use crate::list_overlay;
+
+pub(in crate::ui) fn draw_attachments(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let attachments = app
+        .selected_message()
+        .map(|m| m.attachments.as_slice())
+        .unwrap_or_default();
+    let pref_height = attachments.len().clamp(1, 12) as u16 + 4;
+    let (popup_area, block) = centered_popup(
+        frame,
+        area,
+        ATTACHMENTS_POPUP_WIDTH,
+        pref_height,
+        " Attachments ",
+        theme,
+    );
+
+    let inner_height = popup_area.height.saturating_sub(2) as usize;
+    let inner_w = popup_area.width.saturating_sub(2) as usize;
+    let index = app.attachment_list.index;
+    let (visible_rows, scroll_offset) = list_overlay::scroll_layout(inner_height, 2, index);
+
+    let mut lines: Vec<Line> = Vec::new();
+    let end = (scroll_offset + visible_rows).min(attachments.len());
+    for (i, attachment) in attachments[scroll_offset..end].iter().enumerate() {
+        let is_selected = scroll_offset + i == index;
+        let on_disk = attachment.downloaded_path().is_some();
+        let size = match (on_disk, attachment.size) {
+            (false, _) => "not downloaded".to_string(),
+            (true, Some(bytes)) => format_file_size(bytes),
+            (true, None) => String::new(),
+        };
+        // "  " + label + gap + size + "  "
+        let label_max = inner_w.saturating_sub(size.chars().count() + 5).max(4);
+        let label = truncate(&attachment.label(), label_max);
+        let pad = label_max.saturating_sub(label.chars().count());
+
+        let base = if is_selected {
+            list_overlay::selection_style(theme.bg_selected, theme.fg)
+        } else {
+            Style::default().fg(theme.fg)
+        };
+        let label_style = if on_disk {
+            base
+        } else {
+            base.fg(theme.fg_muted)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {label}{} ", " ".repeat(pad)), label_style),
+            Span::styled(format!("{size}  "), base.fg(theme.fg_muted)),
+        ]));
+    }
+
+    list_overlay::append_footer(
+        &mut lines,
+        visible_rows,
+        "  j/k navigate  |  Enter/o open  |  s save  |  Esc close",
+        theme.fg_muted,
+    );
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
@@ -0,0 +1,110 @@
+//! Call history overlay.
+//!
+//! Lists recent calls across all conversations, newest first: direction
+//! arrow, peer, call type and outcome (or duration for answered calls),
+//! and when the call started. Missed calls are highlighted.
+
+use chrono::{DateTime, Local};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+use super::super::{CALLS_POPUP_WIDTH, CONTACTS_MAX_VISIBLE, centered_popup, truncate};
+use crate::app::App;
+use crate::domain::{CallDirection, CallOutcome, CallRecord, format_call_duration};
+use crate::list_overlay;
+
+/// Width of the summary column; fits "Video call, declined".
+const SUMMARY_WIDTH: usize = 20;
+
+/// "Video call, 1:02", "Voice call, missed", "Call, busy", ...
+fn call_summary(call: &CallRecord) -> String {
+    let kind = match call.is_video {
+        Some(true) => "Video call",
+        Some(false) => "Voice call",
+        None => "Call",
+    };
+    let detail = match (call.outcome, call.duration_ms()) {
+        (CallOutcome::Answered, Some(ms)) => format_call_duration(ms),
+        (outcome, _) => outcome.as_str().to_string(),
+    };
+    format!("{kind}, {detail}")
+}
+
+pub(in crate::ui) fn draw_calls(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let records = &app.calls.records;
+    let max_visible = CONTACTS_MAX_VISIBLE.min(records.len().max(1));
+    let pref_height = max_visible as u16 + 5;
+
+    let (popup_area, block) = centered_popup(
+        frame,
+        area,
+        CALLS_POPUP_WIDTH,
+        pref_height,
+        " Calls ",
+        theme,
+    );
+
+    let inner_height = popup_area.height.saturating_sub(2) as usize;
+    let (visible_rows, scroll_offset) =
+        list_overlay::scroll_layout(inner_height, 2, app.calls.index);
+
+    let mut lines: Vec<Line> = Vec::new();
+    if records.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  No calls yet",
+            Style::default().fg(theme.fg_muted),
+        )));
+    } else {
+        let end = (scroll_offset + visible_rows).min(records.len());
+        let inner_w = popup_area.width.saturating_sub(2) as usize;
+        for (i, call) in records[scroll_offset..end].iter().enumerate() {
+            let is_selected = scroll_offset + i == app.calls.index;
+            let arrow = match call.direction {
+                CallDirection::Incoming => "\u{2199} ",
+                CallDirection::Outgoing => "\u{2197} ",
+            };
+            let summary = call_summary(call);
+            let when = DateTime::from_timestamp_millis(call.started_ms)
+                .map(|t| t.with_timezone(&Local).format("%b %d %H:%M").to_string())
+                .unwrap_or_default();
+            // "  " + arrow + name + "  " + summary + "  " + when
+            let fixed = SUMMARY_WIDTH + when.chars().count() + 8;
+            let name_max = inner_w.saturating_sub(fixed).max(4);
+            let name = truncate(app.conversation_name(&call.peer), name_max);
+            let pad = name_max.saturating_sub(name.chars().count());
+
+            let missed = call.outcome == CallOutcome::Missed;
+            let base = if is_selected {
+                list_overlay::selection_style(theme.bg_selected, theme.fg)
+            } else {
+                Style::default().fg(theme.fg)
+            };
+            let detail_style = if missed {
+                base.fg(theme.error)
+            } else {
+                base.fg(theme.fg_secondary)
+            };
+            lines.push(Line::from(vec![
+                Span::styled("  ", base),
+                Span::styled(arrow, detail_style),
+                Span::styled(format!("{name}{}", " ".repeat(pad)), base),
+                Span::styled(format!("  {summary:<SUMMARY_WIDTH$}"), detail_style),
+                Span::styled(format!("  {when}"), base.fg(theme.fg_muted)),
+            ]));
+        }
+    }
+
+    list_overlay::append_footer(
+        &mut lines,
+        visible_rows,
+        "  j/k navigate  |  Enter open chat  |  Esc close",
+        theme.fg_muted,
+    );
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
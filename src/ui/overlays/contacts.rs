@@ -2,8 +2,11 @@
 //!
 //! Type-to-filter list of phone-number / display-name pairs. Shows a
 //! green checkmark next to contacts that already have a conversation
-//! and tints those names slightly muted. Selecting one opens that
-//! conversation.
+//! and tints those names slightly muted, and a pencil next to contacts
+//! with a local note. Selecting one opens that conversation; Tab swaps
+//! the list for the selected contact's avatar, details and actions, with
+//! the footer doubling as the prompt for their inputs. Opened by
+//! `/share-contact`, the same list picks a contact to send as a card.
 
 use ratatui::{
     Frame,
@@ -13,19 +16,33 @@ use ratatui::{
     widgets::Paragraph,
 };
 
-use super::super::{CONTACTS_MAX_VISIBLE, CONTACTS_POPUP_WIDTH, centered_popup, truncate};
+use super::super::{
+    AVATAR_COLUMN_WIDTH, CONTACTS_MAX_VISIBLE, CONTACTS_POPUP_WIDTH, beside_avatar, centered_popup,
+    place_avatar, truncate,
+};
+use super::devices::input_tail;
 use crate::app::App;
+use crate::domain::ContactsMode;
 use crate::list_overlay;
 
-pub(in crate::ui) fn draw_contacts(frame: &mut Frame, app: &App, area: Rect) {
+pub(in crate::ui) fn draw_contacts(frame: &mut Frame, app: &mut App, area: Rect) {
+    if app.contacts_overlay.mode != ContactsMode::List {
+        draw_contact_menu(frame, app, area);
+        return;
+    }
     let theme = &app.theme;
     let max_visible = CONTACTS_MAX_VISIBLE.min(app.contacts_overlay.filtered.len());
     let pref_height = max_visible as u16 + 5; // +3 border/title +2 footer/filter
 
+    let heading = if app.contacts_overlay.sharing {
+        "Share contact"
+    } else {
+        "Contacts"
+    };
     let title = if app.contacts_overlay.filter.is_empty() {
-        " Contacts ".to_string()
+        format!(" {heading} ")
     } else {
-        format!(" Contacts [{}] ", app.contacts_overlay.filter)
+        format!(" {heading} [{}] ", app.contacts_overlay.filter)
     };
 
     let (popup_area, block) = centered_popup(
@@ -60,8 +77,19 @@ pub(in crate::ui) fn draw_contacts(frame: &mut Frame, app: &App, area: Rect) {
             let is_selected = actual_index == app.contacts_overlay.index;
             let has_conversation = app.store.conversation_order.contains(number);
 
-            // Checkmark for contacts that already have a conversation
-            let marker = if has_conversation { " \u{2713}" } else { "  " };
+            // Checkmark for contacts that already have a conversation,
+            // pencil for those with a note
+            let has_note = app
+                .store
+                .local_contacts
+                .get(number)
+                .is_some_and(|d| d.note.is_some());
+            let marker = match (has_conversation, has_note) {
+                (true, true) => " \u{2713}\u{270e}",
+                (true, false) => " \u{2713}",
+                (false, true) => " \u{270e}",
+                (false, false) => "  ",
+            };
             let marker_style = if has_conversation {
                 Style::default().fg(theme.success)
             } else {
@@ -70,7 +98,8 @@ pub(in crate::ui) fn draw_contacts(frame: &mut Frame, app: &App, area: Rect) {
 
             // Truncate name to fit with number and marker
             let number_display = format!("  {}", number);
-            let name_max = inner_w.saturating_sub(number_display.len() + marker.len() + 2);
+            let name_max =
+                inner_w.saturating_sub(number_display.len() + marker.chars().count() + 2);
             let display_name = truncate(name, name_max);
 
             let name_style = if is_selected {
@@ -99,13 +128,110 @@ pub(in crate::ui) fn draw_contacts(frame: &mut Frame, app: &App, area: Rect) {
         }
     }
 
-    list_overlay::append_footer(
-        &mut lines,
-        visible_rows,
-        "  j/k navigate  |  Enter select  |  Esc close",
-        theme.fg_muted,
-    );
+    let footer = if app.contacts_overlay.sharing {
+        "  Enter send card  |  Esc cancel"
+    } else {
+        "  Enter open  |  Tab manage  |  Esc close"
+    };
+    list_overlay::append_footer(&mut lines, visible_rows, footer, theme.fg_muted);
 
     let popup = Paragraph::new(lines).block(block);
     frame.render_widget(popup, popup_area);
 }
+
+/// Details and actions for the selected contact, with its avatar once
+/// fetched.
+fn draw_contact_menu(frame: &mut Frame, app: &mut App, area: Rect) {
+    let theme = &app.theme;
+    let state = &app.contacts_overlay;
+    let selected = state.filtered.get(state.index);
+    let details = selected.and_then(|(number, _)| app.store.local_contacts.get(number));
+
+    let avatar = selected.and_then(|(number, _)| app.avatars.detail.get(number));
+    let inner_w = (CONTACTS_POPUP_WIDTH as usize)
+        .min(area.width.saturating_sub(4) as usize)
+        .saturating_sub(2);
+    // Room for the text column, right of the avatar if there is one
+    let text_w = inner_w.saturating_sub(if avatar.is_some() {
+        AVATAR_COLUMN_WIDTH
+    } else {
+        0
+    });
+    let label = Style::default().fg(theme.fg_muted);
+    let value = Style::default().fg(theme.fg);
+
+    let mut details_lines: Vec<Line<'static>> = Vec::new();
+    match selected {
+        Some((number, name)) => {
+            details_lines.push(Line::from(Span::styled(
+                format!("  {}", truncate(name, text_w.saturating_sub(2))),
+                value.fg(theme.accent),
+            )));
+            details_lines.push(Line::from(Span::styled(format!("  {number}"), label)));
+            details_lines.push(Line::from(""));
+            for (field, text) in [
+                ("Nickname", details.and_then(|d| d.nickname.as_deref())),
+                ("Note", details.and_then(|d| d.note.as_deref())),
+            ] {
+                let text = text.unwrap_or("-");
+                details_lines.push(Line::from(vec![
+                    Span::styled(format!("  {field:<10}"), label),
+                    Span::styled(truncate(text, text_w.saturating_sub(12)), value),
+                ]));
+            }
+        }
+        None => details_lines.push(Line::from(Span::styled("  No contact selected", label))),
+    }
+    let mut lines = beside_avatar(avatar, details_lines);
+    // Details, blank, two hint rows, blank, footer and the borders
+    let pref_height = lines.len() as u16 + 7;
+    let (popup_area, block) = centered_popup(
+        frame,
+        area,
+        CONTACTS_POPUP_WIDTH,
+        pref_height,
+        " Contact ",
+        theme,
+    );
+
+    lines.push(Line::from(""));
+    for hint in [
+        "  n nickname   e note     r rename",
+        "  d remove     a add      i import .vcf",
+    ] {
+        lines.push(Line::from(Span::styled(
+            hint,
+            Style::default().fg(theme.fg_secondary),
+        )));
+    }
+
+    let footer = match state.mode {
+        ContactsMode::List | ContactsMode::Menu => "  Esc back".to_string(),
+        ContactsMode::ConfirmRemove => {
+            let name = selected.map(|(_, name)| name.as_str()).unwrap_or("contact");
+            format!(
+                "  Remove {}? (y/n)",
+                truncate(name, inner_w.saturating_sub(16))
+            )
+        }
+        mode => {
+            let prompt = match mode {
+                ContactsMode::Nickname => "  Nickname: ",
+                ContactsMode::Note => "  Note: ",
+                ContactsMode::Rename => "  Name: ",
+                ContactsMode::Add => "  +number name: ",
+                _ => "  .vcf path: ",
+            };
+            let room = inner_w.saturating_sub(prompt.len() + 1);
+            format!("{prompt}{}\u{2588}", input_tail(&state.input, room))
+        }
+    };
+    let inner_height = popup_area.height.saturating_sub(2) as usize;
+    let visible_rows = inner_height.saturating_sub(2);
+    list_overlay::append_footer(&mut lines, visible_rows, &footer, theme.fg_muted);
+    let inner = block.inner(popup_area);
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+    if let Some(number) = avatar.and(selected).map(|(number, _)| number.clone()) {
+        place_avatar(frame, app, &number, inner);
+    }
+}
@@ -0,0 +1,135 @@
+//! Linked devices overlay.
+//!
+//! Lists the account's devices in id order: id, name, and the dates the
+//! device was linked and last seen. The primary device and the device
+//! siggy runs as are tagged. The footer doubles as the prompt for the
+//! unlink confirm and the rename and link inputs.
+
+use chrono::{DateTime, Local};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+use super::super::{CONTACTS_MAX_VISIBLE, DEVICES_POPUP_WIDTH, centered_popup, truncate};
+use crate::app::App;
+use crate::domain::DevicesMode;
+use crate::list_overlay;
+use crate::signal::types::Device;
+
+fn format_date(ms: i64) -> String {
+    if ms <= 0 {
+        return "-".to_string();
+    }
+    DateTime::from_timestamp_millis(ms)
+        .map(|t| t.with_timezone(&Local).format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// "Pixel 7 (primary)", "siggy (this device)", ...
+fn device_label(device: &Device) -> String {
+    let name = device.name.as_deref().unwrap_or("Unnamed device");
+    match (device.is_primary(), device.is_this_device) {
+        (true, true) => format!("{name} (primary, this device)"),
+        (true, false) => format!("{name} (primary)"),
+        (false, true) => format!("{name} (this device)"),
+        (false, false) => name.to_string(),
+    }
+}
+
+/// The tail of `input` that fits in `width` columns, so the cursor stays
+/// visible while pasting a long link URI.
+pub(in crate::ui) fn input_tail(input: &str, width: usize) -> String {
+    let len = input.chars().count();
+    input.chars().skip(len.saturating_sub(width)).collect()
+}
+
+pub(in crate::ui) fn draw_devices(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let state = &app.devices;
+    let devices = &state.devices;
+    let max_visible = CONTACTS_MAX_VISIBLE.min(devices.len().max(1));
+    let pref_height = max_visible as u16 + 5;
+
+    let (popup_area, block) = centered_popup(
+        frame,
+        area,
+        DEVICES_POPUP_WIDTH,
+        pref_height,
+        " Linked Devices ",
+        theme,
+    );
+
+    let inner_height = popup_area.height.saturating_sub(2) as usize;
+    let inner_w = popup_area.width.saturating_sub(2) as usize;
+    let (visible_rows, scroll_offset) = list_overlay::scroll_layout(inner_height, 2, state.index);
+
+    let mut lines: Vec<Line> = Vec::new();
+    if devices.is_empty() {
+        let text = if state.loading {
+            "  Loading devices..."
+        } else {
+            "  No devices"
+        };
+        lines.push(Line::from(Span::styled(
+            text,
+            Style::default().fg(theme.fg_muted),
+        )));
+    } else {
+        let end = (scroll_offset + visible_rows).min(devices.len());
+        for (i, device) in devices[scroll_offset..end].iter().enumerate() {
+            let is_selected = scroll_offset + i == state.index;
+            let dates = format!(
+                "  linked {}  seen {}",
+                format_date(device.created_ms),
+                format_date(device.last_seen_ms)
+            );
+            // "  " + id + "  " + name + dates
+            let fixed = dates.chars().count() + 8;
+            let name_max = inner_w.saturating_sub(fixed).max(4);
+            let name = truncate(&device_label(device), name_max);
+            let pad = name_max.saturating_sub(name.chars().count());
+
+            let base = if is_selected {
+                list_overlay::selection_style(theme.bg_selected, theme.fg)
+            } else {
+                Style::default().fg(theme.fg)
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {:>2}  ", device.id), base.fg(theme.fg_muted)),
+                Span::styled(format!("{name}{}", " ".repeat(pad)), base),
+                Span::styled(dates, base.fg(theme.fg_secondary)),
+            ]));
+        }
+    }
+
+    let footer = match state.mode {
+        DevicesMode::List => {
+            "  j/k navigate  |  d unlink  |  n rename  |  a link new  |  Esc close".to_string()
+        }
+        DevicesMode::ConfirmRemove => {
+            let name = state
+                .selected()
+                .map(device_label)
+                .unwrap_or_else(|| "device".to_string());
+            format!(
+                "  Unlink {}? (y/n)",
+                truncate(&name, inner_w.saturating_sub(16))
+            )
+        }
+        DevicesMode::Rename | DevicesMode::Link => {
+            let prompt = if state.mode == DevicesMode::Rename {
+                "  Name: "
+            } else {
+                "  sgnl:// link: "
+            };
+            let room = inner_w.saturating_sub(prompt.len() + 1);
+            format!("{prompt}{}\u{2588}", input_tail(&state.input, room))
+        }
+    };
+    list_overlay::append_footer(&mut lines, visible_rows, &footer, theme.fg_muted);
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
@@ -0,0 +1,117 @@
+//! Edit history overlay.
+//!
+//! Opened from the action menu on an edited message. The top half lists
+//! every stored version, oldest first, with when it was sent and by whom;
+//! the bottom half shows what the selected version changed from the one
+//! before it, with removed words struck through and added words
+//! highlighted. Spoilers stay masked unless the message was revealed.
+
+use chrono::{DateTime, Local};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Wrap},
+};
+
+use super::super::links::split_spans_by_newline;
+use super::super::{EDIT_HISTORY_MAX_VISIBLE, EDIT_HISTORY_POPUP_WIDTH, centered_popup};
+use crate::app::App;
+use crate::domain::DiffChunk;
+use crate::list_overlay;
+
+pub(in crate::ui) fn draw_edit_history(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let state = &app.edit_history;
+    let sender = app
+        .store
+        .conversations
+        .get(&state.conv_id)
+        .and_then(|c| c.find_msg_idx(state.timestamp_ms).map(|i| &c.messages[i]))
+        .map(|m| m.sender.as_str())
+        .unwrap_or_default();
+
+    // Diff of the selected version against the one before it
+    let mut diff_spans: Vec<Span<'static>> = Vec::new();
+    for chunk in &state.diff {
+        diff_spans.push(match chunk {
+            DiffChunk::Same(t) => Span::styled(t.clone(), Style::default().fg(theme.fg)),
+            DiffChunk::Added(t) => Span::styled(
+                t.clone(),
+                Style::default()
+                    .fg(theme.success)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            DiffChunk::Removed(t) => Span::styled(
+                t.clone(),
+                Style::default()
+                    .fg(theme.error)
+                    .add_modifier(Modifier::CROSSED_OUT),
+            ),
+        });
+    }
+    let diff_lines: Vec<Line<'static>> = split_spans_by_newline(diff_spans)
+        .into_iter()
+        .map(|mut spans| {
+            spans.insert(0, Span::raw("  "));
+            Line::from(spans)
+        })
+        .collect();
+
+    let list_rows = state.revisions.len().clamp(1, EDIT_HISTORY_MAX_VISIBLE);
+    let text_w = EDIT_HISTORY_POPUP_WIDTH.saturating_sub(4).max(1) as usize;
+    let diff_rows: usize = diff_lines
+        .iter()
+        .map(|l| l.width().div_ceil(text_w).max(1))
+        .sum();
+    // borders + list + blank + diff + blank + footer
+    let pref_height = (list_rows + diff_rows + 6) as u16;
+    let (popup_area, block) = centered_popup(
+        frame,
+        area,
+        EDIT_HISTORY_POPUP_WIDTH,
+        pref_height,
+        " Edit history ",
+        theme,
+    );
+
+    let (visible_rows, scroll_offset) = list_overlay::scroll_layout(list_rows, 0, state.index);
+    let end = (scroll_offset + visible_rows).min(state.revisions.len());
+    let mut lines: Vec<Line> = Vec::new();
+    for (i, rev) in state.revisions[scroll_offset..end].iter().enumerate() {
+        let index = scroll_offset + i;
+        let is_selected = index == state.index;
+        let when = DateTime::from_timestamp_millis(rev.revision_ms)
+            .map(|t| t.with_timezone(&Local).format("%b %d %H:%M").to_string())
+            .unwrap_or_default();
+        let label = if index == 0 {
+            "Original".to_string()
+        } else {
+            format!("Edit {index}")
+        };
+        let author = if rev.is_outgoing { "you" } else { sender };
+        let base = if is_selected {
+            list_overlay::selection_style(theme.bg_selected, theme.fg)
+        } else {
+            Style::default().fg(theme.fg)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {label:<9}"), base),
+            Span::styled(format!("{when}  "), base.fg(theme.fg_secondary)),
+            Span::styled(author.to_string(), base.fg(theme.fg_muted)),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.extend(diff_lines);
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "  j/k version  |  Esc close",
+        Style::default().fg(theme.fg_muted),
+    )));
+    let popup = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+    frame.render_widget(popup, popup_area);
+}
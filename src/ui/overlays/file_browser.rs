@@ -1,8 +1,10 @@
-//! File browser overlay for picking attachments.
+//! File browser overlay for picking attachments and our profile avatar.
 //!
 //! Shows the current directory path at the top, the list of entries
 //! (directories first, type-to-filter narrowing), and a size column
-//! for files. Sets the title to the active filter when one is set.
+//! for files. The title names what the pick is for and shows the
+//! active filter and the number of marked files when there are any.
+//! Marked files carry a `*` in front of their name.
 
 use ratatui::{
     Frame,
@@ -14,6 +16,7 @@ use ratatui::{
 
 use super::super::{FILE_BROWSER_MAX_VISIBLE, FILE_BROWSER_POPUP_WIDTH, centered_popup, truncate};
 use crate::app::App;
+use crate::domain::FilePickerPurpose;
 
 pub(in crate::ui) fn draw_file_browser(frame: &mut Frame, app: &App, area: Rect) {
     let theme = &app.theme;
@@ -24,10 +27,18 @@ pub(in crate::ui) fn draw_file_browser(frame: &mut Frame, app: &App, area: Rect)
     });
     let pref_height = visible_count as u16 + 5; // border + header + footer
 
+    let label = match app.file_picker.purpose {
+        FilePickerPurpose::Attachment => "Attach File",
+        FilePickerPurpose::ProfileAvatar => "Choose Avatar",
+    };
+    let marked = match app.file_picker.marked.len() {
+        0 => String::new(),
+        n => format!(" ({n} marked)"),
+    };
     let title = if app.file_picker.filter.is_empty() {
-        " Attach File ".to_string()
+        format!(" {label}{marked} ")
     } else {
-        format!(" Attach File [{}] ", app.file_picker.filter)
+        format!(" {label}{marked} [{}] ", app.file_picker.filter)
     };
 
     let (popup_area, block) = centered_popup(
@@ -127,7 +138,12 @@ pub(in crate::ui) fn draw_file_browser(frame: &mut Frame, app: &App, area: Rect)
             };
 
             // Pad name to align size column
-            let name_padded = format!("  {display_name:width$}", width = name_max);
+            let marker = if app.file_picker.is_marked(entry_idx) {
+                "* "
+            } else {
+                "  "
+            };
+            let name_padded = format!("{marker}{display_name:width$}", width = name_max);
             let size_padded = format!("{size_str:>width$}  ", width = size_col_width);
 
             lines.push(Line::from(vec![
@@ -143,8 +159,14 @@ pub(in crate::ui) fn draw_file_browser(frame: &mut Frame, app: &App, area: Rect)
     }
 
     lines.push(Line::from(""));
+    let hints = match app.file_picker.purpose {
+        FilePickerPurpose::Attachment => "  j/k nav  Tab mark  Enter open/select  - up  Esc cancel",
+        FilePickerPurpose::ProfileAvatar => {
+            "  j/k nav  Enter open/select  Backspace/- up  Esc cancel"
+        }
+    };
     lines.push(Line::from(Span::styled(
-        "  j/k nav  Enter open/select  Backspace/- up  Esc cancel",
+        hints,
         Style::default().fg(theme.fg_muted),
     )));
 
@@ -153,7 +175,7 @@ pub(in crate::ui) fn draw_file_browser(frame: &mut Frame, app: &App, area: Rect)
 }
 
 /// Format a file size in human-readable form (B, K, M, G).
-fn format_file_size(bytes: u64) -> String {
+pub(in crate::ui) fn format_file_size(bytes: u64) -> String {
     if bytes < 1024 {
         format!("{bytes}B")
     } else if bytes < 1024 * 1024 {
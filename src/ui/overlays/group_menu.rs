@@ -1,11 +1,16 @@
 //! Group management overlay (multi-screen).
 //!
-//! Six sub-screens driven by `app.group_menu.state`:
-//! - `Menu`: per-group action list (members, add/remove, rename, leave)
-//! - `Members`: scrollable member list with `(you)` marker
+//! Sub-screens driven by `app.group_menu.state`:
+//! - `Menu`: per-group action list, gated on our role and group policies,
+//!   under the group's avatar once it has been fetched
+//! - `Members` / `Admins`: scrollable member list with `(you)` and
+//!   `(admin)` markers; in `Admins` Enter toggles the selected role
 //! - `AddMember` / `RemoveMember`: type-to-filter contact pickers,
 //!   sized to `CONTACTS_POPUP_WIDTH` to match the contacts overlay
-//! - `Rename` / `Create`: text-input popup with block cursor
+//! - `Rename` / `Create` / `Description` / `Avatar` / `JoinLink`:
+//!   text-input popup with block cursor
+//! - `Permissions`: one row per policy with its current setting
+//! - `InviteLink`: the current link (or "off") with enable/reset/disable keys
 //! - `LeaveConfirm`: y/n confirmation prompt
 
 use ratatui::{
@@ -17,12 +22,13 @@ use ratatui::{
 };
 
 use super::super::{
-    CONTACTS_POPUP_WIDTH, GROUP_MEMBER_MAX_VISIBLE, GROUP_MENU_POPUP_WIDTH, centered_popup,
-    truncate,
+    AVATAR_COLUMN_WIDTH, CONTACTS_POPUP_WIDTH, GROUP_MEMBER_MAX_VISIBLE, GROUP_MENU_POPUP_WIDTH,
+    beside_avatar, centered_popup, place_avatar, truncate,
 };
 use crate::app::{App, GroupMenuState};
+use crate::signal::types::{GroupPermission, GroupPolicy};
 
-pub(in crate::ui) fn draw_group_menu(frame: &mut Frame, app: &App, area: Rect) {
+pub(in crate::ui) fn draw_group_menu(frame: &mut Frame, app: &mut App, area: Rect) {
     let theme = &app.theme;
     let state = match &app.group_menu.state {
         Some(s) => s,
@@ -34,12 +40,48 @@ pub(in crate::ui) fn draw_group_menu(frame: &mut Frame, app: &App, area: Rect) {
             if items.is_empty() {
                 return;
             }
-            let popup_height = items.len() as u16 + 4;
-            let title = app
+            let group = app
                 .active_conversation
                 .as_ref()
                 .and_then(|id| app.store.conversations.get(id))
-                .filter(|c| c.is_group)
+                .filter(|c| c.is_group);
+            // Avatar header, once the group's avatar has been fetched
+            let mut lines: Vec<Line> =
+                match group.and_then(|c| Some((c, app.avatars.detail.get(&c.id)?))) {
+                    Some((conv, avatar)) => {
+                        let members = app
+                            .store
+                            .groups
+                            .get(&conv.id)
+                            .map(|g| g.members.len())
+                            .unwrap_or(0);
+                        let text_w = (GROUP_MENU_POPUP_WIDTH as usize)
+                            .saturating_sub(2 + AVATAR_COLUMN_WIDTH);
+                        let mut header = beside_avatar(
+                            Some(avatar),
+                            vec![
+                                Line::from(Span::styled(
+                                    format!("  {}", truncate(&conv.name, text_w.saturating_sub(2))),
+                                    Style::default()
+                                        .fg(theme.accent)
+                                        .add_modifier(Modifier::BOLD),
+                                )),
+                                Line::from(Span::styled(
+                                    format!("  {members} members"),
+                                    Style::default().fg(theme.fg_muted),
+                                )),
+                            ],
+                        );
+                        header.push(Line::from(""));
+                        header
+                    }
+                    None => Vec::new(),
+                };
+            let avatar_id = group
+                .filter(|c| app.avatars.detail.contains_key(&c.id))
+                .map(|c| c.id.clone());
+            let popup_height = (lines.len() + items.len()) as u16 + 4;
+            let title = group
                 .map(|c| format!(" #{} ", c.name))
                 .unwrap_or_else(|| " Group ".to_string());
             let (popup_area, block) = centered_popup(
@@ -53,7 +95,6 @@ pub(in crate::ui) fn draw_group_menu(frame: &mut Frame, app: &App, area: Rect) {
             let inner = block.inner(popup_area);
             frame.render_widget(block, popup_area);
             let content_width = inner.width as usize;
-            let mut lines: Vec<Line> = Vec::new();
             for (i, action) in items.iter().enumerate() {
                 let is_selected = i == app.group_menu.index;
                 let icon = if app.nerd_fonts {
@@ -90,11 +131,16 @@ pub(in crate::ui) fn draw_group_menu(frame: &mut Frame, app: &App, area: Rect) {
             )));
             let popup = Paragraph::new(lines);
             frame.render_widget(popup, inner);
+            if let Some(id) = avatar_id {
+                place_avatar(frame, app, &id, inner);
+            }
         }
-        GroupMenuState::Members => {
+        GroupMenuState::Members | GroupMenuState::Admins => {
+            let is_admins = *state == GroupMenuState::Admins;
+            let group = app.active_group();
             let max_visible = GROUP_MEMBER_MAX_VISIBLE.min(app.group_menu.filtered.len().max(1));
             let pref_height = max_visible as u16 + 5;
-            let title = " Members ".to_string();
+            let title = if is_admins { " Admins " } else { " Members " }.to_string();
             let (popup_area, block) = centered_popup(
                 frame,
                 area,
@@ -126,11 +172,13 @@ pub(in crate::ui) fn draw_group_menu(frame: &mut Frame, app: &App, area: Rect) {
                     let actual_index = scroll_offset + i;
                     let is_selected = actual_index == app.group_menu.index;
                     let is_self = *phone == app.account;
-                    let display = if is_self {
-                        format!("  {} (you)", name)
-                    } else {
-                        format!("  {}", name)
-                    };
+                    let mut display = format!("  {}", name);
+                    if is_self {
+                        display.push_str(" (you)");
+                    }
+                    if group.is_some_and(|g| g.is_admin(phone)) {
+                        display.push_str(" (admin)");
+                    }
                     let name_style = if is_selected {
                         Style::default()
                             .bg(theme.bg_selected)
@@ -154,8 +202,13 @@ pub(in crate::ui) fn draw_group_menu(frame: &mut Frame, app: &App, area: Rect) {
                 lines.push(Line::from(""));
             }
             lines.push(Line::from(""));
+            let footer = if is_admins {
+                "  Enter to toggle admin \u{00b7} Esc to go back"
+            } else {
+                "  Esc to go back"
+            };
             lines.push(Line::from(Span::styled(
-                "  Esc to go back",
+                footer,
                 Style::default().fg(theme.fg_muted),
             )));
             let popup = Paragraph::new(lines).block(block);
@@ -245,12 +298,17 @@ pub(in crate::ui) fn draw_group_menu(frame: &mut Frame, app: &App, area: Rect) {
             let popup = Paragraph::new(lines).block(block);
             frame.render_widget(popup, popup_area);
         }
-        GroupMenuState::Rename | GroupMenuState::Create => {
-            let is_rename = *state == GroupMenuState::Rename;
-            let title = if is_rename {
-                " Rename Group "
-            } else {
-                " Create Group "
+        GroupMenuState::Rename
+        | GroupMenuState::Create
+        | GroupMenuState::Description
+        | GroupMenuState::Avatar
+        | GroupMenuState::JoinLink => {
+            let title = match state {
+                GroupMenuState::Rename => " Rename Group ",
+                GroupMenuState::Description => " Group Description ",
+                GroupMenuState::Avatar => " Group Avatar (image path) ",
+                GroupMenuState::JoinLink => " Join Group (signal.group link) ",
+                _ => " Create Group ",
             };
             let (popup_area, block) =
                 centered_popup(frame, area, GROUP_MENU_POPUP_WIDTH, 6, title, theme);
@@ -270,6 +328,80 @@ pub(in crate::ui) fn draw_group_menu(frame: &mut Frame, app: &App, area: Rect) {
             let popup = Paragraph::new(lines);
             frame.render_widget(popup, inner);
         }
+        GroupMenuState::Permissions => {
+            let (popup_area, block) = centered_popup(
+                frame,
+                area,
+                GROUP_MENU_POPUP_WIDTH,
+                GroupPolicy::ALL.len() as u16 + 4,
+                " Permissions ",
+                theme,
+            );
+            let inner = block.inner(popup_area);
+            frame.render_widget(block, popup_area);
+            let content_width = inner.width as usize;
+            let mut lines: Vec<Line> = Vec::new();
+            for (i, policy) in GroupPolicy::ALL.iter().enumerate() {
+                let value = match app.active_group().map(|g| g.permission(*policy)) {
+                    Some(GroupPermission::OnlyAdmins) => "Only admins",
+                    _ => "Everyone",
+                };
+                let label = format!("  {}", policy.label());
+                let pad = content_width.saturating_sub(label.chars().count() + value.len() + 1);
+                let (row_style, value_style) = if i == app.group_menu.index {
+                    (
+                        Style::default().bg(theme.bg_selected).fg(theme.fg),
+                        Style::default().bg(theme.bg_selected).fg(theme.accent),
+                    )
+                } else {
+                    (
+                        Style::default().fg(theme.fg),
+                        Style::default().fg(theme.fg_muted),
+                    )
+                };
+                lines.push(Line::from(vec![
+                    Span::styled(format!("{label}{}", " ".repeat(pad)), row_style),
+                    Span::styled(format!("{value} "), value_style),
+                ]));
+            }
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                "  Enter to toggle \u{00b7} Esc to go back",
+                Style::default().fg(theme.fg_muted),
+            )));
+            frame.render_widget(Paragraph::new(lines), inner);
+        }
+        GroupMenuState::InviteLink => {
+            let link = app.active_group().and_then(|g| g.invite_link.as_deref());
+            let (popup_area, block) = centered_popup(
+                frame,
+                area,
+                GROUP_MENU_POPUP_WIDTH,
+                5,
+                " Invite Link ",
+                theme,
+            );
+            let inner = block.inner(popup_area);
+            frame.render_widget(block, popup_area);
+            let (text, style, keys) = match link {
+                Some(link) => (
+                    truncate(link, (inner.width as usize).saturating_sub(2)),
+                    Style::default().fg(theme.accent),
+                    "  (r)eset \u{00b7} (d)isable \u{00b7} Esc to go back",
+                ),
+                None => (
+                    "Invite link is off".to_string(),
+                    Style::default().fg(theme.fg_muted),
+                    "  (e)nable \u{00b7} Esc to go back",
+                ),
+            };
+            let lines = vec![
+                Line::from(Span::styled(format!("  {text}"), style)),
+                Line::from(""),
+                Line::from(Span::styled(keys, Style::default().fg(theme.fg_muted))),
+            ];
+            frame.render_widget(Paragraph::new(lines), inner);
+        }
         GroupMenuState::LeaveConfirm => {
             let group_name = app
                 .active_conversation
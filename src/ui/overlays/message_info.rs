@@ -0,0 +1,141 @@
+//! Message info overlay.
+//!
+//! Opened from the action menu. Shows when the message was sent and, for
+//! our own messages, a row per recipient with the time each receipt stage
+//! (delivered, read, viewed) was reached; recipients who haven't sent one
+//! yet show dashes. Below that, every reaction with its sender and time.
+
+use chrono::{DateTime, Local};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+use super::super::{MESSAGE_INFO_POPUP_WIDTH, centered_popup, truncate};
+use crate::app::App;
+
+/// Width of each receipt time column; fits "Oct 17 14:02".
+const TIME_WIDTH: usize = 12;
+
+fn format_when(ms: Option<i64>) -> String {
+    ms.and_then(DateTime::from_timestamp_millis)
+        .map(|t| t.with_timezone(&Local).format("%b %d %H:%M").to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+pub(in crate::ui) fn draw_message_info(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let info = &app.message_info;
+    let inner_w = MESSAGE_INFO_POPUP_WIDTH.saturating_sub(2) as usize;
+    let name_of = |id: &str| -> String {
+        if id == "you" || id == app.account {
+            "you".to_string()
+        } else {
+            app.conversation_name(id).to_string()
+        }
+    };
+    let heading = Style::default()
+        .fg(theme.fg_secondary)
+        .add_modifier(Modifier::BOLD);
+    let muted = Style::default().fg(theme.fg_muted);
+
+    let mut body: Vec<Line> = Vec::new();
+    let sender = app
+        .store
+        .conversations
+        .get(&info.conv_id)
+        .and_then(|c| c.find_msg_idx(info.timestamp_ms).map(|i| &c.messages[i]))
+        .map(|m| m.sender.clone())
+        .unwrap_or_default();
+    if !info.is_outgoing {
+        body.push(Line::from(vec![
+            Span::styled("  From  ", muted),
+            Span::styled(sender, Style::default().fg(theme.fg)),
+        ]));
+    }
+    body.push(Line::from(vec![
+        Span::styled("  Sent  ", muted),
+        Span::styled(
+            format_when(Some(info.timestamp_ms)),
+            Style::default().fg(theme.fg),
+        ),
+    ]));
+
+    if info.is_outgoing {
+        // "  " + name + 3 x ("  " + time)
+        let name_max = inner_w.saturating_sub(2 + 3 * (TIME_WIDTH + 2)).max(4);
+        body.push(Line::from(""));
+        body.push(Line::from(Span::styled(
+            format!(
+                "  {:<name_max$}  {:<TIME_WIDTH$}  {:<TIME_WIDTH$}  {}",
+                "Recipient", "Delivered", "Read", "Viewed"
+            ),
+            heading,
+        )));
+        for receipt in &info.receipts {
+            let name = truncate(&name_of(&receipt.recipient), name_max);
+            let pad = name_max.saturating_sub(name.chars().count());
+            let time_style = |ms: Option<i64>| {
+                if ms.is_some() {
+                    Style::default().fg(theme.fg_secondary)
+                } else {
+                    muted
+                }
+            };
+            let mut spans = vec![Span::styled(
+                format!("  {name}{}", " ".repeat(pad)),
+                Style::default().fg(theme.fg),
+            )];
+            for ms in [receipt.delivered_ms, receipt.read_ms, receipt.viewed_ms] {
+                spans.push(Span::styled(
+                    format!("  {:<TIME_WIDTH$}", format_when(ms)),
+                    time_style(ms),
+                ));
+            }
+            body.push(Line::from(spans));
+        }
+    }
+
+    body.push(Line::from(""));
+    body.push(Line::from(Span::styled("  Reactions", heading)));
+    if info.reactions.is_empty() {
+        body.push(Line::from(Span::styled("  No reactions", muted)));
+    }
+    for reaction in &info.reactions {
+        body.push(Line::from(vec![
+            Span::raw("  "),
+            Span::raw(reaction.emoji.clone()),
+            Span::styled(
+                format!("  {}", name_of(&reaction.sender)),
+                Style::default().fg(theme.fg),
+            ),
+            Span::styled(
+                format!("  {}", format_when(reaction.reacted_at_ms)),
+                Style::default().fg(theme.fg_secondary),
+            ),
+        ]));
+    }
+
+    // borders + body + blank + footer
+    let pref_height = (body.len() + 4) as u16;
+    let (popup_area, block) = centered_popup(
+        frame,
+        area,
+        MESSAGE_INFO_POPUP_WIDTH,
+        pref_height,
+        " Message info ",
+        theme,
+    );
+    let body_rows = popup_area.height.saturating_sub(4) as usize;
+    let scroll = info.scroll.min(body.len().saturating_sub(body_rows));
+    let mut lines: Vec<Line> = body.into_iter().skip(scroll).take(body_rows).collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "  j/k scroll  |  Esc close",
+        muted,
+    )));
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
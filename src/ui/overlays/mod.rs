@@ -10,8 +10,12 @@
 
 pub(super) mod about;
 pub(super) mod action_menu;
+pub(super) mod attachments;
+pub(super) mod calls;
 pub(super) mod contacts;
 pub(super) mod delete_conversation_confirm;
+pub(super) mod devices;
+pub(super) mod edit_history;
 pub(super) mod emoji_picker;
 pub(super) mod file_browser;
 pub(super) mod forward;
@@ -19,13 +23,18 @@ pub(super) mod group_menu;
 pub(super) mod help;
 pub(super) mod keybindings;
 pub(super) mod lock_screen;
+pub(super) mod message_info;
 pub(super) mod message_request;
 pub(super) mod pin_duration;
 pub(super) mod poll_vote;
 pub(super) mod profile;
+pub(super) mod rate_limit;
 pub(super) mod reaction_picker;
 pub(super) mod search;
 pub(super) mod settings;
 pub(super) mod settings_profile;
+pub(super) mod sticker_picker;
+pub(super) mod stories;
 pub(super) mod theme_picker;
 pub(super) mod verify;
+pub(super) mod view_once;
@@ -3,7 +3,11 @@
 //! Edit your own Signal profile: given/family name, about line, and
 //! about-emoji. Each field can be selected and entered for editing
 //! (block-cursor visible while typing). A `[ Save ]` button at index
-//! 4 commits all four fields.
+//! 4 commits all four fields. Below it, the username row is claimed on
+//! its own when its edit is confirmed, and the username link row copies
+//! the link; `d` on either deletes the username. Our avatar, when we have
+//! one, heads the popup; the avatar row picks a new one in the file
+//! browser and `d` there removes it.
 
 use ratatui::{
     Frame,
@@ -13,14 +17,22 @@ use ratatui::{
     widgets::Paragraph,
 };
 
-use super::super::{PROFILE_POPUP_WIDTH, centered_popup};
-use crate::app::App;
+use super::super::{PROFILE_POPUP_WIDTH, beside_avatar, centered_popup, place_avatar, truncate};
+use crate::app::{
+    App, PROFILE_AVATAR_INDEX, PROFILE_SAVE_INDEX, PROFILE_USERNAME_INDEX,
+    PROFILE_USERNAME_LINK_INDEX,
+};
 
-pub(in crate::ui) fn draw_profile(frame: &mut Frame, app: &App, area: Rect) {
+pub(in crate::ui) fn draw_profile(frame: &mut Frame, app: &mut App, area: Rect) {
     let theme = &app.theme;
     let labels = ["Given name", "Family name", "About", "About emoji"];
 
+    let avatar = app.avatars.detail.get(&app.account);
     let mut lines: Vec<Line> = Vec::new();
+    if let Some(avatar) = avatar {
+        lines.extend(beside_avatar(Some(avatar), Vec::new()));
+        lines.push(Line::from(""));
+    }
 
     for (i, label) in labels.iter().enumerate() {
         let is_selected = i == app.profile.index;
@@ -71,7 +83,7 @@ pub(in crate::ui) fn draw_profile(frame: &mut Frame, app: &App, area: Rect) {
     lines.push(Line::from(""));
 
     // Save button
-    let save_selected = app.profile.index == 4;
+    let save_selected = app.profile.index == PROFILE_SAVE_INDEX;
     let save_style = if save_selected {
         Style::default()
             .bg(theme.bg_selected)
@@ -82,10 +94,83 @@ pub(in crate::ui) fn draw_profile(frame: &mut Frame, app: &App, area: Rect) {
     };
     lines.push(Line::from(Span::styled("  [ Save ]", save_style)));
 
+    // Username rows
+    lines.push(Line::from(""));
+    let username_rows = [
+        (
+            PROFILE_USERNAME_INDEX,
+            "Username",
+            app.profile.username.as_deref(),
+        ),
+        (
+            PROFILE_USERNAME_LINK_INDEX,
+            "Username link",
+            app.profile.username_link.as_deref(),
+        ),
+    ];
+    // Borders, indent, padded label and a trailing space leave this much for the value
+    let value_width = (PROFILE_POPUP_WIDTH as usize).saturating_sub(2 + 2 + 15 + 1);
+    for (index, label, value) in username_rows {
+        let value = value.map(|v| truncate(v, value_width));
+        let is_selected = app.profile.index == index;
+        let is_editing = is_selected && app.profile.editing;
+        let label_style = if is_selected {
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.fg_secondary)
+        };
+        let (text, value_style) = match (is_editing, value) {
+            (true, _) => (
+                format!("{}\u{2588}", app.profile.edit_buffer),
+                Style::default().bg(theme.bg_selected).fg(theme.fg),
+            ),
+            (false, Some(v)) if is_selected => {
+                (v, Style::default().bg(theme.bg_selected).fg(theme.fg))
+            }
+            (false, Some(v)) => (v, Style::default().fg(theme.fg)),
+            (false, None) => ("(none)".to_string(), Style::default().fg(theme.fg_muted)),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:<14} ", label), label_style),
+            Span::styled(text, value_style),
+        ]));
+    }
+
+    // Avatar row
+    let is_selected = app.profile.index == PROFILE_AVATAR_INDEX;
+    let label_style = if is_selected {
+        Style::default()
+            .fg(theme.accent)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(theme.fg_secondary)
+    };
+    let (text, value_style) = match (avatar.is_some(), is_selected) {
+        (true, true) => ("(set)", Style::default().bg(theme.bg_selected).fg(theme.fg)),
+        (true, false) => ("(set)", Style::default().fg(theme.fg)),
+        (false, true) => (
+            "(none)",
+            Style::default().bg(theme.bg_selected).fg(theme.fg_muted),
+        ),
+        (false, false) => ("(none)", Style::default().fg(theme.fg_muted)),
+    };
+    lines.push(Line::from(vec![
+        Span::styled(format!("  {:<14} ", "Avatar"), label_style),
+        Span::styled(text, value_style),
+    ]));
+
     // Footer
     lines.push(Line::from(""));
     let footer = if app.profile.editing {
         "  Type to edit | Enter confirm | Esc cancel"
+    } else if app.profile.index == PROFILE_USERNAME_LINK_INDEX {
+        "  Enter copy | d delete username | Esc close"
+    } else if app.profile.index == PROFILE_USERNAME_INDEX {
+        "  Enter set | d delete | Esc close"
+    } else if app.profile.index == PROFILE_AVATAR_INDEX {
+        "  Enter choose file | d remove | Esc close"
     } else {
         "  j/k navigate | Enter edit | Esc close"
     };
@@ -104,6 +189,12 @@ pub(in crate::ui) fn draw_profile(frame: &mut Frame, app: &App, area: Rect) {
         theme,
     );
 
+    let has_avatar = avatar.is_some();
+    let inner = block.inner(popup_area);
     let popup = Paragraph::new(lines).block(block);
     frame.render_widget(popup, popup_area);
+    if has_avatar {
+        let account = app.account.clone();
+        place_avatar(frame, app, &account, inner);
+    }
 }
@@ -0,0 +1,88 @@
+//! Rate-limit overlay.
+//!
+//! Opens when Signal refuses a send over its rate limit. Says how many
+//! messages are held and the time sending should work again. For a proof-required challenge it points at the captcha
+//! page and takes the pasted `signalcaptcha://` link; otherwise Enter just
+//! retries the held messages.
+
+use chrono::{DateTime, Local};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+use super::super::{RATE_LIMIT_POPUP_WIDTH, centered_popup};
+use super::devices::input_tail;
+use crate::app::App;
+use crate::domain::CAPTCHA_URL;
+
+pub(in crate::ui) fn draw_rate_limit(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let state = &app.rate_limit;
+    let inner_w = RATE_LIMIT_POPUP_WIDTH.saturating_sub(2) as usize;
+    let text = Style::default().fg(theme.fg);
+    let muted = Style::default().fg(theme.fg_muted);
+
+    let held = app.pending.rate_limited_sends.len();
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "  Signal is rate-limiting messages from this account.",
+            text,
+        )),
+        Line::from(Span::styled(
+            format!(
+                "  {held} message{} held until this is cleared.",
+                if held == 1 { "" } else { "s" }
+            ),
+            text,
+        )),
+    ];
+    if let Some(at) = state.retry_at_ms.and_then(DateTime::from_timestamp_millis) {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "  Sending should work again after {}.",
+                at.with_timezone(&Local).format("%H:%M")
+            ),
+            text,
+        )));
+    }
+
+    let footer = if state.challenge.is_some() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("  Solve the captcha at", text)));
+        lines.push(Line::from(Span::styled(
+            format!("  {CAPTCHA_URL}"),
+            Style::default().fg(theme.accent),
+        )));
+        lines.push(Line::from(Span::styled(
+            "  then paste the signalcaptcha:// link it opens.",
+            text,
+        )));
+        lines.push(Line::from(""));
+        let prompt = "  Token: ";
+        let room = inner_w.saturating_sub(prompt.len() + 1);
+        lines.push(Line::from(vec![
+            Span::styled(prompt, muted),
+            Span::styled(format!("{}\u{2588}", input_tail(&state.input, room)), text),
+        ]));
+        "  Enter submit  |  Esc later"
+    } else {
+        "  Enter retry now  |  Esc later"
+    };
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(footer, muted)));
+
+    let pref_height = lines.len() as u16 + 2;
+    let (popup_area, block) = centered_popup(
+        frame,
+        area,
+        RATE_LIMIT_POPUP_WIDTH,
+        pref_height,
+        " Rate limited ",
+        theme,
+    );
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
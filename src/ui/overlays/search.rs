@@ -3,7 +3,9 @@
 //! Drives the `/search <query>` overlay: shows up to `SEARCH_MAX_VISIBLE`
 //! results with `[conv]` prefix when searching across all conversations,
 //! truncated sender, and a body snippet centered around the first match.
-//! `n`/`N` cycles between results inside the overlay.
+//! `n`/`N` cycles between results inside the overlay. With earlier
+//! versions included (Tab), a match found only in one is marked "edited:"
+//! and its snippet shows that older text.
 
 use ratatui::{
     Frame,
@@ -22,10 +24,15 @@ pub(in crate::ui) fn draw_search(frame: &mut Frame, app: &App, area: Rect) {
     let max_visible = SEARCH_MAX_VISIBLE.min(app.search.results.len().max(1));
     let pref_height = max_visible as u16 + 5; // +3 border/title +2 footer
 
+    let scope = if app.search.include_revisions {
+        " + old edits"
+    } else {
+        ""
+    };
     let title = if app.search.query.is_empty() {
-        " Search ".to_string()
+        format!(" Search{scope} ")
     } else {
-        format!(" Search [{}] ", app.search.query)
+        format!(" Search [{}]{scope} ", app.search.query)
     };
 
     let (popup_area, block) =
@@ -70,10 +77,17 @@ pub(in crate::ui) fn draw_search(frame: &mut Frame, app: &App, area: Rect) {
             };
 
             let sender_display = truncate(&result.sender, 10);
-            let prefix = format!("  {conv_prefix}{sender_display}: ");
+            let edited = if result.from_revision { "edited: " } else { "" };
+            let prefix = format!("  {conv_prefix}{sender_display}: {edited}");
             let body_max = inner_w.saturating_sub(prefix.len());
+            // Spoilers stay hidden unless revealed in the chat pane
+            let body = if app.spoiler_revealed(&result.conv_id, result.timestamp_ms) {
+                result.body.clone()
+            } else {
+                crate::domain::mask_spoilers(&result.body, &result.style_ranges)
+            };
             // Show a snippet of the body around the match
-            let body_snippet = search_snippet(&result.body, &app.search.query, body_max);
+            let body_snippet = search_snippet(&body, &app.search.query, body_max);
 
             let prefix_style = if is_selected {
                 Style::default().bg(theme.bg_selected).fg(theme.accent)
@@ -114,7 +128,7 @@ pub(in crate::ui) fn draw_search(frame: &mut Frame, app: &App, area: Rect) {
     lines.push(Line::from(vec![
         Span::styled(count_text, Style::default().fg(theme.warning)),
         Span::styled(
-            "  j/k nav | Enter jump | n/N cycle | Esc close",
+            "  j/k nav | Enter jump | Tab old edits | Esc close",
             Style::default().fg(theme.fg_muted),
         ),
     ]));
@@ -0,0 +1,151 @@
+//! Sticker picker overlay.
+//!
+//! Pack tab row across the top, the selected pack's stickers as a grid of
+//! their emoji, and a preview line naming the selected sticker. Shows a
+//! loading or error line until `listStickerPacks` has answered.
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+use super::super::{STICKER_POPUP_HEIGHT, STICKER_POPUP_WIDTH, centered_popup, truncate};
+use crate::app::App;
+use crate::list_overlay;
+
+pub(in crate::ui) fn draw_sticker_picker(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let picker = &app.sticker_picker;
+
+    let (popup_area, block) = centered_popup(
+        frame,
+        area,
+        STICKER_POPUP_WIDTH,
+        STICKER_POPUP_HEIGHT,
+        " Stickers ",
+        theme,
+    );
+
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    if inner.height < 5 || inner.width < 10 {
+        return;
+    }
+
+    let mut lines: Vec<Line<'static>> = Vec::new();
+    let status = if picker.loading && picker.packs.is_empty() {
+        Some("Loading sticker packs...".to_string())
+    } else if let Some(ref err) = picker.error {
+        Some(format!("Could not list sticker packs: {err}"))
+    } else if picker.packs.is_empty() {
+        Some("No sticker packs installed. Use /sticker install <url>".to_string())
+    } else {
+        None
+    };
+    if let Some(status) = status {
+        lines.push(Line::from(Span::styled(
+            format!(" {status}"),
+            Style::default().fg(theme.fg_muted),
+        )));
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            " Esc: close".to_string(),
+            Style::default().fg(theme.fg_muted),
+        )));
+        frame.render_widget(Paragraph::new(lines), inner);
+        return;
+    }
+
+    // Pack tab row, truncated to fit
+    let mut tab_spans: Vec<Span<'static>> = Vec::new();
+    let mut used = 0usize;
+    for (i, pack) in picker.packs.iter().enumerate() {
+        let title = truncate(&pack.title, 14);
+        let width = title.chars().count() + 2;
+        if used + width > inner.width as usize {
+            break;
+        }
+        used += width;
+        let style = if i == picker.pack_index {
+            Style::default()
+                .bg(theme.bg_selected)
+                .fg(theme.fg)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.fg_muted)
+        };
+        tab_spans.push(Span::styled(format!(" {title} "), style));
+    }
+    lines.push(Line::from(tab_spans));
+
+    let sep = "\u{2500}".repeat(inner.width as usize);
+    lines.push(Line::from(Span::styled(
+        sep,
+        Style::default().fg(theme.fg_muted),
+    )));
+
+    let footer_lines = 3; // blank + preview + help
+    let grid_height = (inner.height as usize).saturating_sub(lines.len() + footer_lines);
+    let cols = picker.cols;
+    let selected_row = picker.selected_index / cols;
+    let scroll_offset = if selected_row >= grid_height {
+        selected_row - grid_height + 1
+    } else {
+        0
+    };
+
+    let stickers = picker
+        .current_pack()
+        .map(|p| p.stickers.as_slice())
+        .unwrap_or_default();
+    let total_rows = stickers.len().div_ceil(cols);
+    for row_idx in scroll_offset..(scroll_offset + grid_height).min(total_rows) {
+        let mut row_spans: Vec<Span<'static>> = vec![Span::raw(" ".to_string())];
+        for col_idx in 0..cols {
+            let idx = row_idx * cols + col_idx;
+            let Some(sticker) = stickers.get(idx) else {
+                break;
+            };
+            let style = if idx == picker.selected_index {
+                list_overlay::selection_style(theme.bg_selected, theme.fg)
+            } else {
+                Style::default()
+            };
+            let glyph = sticker.emoji.as_deref().unwrap_or("\u{25a1}");
+            row_spans.push(Span::styled(format!("{glyph} "), style));
+        }
+        lines.push(Line::from(row_spans));
+    }
+
+    while lines.len() < (inner.height as usize).saturating_sub(footer_lines) {
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::from(""));
+
+    // Preview line: selected sticker and its pack
+    if let (Some(pack), Some(sticker)) = (picker.current_pack(), picker.selected_sticker()) {
+        let glyph = sticker.emoji.as_deref().unwrap_or("\u{25a1}");
+        let by = if pack.author.is_empty() {
+            String::new()
+        } else {
+            format!(" by {}", pack.author)
+        };
+        lines.push(Line::from(Span::styled(
+            format!(" {glyph} #{} - {}{by}", sticker.id, pack.title),
+            Style::default().fg(theme.accent),
+        )));
+    } else {
+        lines.push(Line::from(""));
+    }
+
+    lines.push(Line::from(Span::styled(
+        " Tab: pack | arrows/hjkl: nav | Enter: send | Esc".to_string(),
+        Style::default().fg(theme.fg_muted),
+    )));
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
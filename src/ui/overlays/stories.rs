@@ -0,0 +1,175 @@
+//! Stories overlay.
+//!
+//! Two views share one popup: the author list (contacts with live stories,
+//! unviewed ones first and marked with a dot) and the viewer, which pages
+//! through one author's stories. Image stories show the halfblock
+//! rendering prepared by `App::show_current_story`.
+
+use chrono::{DateTime, Local};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Wrap},
+};
+
+use super::super::{CONTACTS_MAX_VISIBLE, STORIES_POPUP_WIDTH, centered_popup, truncate};
+use crate::app::App;
+use crate::list_overlay;
+
+fn author_name<'a>(app: &'a App, author: &'a str) -> &'a str {
+    app.store
+        .contact_names
+        .get(author)
+        .map(String::as_str)
+        .unwrap_or_else(|| app.conversation_name(author))
+}
+
+pub(in crate::ui) fn draw_stories(frame: &mut Frame, app: &App, area: Rect) {
+    if app.stories.viewing.is_some() {
+        draw_viewer(frame, app, area);
+    } else {
+        draw_author_list(frame, app, area);
+    }
+}
+
+fn draw_author_list(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let authors = app.stories.authors();
+    let max_visible = CONTACTS_MAX_VISIBLE.min(authors.len().max(1));
+    let pref_height = max_visible as u16 + 5;
+
+    let unviewed = app.stories.unviewed_count();
+    let title = if unviewed == 0 {
+        " Stories ".to_string()
+    } else {
+        format!(" Stories ({unviewed} new) ")
+    };
+    let (popup_area, block) =
+        centered_popup(frame, area, STORIES_POPUP_WIDTH, pref_height, &title, theme);
+
+    let inner_height = popup_area.height.saturating_sub(2) as usize;
+    let (visible_rows, scroll_offset) =
+        list_overlay::scroll_layout(inner_height, 2, app.stories.index);
+
+    let mut lines: Vec<Line> = Vec::new();
+    if authors.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  No stories in the last 24 hours",
+            Style::default().fg(theme.fg_muted),
+        )));
+    } else {
+        let end = (scroll_offset + visible_rows).min(authors.len());
+        let inner_w = popup_area.width.saturating_sub(2) as usize;
+        for (i, entry) in authors[scroll_offset..end].iter().enumerate() {
+            let is_selected = scroll_offset + i == app.stories.index;
+            let (marker, count) = if entry.unviewed > 0 {
+                ("\u{25cf} ", format!("{} new", entry.unviewed))
+            } else {
+                ("\u{25cb} ", entry.total.to_string())
+            };
+            let name_max = inner_w.saturating_sub(count.len() + 6);
+            let name = truncate(author_name(app, &entry.author), name_max);
+            let pad = inner_w.saturating_sub(name.chars().count() + count.len() + 6);
+
+            let base = if is_selected {
+                list_overlay::selection_style(theme.bg_selected, theme.fg)
+            } else if entry.unviewed > 0 {
+                Style::default().fg(theme.fg)
+            } else {
+                Style::default().fg(theme.fg_secondary)
+            };
+            let marker_style = if entry.unviewed > 0 {
+                base.fg(theme.accent)
+            } else {
+                base.fg(theme.fg_muted)
+            };
+            lines.push(Line::from(vec![
+                Span::styled("  ", base),
+                Span::styled(marker, marker_style),
+                Span::styled(format!("{name}{}", " ".repeat(pad)), base),
+                Span::styled(format!("  {count}"), base.fg(theme.fg_muted)),
+            ]));
+        }
+    }
+
+    list_overlay::append_footer(
+        &mut lines,
+        visible_rows,
+        "  j/k navigate  |  Enter view  |  Esc close",
+        theme.fg_muted,
+    );
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
+
+fn draw_viewer(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let Some((story, pos, total)) = app.stories.current() else {
+        return;
+    };
+
+    let mut lines: Vec<Line> = Vec::new();
+    let posted = DateTime::from_timestamp_millis(story.timestamp_ms)
+        .map(|t| t.with_timezone(&Local).format("%H:%M").to_string())
+        .unwrap_or_default();
+    lines.push(Line::from(Span::styled(
+        format!("  posted {posted}"),
+        Style::default().fg(theme.fg_muted),
+    )));
+    lines.push(Line::from(""));
+
+    if let Some(ref image) = app.stories.image_lines {
+        lines.extend(image.iter().cloned());
+    } else if let Some(ref text) = story.text {
+        for line in text.lines() {
+            lines.push(Line::from(Span::styled(
+                format!("  {line}"),
+                Style::default().fg(theme.fg).add_modifier(Modifier::BOLD),
+            )));
+        }
+    } else {
+        let label = match story.attachment_path {
+            Some(ref path) => format!("  {} {path}", story.snippet()),
+            None => format!("  {} (not downloaded)", story.snippet()),
+        };
+        lines.push(Line::from(Span::styled(
+            label,
+            Style::default().fg(theme.fg_secondary),
+        )));
+    }
+
+    lines.push(Line::from(""));
+    let footer = if story.allows_replies {
+        "  h/l prev/next  |  r reply  |  Esc back"
+    } else {
+        "  h/l prev/next  |  Esc back"
+    };
+    lines.push(Line::from(Span::styled(
+        footer,
+        Style::default().fg(theme.fg_muted),
+    )));
+
+    let title = format!(
+        " {} \u{00b7} {}/{} ",
+        author_name(app, &story.author),
+        pos + 1,
+        total
+    );
+    // Account for wrapped text lines when sizing the popup
+    let inner_w = STORIES_POPUP_WIDTH
+        .min(area.width.saturating_sub(4))
+        .saturating_sub(2)
+        .max(1) as usize;
+    let rows: usize = lines
+        .iter()
+        .map(|l| l.width().div_ceil(inner_w).max(1))
+        .sum();
+    let pref_height = rows as u16 + 2;
+    let (popup_area, block) =
+        centered_popup(frame, area, STORIES_POPUP_WIDTH, pref_height, &title, theme);
+    let popup = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+    frame.render_widget(popup, popup_area);
+}
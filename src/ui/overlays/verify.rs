@@ -4,7 +4,9 @@
 //! state, formatted safety number, and fingerprint. Group chats show
 //! a scrollable member list with trust badges; the selected member's
 //! safety number renders below. The `confirming` flag toggles a
-//! "press v to confirm" prompt for the verify action.
+//! "press v to confirm" prompt for the verify action. Identities whose
+//! safety number changed since it was last acknowledged are flagged, with
+//! `a` offered to acknowledge the change without verifying.
 
 use ratatui::{
     Frame,
@@ -17,6 +19,7 @@ use ratatui::{
 use super::super::centered_popup;
 use crate::app::App;
 use crate::signal::types::TrustLevel;
+use crate::theme::Theme;
 
 pub(in crate::ui) fn draw_verify(frame: &mut Frame, app: &App, area: Rect) {
     let theme = &app.theme;
@@ -74,6 +77,7 @@ pub(in crate::ui) fn draw_verify(frame: &mut Frame, app: &App, area: Rect) {
                 .cloned()
                 .unwrap_or_else(|| number.to_string());
             let (badge, badge_color) = match identity.trust_level {
+                _ if app.identity_change_key(identity).is_some() => ("\u{26A0}", theme.warning),
                 TrustLevel::TrustedVerified => ("\u{2713}", theme.accent),
                 TrustLevel::Untrusted => ("\u{26A0}", theme.warning),
                 TrustLevel::TrustedUnverified => ("\u{2500}", theme.fg_muted),
@@ -124,12 +128,25 @@ pub(in crate::ui) fn draw_verify(frame: &mut Frame, app: &App, area: Rect) {
             }
         }
 
+        let selected_changed = app
+            .verify
+            .identities
+            .get(app.verify.index)
+            .is_some_and(|id| app.identity_change_key(id).is_some());
+        if selected_changed {
+            lines.push(changed_line(theme));
+        }
         lines.push(Line::from(""));
         if app.verify.confirming {
             lines.push(Line::from(Span::styled(
                 "  Compare safety numbers, then press v to confirm",
                 Style::default().fg(theme.warning),
             )));
+        } else if selected_changed {
+            lines.push(Line::from(Span::styled(
+                "  j/k  v: verify  a: acknowledge  Esc: close",
+                Style::default().fg(theme.fg_muted),
+            )));
         } else {
             lines.push(Line::from(Span::styled(
                 "  j/k: navigate  v: verify  Esc: close",
@@ -161,6 +178,10 @@ pub(in crate::ui) fn draw_verify(frame: &mut Frame, app: &App, area: Rect) {
             format!("  Trust: {trust_label}"),
             Style::default().fg(trust_color),
         )));
+        let changed = app.identity_change_key(identity).is_some();
+        if changed {
+            lines.push(changed_line(theme));
+        }
         lines.push(Line::from(""));
 
         if !identity.safety_number.is_empty() {
@@ -196,6 +217,11 @@ pub(in crate::ui) fn draw_verify(frame: &mut Frame, app: &App, area: Rect) {
                 "  Compare safety numbers, then press v to confirm",
                 Style::default().fg(theme.warning),
             )));
+        } else if changed {
+            lines.push(Line::from(Span::styled(
+                "  v: verify key  a: acknowledge  Esc: close",
+                Style::default().fg(theme.fg_muted),
+            )));
         } else {
             lines.push(Line::from(Span::styled(
                 "  v: verify key  Esc: close",
@@ -208,6 +234,17 @@ pub(in crate::ui) fn draw_verify(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(popup, popup_area);
 }
 
+/// Warning shown for an identity whose safety number change hasn't been
+/// acknowledged; sends to them are held until it is.
+fn changed_line(theme: &Theme) -> Line<'static> {
+    Line::from(Span::styled(
+        "  \u{26A0} Safety number changed, sends held",
+        Style::default()
+            .fg(theme.warning)
+            .add_modifier(Modifier::BOLD),
+    ))
+}
+
 /// Format a safety number string as groups of 5 digits, 6 per line.
 fn format_safety_number(sn: &str) -> Vec<String> {
     let digits: String = sn.chars().filter(|c| c.is_ascii_digit()).collect();
@@ -0,0 +1,76 @@
+//! View-once reveal overlay.
+//!
+//! Takes over the whole frame so nothing else is visible next to the media.
+//! Images show the halfblock rendering prepared by `App::open_view_once`;
+//! other media can be handed to the system viewer. The file is purged as
+//! soon as the overlay closes.
+
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+};
+
+use crate::app::App;
+
+pub(in crate::ui) fn draw_view_once(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let state = &app.view_once;
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.accent))
+        .title(format!(" View-once \u{00b7} {} ", state.sender))
+        .title_style(
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        )
+        .style(Style::default().bg(theme.bg));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    let body: Vec<Line> = if let Some(ref image) = state.image_lines {
+        image.clone()
+    } else {
+        let kind = state.content_type.as_deref().unwrap_or("media");
+        let what = if state.is_image() {
+            "This image could not be displayed."
+        } else {
+            "Press o to open it in your system viewer."
+        };
+        vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("View-once {kind}"),
+                Style::default().fg(theme.fg).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(Span::styled(what, Style::default().fg(theme.fg_secondary))),
+        ]
+    };
+    frame.render_widget(Paragraph::new(body).alignment(Alignment::Center), chunks[0]);
+
+    let footer = if state.is_image() {
+        "Esc close \u{2014} the media is deleted when you close"
+    } else {
+        "o open  |  Esc close \u{2014} the media is deleted when you close"
+    };
+    frame.render_widget(
+        Paragraph::new(Line::from(Span::styled(
+            footer,
+            Style::default().fg(theme.fg_muted),
+        )))
+        .alignment(Alignment::Center),
+        chunks[1],
+    );
+}
@@ -1,8 +1,10 @@
 //! Sidebar (conversation list) rendering.
 //!
 //! Renders the left/right pane that lists conversations: active marker,
-//! unread / message-request indicators, group `#` prefix, mute and
-//! blocked decorations. Honors the sidebar filter overlay (`/_`) by
+//! unread / message-request indicators, optional avatar glyph, group `#`
+//! prefix, mute and blocked decorations. With several accounts
+//! configured, the title names the active one and the bottom edge
+//! carries an unread badge per other account. Honors the sidebar filter overlay (`/_`) by
 //! swapping the title and the candidate list. Writes the inner Rect
 //! to `app.mouse.sidebar_inner` so click-to-focus knows where to hit.
 
@@ -16,10 +18,17 @@ use ratatui::{
 
 use super::truncate;
 use crate::app::{App, OverlayKind};
+use crate::domain::AVATAR_GLYPH_WIDTH;
 
 pub(super) fn draw_sidebar(frame: &mut Frame, app: &mut App, area: Rect) {
     let theme = &app.theme;
-    let max_name_width = (area.width as usize).saturating_sub(5); // "• # " + margin
+    // "• # " + margin, plus the avatar glyph and its gap when shown
+    let avatar_width = if app.avatars.sidebar {
+        AVATAR_GLYPH_WIDTH as usize + 1
+    } else {
+        0
+    };
+    let max_name_width = (area.width as usize).saturating_sub(5 + avatar_width);
 
     // Use filtered list when sidebar filter is active.
     // When filtering, show everything (so users can find hidden conversations).
@@ -83,6 +92,15 @@ pub(super) fn draw_sidebar(frame: &mut Frame, app: &mut App, area: Rect) {
                 spans.push(Span::raw("  "));
             }
 
+            // Avatar glyph, blank until fetched so names stay aligned
+            if app.avatars.sidebar {
+                match app.avatars.glyphs.get(id) {
+                    Some(glyph) => spans.extend(glyph.spans.iter().cloned()),
+                    None => spans.push(Span::raw(" ".repeat(AVATAR_GLYPH_WIDTH as usize))),
+                }
+                spans.push(Span::raw(" "));
+            }
+
             // Group prefix (dimmed #)
             if conv.is_group {
                 spans.push(Span::styled("#", Style::default().fg(theme.fg_muted)));
@@ -130,6 +148,8 @@ pub(super) fn draw_sidebar(frame: &mut Frame, app: &mut App, area: Rect) {
         } else {
             format!(" /{} ", app.sidebar_filter)
         }
+    } else if let Some(ref label) = app.accounts.label {
+        format!(" Chats \u{00b7} {label} ")
     } else {
         " Chats ".to_string()
     };
@@ -142,11 +162,32 @@ pub(super) fn draw_sidebar(frame: &mut Frame, app: &mut App, area: Rect) {
             .fg(theme.accent)
             .add_modifier(Modifier::BOLD)
     };
-    let block = Block::default()
+    let mut block = Block::default()
         .borders(border_side)
         .border_type(BorderType::Rounded)
         .title(title)
         .title_style(title_style);
+    // Per-account unread badges along the bottom edge (multi-account only)
+    if app.accounts.is_multi() {
+        let mut badge_spans = vec![Span::raw(" ")];
+        for badge in app.accounts.badges.iter().filter(|b| !b.is_self) {
+            let label = crate::domain::account_label(&badge.account);
+            if badge.unread > 0 {
+                badge_spans.push(Span::styled(
+                    format!("{label} ({}) ", badge.unread),
+                    Style::default()
+                        .fg(theme.warning)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            } else {
+                badge_spans.push(Span::styled(
+                    format!("{label} "),
+                    Style::default().fg(theme.fg_muted),
+                ));
+            }
+        }
+        block = block.title_bottom(Line::from(badge_spans));
+    }
     app.mouse.sidebar_inner = Some(block.inner(area));
 
     let sidebar = List::new(items).block(block);
@@ -1,7 +1,8 @@
 //! Status bar rendering: mode, connection, conversation, scroll position.
 //!
 //! Renders the bottom status line with the input-mode indicator
-//! (`[NORMAL]` / `[INSERT]`), connection dot and label, current
+//! (`[NORMAL]` / `[INSERT]`), connection dot and label, active account
+//! with other accounts' unread total (multi-account only), current
 //! conversation name with `#` prefix for groups, conversation count,
 //! and a scroll-position indicator (`↑N` plus focused-message
 //! timestamp) when scrolled up. Two override paths short-circuit the
@@ -116,6 +117,24 @@ pub(super) fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect, sidebar_
         ));
     }
 
+    // Active account, plus unread waiting in the other accounts
+    if let Some(ref label) = app.accounts.label {
+        segments.push(Span::styled(" │ ", Style::default().fg(theme.fg_muted)));
+        segments.push(Span::styled(
+            label.clone(),
+            Style::default().fg(theme.accent_secondary),
+        ));
+        let other = app.accounts.other_unread();
+        if other > 0 {
+            segments.push(Span::styled(
+                format!(" +{other}"),
+                Style::default()
+                    .fg(theme.warning)
+                    .add_modifier(Modifier::BOLD),
+            ));
+        }
+    }
+
     // Pipe separator
     segments.push(Span::styled(" │ ", Style::default().fg(theme.fg_muted)));
 